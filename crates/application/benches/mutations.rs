@@ -0,0 +1,67 @@
+// Run with: `cargo bench -p application --bench mutations --features testing`
+
+use application::{
+    test_helpers::ApplicationTestExt,
+    Application,
+    MutationOptions,
+};
+use common::{
+    components::{
+        CanonicalizedComponentFunctionPath,
+        ComponentPath,
+    },
+    knobs::RUNTIME_STACK_SIZE,
+    types::FunctionCaller,
+    RequestId,
+};
+use keybroker::Identity;
+use runtime::testing::TestDriver;
+use serde_json::json;
+
+fn main() {
+    // Match the stack size tests run with, since UDF execution needs more than
+    // the default main thread stack.
+    std::thread::Builder::new()
+        .stack_size(*RUNTIME_STACK_SIZE)
+        .spawn(divan::main)
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+/// UDF execution overhead of a mutation that inserts a single document. The
+/// test application's persistence is in memory, so this doesn't include any
+/// durable write latency.
+#[divan::bench(sample_count = 200)]
+fn insert_object_mutation(bencher: divan::Bencher) {
+    let td = TestDriver::new();
+    let rt = td.rt();
+    let application = td
+        .run_until(async {
+            let application = Application::new_for_tests(&rt).await?;
+            application.load_udf_tests_modules().await?;
+            anyhow::Ok(application)
+        })
+        .unwrap();
+    let path = CanonicalizedComponentFunctionPath {
+        component: ComponentPath::test_user(),
+        udf_path: "basic:insertObject".parse().unwrap(),
+    };
+    bencher.bench_local(|| {
+        td.run_until(application.mutation_udf(
+            RequestId::new(),
+            path.clone(),
+            vec![json!({"an": "object"})],
+            Identity::system(),
+            None,
+            FunctionCaller::Action {
+                parent_scheduled_job: None,
+                parent_execution_id: None,
+            },
+            None,
+            MutationOptions::default(),
+        ))
+        .unwrap()
+        .unwrap()
+    });
+}
@@ -68,6 +68,7 @@ use crate::{
     Application,
     FunctionError,
     FunctionReturn,
+    MutationOptions,
     RedactedActionError,
     RedactedActionReturn,
     RedactedMutationError,
@@ -364,6 +365,7 @@ impl<RT: Runtime> ApplicationApi for Application<RT> {
             mutation_identifier,
             caller,
             mutation_queue_length,
+            MutationOptions::default(),
         )
         .await
     }
@@ -391,6 +393,7 @@ impl<RT: Runtime> ApplicationApi for Application<RT> {
             mutation_identifier,
             caller,
             mutation_queue_length,
+            MutationOptions::default(),
         )
         .await
     }
@@ -0,0 +1,67 @@
+//! Serializes mutations that declare up front which documents they'll write.
+//!
+//! Two mutations updating the same document concurrently means one of them
+//! fails OCC and reruns from scratch. When the caller already knows which
+//! documents a mutation touches, queuing it behind other mutations with an
+//! overlapping hint is cheaper than letting them race. Hints are purely an
+//! optimization: OCC is still checked at commit, so a wrong hint only costs
+//! retries, never correctness.
+
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    sync::{
+        Arc,
+        Weak,
+    },
+};
+
+use parking_lot::Mutex;
+use tokio::sync::{
+    Mutex as AsyncMutex,
+    OwnedMutexGuard,
+};
+use value::id_v6::DeveloperDocumentId;
+
+#[derive(Clone, Default)]
+pub struct ConflictHintLocks {
+    locks: Arc<Mutex<BTreeMap<DeveloperDocumentId, Weak<AsyncMutex<()>>>>>,
+}
+
+/// Held for the duration of a mutation, including its OCC retries.
+pub struct ConflictHintGuard {
+    _guards: Vec<OwnedMutexGuard<()>>,
+}
+
+impl ConflictHintLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait until no other mutation holds a hint for any of `hint`.
+    pub async fn acquire(&self, hint: &[DeveloperDocumentId]) -> ConflictHintGuard {
+        // Acquire in sorted order so mutations with overlapping hints can't
+        // deadlock.
+        let ids: BTreeSet<_> = hint.iter().copied().collect();
+        let mut guards = Vec::with_capacity(ids.len());
+        for id in ids {
+            let lock = {
+                let mut locks = self.locks.lock();
+                // Clean up locks no one is holding or waiting on.
+                locks.retain(|_, lock| lock.strong_count() > 0);
+                match locks.get(&id).and_then(Weak::upgrade) {
+                    Some(lock) => lock,
+                    None => {
+                        let lock = Arc::new(AsyncMutex::new(()));
+                        locks.insert(id, Arc::downgrade(&lock));
+                        lock
+                    },
+                }
+            };
+            guards.push(lock.lock_owned().await);
+        }
+        ConflictHintGuard { _guards: guards }
+    }
+}
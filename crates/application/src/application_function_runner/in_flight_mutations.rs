@@ -0,0 +1,95 @@
+//! Tracks running mutations so an operator can abort a stuck one.
+//!
+//! Each mutation attempt is registered under its execution id until its UDF
+//! finishes running. Aborting drops the attempt's future, which terminates
+//! the isolate running it and drops its transaction uncommitted. Commit
+//! happens after the attempt is unregistered, so an abort can never race with
+//! a commit that's already in progress.
+
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+};
+
+use common::{
+    components::CanonicalizedComponentFunctionPath,
+    execution_context::ExecutionId,
+    RequestId,
+};
+use futures::future::{
+    AbortHandle,
+    AbortRegistration,
+};
+use parking_lot::Mutex;
+
+use super::metrics::log_in_flight_mutations;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InFlightMutation {
+    pub execution_id: ExecutionId,
+    pub request_id: RequestId,
+    pub path: CanonicalizedComponentFunctionPath,
+}
+
+#[derive(Clone, Default)]
+pub struct InFlightMutations {
+    mutations: Arc<Mutex<BTreeMap<ExecutionId, (InFlightMutation, AbortHandle)>>>,
+}
+
+/// Unregisters the mutation attempt when dropped.
+pub struct InFlightMutationGuard {
+    mutations: InFlightMutations,
+    execution_id: ExecutionId,
+}
+
+impl InFlightMutations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a mutation attempt. The returned registration should be used
+    /// to make the attempt's future abortable.
+    pub fn register(
+        &self,
+        mutation: InFlightMutation,
+    ) -> (InFlightMutationGuard, AbortRegistration) {
+        let (handle, registration) = AbortHandle::new_pair();
+        let execution_id = mutation.execution_id;
+        let mut mutations = self.mutations.lock();
+        mutations.insert(execution_id, (mutation, handle));
+        log_in_flight_mutations(mutations.len());
+        let guard = InFlightMutationGuard {
+            mutations: self.clone(),
+            execution_id,
+        };
+        (guard, registration)
+    }
+
+    pub fn list(&self) -> Vec<InFlightMutation> {
+        self.mutations
+            .lock()
+            .values()
+            .map(|(mutation, _)| mutation.clone())
+            .collect()
+    }
+
+    /// Abort the mutation attempt with `execution_id`, returning whether it
+    /// was still running.
+    pub fn abort(&self, execution_id: ExecutionId) -> bool {
+        match self.mutations.lock().get(&execution_id) {
+            Some((_, handle)) => {
+                handle.abort();
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+impl Drop for InFlightMutationGuard {
+    fn drop(&mut self) {
+        let mut mutations = self.mutations.mutations.lock();
+        mutations.remove(&self.execution_id);
+        log_in_flight_mutations(mutations.len());
+    }
+}
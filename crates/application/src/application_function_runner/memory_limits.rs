@@ -0,0 +1,41 @@
+//! Per-caller overrides of how much memory a single function invocation may
+//! use, from the `ISOLATE_INVOCATION_MEMORY_LIMIT_BY_CALLER` knob. Invocations
+//! from callers without an override fall back to the
+//! `ISOLATE_INVOCATION_MEMORY_LIMIT` knob.
+
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+};
+
+use common::{
+    execution_context::ExecutionContext,
+    knobs::ISOLATE_INVOCATION_MEMORY_LIMIT_BY_CALLER,
+    types::FunctionCaller,
+};
+use parking_lot::RwLock;
+
+#[derive(Clone)]
+pub struct CallerMemoryLimits {
+    /// Keyed by the caller's name, e.g. `Cron` or `HttpEndpoint`.
+    by_caller: Arc<RwLock<BTreeMap<String, usize>>>,
+}
+
+impl CallerMemoryLimits {
+    pub fn from_knobs() -> Self {
+        Self {
+            by_caller: Arc::new(RwLock::new(
+                ISOLATE_INVOCATION_MEMORY_LIMIT_BY_CALLER.clone(),
+            )),
+        }
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    pub fn set_for_caller(&self, caller: &str, limit: usize) {
+        self.by_caller.write().insert(caller.to_string(), limit);
+    }
+
+    pub fn apply(&self, caller: &FunctionCaller, context: &mut ExecutionContext) {
+        context.memory_limit = self.by_caller.read().get(&caller.to_string()).copied();
+    }
+}
@@ -6,7 +6,9 @@ use metrics::{
     log_counter_with_labels,
     log_distribution,
     log_distribution_with_labels,
+    log_gauge,
     register_convex_counter,
+    register_convex_gauge,
     register_convex_histogram,
     StaticMetricLabel,
     StatusTimer,
@@ -62,6 +64,14 @@ pub fn mutation_timer() -> StatusTimer {
     StatusTimer::new(&APPLICATION_MUTATION_SECONDS)
 }
 
+register_convex_gauge!(
+    APPLICATION_IN_FLIGHT_MUTATIONS_INFO,
+    "Number of mutation attempts currently running their UDF"
+);
+pub fn log_in_flight_mutations(count: usize) {
+    log_gauge(&APPLICATION_IN_FLIGHT_MUTATIONS_INFO, count as f64);
+}
+
 pub enum OutstandingFunctionState {
     Running,
     Waiting,
@@ -4,6 +4,7 @@ use std::{
         BTreeMap,
         BTreeSet,
     },
+    future::Future,
     sync::{
         atomic::AtomicUsize,
         Arc,
@@ -17,7 +18,6 @@ use async_trait::async_trait;
 use authentication::token_to_authorization_header;
 use common::{
     auth::AuthConfig,
-    backoff::Backoff,
     bootstrap_model::components::{
         definition::ComponentDefinitionMetadata,
         handles::FunctionHandle,
@@ -33,7 +33,10 @@ use common::{
         Resource,
     },
     errors::JsError,
-    execution_context::ExecutionContext,
+    execution_context::{
+        ExecutionContext,
+        ExecutionId,
+    },
     fastrace_helpers::EncodedSpan,
     knobs::{
         APPLICATION_FUNCTION_RUNNER_SEMAPHORE_TIMEOUT,
@@ -44,12 +47,10 @@ use common::{
         APPLICATION_MAX_CONCURRENT_V8_ACTIONS,
         DEFAULT_APPLICATION_MAX_FUNCTION_CONCURRENCY,
         ISOLATE_MAX_USER_HEAP_SIZE,
-        UDF_EXECUTOR_OCC_INITIAL_BACKOFF,
-        UDF_EXECUTOR_OCC_MAX_BACKOFF,
-        UDF_EXECUTOR_OCC_MAX_RETRIES,
     },
     log_lines::{
         run_function_and_collect_log_lines,
+        LogLevel,
         LogLine,
         LogLines,
     },
@@ -68,6 +69,8 @@ use common::{
         FunctionCaller,
         ModuleEnvironment,
         NodeDependency,
+        TableName,
+        TableStats,
         Timestamp,
         UdfType,
     },
@@ -77,6 +80,8 @@ use common::{
 use database::{
     unauthorized_error,
     Database,
+    ReadSetSize,
+    ReadWriteSets,
     Token,
     Transaction,
 };
@@ -95,10 +100,18 @@ use function_runner::{
     FunctionWrites,
 };
 use futures::{
+    future::{
+        self,
+        Abortable,
+        Aborted,
+    },
     select_biased,
     FutureExt,
 };
-use isolate::ActionCallbacks;
+use isolate::{
+    ActionCallbacks,
+    ConcurrencyPermitStats,
+};
 use keybroker::{
     Identity,
     KeyBroker,
@@ -133,7 +146,10 @@ use model::{
         },
         ModuleModel,
     },
-    scheduled_jobs::VirtualSchedulerModel,
+    scheduled_jobs::{
+        VirtualSchedulerModel,
+        SCHEDULED_JOBS_TABLE,
+    },
     session_requests::{
         types::{
             SessionRequestIdentifier,
@@ -154,6 +170,7 @@ use node_executor::{
     BuildDepsRequest,
     ExecuteRequest,
 };
+use parking_lot::Mutex;
 use serde_json::Value as JsonValue;
 use storage::Storage;
 use sync_types::{
@@ -163,7 +180,10 @@ use sync_types::{
 use tokio::sync::mpsc;
 use udf::{
     environment::system_env_vars,
-    helpers::parse_udf_args,
+    helpers::{
+        parse_udf_args,
+        UdfArgs,
+    },
     validation::{
         validate_schedule_args,
         ValidatedActionOutcome,
@@ -183,6 +203,7 @@ use usage_tracking::{
     OccInfo,
 };
 use value::{
+    export::ValueFormat,
     id_v6::DeveloperDocumentId,
     identifier::Identifier,
     serialized_args_ext::SerializedArgsExt,
@@ -194,14 +215,32 @@ use vector::{
     VectorSearch,
 };
 
-use self::metrics::{
-    function_waiter_timer,
-    log_occ_retries,
-    log_outstanding_functions,
-    log_udf_executor_result,
-    mutation_timer,
-    OutstandingFunctionState,
-    UdfExecutorResult,
+pub(crate) use self::memory_limits::CallerMemoryLimits;
+use self::{
+    conflict_hints::ConflictHintLocks,
+    in_flight_mutations::InFlightMutations,
+    metrics::{
+        function_waiter_timer,
+        log_occ_retries,
+        log_outstanding_functions,
+        log_udf_executor_result,
+        mutation_timer,
+        OutstandingFunctionState,
+        UdfExecutorResult,
+    },
+    retry_policy::RetryPolicies,
+};
+pub use self::{
+    in_flight_mutations::InFlightMutation,
+    occ_retry_observer::{
+        OccRetryObserver,
+        OccRetryOutcome,
+    },
+    retry_policy::{
+        DefaultRetryPolicy,
+        OccRetryPolicy,
+        RetryPolicy,
+    },
 };
 use crate::{
     application_function_runner::metrics::{
@@ -220,13 +259,25 @@ use crate::{
     },
     ActionError,
     ActionReturn,
+    DryRunWrite,
+    DryRunWriteKind,
+    FunctionTiming,
+    MutationBatchError,
+    MutationBatchReturn,
     MutationError,
+    MutationOptions,
     MutationReturn,
+    OccRetriesExhausted,
     QueryReturn,
 };
 
+mod conflict_hints;
 mod http_routing;
+mod in_flight_mutations;
+mod memory_limits;
 mod metrics;
+mod occ_retry_observer;
+mod retry_policy;
 
 static BUILD_DEPS_TIMEOUT: LazyLock<Duration> = LazyLock::new(|| Duration::from_secs(1200));
 
@@ -425,7 +476,10 @@ impl<RT: Runtime> FunctionRouter<RT> {
                 user_tx_size,
                 system_tx_size,
             } = function_tx.reads;
-            let FunctionWrites { updates } = function_tx.writes;
+            let FunctionWrites {
+                updates,
+                counter_increments,
+            } = function_tx.writes;
             tx.apply_function_runner_tx(
                 function_tx.begin_timestamp,
                 reads,
@@ -433,6 +487,7 @@ impl<RT: Runtime> FunctionRouter<RT> {
                 user_tx_size,
                 system_tx_size,
                 updates,
+                counter_increments,
                 function_tx.rows_read_by_tablet,
             )?;
             Some(tx)
@@ -567,6 +622,32 @@ impl Drop for RequestGuard<'_> {
     }
 }
 
+/// What [`ApplicationFunctionRunner::run_mutations`] committed.
+struct MutationsReturn {
+    /// Each mutation's value, log lines and timing, in order. Timing is `None`
+    /// if the mutation had already been committed by an earlier request.
+    outcomes: Vec<(JsonPackedValue, LogLines, Option<FunctionTiming>)>,
+    ts: Timestamp,
+    committed_writes: bool,
+    read_set_size: Option<ReadSetSize>,
+    occ_retries: usize,
+    occ_retry_time: Duration,
+    read_write_sets: Option<ReadWriteSets>,
+    dry_run_writes: Option<Vec<DryRunWrite>>,
+}
+
+/// One mutation of an attempt that ran successfully and is waiting on the
+/// commit.
+struct MutationAttempt {
+    value: JsonPackedValue,
+    outcome: ValidatedUdfOutcome,
+    stats: BTreeMap<TableName, TableStats>,
+    start: tokio::time::Instant,
+    execution_time: Duration,
+    usage_tracker: FunctionUsageTracker,
+    context: ExecutionContext,
+}
+
 /// Executes UDFs for backends.
 ///
 /// This struct directly executes http and node actions. Queries, Mutations and
@@ -591,6 +672,12 @@ pub struct ApplicationFunctionRunner<RT: Runtime> {
     cache_manager: CacheManager<RT>,
     default_system_env_vars: BTreeMap<EnvVarName, EnvVarValue>,
     node_action_limiter: Limiter,
+    conflict_hint_locks: ConflictHintLocks,
+    in_flight_mutations: InFlightMutations,
+    retry_policies: RetryPolicies,
+    pinned_unix_timestamp: Arc<Mutex<Option<UnixTimestamp>>>,
+    memory_limits: CallerMemoryLimits,
+    occ_retry_observer: Mutex<Option<Arc<dyn OccRetryObserver>>>,
 }
 
 impl<RT: Runtime> ApplicationFunctionRunner<RT> {
@@ -613,11 +700,15 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
             database.clone(),
             default_system_env_vars.clone(),
         );
+        let pinned_unix_timestamp = Arc::new(Mutex::new(None));
+        let memory_limits = CallerMemoryLimits::from_knobs();
         let cache_manager = CacheManager::new(
             runtime.clone(),
             database.clone(),
             isolate_functions.clone(),
             function_log.clone(),
+            pinned_unix_timestamp.clone(),
+            memory_limits.clone(),
             cache,
         );
 
@@ -638,6 +729,12 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
                 UdfType::Action,
                 *APPLICATION_MAX_CONCURRENT_NODE_ACTIONS,
             ),
+            conflict_hint_locks: ConflictHintLocks::new(),
+            in_flight_mutations: InFlightMutations::new(),
+            retry_policies: RetryPolicies::new(),
+            pinned_unix_timestamp,
+            memory_limits,
+            occ_retry_observer: Mutex::new(None),
         }
     }
 
@@ -646,7 +743,7 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
         Ok(())
     }
 
-    // Only used for running queries from REPLs.
+    // Only used for running queries from REPLs and health checks.
     pub async fn run_query_without_caching(
         &self,
         request_id: RequestId,
@@ -717,17 +814,66 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
         Ok((result, log_lines))
     }
 
+    /// Mutation attempts that are currently running their UDF.
+    pub fn in_flight_mutations(&self) -> Vec<InFlightMutation> {
+        self.in_flight_mutations.list()
+    }
+
+    /// Abort the running mutation attempt with `execution_id`, rolling back
+    /// its transaction. Returns whether the attempt was found.
+    pub fn abort_mutation(&self, execution_id: ExecutionId) -> bool {
+        self.in_flight_mutations.abort(execution_id)
+    }
+
+    pub fn set_function_retry_policy(
+        &self,
+        path: CanonicalizedComponentFunctionPath,
+        policy: Arc<dyn RetryPolicy>,
+    ) {
+        self.retry_policies.set_for_function(path, policy);
+    }
+
+    pub fn set_caller_retry_policy(
+        &self,
+        matches: fn(&FunctionCaller) -> bool,
+        policy: Arc<dyn RetryPolicy>,
+    ) {
+        self.retry_policies.set_for_caller(matches, policy);
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    pub fn set_pinned_unix_timestamp(&self, unix_timestamp: Option<UnixTimestamp>) {
+        *self.pinned_unix_timestamp.lock() = unix_timestamp;
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    pub fn set_caller_memory_limit(&self, caller: &str, limit: usize) {
+        self.memory_limits.set_for_caller(caller, limit);
+    }
+
+    pub fn concurrency_permit_stats(&self) -> Option<ConcurrencyPermitStats> {
+        self.isolate_functions
+            .function_runner
+            .concurrency_permit_stats()
+    }
+
+    pub fn set_occ_retry_observer(&self, observer: Option<Arc<dyn OccRetryObserver>>) {
+        *self.occ_retry_observer.lock() = observer;
+    }
+
     /// Runs a mutations and retries on OCC errors.
     #[fastrace::trace]
     pub async fn retry_mutation(
         &self,
         request_id: RequestId,
         path: PublicFunctionPath,
-        arguments: Vec<JsonValue>,
+        arguments: UdfArgs,
         identity: Identity,
         mutation_identifier: Option<SessionRequestIdentifier>,
         caller: FunctionCaller,
         mutation_queue_length: Option<usize>,
+        options: MutationOptions,
+        dry_run: bool,
     ) -> anyhow::Result<Result<MutationReturn, MutationError>> {
         let timer = mutation_timer();
         let result = self
@@ -739,6 +885,8 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
                 mutation_identifier,
                 caller,
                 mutation_queue_length,
+                options,
+                dry_run,
             )
             .await;
         match &result {
@@ -749,21 +897,34 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
     }
 
     /// Runs a mutations and retries on OCC errors.
+    ///
+    /// If `dry_run` is set, each attempt's writes are discarded instead of
+    /// committed, but an attempt still fails with an OCC error if its reads
+    /// have changed since it began, just as its commit would have.
+    ///
+    /// `options.occ_retry_policy` takes precedence over any policy registered
+    /// for the function or caller.
+    ///
+    /// If `options.idempotency_key` was used by a mutation of the same
+    /// function and identity that committed, returns its result instead of
+    /// running the mutation again.
     #[fastrace::trace]
     async fn _retry_mutation(
         &self,
         request_id: RequestId,
         path: PublicFunctionPath,
-        arguments: Vec<JsonValue>,
+        arguments: UdfArgs,
         identity: Identity,
         mutation_identifier: Option<SessionRequestIdentifier>,
         caller: FunctionCaller,
         mutation_queue_length: Option<usize>,
+        options: MutationOptions,
+        dry_run: bool,
     ) -> anyhow::Result<Result<MutationReturn, MutationError>> {
         if path.is_system() && !(identity.is_admin() || identity.is_system()) {
             anyhow::bail!(unauthorized_error("mutation"));
         }
-        let arguments = match parse_udf_args(path.udf_path(), arguments) {
+        let arguments = match arguments.into_convex_array(path.udf_path()) {
             Ok(arguments) => arguments,
             Err(error) => {
                 return Ok(Err(MutationError {
@@ -772,215 +933,642 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
                 }))
             },
         };
-        let udf_path_string = (!path.is_system()).then_some(path.udf_path().to_string());
+        let allowed_visibility = caller.allowed_visibility();
+        let result = self
+            .run_mutations(
+                request_id,
+                vec![(path, arguments)],
+                identity,
+                mutation_identifier,
+                caller,
+                allowed_visibility,
+                mutation_queue_length,
+                options,
+                dry_run,
+            )
+            .await?;
+        Ok(match result {
+            Ok(mut mutations_return) => {
+                let (value, log_lines, timing) = mutations_return
+                    .outcomes
+                    .pop()
+                    .context("Missing mutation outcome")?;
+                Ok(MutationReturn {
+                    value,
+                    log_lines,
+                    ts: mutations_return.ts,
+                    committed_writes: mutations_return.committed_writes,
+                    read_set_size: mutations_return.read_set_size,
+                    occ_retries: mutations_return.occ_retries,
+                    occ_retry_time: mutations_return.occ_retry_time,
+                    read_write_sets: mutations_return.read_write_sets,
+                    timing,
+                    dry_run_writes: mutations_return.dry_run_writes,
+                })
+            },
+            Err(MutationBatchError {
+                index: _,
+                error,
+                log_lines,
+            }) => Err(MutationError { error, log_lines }),
+        })
+    }
 
-        let mut backoff = Backoff::new(
-            *UDF_EXECUTOR_OCC_INITIAL_BACKOFF,
-            *UDF_EXECUTOR_OCC_MAX_BACKOFF,
-        );
+    /// Runs `mutations` in order in one transaction, committing them together
+    /// and retrying the whole batch on OCC errors. Returns as soon as one of
+    /// them throws, without committing anything.
+    #[fastrace::trace]
+    pub async fn retry_mutation_batch(
+        &self,
+        request_id: RequestId,
+        mutations: Vec<(PublicFunctionPath, Vec<JsonValue>)>,
+        identity: Identity,
+        allowed_visibility: AllowedVisibility,
+        caller: FunctionCaller,
+    ) -> anyhow::Result<Result<MutationBatchReturn, MutationBatchError>> {
+        let mut parsed = Vec::with_capacity(mutations.len());
+        for (index, (path, arguments)) in mutations.into_iter().enumerate() {
+            if path.is_system() && !(identity.is_admin() || identity.is_system()) {
+                anyhow::bail!(unauthorized_error("mutation"));
+            }
+            match parse_udf_args(path.udf_path(), arguments) {
+                Ok(arguments) => parsed.push((path, arguments)),
+                Err(error) => {
+                    return Ok(Err(MutationBatchError {
+                        index,
+                        error,
+                        log_lines: vec![].into(),
+                    }))
+                },
+            }
+        }
+        let timer = mutation_timer();
+        let result = self
+            .run_mutations(
+                request_id,
+                parsed,
+                identity,
+                None,
+                caller,
+                allowed_visibility,
+                None,
+                MutationOptions::default(),
+                false,
+            )
+            .await;
+        match &result {
+            Ok(_) => timer.finish(),
+            Err(e) => timer.finish_with(e.metric_status_label_value()),
+        };
+        Ok(result?.map(|mutations_return| {
+            let (values, log_lines) = mutations_return
+                .outcomes
+                .into_iter()
+                .map(|(value, log_lines, _)| (value, log_lines))
+                .unzip();
+            MutationBatchReturn {
+                values,
+                log_lines,
+                ts: mutations_return.ts,
+                occ_retries: mutations_return.occ_retries,
+            }
+        }))
+    }
 
-        loop {
-            let mutation_retry_count = backoff.failures() as usize;
-            let usage_tracker = FunctionUsageTracker::new();
+    /// Runs `mutations` one after another in a single transaction per
+    /// attempt, committing them together and retrying the whole attempt on
+    /// OCC errors. Single mutations and batches both run through here.
+    ///
+    /// Only a single mutation can carry a `mutation_identifier` or
+    /// idempotency key, since only one outcome can be saved for them. An
+    /// idempotency key is turned into a `mutation_identifier`, so both are
+    /// deduplicated through `_session_requests`. A batch
+    /// retries under the policy for its first function, and failures at
+    /// commit are reported against its last mutation.
+    async fn run_mutations(
+        &self,
+        request_id: RequestId,
+        mutations: Vec<(PublicFunctionPath, ConvexArray)>,
+        identity: Identity,
+        mutation_identifier: Option<SessionRequestIdentifier>,
+        caller: FunctionCaller,
+        allowed_visibility: AllowedVisibility,
+        mutation_queue_length: Option<usize>,
+        options: MutationOptions,
+        dry_run: bool,
+    ) -> anyhow::Result<Result<MutationsReturn, MutationBatchError>> {
+        let MutationOptions {
+            conflict_hint,
+            rng_seed,
+            occ_retry_policy,
+            idempotency_key,
+            min_log_level,
+            report_read_set_size,
+        } = options;
+        anyhow::ensure!(!mutations.is_empty(), "No mutations to run");
+        anyhow::ensure!(
+            mutations.len() == 1 || (mutation_identifier.is_none() && idempotency_key.is_none()),
+            "Only single mutations can be deduplicated"
+        );
+        let udf_path_string = match &mutations[..] {
+            [(path, _)] => (!path.is_system()).then_some(path.udf_path().to_string()),
+            _ => None,
+        };
+        let component_paths: Vec<_> = mutations
+            .iter()
+            .map(|(path, _)| path.clone().debug_into_component_path())
+            .collect();
+        let first_component_path = &component_paths[0];
+        let retry_policy: Arc<dyn RetryPolicy> = match occ_retry_policy {
+            Some(policy) => Arc::new(policy),
+            None => self
+                .retry_policies
+                .policy_for(first_component_path, &caller),
+        };
+        let mutation_identifier = match idempotency_key {
+            Some(key) => {
+                anyhow::ensure!(
+                    mutation_identifier.is_none(),
+                    "Mutations can't have both a mutation_identifier and an idempotency key"
+                );
+                // Idempotency keys are scoped to the function, including its
+                // component.
+                let udf_path = if first_component_path.component.is_root() {
+                    first_component_path.udf_path.to_string()
+                } else {
+                    format!(
+                        "{}/{}",
+                        first_component_path.component, first_component_path.udf_path
+                    )
+                };
+                Some(SessionRequestIdentifier::for_idempotency_key(
+                    &udf_path,
+                    &identity.clone().into(),
+                    &key,
+                )?)
+            },
+            None => mutation_identifier,
+        };
+        let occ_retry_observer = self.occ_retry_observer.lock().clone();
+        let mut occ_retries = 0;
 
-            // Note that we use different context for every mutation attempt.
-            // This so every JS function run gets a different executionId.
-            let context = ExecutionContext::new(request_id.clone(), &caller);
+        // Wait for other mutations that declared they'll write the same
+        // documents, and hold them off until we've committed (or given up).
+        let _conflict_hint_guard = self.conflict_hint_locks.acquire(&conflict_hint).await;
 
-            let start = self.runtime.monotonic_now();
+        let first_attempt_start = self.runtime.monotonic_now();
+        loop {
+            let mutation_retry_count = occ_retries;
+            let occ_retry_time = self.runtime.monotonic_now() - first_attempt_start;
+            // The first mutation's usage includes checking and saving whether
+            // it already ran.
+            let first_usage_tracker = FunctionUsageTracker::new();
             let mut tx = self
                 .database
-                .begin_with_usage(identity.clone(), usage_tracker.clone())
+                .begin_with_usage(identity.clone(), first_usage_tracker.clone())
                 .await?;
             let pause_client = self.runtime.pause_client();
             pause_client.wait("retry_mutation_loop_start").await;
-            let identity = tx.inert_identity();
+            let inert_identity = tx.inert_identity();
 
             // Return the previous execution's result if the mutation was committed already.
             if let Some(result) = self
                 .check_mutation_status(&mut tx, &mutation_identifier)
                 .await?
             {
-                return Ok(result);
+                return Ok(match result {
+                    Ok(mutation_return) => Ok(MutationsReturn {
+                        outcomes: vec![(mutation_return.value, mutation_return.log_lines, None)],
+                        ts: mutation_return.ts,
+                        committed_writes: mutation_return.committed_writes,
+                        read_set_size: None,
+                        occ_retries: mutation_retry_count,
+                        occ_retry_time,
+                        read_write_sets: None,
+                        dry_run_writes: None,
+                    }),
+                    Err(MutationError { error, log_lines }) => Err(MutationBatchError {
+                        index: 0,
+                        error,
+                        log_lines,
+                    }),
+                });
             }
 
-            let result: Result<(Transaction<RT>, ValidatedUdfOutcome), anyhow::Error> = self
-                .run_mutation_no_udf_log(
-                    tx,
-                    path.clone(),
-                    arguments.clone(),
-                    caller.allowed_visibility(),
-                    context.clone(),
-                    mutation_queue_length,
-                )
-                .await;
-            let (mut tx, mut outcome) = match result {
-                Ok(r) => r,
-                Err(e) => {
-                    self.function_log
-                        .log_mutation_system_error(
-                            &e,
-                            path.debug_into_component_path(),
-                            arguments,
-                            identity,
-                            start,
-                            caller,
+            let mut attempts: Vec<MutationAttempt> = Vec::with_capacity(mutations.len());
+            for (index, (path, arguments)) in mutations.iter().enumerate() {
+                // Track each mutation's usage separately so it's attributed to
+                // the right function.
+                let usage_tracker = if index == 0 {
+                    first_usage_tracker.clone()
+                } else {
+                    let usage_tracker = FunctionUsageTracker::new();
+                    tx.usage_tracker = usage_tracker.clone();
+                    usage_tracker
+                };
+
+                // Note that we use different context for every mutation attempt.
+                // This so every JS function run gets a different executionId.
+                let mut context = ExecutionContext::new(request_id.clone(), &caller);
+                context.rng_seed = rng_seed;
+                context.unix_timestamp = *self.pinned_unix_timestamp.lock();
+                context.min_log_level = min_log_level.clone();
+                self.memory_limits.apply(&caller, &mut context);
+                let (in_flight_guard, abort_registration) =
+                    self.in_flight_mutations.register(InFlightMutation {
+                        execution_id: context.execution_id,
+                        request_id: request_id.clone(),
+                        path: component_paths[index].clone(),
+                    });
+                pause_client.wait("in_flight_mutation_registered").await;
+
+                let start = self.runtime.monotonic_now();
+                let result: Result<(Transaction<RT>, ValidatedUdfOutcome), anyhow::Error> =
+                    Abortable::new(
+                        self.run_mutation_no_udf_log(
+                            tx,
+                            path.clone(),
+                            arguments.clone(),
+                            allowed_visibility,
                             context.clone(),
                             mutation_queue_length,
+                        ),
+                        abort_registration,
+                    )
+                    .await
+                    .unwrap_or_else(|Aborted| {
+                        Err(anyhow::anyhow!(ErrorMetadata::bad_request(
+                            "MutationAborted",
+                            "This mutation was aborted by an administrator before it committed.",
+                        )))
+                    });
+                // Past this point the attempt can no longer be aborted.
+                drop(in_flight_guard);
+                let (next_tx, outcome) = match result {
+                    Ok(r) => r,
+                    Err(e) => {
+                        self.log_rolled_back_mutations(
+                            attempts,
+                            index,
+                            &caller,
+                            mutation_queue_length,
                             mutation_retry_count,
                         )
-                        .await?;
-                    return Err(e);
-                },
-            };
+                        .await;
+                        self.function_log
+                            .log_mutation_system_error(
+                                &e,
+                                component_paths[index].clone(),
+                                arguments.clone(),
+                                inert_identity,
+                                start,
+                                caller,
+                                context,
+                                mutation_queue_length,
+                                mutation_retry_count,
+                            )
+                            .await?;
+                        return Err(e);
+                    },
+                };
+                tx = next_tx;
+                let stats = tx.take_stats();
+                let execution_time = start.elapsed();
+                // If it's an error inside the UDF, log the failed execution and
+                // return the developer error.
+                let value = match outcome.result {
+                    Ok(ref value) => value.clone(),
+                    Err(ref error) => {
+                        drop(tx);
+                        let error = error.clone();
+                        let log_lines = outcome.log_lines.clone();
+                        self.log_rolled_back_mutations(
+                            attempts,
+                            index,
+                            &caller,
+                            mutation_queue_length,
+                            mutation_retry_count,
+                        )
+                        .await;
+                        self.function_log
+                            .log_mutation(
+                                outcome,
+                                stats,
+                                execution_time,
+                                caller,
+                                usage_tracker,
+                                context,
+                                mutation_queue_length,
+                                mutation_retry_count,
+                            )
+                            .await;
+                        return Ok(Err(MutationBatchError {
+                            index,
+                            error,
+                            log_lines,
+                        }));
+                    },
+                };
+                attempts.push(MutationAttempt {
+                    value,
+                    outcome,
+                    stats,
+                    start,
+                    execution_time,
+                    usage_tracker,
+                    context,
+                });
+            }
 
             // Save a CommittedMutation object so we won't rerun this mutation if
             // successful.
-            self.write_mutation_status(&mut tx, &mutation_identifier, &outcome)
-                .await?;
+            if let [attempt] = &attempts[..] {
+                self.write_mutation_status(&mut tx, &mutation_identifier, &attempt.outcome)
+                    .await?;
+            }
 
-            let stats = tx.take_stats();
-            let execution_time = start.elapsed();
-            let log_lines = outcome.log_lines.clone();
-            let value = match outcome.result {
-                Ok(ref value) => value.clone(),
-                // If it's an error inside the UDF, log the failed execution and return the
-                // developer error.
-                Err(ref error) => {
-                    drop(tx);
+            let read_set_size = report_read_set_size.then(|| tx.read_set_size());
+            let read_write_sets =
+                cfg!(any(test, feature = "testing")).then(|| tx.read_write_sets());
+            let committed_writes = !dry_run && !tx.is_readonly();
+            let dry_run_writes = dry_run.then(|| Self::dry_run_writes(&mut tx)).transpose()?;
+            // Attempt to commit the transaction and log an error if commit failed,
+            // even if it was an OCC error. We may decide later to suppress OCC
+            // errors from the log.
+            let commit_result = if dry_run {
+                self.discard_dry_run(tx).await
+            } else {
+                self.database
+                    .commit_with_write_source(tx, udf_path_string.clone())
+                    .await
+            };
+            let e = match commit_result {
+                Ok(ts) => {
+                    let mut outcomes = Vec::with_capacity(attempts.len());
+                    for attempt in attempts {
+                        let timing = FunctionTiming::new(
+                            attempt.execution_time,
+                            &attempt.outcome.syscall_trace,
+                            attempt.outcome.permit_wait,
+                        );
+                        outcomes.push((
+                            attempt.value,
+                            attempt.outcome.log_lines.clone(),
+                            Some(timing),
+                        ));
+                        self.function_log
+                            .log_mutation(
+                                attempt.outcome,
+                                attempt.stats,
+                                attempt.execution_time,
+                                caller.clone(),
+                                attempt.usage_tracker,
+                                attempt.context,
+                                mutation_queue_length,
+                                mutation_retry_count,
+                            )
+                            .await;
+                    }
+                    self.finish_occ_retries(
+                        &occ_retry_observer,
+                        &component_paths,
+                        occ_retries,
+                        OccRetryOutcome::Committed,
+                    );
+                    return Ok(Ok(MutationsReturn {
+                        outcomes,
+                        ts,
+                        committed_writes,
+                        read_set_size,
+                        occ_retries: mutation_retry_count,
+                        occ_retry_time,
+                        read_write_sets,
+                        dry_run_writes,
+                    }));
+                },
+                Err(e) => e,
+            };
+
+            if e.is_deterministic_user_error() {
+                let js_error = JsError::from_error(e);
+                let index = attempts.len() - 1;
+                let log_lines = attempts[index].outcome.log_lines.clone();
+                for mut attempt in attempts {
+                    attempt.outcome.result = Err(js_error.clone());
                     self.function_log
                         .log_mutation(
-                            outcome.clone(),
-                            stats,
-                            execution_time,
-                            caller,
-                            usage_tracker,
-                            context.clone(),
+                            attempt.outcome,
+                            attempt.stats,
+                            attempt.execution_time,
+                            caller.clone(),
+                            attempt.usage_tracker,
+                            attempt.context,
                             mutation_queue_length,
                             mutation_retry_count,
                         )
                         .await;
-                    return Ok(Err(MutationError {
-                        error: error.to_owned(),
-                        log_lines,
-                    }));
-                },
-            };
+                }
+                self.finish_occ_retries(
+                    &occ_retry_observer,
+                    &component_paths,
+                    occ_retries,
+                    OccRetryOutcome::Failed,
+                );
+                return Ok(Err(MutationBatchError {
+                    index,
+                    error: js_error,
+                    log_lines,
+                }));
+            }
 
-            // Attempt to commit the transaction and log an error if commit failed,
-            // even if it was an OCC error. We may decide later to suppress OCC
-            // errors from the log.
-            let result = match self
-                .database
-                .commit_with_write_source(tx, udf_path_string.clone())
-                .await
+            if e.is_occ()
+                && let Some(observer) = &occ_retry_observer
             {
-                Ok(ts) => Ok(MutationReturn {
-                    value,
-                    log_lines,
-                    ts,
-                }),
-                Err(e) => {
-                    if e.is_deterministic_user_error() {
-                        let js_error = JsError::from_error(e);
-                        outcome.result = Err(js_error.clone());
-                        Err(MutationError {
-                            error: js_error,
-                            log_lines,
-                        })
-                    } else {
-                        if e.is_occ()
-                            && (backoff.failures() as usize) < *UDF_EXECUTOR_OCC_MAX_RETRIES
-                        {
-                            let sleep = backoff.fail(&mut self.runtime.rng());
-                            tracing::warn!(
-                                "Optimistic concurrency control failed ({e}), retrying \
-                                 {udf_path_string:?} after {sleep:?}",
-                            );
-                            self.runtime.wait(sleep).await;
-                            let (table_name, document_id, write_source) =
-                                e.occ_info().unwrap_or((None, None, None));
-                            self.function_log
-                                .log_mutation_occ_error(
-                                    outcome,
-                                    stats,
-                                    execution_time,
-                                    caller.clone(),
-                                    usage_tracker,
-                                    context.clone(),
-                                    OccInfo {
-                                        table_name,
-                                        document_id,
-                                        write_source,
-                                        retry_count: mutation_retry_count as u64,
-                                    },
-                                    mutation_queue_length,
-                                    mutation_retry_count,
-                                )
-                                .await;
-                            continue;
-                        }
-                        outcome.result = Err(JsError::from_error_ref(&e));
-
-                        if e.is_occ() {
-                            let (table_name, document_id, write_source) =
-                                e.occ_info().unwrap_or((None, None, None));
-                            self.function_log
-                                .log_mutation_occ_error(
-                                    outcome,
-                                    stats,
-                                    execution_time,
-                                    caller,
-                                    usage_tracker,
-                                    context.clone(),
-                                    OccInfo {
-                                        table_name,
-                                        document_id,
-                                        write_source,
-                                        retry_count: mutation_retry_count as u64,
-                                    },
-                                    mutation_queue_length,
-                                    mutation_retry_count,
-                                )
-                                .await;
-                        } else {
-                            self.function_log
-                                .log_mutation_system_error(
-                                    &e,
-                                    path.debug_into_component_path(),
-                                    arguments,
-                                    identity,
-                                    start,
-                                    caller,
-                                    context,
-                                    mutation_queue_length,
-                                    mutation_retry_count,
-                                )
-                                .await?;
-                        }
-                        log_occ_retries(backoff.failures() as usize);
-                        return Err(e);
-                    }
-                },
+                for component_path in &component_paths {
+                    observer.on_occ_error(component_path, occ_retries + 1);
+                }
+            }
+            let retry_sleep = e
+                .is_occ()
+                .then(|| {
+                    retry_policy.should_retry(
+                        occ_retries + 1,
+                        first_attempt_start.elapsed(),
+                        &mut self.runtime.rng(),
+                    )
+                })
+                .flatten();
+            if let Some(sleep) = retry_sleep {
+                occ_retries += 1;
+                tracing::warn!(
+                    "Optimistic concurrency control failed ({e}), retrying {udf_path_string:?} \
+                     after {sleep:?}",
+                );
+                self.runtime.wait(sleep).await;
+            }
+
+            let occ_info = e.occ_info();
+            for (index, mut attempt) in attempts.into_iter().enumerate() {
+                if retry_sleep.is_none() {
+                    attempt.outcome.result = Err(JsError::from_error_ref(&e));
+                }
+                match &occ_info {
+                    Some(occ_info) => {
+                        self.function_log
+                            .log_mutation_occ_error(
+                                attempt.outcome,
+                                attempt.stats,
+                                attempt.execution_time,
+                                caller.clone(),
+                                attempt.usage_tracker,
+                                attempt.context,
+                                OccInfo {
+                                    table_name: occ_info.table_name.clone(),
+                                    document_id: occ_info.document_id.clone(),
+                                    write_source: occ_info.write_source.clone(),
+                                    retry_count: mutation_retry_count as u64,
+                                },
+                                mutation_queue_length,
+                                mutation_retry_count,
+                            )
+                            .await;
+                    },
+                    None => {
+                        self.function_log
+                            .log_mutation_system_error(
+                                &e,
+                                component_paths[index].clone(),
+                                mutations[index].1.clone(),
+                                inert_identity.clone(),
+                                attempt.start,
+                                caller.clone(),
+                                attempt.context,
+                                mutation_queue_length,
+                                mutation_retry_count,
+                            )
+                            .await?;
+                    },
+                }
+            }
+            if retry_sleep.is_some() {
+                continue;
+            }
+
+            let outcome = if e.is_occ() {
+                OccRetryOutcome::GaveUp
+            } else {
+                OccRetryOutcome::Failed
             };
+            self.finish_occ_retries(&occ_retry_observer, &component_paths, occ_retries, outcome);
+            if e.is_occ() {
+                let stats = OccRetriesExhausted::new(occ_retries, occ_retry_time, &e);
+                return Err(e.context(stats));
+            }
+            return Err(e);
+        }
+    }
 
+    /// Logs the mutations in a batch that ran before the one at `index`
+    /// failed. Their writes were rolled back along with the failed one's.
+    async fn log_rolled_back_mutations(
+        &self,
+        attempts: Vec<MutationAttempt>,
+        index: usize,
+        caller: &FunctionCaller,
+        mutation_queue_length: Option<usize>,
+        mutation_retry_count: usize,
+    ) {
+        for mut attempt in attempts {
+            attempt.outcome.result = Err(JsError::from_message(format!(
+                "Rolled back because mutation {index} in the same batch failed"
+            )));
             self.function_log
                 .log_mutation(
-                    outcome.clone(),
-                    stats,
-                    execution_time,
-                    caller,
-                    usage_tracker,
-                    context.clone(),
+                    attempt.outcome,
+                    attempt.stats,
+                    attempt.execution_time,
+                    caller.clone(),
+                    attempt.usage_tracker,
+                    attempt.context,
                     mutation_queue_length,
                     mutation_retry_count,
                 )
                 .await;
-            log_occ_retries(backoff.failures() as usize);
-            return Ok(result);
         }
     }
 
+    fn finish_occ_retries(
+        &self,
+        occ_retry_observer: &Option<Arc<dyn OccRetryObserver>>,
+        component_paths: &[CanonicalizedComponentFunctionPath],
+        occ_retries: usize,
+        outcome: OccRetryOutcome,
+    ) {
+        if let Some(observer) = occ_retry_observer
+            && (occ_retries > 0 || outcome == OccRetryOutcome::GaveUp)
+        {
+            for component_path in component_paths {
+                observer.on_finished(component_path, occ_retries + 1, outcome);
+            }
+        }
+        log_occ_retries(occ_retries);
+    }
+
+    /// Drops `tx`'s writes in place of committing them. Fails with an OCC
+    /// error if any of its reads have changed since it began, and otherwise
+    /// returns its begin timestamp.
+    async fn discard_dry_run(&self, tx: Transaction<RT>) -> anyhow::Result<Timestamp> {
+        let begin_ts = *tx.begin_timestamp();
+        let (reads, _) = tx.into_reads_and_writes();
+        let token = Token::new(Arc::new(reads.into_read_set()), begin_ts);
+        let latest_ts = *self.database.now_ts_for_reads();
+        if self
+            .database
+            .refresh_token(token, latest_ts)
+            .await?
+            .is_err()
+        {
+            anyhow::bail!(ErrorMetadata::user_occ(None, None, None, None));
+        }
+        Ok(begin_ts)
+    }
+
+    fn dry_run_writes(tx: &mut Transaction<RT>) -> anyhow::Result<Vec<DryRunWrite>> {
+        let writes: Vec<_> = tx
+            .writes()
+            .coalesced_writes()
+            .map(|(id, update)| {
+                let old_document = update.old_document.as_ref().map(|(d, _)| d.clone());
+                (*id, old_document, update.new_document.clone())
+            })
+            .collect();
+        let table_mapping = tx.table_mapping();
+        let mut result = Vec::with_capacity(writes.len());
+        for (id, old_document, new_document) in writes {
+            let kind = match (&old_document, &new_document) {
+                (None, Some(_)) => DryRunWriteKind::Insert,
+                (Some(_), Some(_)) => DryRunWriteKind::Replace,
+                (Some(_), None) => DryRunWriteKind::Delete,
+                // Inserted and then deleted within the transaction.
+                (None, None) => continue,
+            };
+            let table_name = table_mapping.tablet_name(id.tablet_id)?;
+            // Leave out bookkeeping like creating a table on its first insert,
+            // but keep scheduled functions, which callers asked to see.
+            if table_name.is_system() && table_name != *SCHEDULED_JOBS_TABLE {
+                continue;
+            }
+            result.push(DryRunWrite {
+                table_name,
+                document_id: id.developer_id,
+                kind,
+                old_value: old_document.map(|d| d.export(ValueFormat::ConvexCleanJSON)),
+                new_value: new_document.map(|d| d.export(ValueFormat::ConvexCleanJSON)),
+            });
+        }
+        Ok(result)
+    }
+
     /// Attempts to run a mutation once using the given transaction.
     /// The method is not idempotent. It is the caller responsibility to
     /// drive retries as we as log in the UDF log.
@@ -1098,6 +1686,8 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
         arguments: Vec<JsonValue>,
         identity: Identity,
         caller: FunctionCaller,
+        min_log_level: Option<LogLevel>,
+        cancellation: impl Future<Output = ()>,
     ) -> anyhow::Result<Result<ActionReturn, ActionError>> {
         if path.is_system() && !(identity.is_admin() || identity.is_system()) {
             anyhow::bail!(unauthorized_error("action"));
@@ -1111,19 +1701,47 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
                 }))
             },
         };
-        let context = ExecutionContext::new(request_id.clone(), &caller);
+        let mut context = ExecutionContext::new(request_id.clone(), &caller);
+        context.unix_timestamp = *self.pinned_unix_timestamp.lock();
+        context.min_log_level = min_log_level;
+        self.memory_limits.apply(&caller, &mut context);
         let usage_tracking = FunctionUsageTracker::new();
         let start = self.runtime.monotonic_now();
-        let completion_result = self
-            .run_action_no_udf_log(
-                path.clone(),
-                arguments.clone(),
-                identity.clone(),
-                caller.clone(),
-                usage_tracking.clone(),
-                context.clone(),
-            )
-            .await;
+        let completion_result = select_biased! {
+            result = self
+                .run_action_no_udf_log(
+                    path.clone(),
+                    arguments.clone(),
+                    identity.clone(),
+                    caller.clone(),
+                    usage_tracking.clone(),
+                    context.clone(),
+                )
+                .fuse() => result,
+            // Dropping the action's future drops the isolate's response
+            // channel, which terminates the action in the isolate.
+            _ = cancellation.fuse() => {
+                let error = JsError::from_message(
+                    "The action was cancelled before it finished running".to_string(),
+                );
+                self.function_log
+                    .log_action_cancelled(
+                        error.clone(),
+                        path.debug_into_component_path(),
+                        arguments,
+                        identity.into(),
+                        start,
+                        caller,
+                        context,
+                        usage_tracking,
+                    )
+                    .await;
+                return Ok(Err(ActionError {
+                    error,
+                    log_lines: vec![].into(),
+                }));
+            },
+        };
         let completion = match completion_result {
             Ok(c) => c,
             Err(e) => {
@@ -1144,6 +1762,11 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
         };
         let log_lines = completion.log_lines().clone();
         let result = completion.outcome.result.clone();
+        let timing = FunctionTiming::for_action(
+            completion.execution_time,
+            &completion.outcome.syscall_trace,
+            completion.outcome.async_op_wait,
+        );
         self.function_log
             .log_action(completion, usage_tracking)
             .await;
@@ -1155,7 +1778,11 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
             Err(error) => return Ok(Err(ActionError { error, log_lines })),
         };
 
-        Ok(Ok(ActionReturn { value, log_lines }))
+        Ok(Ok(ActionReturn {
+            value,
+            log_lines,
+            timing,
+        }))
     }
 
     /// Runs the actions without logging to the UDF log. It is the caller
@@ -1347,8 +1974,9 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
                     .into_value();
                 let mut environment_variables =
                     system_env_vars(&mut tx, self.default_system_env_vars.clone()).await?;
-                let user_environment_variables =
-                    EnvironmentVariablesModel::new(&mut tx).get_all().await?;
+                let user_environment_variables = EnvironmentVariablesModel::new(&mut tx)
+                    .get_all_for_function(&path.udf_path)
+                    .await?;
                 environment_variables.extend(user_environment_variables);
 
                 // Fetch source and external_deps presigned URI first
@@ -1441,6 +2069,7 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
                         unix_timestamp,
                         result: node_outcome.result.map(JsonPackedValue::pack),
                         syscall_trace: node_outcome.syscall_trace,
+                        async_op_wait: Duration::ZERO,
                         udf_server_version,
                     };
                     let outcome =
@@ -1761,7 +2390,7 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
         &self,
         request_id: RequestId,
         path: PublicFunctionPath,
-        args: Vec<JsonValue>,
+        args: UdfArgs,
         identity: Identity,
         ts: Timestamp,
         journal: Option<QueryJournal>,
@@ -1794,7 +2423,7 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
         &self,
         request_id: RequestId,
         path: PublicFunctionPath,
-        args: Vec<JsonValue>,
+        args: UdfArgs,
         identity: Identity,
         ts: Timestamp,
         journal: Option<QueryJournal>,
@@ -1803,7 +2432,7 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
         if path.is_system() && !(identity.is_admin() || identity.is_system()) {
             anyhow::bail!(unauthorized_error("query"));
         }
-        let args = match parse_udf_args(path.udf_path(), args) {
+        let args = match args.into_convex_array(path.udf_path()) {
             Ok(arguments) => arguments,
             Err(js_error) => {
                 return Ok(QueryReturn {
@@ -1811,6 +2440,8 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
                     log_lines: vec![].into(),
                     token: Token::empty(ts),
                     journal: QueryJournal::new(),
+                    timing: None,
+                    index_reads: None,
                 });
             },
         };
@@ -1856,6 +2487,16 @@ impl<RT: Runtime> ApplicationFunctionRunner<RT> {
                     value: result,
                     log_lines,
                     ts,
+                    // Saving the outcome was a write, so the earlier run
+                    // committed at `ts` even if the mutation itself wrote
+                    // nothing.
+                    committed_writes: true,
+                    read_set_size: None,
+                    occ_retries: 0,
+                    occ_retry_time: Duration::ZERO,
+                    read_write_sets: None,
+                    timing: None,
+                    dry_run_writes: None,
                 })
             },
             None => return Ok(None),
@@ -1917,7 +2558,7 @@ impl<RT: Runtime> ActionCallbacks for ApplicationFunctionRunner<RT> {
             .run_query_at_ts(
                 context.request_id,
                 PublicFunctionPath::Component(path),
-                args.into_args()?,
+                args.into_args()?.into(),
                 identity,
                 *ts,
                 None,
@@ -1943,7 +2584,7 @@ impl<RT: Runtime> ActionCallbacks for ApplicationFunctionRunner<RT> {
             .retry_mutation(
                 context.request_id,
                 PublicFunctionPath::Component(path),
-                args.into_args()?,
+                args.into_args()?.into(),
                 identity,
                 None,
                 FunctionCaller::Action {
@@ -1951,6 +2592,8 @@ impl<RT: Runtime> ActionCallbacks for ApplicationFunctionRunner<RT> {
                     parent_execution_id: Some(context.execution_id),
                 },
                 None,
+                MutationOptions::default(),
+                false,
             )
             .await
             .map(|r| match r {
@@ -1979,6 +2622,9 @@ impl<RT: Runtime> ActionCallbacks for ApplicationFunctionRunner<RT> {
                     parent_scheduled_job: context.parent_scheduled_job,
                     parent_execution_id: Some(context.execution_id),
                 },
+                // Actions called from an action log at the same level.
+                context.min_log_level,
+                future::pending(),
             )
             .await
             .map(|r| match r {
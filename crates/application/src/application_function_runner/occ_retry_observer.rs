@@ -0,0 +1,30 @@
+//! Hooks for watching mutations retry after OCC errors, e.g. to export
+//! per-function retry counts to a metrics backend.
+
+use common::components::CanonicalizedComponentFunctionPath;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OccRetryOutcome {
+    /// A later attempt committed.
+    Committed,
+    /// The retry policy gave up after an OCC error.
+    GaveUp,
+    /// A later attempt failed with some other error.
+    Failed,
+}
+
+pub trait OccRetryObserver: Send + Sync {
+    /// Called each time attempt number `attempt` (starting at 1) of the
+    /// mutation at `path` fails to commit with an OCC error, whether or not
+    /// it's retried.
+    fn on_occ_error(&self, path: &CanonicalizedComponentFunctionPath, attempt: usize);
+
+    /// Called once a mutation that hit at least one OCC error stops retrying,
+    /// after `attempts` attempts in total.
+    fn on_finished(
+        &self,
+        path: &CanonicalizedComponentFunctionPath,
+        attempts: usize,
+        outcome: OccRetryOutcome,
+    );
+}
@@ -0,0 +1,148 @@
+//! Decides whether, and after how long, a mutation is retried after an OCC
+//! error.
+//!
+//! Policies can be attached to a single function or to every mutation run by
+//! matching callers. A function's policy takes precedence over a caller's, and
+//! mutations with neither use [`DefaultRetryPolicy`], which follows the
+//! `UDF_EXECUTOR_OCC_*` knobs. A single call can override all of these by
+//! passing an [`OccRetryPolicy`].
+
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::Duration,
+};
+
+use common::{
+    backoff::Backoff,
+    components::CanonicalizedComponentFunctionPath,
+    knobs::{
+        DATABASE_UDF_SYSTEM_TIMEOUT,
+        UDF_EXECUTOR_OCC_INITIAL_BACKOFF,
+        UDF_EXECUTOR_OCC_MAX_BACKOFF,
+        UDF_EXECUTOR_OCC_MAX_RETRIES,
+    },
+    types::FunctionCaller,
+};
+use parking_lot::Mutex;
+use rand::RngCore;
+
+pub trait RetryPolicy: Send + Sync {
+    /// Called when attempt number `attempt` (starting at 1) fails with an OCC
+    /// error, `elapsed` after the first attempt started. Returns how long to
+    /// wait before the next attempt, or `None` to give up.
+    fn should_retry(
+        &self,
+        attempt: usize,
+        elapsed: Duration,
+        rng: &mut dyn RngCore,
+    ) -> Option<Duration>;
+}
+
+/// Jittered exponential backoff after attempt number `attempt` fails, following
+/// the `UDF_EXECUTOR_OCC_*_BACKOFF` knobs.
+fn occ_backoff(attempt: usize, mut rng: &mut dyn RngCore) -> Duration {
+    let mut backoff = Backoff::new(
+        *UDF_EXECUTOR_OCC_INITIAL_BACKOFF,
+        *UDF_EXECUTOR_OCC_MAX_BACKOFF,
+    );
+    backoff.set_failures(attempt as u32 - 1);
+    backoff.fail(&mut rng)
+}
+
+/// Retries up to `UDF_EXECUTOR_OCC_MAX_RETRIES` times with jittered
+/// exponential backoff, giving up early rather than sleeping past the deadline
+/// we'd give a single attempt.
+pub struct DefaultRetryPolicy;
+
+impl RetryPolicy for DefaultRetryPolicy {
+    fn should_retry(
+        &self,
+        attempt: usize,
+        elapsed: Duration,
+        rng: &mut dyn RngCore,
+    ) -> Option<Duration> {
+        if attempt > *UDF_EXECUTOR_OCC_MAX_RETRIES {
+            return None;
+        }
+        let sleep = occ_backoff(attempt, rng);
+        (elapsed + sleep < *DATABASE_UDF_SYSTEM_TIMEOUT).then_some(sleep)
+    }
+}
+
+/// Limits on retrying a single mutation call, overriding any policy set for
+/// its function or caller. Backoff between attempts is the same as
+/// [`DefaultRetryPolicy`]'s.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OccRetryPolicy {
+    /// Total attempts, including the first. `1` disables retries.
+    pub max_attempts: usize,
+    /// Don't start another attempt more than this long after the first one
+    /// started.
+    pub max_total_duration: Duration,
+}
+
+impl RetryPolicy for OccRetryPolicy {
+    fn should_retry(
+        &self,
+        attempt: usize,
+        elapsed: Duration,
+        rng: &mut dyn RngCore,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        let sleep = occ_backoff(attempt, rng);
+        (elapsed + sleep < self.max_total_duration).then_some(sleep)
+    }
+}
+
+type CallerMatcher = fn(&FunctionCaller) -> bool;
+
+#[derive(Clone, Default)]
+pub struct RetryPolicies {
+    inner: Arc<Mutex<RetryPoliciesInner>>,
+}
+
+#[derive(Default)]
+struct RetryPoliciesInner {
+    by_function: BTreeMap<CanonicalizedComponentFunctionPath, Arc<dyn RetryPolicy>>,
+    by_caller: Vec<(CallerMatcher, Arc<dyn RetryPolicy>)>,
+}
+
+impl RetryPolicies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_for_function(
+        &self,
+        path: CanonicalizedComponentFunctionPath,
+        policy: Arc<dyn RetryPolicy>,
+    ) {
+        self.inner.lock().by_function.insert(path, policy);
+    }
+
+    /// Later registrations take precedence when several match a caller.
+    pub fn set_for_caller(&self, matches: CallerMatcher, policy: Arc<dyn RetryPolicy>) {
+        self.inner.lock().by_caller.push((matches, policy));
+    }
+
+    pub fn policy_for(
+        &self,
+        path: &CanonicalizedComponentFunctionPath,
+        caller: &FunctionCaller,
+    ) -> Arc<dyn RetryPolicy> {
+        let inner = self.inner.lock();
+        if let Some(policy) = inner.by_function.get(path) {
+            return policy.clone();
+        }
+        inner
+            .by_caller
+            .iter()
+            .rev()
+            .find(|(matches, _)| matches(caller))
+            .map(|(_, policy)| policy.clone())
+            .unwrap_or_else(|| Arc::new(DefaultRetryPolicy))
+    }
+}
@@ -31,10 +31,15 @@ use common::{
         DATABASE_UDF_USER_TIMEOUT,
     },
     query_journal::QueryJournal,
-    runtime::Runtime,
+    runtime::{
+        Runtime,
+        UnixTimestamp,
+    },
     types::{
         AllowedVisibility,
         FunctionCaller,
+        IndexName,
+        IndexReadStats,
         TableName,
         TableStats,
         Timestamp,
@@ -92,8 +97,12 @@ use value::{
 };
 
 use crate::{
-    application_function_runner::FunctionRouter,
+    application_function_runner::{
+        CallerMemoryLimits,
+        FunctionRouter,
+    },
     function_log::FunctionExecutionLog,
+    FunctionTiming,
     QueryReturn,
 };
 
@@ -114,6 +123,8 @@ pub struct CacheManager<RT: Runtime> {
     database: Database<RT>,
     function_router: FunctionRouter<RT>,
     udf_execution: FunctionExecutionLog<RT>,
+    pinned_unix_timestamp: Arc<Mutex<Option<UnixTimestamp>>>,
+    memory_limits: CallerMemoryLimits,
 
     instance_id: InstanceId,
     cache: QueryCache,
@@ -301,6 +312,8 @@ impl<RT: Runtime> CacheManager<RT> {
         database: Database<RT>,
         function_router: FunctionRouter<RT>,
         udf_execution: FunctionExecutionLog<RT>,
+        pinned_unix_timestamp: Arc<Mutex<Option<UnixTimestamp>>>,
+        memory_limits: CallerMemoryLimits,
         cache: QueryCache,
     ) -> Self {
         // each `CacheManager` (for a different instance) gets its own cache key space
@@ -311,6 +324,8 @@ impl<RT: Runtime> CacheManager<RT> {
             database,
             function_router,
             udf_execution,
+            pinned_unix_timestamp,
+            memory_limits,
             instance_id,
             cache,
         }
@@ -381,7 +396,9 @@ impl<RT: Runtime> CacheManager<RT> {
             journal: journal.unwrap_or_else(QueryJournal::new),
             allowed_visibility: caller.allowed_visibility(),
         };
-        let context = ExecutionContext::new(request_id, &caller);
+        let mut context = ExecutionContext::new(request_id, &caller);
+        context.unix_timestamp = *self.pinned_unix_timestamp.lock();
+        self.memory_limits.apply(&caller, &mut context);
         // If the query exists at some cache key, but the cached entry is invalid,
         // create a Waiting entry at that key, even if it's not the most precise for the
         // request. e.g. if the query was cached with identity:None, create a
@@ -443,7 +460,7 @@ impl<RT: Runtime> CacheManager<RT> {
                 // We are executing ourselves.
                 CacheOp::Go { .. } => false,
             };
-            let (result, table_stats) = match self
+            let (result, table_stats, index_reads) = match self
                 .perform_cache_op(&requested_key, &stored_key, op, usage_tracker.clone())
                 .await?
             {
@@ -496,6 +513,14 @@ impl<RT: Runtime> CacheManager<RT> {
                 log_lines: cache_result.outcome.log_lines.clone(),
                 token: cache_result.token,
                 journal: cache_result.outcome.journal.clone(),
+                timing: (!is_cache_hit).then(|| {
+                    FunctionTiming::new(
+                        start.elapsed(),
+                        &cache_result.outcome.syscall_trace,
+                        cache_result.outcome.permit_wait,
+                    )
+                }),
+                index_reads: (!is_cache_hit).then_some(index_reads),
             };
             return Ok((result, is_cache_hit));
         }
@@ -508,7 +533,13 @@ impl<RT: Runtime> CacheManager<RT> {
         key: &StoredCacheKey,
         op: CacheOp<'_>,
         usage_tracker: FunctionUsageTracker,
-    ) -> anyhow::Result<Option<(CacheResult, BTreeMap<TableName, TableStats>)>> {
+    ) -> anyhow::Result<
+        Option<(
+            CacheResult,
+            BTreeMap<TableName, TableStats>,
+            BTreeMap<IndexName, IndexReadStats>,
+        )>,
+    > {
         let pause_client = self.rt.pause_client();
         pause_client.wait("perform_cache_op").await;
         let r = match op {
@@ -516,7 +547,7 @@ impl<RT: Runtime> CacheManager<RT> {
                 if result.outcome.result.is_err() {
                     panic!("Developer error: Cache contained failed execution for {key:?}")
                 }
-                (result, BTreeMap::new())
+                (result, BTreeMap::new(), BTreeMap::new())
             },
             CacheOp::Wait {
                 waiting_entry_id,
@@ -546,7 +577,7 @@ impl<RT: Runtime> CacheManager<RT> {
                 if result.outcome.result.is_err() {
                     panic!("Developer error: CacheOp::Go sent failed execution for {key:?}")
                 }
-                (result, BTreeMap::new())
+                (result, BTreeMap::new(), BTreeMap::new())
             },
             CacheOp::Go {
                 waiting_entry_id: _,
@@ -621,6 +652,7 @@ impl<RT: Runtime> CacheManager<RT> {
                 };
                 let ts = tx.begin_timestamp();
                 let table_stats = tx.take_stats();
+                let index_reads = tx.take_index_read_stats();
                 let token = tx.into_token()?;
                 let result = CacheResult {
                     outcome: Arc::new(query_outcome),
@@ -638,7 +670,7 @@ impl<RT: Runtime> CacheManager<RT> {
                     drop(sender);
                 }
                 log_perform_go(result.outcome.result.is_ok());
-                (result, table_stats)
+                (result, table_stats, index_reads)
             },
         };
         Ok(Some(r))
@@ -671,7 +703,10 @@ impl<RT: Runtime> CacheManager<RT> {
             },
         };
         if result.outcome.observed_time {
-            let sys_now = self.rt.unix_timestamp();
+            let sys_now = self
+                .pinned_unix_timestamp
+                .lock()
+                .unwrap_or_else(|| self.rt.unix_timestamp());
             let cached_time = result.outcome.unix_timestamp;
             match sys_now.checked_sub(cached_time) {
                 Some(entry_age) if entry_age > *MAX_CACHE_AGE => {
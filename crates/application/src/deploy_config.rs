@@ -268,6 +268,7 @@ impl<RT: Runtime> Application<RT> {
         app: &CheckedComponent,
         evaluated_components: &BTreeMap<ComponentDefinitionPath, EvaluatedComponentDefinition>,
     ) -> anyhow::Result<SchemaChange> {
+        let _schema_lock = self.database.lock_schema_for_change().await?;
         // Even in dry run mode, we need to commit the schema changes so that
         // wait_for_schema can validate the schema against existing data.
         let (_ts, schema_change) = self
@@ -591,6 +592,7 @@ impl<RT: Runtime> Application<RT> {
             definition.definition.exports = BTreeMap::new();
         }
 
+        let _schema_lock = self.database.lock_schema_for_change().await?;
         let diff = self
             .execute_with_audit_log_events_and_occ_retries(identity.clone(), "finish_push", |tx| {
                 let start_push = &start_push;
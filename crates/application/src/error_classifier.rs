@@ -0,0 +1,110 @@
+//! Classifies function failures so operators can route them to different
+//! alert channels.
+//!
+//! Every failed query, mutation, or action is passed to the application's
+//! [`ErrorClassifier`], and the resulting [`ErrorClassification`] is logged as
+//! a metric labeled by category and severity.
+
+use common::{
+    errors::JsError,
+    types::UdfType,
+};
+use errors::ErrorMetadataAnyhowExt;
+use strum::IntoStaticStr;
+
+use crate::metrics::log_function_failure;
+
+/// A failure from running a function.
+#[derive(Clone, Copy, Debug)]
+pub enum FunctionFailure<'a> {
+    /// The function threw, or hit an error the developer is responsible for.
+    User(&'a JsError),
+    /// The function couldn't run to completion because of a system error.
+    System(&'a anyhow::Error),
+}
+
+#[derive(IntoStaticStr, Clone, Copy, Debug, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+pub enum ErrorCategory {
+    UserThrown,
+    OccExhausted,
+    Overloaded,
+    Internal,
+}
+
+#[derive(IntoStaticStr, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[strum(serialize_all = "snake_case")]
+pub enum ErrorSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ErrorClassification {
+    pub category: ErrorCategory,
+    pub severity: ErrorSeverity,
+}
+
+pub trait ErrorClassifier: Send + Sync {
+    fn classify(&self, failure: FunctionFailure<'_>) -> ErrorClassification;
+}
+
+/// Treats developer errors as informational, expected system errors (OCCs
+/// after exhausting retries, overload) as warnings, and everything else as an
+/// internal error.
+pub struct DefaultErrorClassifier;
+
+impl ErrorClassifier for DefaultErrorClassifier {
+    fn classify(&self, failure: FunctionFailure<'_>) -> ErrorClassification {
+        let (category, severity) = match failure {
+            FunctionFailure::User(_) => (ErrorCategory::UserThrown, ErrorSeverity::Info),
+            FunctionFailure::System(e) if e.is_deterministic_user_error() => {
+                (ErrorCategory::UserThrown, ErrorSeverity::Info)
+            },
+            FunctionFailure::System(e) if e.is_occ() => {
+                (ErrorCategory::OccExhausted, ErrorSeverity::Warning)
+            },
+            FunctionFailure::System(e) if e.is_overloaded() => {
+                (ErrorCategory::Overloaded, ErrorSeverity::Warning)
+            },
+            FunctionFailure::System(_) => (ErrorCategory::Internal, ErrorSeverity::Error),
+        };
+        ErrorClassification { category, severity }
+    }
+}
+
+pub(crate) fn classify_and_log(
+    classifier: &dyn ErrorClassifier,
+    udf_type: UdfType,
+    failure: FunctionFailure<'_>,
+) -> ErrorClassification {
+    let classification = classifier.classify(failure);
+    log_function_failure(udf_type, classification);
+    classification
+}
+
+#[cfg(test)]
+mod tests {
+    use errors::ErrorMetadata;
+
+    use super::*;
+
+    #[test]
+    fn test_occ_exhaustion_and_user_errors_are_classified_differently() {
+        let occ = anyhow::anyhow!("retries exhausted")
+            .context(ErrorMetadata::user_occ(None, None, None, None));
+        let occ = DefaultErrorClassifier.classify(FunctionFailure::System(&occ));
+        assert_eq!(occ.category, ErrorCategory::OccExhausted);
+
+        let thrown = JsError::from_message("Uncaught Error: oops".to_string());
+        let thrown = DefaultErrorClassifier.classify(FunctionFailure::User(&thrown));
+        assert_eq!(thrown.category, ErrorCategory::UserThrown);
+        assert!(thrown.severity < occ.severity);
+
+        let internal = anyhow::anyhow!("something broke");
+        let internal = DefaultErrorClassifier.classify(FunctionFailure::System(&internal));
+        assert_eq!(internal.category, ErrorCategory::Internal);
+        assert_eq!(internal.severity, ErrorSeverity::Error);
+    }
+}
@@ -1,7 +1,9 @@
 use std::{
     cell::Cell,
+    cmp::Reverse,
     collections::{
         BTreeMap,
+        BTreeSet,
         HashMap,
         VecDeque,
     },
@@ -878,6 +880,44 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
         Ok(())
     }
 
+    /// Log an action that was cancelled before it finished, counting the
+    /// usage it accrued until then.
+    pub async fn log_action_cancelled(
+        &self,
+        error: JsError,
+        path: CanonicalizedComponentFunctionPath,
+        arguments: ConvexArray,
+        identity: InertIdentity,
+        start: tokio::time::Instant,
+        caller: FunctionCaller,
+        context: ExecutionContext,
+        usage: FunctionUsageTracker,
+    ) {
+        let unix_timestamp = self.rt.unix_timestamp();
+        let completion = ActionCompletion {
+            outcome: ValidatedActionOutcome {
+                path,
+                arguments,
+                identity,
+                unix_timestamp,
+                result: Err(error),
+                syscall_trace: SyscallTrace::new(),
+                async_op_wait: Duration::ZERO,
+                udf_server_version: None,
+                mutation_queue_length: None,
+            },
+            execution_time: start.elapsed(),
+            // The action never reported which environment it ran in.
+            environment: ModuleEnvironment::Invalid,
+            memory_in_mb: 0,
+            context,
+            unix_timestamp,
+            caller,
+            log_lines: vec![].into(),
+        };
+        self._log_action(completion, TrackUsage::Track(usage)).await;
+    }
+
     async fn _log_action(&self, completion: ActionCompletion, usage: TrackUsage) {
         let outcome = completion.outcome;
         let log_lines = completion.log_lines;
@@ -1392,6 +1432,43 @@ impl<RT: Runtime> FunctionExecutionLog<RT> {
         (Some(summary), new_cursor)
     }
 
+    /// Summarize OCC conflicts among the mutations logged in the last
+    /// `window`, reporting the `k` functions with the most conflicts. Only
+    /// executions still in the in-memory log are counted.
+    pub fn occ_stats(&self, window: Duration, k: usize) -> OccStats {
+        let start = self.rt.unix_timestamp() - window;
+        let inner = self.inner.lock();
+        let mut stats = OccStats::default();
+        let mut mutations = BTreeSet::new();
+        let mut conflicts_by_function = BTreeMap::new();
+        for (_, entry) in inner.log.iter() {
+            let FunctionExecutionPart::Completion(entry) = entry else {
+                continue;
+            };
+            if entry.udf_type != UdfType::Mutation || entry.unix_timestamp < start {
+                continue;
+            }
+            let UdfParams::Function { identifier, .. } = &entry.params else {
+                continue;
+            };
+            // Every attempt of a mutation is logged with the same execution
+            // id, so this counts retries of a mutation once.
+            mutations.insert(entry.context.execution_id);
+            stats.attempts += 1;
+            if entry.occ_info.is_some() {
+                stats.conflicts += 1;
+                *conflicts_by_function.entry(identifier.clone()).or_insert(0) += 1;
+            }
+        }
+        stats.mutations = mutations.len();
+        stats.top_conflicting_functions = conflicts_by_function
+            .into_iter()
+            .sorted_by_key(|(_, conflicts)| Reverse(*conflicts))
+            .take(k)
+            .collect();
+        stats
+    }
+
     pub async fn stream(&self, cursor: CursorMs) -> (Vec<FunctionExecution>, CursorMs) {
         loop {
             let rx = {
@@ -1758,6 +1835,36 @@ fn signed_duration_since(t1: SystemTime, t2: SystemTime) -> f32 {
     }
 }
 
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OccStats {
+    /// Mutations executed, counting all attempts of a mutation once.
+    pub mutations: usize,
+    /// Mutation attempts, including retries.
+    pub attempts: usize,
+    /// Attempts that failed with an OCC conflict.
+    pub conflicts: usize,
+    /// Functions with the most conflicts, most conflicting first.
+    pub top_conflicting_functions: Vec<(CanonicalizedComponentFunctionPath, usize)>,
+}
+
+impl OccStats {
+    /// Fraction of mutation attempts that hit an OCC conflict.
+    pub fn conflict_rate(&self) -> f64 {
+        if self.attempts == 0 {
+            return 0.0;
+        }
+        self.conflicts as f64 / self.attempts as f64
+    }
+
+    /// Average number of retries each mutation needed.
+    pub fn retries_per_mutation(&self) -> f64 {
+        if self.mutations == 0 {
+            return 0.0;
+        }
+        (self.attempts - self.mutations) as f64 / self.mutations as f64
+    }
+}
+
 #[derive(Default)]
 pub struct UdfMetricSummary {
     // Aggregated metrics for backwards compatibility.
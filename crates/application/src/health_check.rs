@@ -0,0 +1,89 @@
+use common::{
+    components::{
+        CanonicalizedComponentFunctionPath,
+        ComponentPath,
+    },
+    knobs::{
+        HEALTH_CHECK_DEGRADED_THRESHOLD,
+        HEALTH_CHECK_TIMEOUT,
+    },
+    runtime::Runtime,
+    types::FunctionCaller,
+    version::ClientVersion,
+    RequestId,
+};
+use futures::{
+    select_biased,
+    FutureExt,
+};
+use keybroker::Identity;
+use value::{
+    ConvexArray,
+    ConvexObject,
+    ConvexValue,
+};
+
+use crate::Application;
+
+/// The probe query run by [`Application::health_check`]. It's a system UDF
+/// that reads a single document, so running it exercises the isolate, the
+/// function runner and the database read path.
+const HEALTH_CHECK_QUERY: &str = "_system/frontend/getVersion";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HealthStatus {
+    /// The probe query succeeded within `HEALTH_CHECK_DEGRADED_THRESHOLD`.
+    Ok,
+    /// The probe query failed, or succeeded but was slow.
+    Degraded,
+    /// The probe query didn't finish within `HEALTH_CHECK_TIMEOUT`.
+    Timeout,
+}
+
+impl<RT: Runtime> Application<RT> {
+    /// Run a tiny query end-to-end, bypassing the query cache, and report
+    /// how it went. Intended for readiness probes.
+    pub async fn health_check(&self) -> HealthStatus {
+        let start = self.runtime.monotonic_now();
+        let probe = async {
+            let path = CanonicalizedComponentFunctionPath {
+                component: ComponentPath::root(),
+                udf_path: HEALTH_CHECK_QUERY.parse()?,
+            };
+            let arguments =
+                ConvexArray::try_from(vec![ConvexValue::Object(ConvexObject::empty())])?;
+            let tx = self.begin(Identity::system()).await?;
+            let (result, _) = self
+                .runner
+                .run_query_without_caching(
+                    RequestId::new(),
+                    tx,
+                    path,
+                    arguments,
+                    FunctionCaller::HttpApi(ClientVersion::unknown()),
+                )
+                .await?;
+            if let Err(e) = result {
+                anyhow::bail!("Health check query failed: {e}");
+            }
+            anyhow::Ok(())
+        };
+        select_biased! {
+            result = probe.fuse() => match result {
+                Ok(()) if start.elapsed() <= *HEALTH_CHECK_DEGRADED_THRESHOLD => HealthStatus::Ok,
+                Ok(()) => {
+                    tracing::warn!("Health check query took {:?}", start.elapsed());
+                    HealthStatus::Degraded
+                },
+                Err(e) => {
+                    tracing::error!("Health check failed: {e:#}");
+                    HealthStatus::Degraded
+                },
+            },
+            _ = self.runtime.wait(*HEALTH_CHECK_TIMEOUT) => {
+                tracing::error!("Health check timed out after {:?}", *HEALTH_CHECK_TIMEOUT);
+                HealthStatus::Timeout
+            },
+        }
+    }
+}
@@ -13,6 +13,8 @@ use std::{
         BTreeSet,
         HashSet,
     },
+    fmt,
+    future::Future,
     ops::Bound,
     sync::Arc,
     time::{
@@ -54,6 +56,7 @@ use common::{
         index::{
             database_index::IndexedFields,
             index_validation_error,
+            IndexConfig,
             IndexMetadata,
         },
         schema::{
@@ -80,6 +83,7 @@ use common::{
         report_error,
         JsError,
     },
+    execution_context::ExecutionId,
     http::{
         fetch::FetchClient,
         RequestDestination,
@@ -89,11 +93,18 @@ use common::{
         ENABLE_INDEX_BACKFILL,
         MAX_JOBS_CANCEL_BATCH,
         MAX_USER_MODULES,
+        PAGINATED_QUERY_MAX_PAGE_SIZE,
+    },
+    log_lines::{
+        LogLevel,
+        LogLines,
     },
-    log_lines::LogLines,
     log_streaming::LogSender,
     paths::FieldPath,
-    persistence::Persistence,
+    persistence::{
+        Persistence,
+        RetentionValidator,
+    },
     query::{
         IndexRange,
         IndexRangeExpression,
@@ -108,7 +119,9 @@ use common::{
         UnixTimestamp,
     },
     schemas::{
+        json::DatabaseSchemaJson,
         DatabaseSchema,
+        IndexSchema,
         TableDefinition,
     },
     shutdown::ShutdownSignal,
@@ -125,6 +138,7 @@ use common::{
         FunctionCaller,
         IndexId,
         IndexName,
+        IndexReadStats,
         ModuleEnvironment,
         NodeDependency,
         ObjectKey,
@@ -153,7 +167,10 @@ use database::{
     IndexModel,
     IndexWorker,
     OccRetryStats,
+    ReadSetSize,
+    ReadWriteSets,
     ResolvedQuery,
+    SchemaChangeGuard,
     SchemaModel,
     SearchIndexWorkers,
     Snapshot,
@@ -185,7 +202,10 @@ use function_log::{
     FunctionExecutionPart,
 };
 use function_runner::FunctionRunner;
-use futures::stream::BoxStream;
+use futures::{
+    future,
+    stream::BoxStream,
+};
 use headers::{
     ContentLength,
     ContentType,
@@ -194,10 +214,14 @@ use http_client::{
     cached_http_client_for,
     ClientPurpose,
 };
-use isolate::helpers::source_map_from_slice;
+use isolate::{
+    helpers::source_map_from_slice,
+    ConcurrencyPermitStats,
+};
 use keybroker::{
     Identity,
     KeyBroker,
+    PaginatedQueryCursor,
 };
 use log_streaming::add_local_log_sink_on_startup;
 use maplit::{
@@ -306,7 +330,11 @@ use search::{
     },
 };
 use semver::Version;
-use serde_json::Value as JsonValue;
+use serde::Deserialize;
+use serde_json::{
+    json,
+    Value as JsonValue,
+};
 use short_future::ShortBoxFuture;
 use snapshot_import::{
     clear_tables,
@@ -346,10 +374,14 @@ use udf::{
         CONVEX_ORIGIN,
         CONVEX_SITE,
     },
-    helpers::parse_udf_args,
+    helpers::{
+        parse_udf_args,
+        UdfArgs,
+    },
     HttpActionRequest,
     HttpActionResponseStreamer,
     HttpActionResult,
+    SyscallTrace,
 };
 use udf_metrics::{
     MetricsWindow,
@@ -364,10 +396,13 @@ use usage_tracking::{
 use value::{
     id_v6::DeveloperDocumentId,
     sha256::Sha256Digest,
+    ConvexArray,
+    ConvexObject,
     JsonPackedValue,
     Namespace,
     ResolvedDocumentId,
     TableNamespace,
+    TabletId,
 };
 use vector::{
     PublicVectorSearchQueryResult,
@@ -375,10 +410,23 @@ use vector::{
 };
 
 use crate::{
-    application_function_runner::ApplicationFunctionRunner,
+    application_function_runner::{
+        ApplicationFunctionRunner,
+        InFlightMutation,
+        OccRetryObserver,
+        OccRetryPolicy,
+        RetryPolicy,
+    },
+    error_classifier::{
+        classify_and_log,
+        DefaultErrorClassifier,
+        ErrorClassifier,
+        FunctionFailure,
+    },
     exports::worker::ExportWorker,
     function_log::{
         FunctionExecutionLog,
+        OccStats,
         TableRate,
         UdfMetricSummary,
         UdfRate,
@@ -398,8 +446,10 @@ pub mod application_function_runner;
 mod cache;
 pub mod cron_jobs;
 pub mod deploy_config;
+pub mod error_classifier;
 mod exports;
 pub mod function_log;
+pub mod health_check;
 mod log_streaming;
 pub mod log_visibility;
 mod metrics;
@@ -440,12 +490,68 @@ pub struct ApplyConfigArgs {
     pub analyze_results: BTreeMap<CanonicalizedModulePath, AnalyzedModule>,
 }
 
+/// Where the time went while running a function, measured with the runtime's
+/// monotonic clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FunctionTiming {
+    /// Wall time from when we started handling the request until the function
+    /// finished.
+    pub total: Duration,
+    /// Time blocked on async syscalls, e.g. database reads or `fetch`.
+    /// Concurrent syscalls are each counted in full, so for actions this can
+    /// exceed `total`.
+    pub syscalls: Duration,
+    /// Time the JavaScript sat idle waiting on async ops, including syscalls,
+    /// timers and sleeps. Queries and mutations block on one syscall at a
+    /// time, so for them this equals `syscalls`.
+    pub async_op_wait: Duration,
+    /// Everything else: executing JavaScript.
+    pub js: Duration,
+    /// Time spent waiting for an isolate concurrency permit before the
+    /// function started running. Always zero for actions.
+    pub permit_wait: Duration,
+}
+
+impl FunctionTiming {
+    pub fn new(total: Duration, syscall_trace: &SyscallTrace, permit_wait: Duration) -> Self {
+        let syscalls = syscall_trace.total_duration();
+        Self {
+            total,
+            syscalls,
+            async_op_wait: syscalls,
+            js: total.saturating_sub(syscalls).saturating_sub(permit_wait),
+            permit_wait,
+        }
+    }
+
+    /// Actions run their async ops concurrently, so the time their JS waited
+    /// is measured directly rather than derived from `syscall_trace`.
+    pub fn for_action(
+        total: Duration,
+        syscall_trace: &SyscallTrace,
+        async_op_wait: Duration,
+    ) -> Self {
+        Self {
+            total,
+            syscalls: syscall_trace.total_duration(),
+            async_op_wait,
+            js: total.saturating_sub(async_op_wait),
+            permit_wait: Duration::ZERO,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct QueryReturn {
     pub result: Result<JsonPackedValue, JsError>,
     pub log_lines: LogLines,
     pub token: Token,
     pub journal: QueryJournal,
+    /// `None` if the result was served from the cache.
+    pub timing: Option<FunctionTiming>,
+    /// Rows and bytes the query read through each index. `None` if the result
+    /// was served from the cache.
+    pub index_reads: Option<BTreeMap<IndexName, IndexReadStats>>,
 }
 
 #[derive(Debug)]
@@ -454,6 +560,48 @@ pub struct RedactedQueryReturn {
     pub log_lines: RedactedLogLines,
     pub token: Token,
     pub journal: SerializedQueryJournal,
+    pub timing: Option<FunctionTiming>,
+    pub index_reads: Option<BTreeMap<IndexName, IndexReadStats>>,
+}
+
+/// A page of results from [`Application::paginated_query_udf`].
+#[derive(Debug)]
+pub struct QueryPage {
+    pub page: Vec<JsonValue>,
+    /// Pass this back to get the next page. `None` once the results are
+    /// exhausted.
+    pub continue_cursor: Option<String>,
+    pub log_lines: RedactedLogLines,
+}
+
+/// Optional settings for [`Application::mutation_udf`]. The defaults run the
+/// mutation the same way a client request would.
+#[derive(Clone, Debug, Default)]
+pub struct MutationOptions {
+    /// Documents the mutation is expected to write. Mutations with
+    /// overlapping hints run one at a time rather than racing and retrying on
+    /// OCC.
+    pub conflict_hint: Vec<DeveloperDocumentId>,
+    /// Seeds the mutation's `Math.random()` so tests can reproduce runs.
+    /// Every attempt gets fresh entropy if this is `None`.
+    pub rng_seed: Option<[u8; 32]>,
+    /// Overrides how often and for how long to retry on OCC errors. Falls
+    /// back to the function's or caller's policy, or the `UDF_EXECUTOR_OCC_*`
+    /// knobs, if this is `None`.
+    pub occ_retry_policy: Option<OccRetryPolicy>,
+    /// Caller-supplied key that makes retries of this call safe. Calls with
+    /// the same key, function, and identity run the mutation at most once;
+    /// later ones return the first one's result. Keys are kept as long as
+    /// session requests (`MAX_SESSION_CLEANUP_DURATION`), can be at most
+    /// `MAX_IDEMPOTENCY_KEY_LENGTH` bytes, and can't be used anonymously.
+    pub idempotency_key: Option<String>,
+    /// Drops the mutation's console messages below this level, in place of
+    /// the `UDF_MIN_LOG_LEVEL` knob. Errors are always kept.
+    pub min_log_level: Option<LogLevel>,
+    /// Reports how much the committed attempt read in
+    /// [`MutationReturn::read_set_size`]. A larger read set gives concurrent
+    /// writes more to conflict with, so this helps narrow reads that OCC.
+    pub report_read_set_size: bool,
 }
 
 #[derive(Debug)]
@@ -461,6 +609,84 @@ pub struct MutationReturn {
     pub value: JsonPackedValue,
     pub log_lines: LogLines,
     pub ts: Timestamp,
+    /// Whether the mutation's writes were committed at `ts`. Mutations that
+    /// write nothing, and dry runs, don't advance the commit timestamp, so for
+    /// them `ts` is only the snapshot they read at.
+    ///
+    /// Mutations run with a `mutation_identifier` or idempotency key always
+    /// commit, since saving their outcome is itself a write, so this is always
+    /// `true` for them, including when an earlier result is returned.
+    pub committed_writes: bool,
+    /// How much the committed attempt read, if
+    /// [`MutationOptions::report_read_set_size`] was set. This is `None`
+    /// otherwise, or if the mutation had already been committed by an earlier
+    /// request.
+    pub read_set_size: Option<ReadSetSize>,
+    /// How many attempts failed with an OCC before this one succeeded.
+    pub occ_retries: usize,
+    /// Time from the start of the first attempt to the start of the one that
+    /// succeeded, including backoff.
+    pub occ_retry_time: Duration,
+    /// What the committed attempt read and wrote. Only recorded in tests, and
+    /// `None` if the mutation had already been committed.
+    pub read_write_sets: Option<ReadWriteSets>,
+    /// Timing of the committed attempt, not including the commit itself.
+    /// `None` if the mutation had already been committed.
+    pub timing: Option<FunctionTiming>,
+    /// The writes a dry run discarded, in document ID order. `None` unless
+    /// the mutation was a dry run.
+    pub dry_run_writes: Option<Vec<DryRunWrite>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DryRunWriteKind {
+    Insert,
+    Replace,
+    Delete,
+}
+
+/// A document write from [`Application::dry_run_mutation_udf`]. Values are
+/// serialized as `ConvexCleanJSON`, the same as snapshot exports.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DryRunWrite {
+    pub table_name: TableName,
+    pub document_id: DeveloperDocumentId,
+    pub kind: DryRunWriteKind,
+    pub old_value: Option<JsonValue>,
+    pub new_value: Option<JsonValue>,
+}
+
+/// Attached as context to the error a mutation returns when it gives up after
+/// OCC errors, so callers can tell how long it kept retrying with
+/// `downcast_ref::<OccRetriesExhausted>()`. Successful mutations report the
+/// same in [`MutationReturn`].
+///
+/// Displays as the error it's attached to, so the message is unchanged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OccRetriesExhausted {
+    /// How many attempts failed with an OCC before the last one, which did
+    /// too.
+    pub occ_retries: usize,
+    /// Time from the start of the first attempt to the start of the last,
+    /// including backoff.
+    pub occ_retry_time: Duration,
+    message: String,
+}
+
+impl OccRetriesExhausted {
+    pub(crate) fn new(occ_retries: usize, occ_retry_time: Duration, error: &anyhow::Error) -> Self {
+        Self {
+            occ_retries,
+            occ_retry_time,
+            message: error.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for OccRetriesExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
 }
 
 #[derive(Debug)]
@@ -468,6 +694,24 @@ pub struct RedactedMutationReturn {
     pub value: JsonPackedValue,
     pub log_lines: RedactedLogLines,
     pub ts: Timestamp,
+    pub committed_writes: bool,
+    pub read_set_size: Option<ReadSetSize>,
+    pub occ_retries: usize,
+    pub occ_retry_time: Duration,
+    pub read_write_sets: Option<ReadWriteSets>,
+    pub timing: Option<FunctionTiming>,
+    pub dry_run_writes: Option<Vec<DryRunWrite>>,
+}
+
+/// The result of [`Application::mutation_then_subscribe`].
+pub struct MutationThenSubscribeReturn {
+    pub mutation: RedactedMutationReturn,
+    /// The query's result at the mutation's commit timestamp.
+    pub query: RedactedQueryReturn,
+    /// Subscription to `query`'s read set starting at the mutation's commit
+    /// timestamp. It's invalidated by any later write that changes the
+    /// query's result.
+    pub subscription: Subscription,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -484,16 +728,57 @@ pub struct RedactedMutationError {
     pub log_lines: RedactedLogLines,
 }
 
+#[derive(Debug)]
+pub struct MutationBatchReturn {
+    /// Each mutation's return value, in batch order.
+    pub values: Vec<JsonPackedValue>,
+    /// Each mutation's log lines, in batch order.
+    pub log_lines: Vec<LogLines>,
+    /// When the whole batch committed.
+    pub ts: Timestamp,
+    /// How many attempts at the whole batch failed with an OCC before this one
+    /// succeeded.
+    pub occ_retries: usize,
+}
+
+#[derive(Debug)]
+pub struct RedactedMutationBatchReturn {
+    pub values: Vec<JsonPackedValue>,
+    pub log_lines: Vec<RedactedLogLines>,
+    pub ts: Timestamp,
+    pub occ_retries: usize,
+}
+
+/// A mutation in a batch threw, so none of the batch was committed.
+#[derive(thiserror::Error, Debug)]
+#[error("Mutation {index} in batch failed: {error}")]
+pub struct MutationBatchError {
+    /// Position of the failed mutation in the batch.
+    pub index: usize,
+    pub error: JsError,
+    pub log_lines: LogLines,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Mutation {index} in batch failed: {error}")]
+pub struct RedactedMutationBatchError {
+    pub index: usize,
+    pub error: RedactedJsError,
+    pub log_lines: RedactedLogLines,
+}
+
 #[derive(Debug)]
 pub struct ActionReturn {
     pub value: JsonPackedValue,
     pub log_lines: LogLines,
+    pub timing: FunctionTiming,
 }
 
 #[derive(Debug)]
 pub struct RedactedActionReturn {
     pub value: JsonPackedValue,
     pub log_lines: RedactedLogLines,
+    pub timing: FunctionTiming,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -566,6 +851,7 @@ pub struct Application<RT: Runtime> {
     system_env_var_names: HashSet<EnvVarName>,
     app_auth: Arc<ApplicationAuth>,
     log_manager_client: LogManagerClient,
+    error_classifier: Arc<dyn ErrorClassifier>,
 }
 
 impl<RT: Runtime> Clone for Application<RT> {
@@ -597,6 +883,7 @@ impl<RT: Runtime> Clone for Application<RT> {
             system_env_var_names: self.system_env_var_names.clone(),
             app_auth: self.app_auth.clone(),
             log_manager_client: self.log_manager_client.clone(),
+            error_classifier: self.error_classifier.clone(),
         }
     }
 }
@@ -871,9 +1158,65 @@ impl<RT: Runtime> Application<RT> {
             system_env_var_names: default_system_env_vars.into_keys().collect(),
             app_auth,
             log_manager_client,
+            error_classifier: Arc::new(DefaultErrorClassifier),
         })
     }
 
+    /// Replace the policy used to classify function failures for alerting.
+    pub fn with_error_classifier(mut self, error_classifier: Arc<dyn ErrorClassifier>) -> Self {
+        self.error_classifier = error_classifier;
+        self
+    }
+
+    /// Decide when to retry OCC errors from the mutation at `path` with
+    /// `policy`, regardless of who calls it.
+    pub fn set_function_retry_policy(
+        &self,
+        path: CanonicalizedComponentFunctionPath,
+        policy: Arc<dyn RetryPolicy>,
+    ) {
+        self.runner.set_function_retry_policy(path, policy);
+    }
+
+    /// Decide when to retry OCC errors with `policy` for mutations run by
+    /// callers that `matches` accepts, unless the function has its own
+    /// policy.
+    pub fn set_caller_retry_policy(
+        &self,
+        matches: fn(&FunctionCaller) -> bool,
+        policy: Arc<dyn RetryPolicy>,
+    ) {
+        self.runner.set_caller_retry_policy(matches, policy);
+    }
+
+    /// How many isolate concurrency permits are held versus still available,
+    /// or `None` if functions don't run in this process.
+    pub fn concurrency_permit_stats(&self) -> Option<ConcurrencyPermitStats> {
+        self.runner.concurrency_permit_stats()
+    }
+
+    /// Report every OCC error and the eventual outcome of mutations that
+    /// retry to `observer`, replacing any previous one.
+    pub fn set_occ_retry_observer(&self, observer: Option<Arc<dyn OccRetryObserver>>) {
+        self.runner.set_occ_retry_observer(observer);
+    }
+
+    /// Run queries, mutations and actions as if the current time were
+    /// `unix_timestamp` until it's unpinned again with `None`. Cached results
+    /// of queries that read the clock expire relative to the pinned time.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn set_pinned_unix_timestamp(&self, unix_timestamp: Option<UnixTimestamp>) {
+        self.runner.set_pinned_unix_timestamp(unix_timestamp);
+    }
+
+    /// Terminate functions run by the named caller (e.g. `Cron`) once they use
+    /// more than `limit` bytes of memory, as if it were set in
+    /// `ISOLATE_INVOCATION_MEMORY_LIMIT_BY_CALLER`.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn set_caller_memory_limit(&self, caller: &str, limit: usize) {
+        self.runner.set_caller_memory_limit(caller, limit);
+    }
+
     pub fn runtime(&self) -> RT {
         self.runtime.clone()
     }
@@ -936,6 +1279,17 @@ impl<RT: Runtime> Application<RT> {
         self.database.subscribe(token).await
     }
 
+    #[fastrace::trace]
+    pub async fn subscribe_with_table_filter(
+        &self,
+        token: Token,
+        tablet_ids: &BTreeSet<TabletId>,
+    ) -> anyhow::Result<Subscription> {
+        self.database
+            .subscribe_with_table_filter(token, tablet_ids)
+            .await
+    }
+
     pub fn usage_counter(&self) -> UsageCounter {
         self.database.usage_counter()
     }
@@ -1032,13 +1386,29 @@ impl<RT: Runtime> Application<RT> {
     pub async fn read_only_udf(
         &self,
         request_id: RequestId,
-        path: PublicFunctionPath,
+        path: impl Into<PublicFunctionPath>,
         args: Vec<JsonValue>,
         identity: Identity,
         caller: FunctionCaller,
     ) -> anyhow::Result<RedactedQueryReturn> {
         let ts = *self.now_ts_for_reads();
-        self.read_only_udf_at_ts(request_id, path, args, identity, ts, None, caller)
+        self.read_only_udf_at_ts(request_id, path.into(), args, identity, ts, None, caller)
+            .await
+    }
+
+    /// Like [`Application::read_only_udf`], but takes the arguments as values
+    /// rather than JSON, so ones JSON can't represent exactly (`Int64`s
+    /// outside of 2^53, for example) reach the query intact.
+    pub async fn read_only_udf_with_values(
+        &self,
+        request_id: RequestId,
+        path: impl Into<PublicFunctionPath>,
+        args: ConvexArray,
+        identity: Identity,
+        caller: FunctionCaller,
+    ) -> anyhow::Result<RedactedQueryReturn> {
+        let ts = *self.now_ts_for_reads();
+        self.read_only_udf_at_ts(request_id, path.into(), args, identity, ts, None, caller)
             .await
     }
 
@@ -1047,7 +1417,7 @@ impl<RT: Runtime> Application<RT> {
         &self,
         request_id: RequestId,
         path: PublicFunctionPath,
-        args: Vec<JsonValue>,
+        args: impl Into<UdfArgs>,
         identity: Identity,
         ts: Timestamp,
         journal: Option<Option<String>>,
@@ -1074,7 +1444,7 @@ impl<RT: Runtime> Application<RT> {
                 .run_query_at_ts(
                     request_id.clone(),
                     path,
-                    args,
+                    args.into(),
                     identity,
                     ts,
                     journal,
@@ -1087,20 +1457,29 @@ impl<RT: Runtime> Application<RT> {
             Ok(query_return) => RedactedQueryReturn {
                 result: match query_return.result {
                     Ok(r) => Ok(r),
-                    Err(e) => Err(RedactedJsError::from_js_error(e, block_logging, request_id)),
+                    Err(e) => {
+                        self.classify_failure(UdfType::Query, FunctionFailure::User(&e));
+                        Err(RedactedJsError::from_js_error(e, block_logging, request_id))
+                    },
                 },
                 log_lines: RedactedLogLines::from_log_lines(query_return.log_lines, block_logging),
                 token: query_return.token,
                 journal: self
                     .key_broker
                     .encrypt_query_journal(&query_return.journal, persistence_version),
+                timing: query_return.timing,
+                index_reads: query_return.index_reads,
             },
             Err(e) if e.is_deterministic_user_error() => RedactedQueryReturn {
-                result: Err(RedactedJsError::from_js_error(
-                    JsError::from_error(e),
-                    block_logging,
-                    request_id,
-                )),
+                result: {
+                    let error = JsError::from_error(e);
+                    self.classify_failure(UdfType::Query, FunctionFailure::User(&error));
+                    Err(RedactedJsError::from_js_error(
+                        error,
+                        block_logging,
+                        request_id,
+                    ))
+                },
                 log_lines: RedactedLogLines::empty(),
                 // Create a token for an empty read set because we haven't
                 // done any reads yet.
@@ -1108,23 +1487,351 @@ impl<RT: Runtime> Application<RT> {
                 journal: self
                     .key_broker
                     .encrypt_query_journal(&QueryJournal::new(), persistence_version),
+                timing: None,
+                index_reads: None,
+            },
+            Err(e) => {
+                self.classify_failure(UdfType::Query, FunctionFailure::System(&e));
+                anyhow::bail!(e)
             },
-            Err(e) => anyhow::bail!(e),
         };
         Ok(redacted_query_return)
     }
 
+    /// Run a paginated query one page of at most `page_size` results at a
+    /// time, capped at `PAGINATED_QUERY_MAX_PAGE_SIZE`. The query at `path`
+    /// must return `.paginate(args.paginationOpts)`, so each page only reads
+    /// its own index range. `cursor` is `None` for the first page and the
+    /// previous page's `continue_cursor` after that. Every page reads the
+    /// snapshot the first page did.
+    pub async fn paginated_query_udf(
+        &self,
+        request_id: RequestId,
+        path: CanonicalizedComponentFunctionPath,
+        args: JsonValue,
+        page_size: usize,
+        cursor: Option<String>,
+        identity: Identity,
+        caller: FunctionCaller,
+    ) -> anyhow::Result<Result<QueryPage, RedactedJsError>> {
+        // Seal cursors with the function they came from and the snapshot the
+        // first page read, since the query's own cursor only identifies the
+        // index range it reads.
+        let component_path = path.component.to_string();
+        let udf_path = path.udf_path.to_string();
+        let (ts, cursor) = match cursor {
+            Some(cursor) => {
+                let cursor = self.key_broker.decrypt_paginated_query_cursor(&cursor)?;
+                anyhow::ensure!(
+                    cursor.component_path == component_path && cursor.udf_path == udf_path,
+                    ErrorMetadata::bad_request(
+                        "InvalidCursor",
+                        format!("This cursor wasn't returned by a query to {path:?}"),
+                    )
+                );
+                (cursor.ts, Some(cursor.cursor))
+            },
+            None => (*self.now_ts_for_reads(), None),
+        };
+        let page_size = page_size.min(*PAGINATED_QUERY_MAX_PAGE_SIZE);
+        let JsonValue::Object(mut args) = args else {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidArgs",
+                "Paginated query arguments must be an object"
+            ));
+        };
+        args.insert(
+            "paginationOpts".to_string(),
+            json!({ "numItems": page_size, "cursor": cursor }),
+        );
+        let query_return = self
+            .read_only_udf_at_ts(
+                request_id,
+                PublicFunctionPath::Component(path),
+                vec![JsonValue::Object(args)],
+                identity,
+                ts,
+                None,
+                caller,
+            )
+            .await?;
+        let value = match query_return.result {
+            Ok(value) => value.json_value(),
+            Err(e) => return Ok(Err(e)),
+        };
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PaginationResult {
+            page: Vec<JsonValue>,
+            is_done: bool,
+            continue_cursor: String,
+        }
+        let PaginationResult {
+            page,
+            is_done,
+            continue_cursor,
+        } = serde_json::from_value(value).context(ErrorMetadata::bad_request(
+            "InvalidPaginatedQuery",
+            "Paginated queries must return the result of `.paginate()`",
+        ))?;
+        Ok(Ok(QueryPage {
+            page,
+            continue_cursor: (!is_done).then(|| {
+                self.key_broker
+                    .encrypt_paginated_query_cursor(&PaginatedQueryCursor {
+                        component_path,
+                        udf_path,
+                        ts,
+                        cursor: continue_cursor,
+                    })
+            }),
+            log_lines: query_return.log_lines,
+        }))
+    }
+
+    /// Run a query against the snapshot at `ts` rather than the latest one.
+    /// Fails with a `SnapshotTooNew` error if `ts` is in the future, and with
+    /// an out of retention error (see `is_out_of_retention`) if the snapshot
+    /// has already been cleaned up.
     #[fastrace::trace]
-    pub async fn mutation_udf(
+    pub async fn query_udf_at(
+        &self,
+        request_id: RequestId,
+        path: PublicFunctionPath,
+        args: Vec<JsonValue>,
+        identity: Identity,
+        ts: Timestamp,
+        caller: FunctionCaller,
+    ) -> anyhow::Result<RedactedQueryReturn> {
+        let now = self.now_ts_for_reads();
+        let ts = now.prior_ts(ts).with_context(|| {
+            ErrorMetadata::bad_request(
+                "SnapshotTooNew",
+                format!("Snapshot value {ts} is in the future."),
+            )
+        })?;
+        self.database
+            .retention_validator()
+            .validate_snapshot(*ts)
+            .await?;
+        self.read_only_udf_at_ts(request_id, path, args, identity, *ts, None, caller)
+            .await
+    }
+
+    /// Run a query at the latest timestamp purely to populate the query cache,
+    /// so the first client request after a deploy is a cache hit.
+    ///
+    /// The result is discarded. Queries that throw aren't cached, so their
+    /// errors are ignored here and surface on the first real request instead.
+    #[fastrace::trace]
+    pub async fn warm_query(
         &self,
         request_id: RequestId,
         path: PublicFunctionPath,
         args: Vec<JsonValue>,
         identity: Identity,
+        caller: FunctionCaller,
+    ) -> anyhow::Result<()> {
+        let ts = *self.now_ts_for_reads();
+        let result = self
+            .runner
+            .run_query_at_ts(request_id, path, args.into(), identity, ts, None, caller)
+            .await;
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) if e.is_deterministic_user_error() => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Run `queries` at a single timestamp, so they all observe the same
+    /// snapshot. Each query succeeds or fails independently, and the results
+    /// are in the same order as `queries`.
+    #[fastrace::trace]
+    pub async fn query_udf_batch(
+        &self,
+        request_id: RequestId,
+        queries: Vec<(PublicFunctionPath, Vec<JsonValue>)>,
+        identity: Identity,
+        caller: FunctionCaller,
+    ) -> Vec<anyhow::Result<RedactedQueryReturn>> {
+        let ts = *self.now_ts_for_reads();
+        let results = queries.into_iter().map(|(path, args)| {
+            self.read_only_udf_at_ts(
+                request_id.clone(),
+                path,
+                args,
+                identity.clone(),
+                ts,
+                None,
+                caller.clone(),
+            )
+        });
+        futures::future::join_all(results).await
+    }
+
+    #[fastrace::trace]
+    pub async fn mutation_udf(
+        &self,
+        request_id: RequestId,
+        path: impl Into<PublicFunctionPath>,
+        args: Vec<JsonValue>,
+        identity: Identity,
         // Identifier used to make this mutation idempotent.
         mutation_identifier: Option<SessionRequestIdentifier>,
         caller: FunctionCaller,
         mutation_queue_length: Option<usize>,
+        options: MutationOptions,
+    ) -> anyhow::Result<Result<RedactedMutationReturn, RedactedMutationError>> {
+        self.run_mutation_udf(
+            request_id,
+            path.into(),
+            args.into(),
+            identity,
+            mutation_identifier,
+            caller,
+            mutation_queue_length,
+            options,
+            false,
+        )
+        .await
+    }
+
+    /// Like [`Application::mutation_udf`], but takes the arguments as values
+    /// rather than JSON, so ones JSON can't represent exactly (`Int64`s
+    /// outside of 2^53, for example) reach the mutation intact.
+    #[fastrace::trace]
+    pub async fn mutation_udf_with_values(
+        &self,
+        request_id: RequestId,
+        path: impl Into<PublicFunctionPath>,
+        args: ConvexArray,
+        identity: Identity,
+        mutation_identifier: Option<SessionRequestIdentifier>,
+        caller: FunctionCaller,
+        mutation_queue_length: Option<usize>,
+        options: MutationOptions,
+    ) -> anyhow::Result<Result<RedactedMutationReturn, RedactedMutationError>> {
+        self.run_mutation_udf(
+            request_id,
+            path.into(),
+            args.into(),
+            identity,
+            mutation_identifier,
+            caller,
+            mutation_queue_length,
+            options,
+            false,
+        )
+        .await
+    }
+
+    /// Runs a mutation exactly as [`Application::mutation_udf`] would,
+    /// retrying on OCC errors, but discards its writes rather than committing
+    /// them. The returned `ts` is the snapshot the final attempt ran at, and
+    /// `dry_run_writes` holds what it would have written, including any
+    /// functions it scheduled.
+    #[fastrace::trace]
+    pub async fn dry_run_mutation_udf(
+        &self,
+        request_id: RequestId,
+        path: impl Into<PublicFunctionPath>,
+        args: Vec<JsonValue>,
+        identity: Identity,
+        caller: FunctionCaller,
+    ) -> anyhow::Result<Result<RedactedMutationReturn, RedactedMutationError>> {
+        self.run_mutation_udf(
+            request_id,
+            path.into(),
+            args.into(),
+            identity,
+            None,
+            caller,
+            None,
+            MutationOptions::default(),
+            true,
+        )
+        .await
+    }
+
+    /// Runs `mutations` one after another in a single transaction and commits
+    /// them together. If any of them throws, none of the batch's writes are
+    /// committed and the error says which one failed. OCC errors retry the
+    /// whole batch.
+    #[fastrace::trace]
+    pub async fn mutation_udf_batch(
+        &self,
+        request_id: RequestId,
+        mutations: Vec<(PublicFunctionPath, Vec<JsonValue>)>,
+        identity: Identity,
+        allowed_visibility: AllowedVisibility,
+        caller: FunctionCaller,
+    ) -> anyhow::Result<Result<RedactedMutationBatchReturn, RedactedMutationBatchError>> {
+        identity.ensure_can_run_function(UdfType::Mutation)?;
+        let block_logging = self
+            .log_visibility
+            .should_redact_logs_and_error(
+                &mut self.begin(identity.clone()).await?,
+                identity.clone(),
+                allowed_visibility,
+            )
+            .await?;
+        let result = match self
+            .runner
+            .retry_mutation_batch(
+                request_id.clone(),
+                mutations,
+                identity,
+                allowed_visibility,
+                caller,
+            )
+            .await
+        {
+            Ok(Ok(batch_return)) => Ok(RedactedMutationBatchReturn {
+                values: batch_return.values,
+                log_lines: batch_return
+                    .log_lines
+                    .into_iter()
+                    .map(|log_lines| RedactedLogLines::from_log_lines(log_lines, block_logging))
+                    .collect(),
+                ts: batch_return.ts,
+                occ_retries: batch_return.occ_retries,
+            }),
+            Ok(Err(batch_error)) => {
+                self.classify_failure(UdfType::Mutation, FunctionFailure::User(&batch_error.error));
+                Err(RedactedMutationBatchError {
+                    index: batch_error.index,
+                    error: RedactedJsError::from_js_error(
+                        batch_error.error,
+                        block_logging,
+                        request_id,
+                    ),
+                    log_lines: RedactedLogLines::from_log_lines(
+                        batch_error.log_lines,
+                        block_logging,
+                    ),
+                })
+            },
+            Err(e) => {
+                self.classify_failure(UdfType::Mutation, FunctionFailure::System(&e));
+                anyhow::bail!(e)
+            },
+        };
+        Ok(result)
+    }
+
+    async fn run_mutation_udf(
+        &self,
+        request_id: RequestId,
+        path: PublicFunctionPath,
+        args: UdfArgs,
+        identity: Identity,
+        mutation_identifier: Option<SessionRequestIdentifier>,
+        caller: FunctionCaller,
+        mutation_queue_length: Option<usize>,
+        options: MutationOptions,
+        dry_run: bool,
     ) -> anyhow::Result<Result<RedactedMutationReturn, RedactedMutationError>> {
         identity.ensure_can_run_function(UdfType::Mutation)?;
         let block_logging = self
@@ -1145,6 +1852,8 @@ impl<RT: Runtime> Application<RT> {
                 mutation_identifier,
                 caller,
                 mutation_queue_length,
+                options,
+                dry_run,
             )
             .await
         {
@@ -1155,39 +1864,140 @@ impl<RT: Runtime> Application<RT> {
                     block_logging,
                 ),
                 ts: mutation_return.ts,
+                committed_writes: mutation_return.committed_writes,
+                read_set_size: mutation_return.read_set_size,
+                occ_retries: mutation_return.occ_retries,
+                occ_retry_time: mutation_return.occ_retry_time,
+                read_write_sets: mutation_return.read_write_sets,
+                timing: mutation_return.timing,
+                dry_run_writes: mutation_return.dry_run_writes,
             }),
-            Ok(Err(mutation_error)) => Err(RedactedMutationError {
-                error: RedactedJsError::from_js_error(
-                    mutation_error.error,
-                    block_logging,
-                    request_id,
-                ),
-                log_lines: RedactedLogLines::from_log_lines(
-                    mutation_error.log_lines,
-                    block_logging,
-                ),
-            }),
-            Err(e) if e.is_deterministic_user_error() => Err(RedactedMutationError {
-                error: RedactedJsError::from_js_error(
-                    JsError::from_error(e),
-                    block_logging,
-                    request_id,
-                ),
-                log_lines: RedactedLogLines::empty(),
-            }),
-            Err(e) => anyhow::bail!(e),
+            Ok(Err(mutation_error)) => {
+                self.classify_failure(
+                    UdfType::Mutation,
+                    FunctionFailure::User(&mutation_error.error),
+                );
+                Err(RedactedMutationError {
+                    error: RedactedJsError::from_js_error(
+                        mutation_error.error,
+                        block_logging,
+                        request_id,
+                    ),
+                    log_lines: RedactedLogLines::from_log_lines(
+                        mutation_error.log_lines,
+                        block_logging,
+                    ),
+                })
+            },
+            Err(e) if e.is_deterministic_user_error() => {
+                let error = JsError::from_error(e);
+                self.classify_failure(UdfType::Mutation, FunctionFailure::User(&error));
+                Err(RedactedMutationError {
+                    error: RedactedJsError::from_js_error(error, block_logging, request_id),
+                    log_lines: RedactedLogLines::empty(),
+                })
+            },
+            Err(e) => {
+                self.classify_failure(UdfType::Mutation, FunctionFailure::System(&e));
+                anyhow::bail!(e)
+            },
         };
         Ok(result)
     }
 
+    fn classify_failure(&self, udf_type: UdfType, failure: FunctionFailure<'_>) {
+        classify_and_log(self.error_classifier.as_ref(), udf_type, failure);
+    }
+
+    /// Run a mutation and then subscribe to a query as of the mutation's
+    /// commit. The query runs at exactly the commit timestamp, so its first
+    /// result always includes the mutation's writes, and the subscription
+    /// picks up from there, so no write between the two can be missed.
+    #[fastrace::trace]
+    pub async fn mutation_then_subscribe(
+        &self,
+        request_id: RequestId,
+        mutation_path: PublicFunctionPath,
+        mutation_args: Vec<JsonValue>,
+        query_path: PublicFunctionPath,
+        query_args: Vec<JsonValue>,
+        identity: Identity,
+        caller: FunctionCaller,
+    ) -> anyhow::Result<Result<MutationThenSubscribeReturn, RedactedMutationError>> {
+        let mutation = match self
+            .mutation_udf(
+                request_id.clone(),
+                mutation_path,
+                mutation_args,
+                identity.clone(),
+                None,
+                caller.clone(),
+                None,
+                MutationOptions::default(),
+            )
+            .await?
+        {
+            Ok(mutation) => mutation,
+            Err(e) => return Ok(Err(e)),
+        };
+        let query = self
+            .read_only_udf_at_ts(
+                request_id,
+                query_path,
+                query_args,
+                identity,
+                mutation.ts,
+                None,
+                caller,
+            )
+            .await?;
+        let subscription = self.subscribe(query.token.clone()).await?;
+        Ok(Ok(MutationThenSubscribeReturn {
+            mutation,
+            query,
+            subscription,
+        }))
+    }
+
     #[fastrace::trace]
     pub async fn action_udf(
+        &self,
+        request_id: RequestId,
+        name: impl Into<PublicFunctionPath>,
+        args: Vec<JsonValue>,
+        identity: Identity,
+        caller: FunctionCaller,
+    ) -> anyhow::Result<Result<RedactedActionReturn, RedactedActionError>> {
+        self.action_udf_with_cancellation(
+            request_id,
+            name.into(),
+            args,
+            identity,
+            caller,
+            None,
+            future::pending(),
+        )
+        .await
+    }
+
+    /// Like [`Application::action_udf`], but stops the action as soon as
+    /// `cancellation` resolves, even if the caller asked for the action to run
+    /// to completion. Stopping the action drops any of its pending async ops,
+    /// and the action fails with a user error saying it was cancelled.
+    ///
+    /// If `min_log_level` is set, the action's console messages below it are
+    /// dropped in place of the `UDF_MIN_LOG_LEVEL` knob. Errors are always
+    /// kept.
+    #[fastrace::trace]
+    pub async fn action_udf_with_cancellation(
         &self,
         request_id: RequestId,
         name: PublicFunctionPath,
         args: Vec<JsonValue>,
         identity: Identity,
         caller: FunctionCaller,
+        min_log_level: Option<LogLevel>,
+        cancellation: impl Future<Output = ()> + Send + 'static,
     ) -> anyhow::Result<Result<RedactedActionReturn, RedactedActionError>> {
         identity.ensure_can_run_function(UdfType::Action)?;
 
@@ -1208,7 +2018,15 @@ impl<RT: Runtime> Application<RT> {
             .unwrap_or(Span::noop());
         let run_action = async move {
             runner
-                .run_action(request_id_, name, args, identity, caller)
+                .run_action(
+                    request_id_,
+                    name,
+                    args,
+                    identity,
+                    caller,
+                    min_log_level,
+                    cancellation,
+                )
                 .in_span(span)
                 .await
         };
@@ -1233,16 +2051,26 @@ impl<RT: Runtime> Application<RT> {
             Ok(Ok(action_return)) => Ok(RedactedActionReturn {
                 value: action_return.value,
                 log_lines: RedactedLogLines::from_log_lines(action_return.log_lines, block_logging),
+                timing: action_return.timing,
             }),
-            Ok(Err(action_error)) => Err(RedactedActionError {
-                error: RedactedJsError::from_js_error(
-                    action_error.error,
-                    block_logging,
-                    request_id,
-                ),
-                log_lines: RedactedLogLines::from_log_lines(action_error.log_lines, block_logging),
-            }),
-            Err(e) => anyhow::bail!(e),
+            Ok(Err(action_error)) => {
+                self.classify_failure(UdfType::Action, FunctionFailure::User(&action_error.error));
+                Err(RedactedActionError {
+                    error: RedactedJsError::from_js_error(
+                        action_error.error,
+                        block_logging,
+                        request_id,
+                    ),
+                    log_lines: RedactedLogLines::from_log_lines(
+                        action_error.log_lines,
+                        block_logging,
+                    ),
+                })
+            },
+            Err(e) => {
+                self.classify_failure(UdfType::Action, FunctionFailure::System(&e));
+                anyhow::bail!(e)
+            },
         };
         Ok(result)
     }
@@ -1395,6 +2223,7 @@ impl<RT: Runtime> Application<RT> {
                     None,
                     caller,
                     None,
+                    MutationOptions::default(),
                 )
                 .await
                 .map(|res| {
@@ -1906,6 +2735,12 @@ impl<RT: Runtime> Application<RT> {
             .await
     }
 
+    /// Wait for any in-progress snapshot imports to finish before changing the
+    /// schema.
+    pub async fn lock_schema_for_change(&self) -> anyhow::Result<SchemaChangeGuard> {
+        self.database.lock_schema_for_change().await
+    }
+
     #[fastrace::trace]
     pub async fn apply_config_with_retries(
         &self,
@@ -1913,6 +2748,7 @@ impl<RT: Runtime> Application<RT> {
         apply_config_args: ApplyConfigArgs,
     ) -> anyhow::Result<(ConfigMetadataAndSchema, OccRetryStats)> {
         let runner = self.runner.clone();
+        let _schema_lock = self.database.lock_schema_for_change().await?;
         self.execute_with_audit_log_events_and_occ_retries_reporting_stats(
             identity,
             "apply_config",
@@ -2561,6 +3397,27 @@ impl<RT: Runtime> Application<RT> {
         Ok(count)
     }
 
+    /// Inserts the given documents into a user table in one transaction, so
+    /// the whole batch commits atomically and contends with other writers
+    /// only once. If any insert fails, none of the batch is committed.
+    /// Returns the number of documents inserted and the commit timestamp.
+    pub async fn insert_documents(
+        &self,
+        identity: &Identity,
+        table_namespace: TableNamespace,
+        table_name: TableName,
+        documents: Vec<ConvexObject>,
+    ) -> anyhow::Result<(usize, Timestamp)> {
+        let mut tx = self.begin(identity.clone()).await?;
+        let count = documents.len();
+        let mut model = UserFacingModel::new(&mut tx, table_namespace);
+        for document in documents {
+            model.insert(table_name.clone(), document).await?;
+        }
+        let ts = self.commit(tx, "insert_documents").await?;
+        Ok((count, ts))
+    }
+
     pub async fn delete_component(
         &self,
         identity: &Identity,
@@ -2909,6 +3766,53 @@ impl<RT: Runtime> Application<RT> {
         Ok(self.function_log.udf_summary(cursor))
     }
 
+    /// OCC contention over the last `window`: how often mutations conflicted
+    /// and retried, and the `k` functions that conflicted most.
+    pub async fn occ_stats(
+        &self,
+        identity: Identity,
+        window: Duration,
+        k: usize,
+    ) -> anyhow::Result<OccStats> {
+        if !(identity.is_admin() || identity.is_system()) {
+            anyhow::bail!(unauthorized_error("occ_stats"));
+        }
+        Ok(self.function_log.occ_stats(window, k))
+    }
+
+    /// Mutation attempts that are currently running their UDF, keyed by
+    /// execution id.
+    pub async fn in_flight_mutations(
+        &self,
+        identity: Identity,
+    ) -> anyhow::Result<Vec<InFlightMutation>> {
+        if !(identity.is_admin() || identity.is_system()) {
+            anyhow::bail!(unauthorized_error("in_flight_mutations"));
+        }
+        Ok(self.runner.in_flight_mutations())
+    }
+
+    /// Abort the running mutation attempt with `execution_id`, terminating
+    /// its isolate and rolling back its transaction. The mutation fails with
+    /// a `MutationAborted` error rather than being retried.
+    pub async fn abort_transaction(
+        &self,
+        identity: Identity,
+        execution_id: ExecutionId,
+    ) -> anyhow::Result<()> {
+        if !(identity.is_admin() || identity.is_system()) {
+            anyhow::bail!(unauthorized_error("abort_transaction"));
+        }
+        if !self.runner.abort_mutation(execution_id) {
+            anyhow::bail!(ErrorMetadata::not_found(
+                "TransactionNotFound",
+                format!("No running mutation with execution id {execution_id}"),
+            ));
+        }
+        tracing::info!("Aborted mutation with execution id {execution_id}");
+        Ok(())
+    }
+
     pub async fn table_rate(
         &self,
         identity: Identity,
@@ -2922,6 +3826,22 @@ impl<RT: Runtime> Application<RT> {
         self.function_log.table_rate(name, metric, window)
     }
 
+    /// The number of documents in the table at the latest snapshot. Counts
+    /// are maintained incrementally by the write path, so this never scans
+    /// the table.
+    pub async fn table_count(
+        &self,
+        identity: Identity,
+        namespace: TableNamespace,
+        name: TableName,
+    ) -> anyhow::Result<u64> {
+        if !(identity.is_admin() || identity.is_system()) {
+            anyhow::bail!(unauthorized_error("table_count"));
+        }
+        let mut tx = self.begin(identity).await?;
+        TableModel::new(&mut tx).must_count(namespace, &name).await
+    }
+
     pub async fn stream_udf_execution(
         &self,
         identity: Identity,
@@ -3288,6 +4208,7 @@ impl<RT: Runtime> Application<RT> {
                     )?,
                     range,
                     order: Order::Asc,
+                    collation: None,
                 };
                 let query = common::query::Query::index_range(index_range);
                 let mut query_stream = ResolvedQuery::new(tx, namespace, query)?;
@@ -3432,6 +4353,78 @@ impl<RT: Runtime> Application<RT> {
             .map(|(_id, schema)| schema))
     }
 
+    /// Export every table in `namespace` along with its validator and
+    /// indexes, in the same JSON form the CLI pushes. Tables that aren't in
+    /// the active schema are included without a validator, with whatever
+    /// database indexes they have. Convert the result back with
+    /// `DatabaseSchema::try_from` to re-import it.
+    pub async fn export_schema(
+        &self,
+        namespace: TableNamespace,
+        identity: &Identity,
+    ) -> anyhow::Result<DatabaseSchemaJson> {
+        if !(identity.is_admin() || identity.is_system()) {
+            anyhow::bail!(unauthorized_error("export_schema"));
+        }
+        let mut tx = self.begin(identity.clone()).await?;
+        let mut schema = SchemaModel::new(&mut tx, namespace)
+            .get_by_state(SchemaState::Active)
+            .await?
+            .map(|(_id, schema)| Arc::unwrap_or_clone(schema))
+            .unwrap_or_default();
+
+        let mut schemaless_tables = BTreeSet::new();
+        for (_, _, table_name) in tx
+            .table_mapping()
+            .namespace(namespace)
+            .iter_active_user_tables()
+        {
+            if !schema.tables.contains_key(table_name) {
+                schemaless_tables.insert(table_name.clone());
+            }
+        }
+        for table_name in &schemaless_tables {
+            schema.tables.insert(
+                table_name.clone(),
+                TableDefinition {
+                    table_name: table_name.clone(),
+                    indexes: BTreeMap::new(),
+                    staged_db_indexes: BTreeMap::new(),
+                    text_indexes: BTreeMap::new(),
+                    staged_text_indexes: BTreeMap::new(),
+                    vector_indexes: BTreeMap::new(),
+                    staged_vector_indexes: BTreeMap::new(),
+                    document_type: None,
+                },
+            );
+        }
+        // Tables in the schema already list their indexes, so only the
+        // schemaless tables need theirs filled in from the index metadata.
+        for index in IndexModel::new(&mut tx)
+            .get_application_indexes(namespace)
+            .await?
+        {
+            let IndexConfig::Database { spec, .. } = &index.config else {
+                continue;
+            };
+            if !index.config.is_enabled() || !schemaless_tables.contains(index.name.table()) {
+                continue;
+            }
+            let table = schema
+                .tables
+                .get_mut(index.name.table())
+                .context("Missing table for index")?;
+            table.indexes.insert(
+                index.name.descriptor().clone(),
+                IndexSchema {
+                    index_descriptor: index.name.descriptor().clone(),
+                    fields: spec.fields.clone(),
+                },
+            );
+        }
+        schema.try_into()
+    }
+
     pub async fn fivetran_create_table(
         &self,
         identity: &Identity,
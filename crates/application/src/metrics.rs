@@ -1,3 +1,4 @@
+use common::types::UdfType;
 use metrics::{
     log_counter_with_labels,
     log_distribution_with_labels,
@@ -9,6 +10,8 @@ use metrics::{
 };
 use model::source_packages::types::PackageSize;
 
+use crate::error_classifier::ErrorClassification;
+
 register_convex_counter!(
     EXTERNAL_DEPS_PACKAGES_TOTAL,
     "Total pushes with external dependency packages",
@@ -84,3 +87,22 @@ register_convex_histogram!(
 pub fn table_summary_bootstrap_timer() -> StatusTimer {
     StatusTimer::new(&TABLE_SUMMARY_BOOTSTRAP_SECONDS)
 }
+
+register_convex_counter!(
+    FUNCTION_FAILURES_TOTAL,
+    "Count of failed function executions by error classification",
+    &["udf_type", "category", "severity"],
+);
+pub fn log_function_failure(udf_type: UdfType, classification: ErrorClassification) {
+    let category: &'static str = classification.category.into();
+    let severity: &'static str = classification.severity.into();
+    log_counter_with_labels(
+        &FUNCTION_FAILURES_TOTAL,
+        1,
+        vec![
+            StaticMetricLabel::new("udf_type", udf_type.to_lowercase_string()),
+            StaticMetricLabel::new("category", category),
+            StaticMetricLabel::new("severity", severity),
+        ],
+    );
+}
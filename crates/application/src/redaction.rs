@@ -109,6 +109,12 @@ impl RedactedJsError {
         }
     }
 
+    /// Whether the function was rejected by its `args` validator before any
+    /// of its code ran, as opposed to failing while running.
+    pub fn is_argument_validation_error(&self) -> bool {
+        self.error.argument_validation.is_some()
+    }
+
     pub fn custom_data_if_any(self) -> Option<ConvexValue> {
         self.error.custom_data
     }
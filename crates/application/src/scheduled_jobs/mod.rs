@@ -14,6 +14,7 @@ use std::{
 use common::{
     backoff::Backoff,
     components::{
+        CanonicalizedComponentFunctionPath,
         ComponentId,
         PublicFunctionPath,
     },
@@ -98,7 +99,10 @@ use sentry::SentryFutureExt;
 use sync_types::Timestamp;
 use tokio::sync::mpsc;
 use usage_tracking::FunctionUsageTracker;
-use value::ResolvedDocumentId;
+use value::{
+    ConvexArray,
+    ResolvedDocumentId,
+};
 
 use crate::{
     application_function_runner::ApplicationFunctionRunner,
@@ -171,6 +175,25 @@ pub struct ScheduledJobContext<RT: Runtime> {
     function_log: FunctionExecutionLog<RT>,
 }
 
+/// Identifies a scheduled job by what it runs rather than by its document ID,
+/// so an execution order recorded in one run can be replayed against jobs
+/// scheduled in another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduledJobKey {
+    pub path: CanonicalizedComponentFunctionPath,
+    pub args: ConvexArray,
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl ScheduledJobKey {
+    fn new(job: &ScheduledJob) -> anyhow::Result<Self> {
+        Ok(Self {
+            path: job.path.clone(),
+            args: job.udf_args()?,
+        })
+    }
+}
+
 impl<RT: Runtime> ScheduledJobContext<RT> {
     #[cfg(any(test, feature = "testing"))]
     pub fn new(
@@ -414,6 +437,7 @@ impl<RT: Runtime> ScheduledJobContext<RT> {
                 value::ConvexValue::Null.into(),
             )],
             order: Order::Asc,
+            collation: None,
         });
         // Key is (next_ts, namespace), where next_ts is for sorting and namespace
         // is for deduping.
@@ -442,6 +466,61 @@ impl<RT: Runtime> ScheduledJobContext<RT> {
         }
     }
 
+    /// Run every job that's due, one at a time, returning the order they ran
+    /// in. The executor runs due jobs concurrently, so this is for tests that
+    /// need a deterministic order. If `replay` is set, the due jobs are run in
+    /// that order instead of the scheduler's, and it's an error if the
+    /// recorded jobs don't match the ones that are due.
+    #[cfg(any(test, feature = "testing"))]
+    pub async fn run_due_jobs_in_order(
+        &self,
+        replay: Option<&[ScheduledJobKey]>,
+    ) -> anyhow::Result<Vec<ScheduledJobKey>> {
+        let mut tx = self.database.begin(Identity::system()).await?;
+        let now = self.rt.generate_timestamp()?;
+        let mut due = vec![];
+        {
+            let mut job_stream = self.stream_jobs_to_run(&mut tx);
+            while let Some(job) = job_stream.try_next().await? {
+                // Jobs are streamed in `next_ts` order, so the rest aren't due
+                // either.
+                if job.next_ts.is_some_and(|next_ts| next_ts > now) {
+                    break;
+                }
+                let (job_id, job) = job.into_id_and_value();
+                due.push((ScheduledJobKey::new(&job)?, job_id, job));
+            }
+        }
+        drop(tx);
+
+        let ordered = match replay {
+            None => due,
+            Some(replay) => {
+                anyhow::ensure!(
+                    replay.len() == due.len(),
+                    "Recorded {} jobs but {} are due",
+                    replay.len(),
+                    due.len()
+                );
+                let mut ordered = Vec::with_capacity(replay.len());
+                for key in replay {
+                    let position = due
+                        .iter()
+                        .position(|(due_key, ..)| due_key == key)
+                        .ok_or_else(|| anyhow::anyhow!("Recorded job {key:?} isn't due"))?;
+                    ordered.push(due.remove(position));
+                }
+                ordered
+            },
+        };
+        let mut executed = Vec::with_capacity(ordered.len());
+        for (key, job_id, job) in ordered {
+            self.execute_job(job, job_id).await;
+            executed.push(key);
+        }
+        Ok(executed)
+    }
+
     // This handles re-running the scheduled function on transient errors. It
     // guarantees that the job was successfully run or the job state changed.
     pub async fn execute_job(&self, job: ScheduledJob, job_id: ResolvedDocumentId) {
@@ -981,6 +1060,7 @@ impl<RT: Runtime> ScheduledJobGarbageCollector<RT> {
                         value::ConvexValue::Null.into(),
                     )],
                     order: Order::Asc,
+                    collation: None,
                 })
                 .limit(*SCHEDULED_JOB_GARBAGE_COLLECTION_BATCH_SIZE);
                 let mut query_stream = ResolvedQuery::new(&mut tx, namespace, index_query)?;
@@ -329,6 +329,9 @@ impl<RT: Runtime> SnapshotImportExecutor<RT> {
         snapshot_import: ParsedDocument<SnapshotImport>,
     ) -> anyhow::Result<(Timestamp, u64)> {
         self.fail_if_too_old(&snapshot_import)?;
+        // Hold off schema changes until the import is finalized, since they'd make
+        // it fail with `ImportSchemaChanged`.
+        let _schema_freeze = self.database.freeze_schema().await;
         let (initial_schemas, objects) = self.parse_import(snapshot_import.id()).await?;
 
         let usage = FunctionUsageTracker::new();
@@ -528,6 +528,44 @@ a
     Ok(())
 }
 
+#[convex_macro::test_runtime]
+async fn schema_change_blocked_during_import(
+    rt: TestRuntime,
+    pause_controller: PauseController,
+) -> anyhow::Result<()> {
+    let app = Application::new_for_tests(&rt).await?;
+    let table_name = "table1";
+    let test_csv = r#"
+a
+"string"
+"#;
+
+    let hold_guard = pause_controller.hold("before_finalize_import");
+
+    let mut import_fut = run_csv_import(&app, table_name, test_csv).boxed();
+
+    select! {
+        r = import_fut.as_mut().fuse() => {
+            anyhow::bail!("import finished before pausing: {r:?}");
+        },
+        pause_guard = hold_guard.wait_for_blocked().fuse() => {
+            let pause_guard = pause_guard.unwrap();
+            // The import holds the schema lock, so this times out.
+            let Err(err) = app.lock_schema_for_change().await else {
+                anyhow::bail!("schema change wasn't blocked by the import");
+            };
+            assert!(err.is_bad_request());
+            assert_eq!(err.short_msg(), "SchemaLockedByImport");
+            pause_guard.unpause();
+        },
+    }
+    import_fut.await?;
+
+    // Once the import finishes, schema changes can proceed.
+    app.lock_schema_for_change().await?;
+    Ok(())
+}
+
 #[convex_macro::test_runtime]
 async fn import_would_break_foreign_key(rt: TestRuntime) -> anyhow::Result<()> {
     let app = Application::new_for_tests(&rt).await?;
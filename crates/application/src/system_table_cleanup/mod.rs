@@ -363,6 +363,7 @@ impl<RT: Runtime> SystemTableCleanupWorker<RT> {
             index_name: IndexName::by_creation_time(table.clone()),
             range,
             order: Order::Asc,
+            collation: None,
         });
         if let Some((creation_time, id)) = cursor {
             index_scan = index_scan.filter(Expression::Or(vec![
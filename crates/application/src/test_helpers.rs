@@ -2,6 +2,7 @@ use std::{
     collections::BTreeMap,
     fs::File,
     io::Read,
+    mem,
     path::Path,
     sync::{
         Arc,
@@ -22,16 +23,26 @@ use common::{
         database_index::IndexedFields,
         IndexMetadata,
     },
-    components::ComponentId,
+    components::{
+        CanonicalizedComponentFunctionPath,
+        ComponentId,
+        ComponentPath,
+        PublicFunctionPath,
+    },
     db_schema,
     http::fetch::StaticFetchClient,
     knobs::{
         ACTION_USER_TIMEOUT,
         UDF_CACHE_MAX_SIZE,
     },
+    pause::{
+        PauseController,
+        PauseGuard,
+    },
     persistence::Persistence,
     runtime::{
         new_unlimited_rate_limiter,
+        tokio_spawn,
         Runtime,
     },
     shutdown::ShutdownSignal,
@@ -39,7 +50,9 @@ use common::{
     types::{
         ConvexOrigin,
         FullyQualifiedObjectKey,
+        FunctionCaller,
     },
+    RequestId,
 };
 use database::{
     Database,
@@ -60,6 +73,10 @@ use function_runner::{
     in_process_function_runner::InProcessFunctionRunner,
     server::InstanceStorage,
 };
+use futures::{
+    future::BoxFuture,
+    FutureExt,
+};
 use isolate::{
     bundled_js::OUT_DIR,
     test_helpers::{
@@ -98,7 +115,9 @@ use node_executor::{
     Actions,
     NodeExecutor,
 };
+use serde_json::Value as JsonValue;
 use storage::Storage;
+use tokio::task::JoinHandle;
 use value::{
     ResolvedDocumentId,
     TableName,
@@ -115,8 +134,14 @@ use crate::{
         StartPushResponse,
     },
     log_visibility::RedactLogsToClient,
-    scheduled_jobs::ScheduledJobContext,
+    scheduled_jobs::{
+        ScheduledJobContext,
+        ScheduledJobKey,
+    },
     Application,
+    MutationOptions,
+    RedactedMutationError,
+    RedactedMutationReturn,
 };
 
 pub static OBJECTS_TABLE: LazyLock<TableName> = LazyLock::new(|| "objects".parse().unwrap());
@@ -157,6 +182,12 @@ pub trait ApplicationTestExt<RT: Runtime> {
         job: ScheduledJob,
         job_id: ResolvedDocumentId,
     ) -> anyhow::Result<()>;
+    /// Run all due scheduled jobs one at a time, optionally in a recorded
+    /// order, returning the order they ran in.
+    async fn test_run_due_scheduled_jobs(
+        &self,
+        replay: Option<&[ScheduledJobKey]>,
+    ) -> anyhow::Result<Vec<ScheduledJobKey>>;
     /// Load the modules from npm-packages/udf-tests
     async fn load_udf_tests_modules(&self) -> anyhow::Result<()>;
     async fn load_udf_tests_modules_with_node(&self) -> anyhow::Result<()>;
@@ -183,6 +214,105 @@ pub trait ApplicationTestExt<RT: Runtime> {
         &self,
         index: IndexMetadata<TableName>,
     ) -> anyhow::Result<ResolvedDocumentId>;
+
+    /// Run `mutations` concurrently, interleaving their attempts as `plan`
+    /// says, and report the order they committed in. This holds the
+    /// `retry_mutation_loop_start` breakpoint on `pause` for the rest of the
+    /// test.
+    async fn test_run_interleaved_mutations(
+        &self,
+        pause: &PauseController,
+        mutations: Vec<InterleavedMutation>,
+        plan: &[InterleavingStep],
+    ) -> anyhow::Result<InterleavingOutcome>;
+}
+
+/// A mutation for [`ApplicationTestExt::test_run_interleaved_mutations`].
+pub struct InterleavedMutation {
+    pub path: CanonicalizedComponentFunctionPath,
+    pub args: Vec<JsonValue>,
+}
+
+impl InterleavedMutation {
+    pub fn new(udf_path: &str, args: JsonValue) -> anyhow::Result<Self> {
+        Ok(Self {
+            path: CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: udf_path.parse()?,
+            },
+            args: vec![args],
+        })
+    }
+}
+
+/// One step of an interleaving plan. Mutations are referred to by their index
+/// in the list passed to
+/// [`ApplicationTestExt::test_run_interleaved_mutations`], and at most one of
+/// them runs at a time: every other mutation is either finished, not yet
+/// started, or held at the start of an attempt, after it's begun its
+/// transaction but before it runs any code.
+#[derive(Clone, Copy, Debug)]
+pub enum InterleavingStep {
+    /// Start the mutation and hold it at the start of its first attempt.
+    Begin(usize),
+    /// Let a held mutation run until it finishes, or until it's held again at
+    /// the start of its next attempt after an OCC.
+    Run(usize),
+}
+
+pub struct InterleavingOutcome {
+    /// Each mutation's result, in the order the mutations were passed in.
+    pub results: Vec<anyhow::Result<Result<RedactedMutationReturn, RedactedMutationError>>>,
+    /// How many attempts each mutation started.
+    pub attempts: Vec<usize>,
+    /// Indexes of the mutations that committed writes, ordered by commit
+    /// timestamp.
+    pub commit_order: Vec<usize>,
+}
+
+const INTERLEAVING_BREAKPOINT: &str = "retry_mutation_loop_start";
+const INTERLEAVING_STEP_TIMEOUT: Duration = Duration::from_secs(60);
+
+type MutationResult = anyhow::Result<Result<RedactedMutationReturn, RedactedMutationError>>;
+
+enum InterleavedMutationState {
+    NotStarted(InterleavedMutation),
+    Held(JoinHandle<MutationResult>, PauseGuard),
+    /// The mutation is the one currently running.
+    Running,
+    Finished(MutationResult),
+}
+
+enum AttemptEnd {
+    Held(PauseGuard),
+    Finished(MutationResult),
+}
+
+/// Waits for the only running mutation to either finish or start another
+/// attempt. The breakpoint's waiter is kept if the mutation finishes instead,
+/// since the breakpoint can't be held again until something hits it.
+async fn run_until_held_or_finished(
+    waiter: &mut Option<BoxFuture<'static, Option<PauseGuard>>>,
+    handle: &mut JoinHandle<MutationResult>,
+    index: usize,
+) -> anyhow::Result<AttemptEnd> {
+    let blocked = waiter.as_mut().context("Breakpoint isn't held")?;
+    let end = tokio::time::timeout(INTERLEAVING_STEP_TIMEOUT, async {
+        tokio::select! {
+            guard = blocked => guard
+                .map(AttemptEnd::Held)
+                .context("Breakpoint was closed"),
+            result = handle => result
+                .map(AttemptEnd::Finished)
+                .with_context(|| format!("Mutation {index} panicked")),
+        }
+    })
+    .await
+    .with_context(|| format!("Mutation {index} neither finished nor started another attempt"))??;
+    if let AttemptEnd::Held(_) = end {
+        *waiter = None;
+    }
+    Ok(end)
 }
 
 #[async_trait]
@@ -313,6 +443,19 @@ impl<RT: Runtime> ApplicationTestExt<RT> for Application<RT> {
         Ok(())
     }
 
+    async fn test_run_due_scheduled_jobs(
+        &self,
+        replay: Option<&[ScheduledJobKey]>,
+    ) -> anyhow::Result<Vec<ScheduledJobKey>> {
+        let test_executor = ScheduledJobContext::new(
+            self.runtime.clone(),
+            self.database.clone(),
+            self.runner.clone(),
+            self.function_log.clone(),
+        );
+        test_executor.run_due_jobs_in_order(replay).await
+    }
+
     async fn test_one_off_cron_job_executor_run(&self, job: CronJob) -> anyhow::Result<()> {
         let test_executor = CronJobContext::new(
             self.runtime.clone(),
@@ -421,6 +564,109 @@ impl<RT: Runtime> ApplicationTestExt<RT> for Application<RT> {
         self.commit_test(tx).await?;
         Ok(index_id)
     }
+
+    async fn test_run_interleaved_mutations(
+        &self,
+        pause: &PauseController,
+        mutations: Vec<InterleavedMutation>,
+        plan: &[InterleavingStep],
+    ) -> anyhow::Result<InterleavingOutcome> {
+        let mut attempts = vec![0; mutations.len()];
+        let mut states: Vec<_> = mutations
+            .into_iter()
+            .map(InterleavedMutationState::NotStarted)
+            .collect();
+        let mut waiter = None;
+        let mut steps = plan.iter().copied();
+        loop {
+            // Once the plan runs out, let held mutations finish in order.
+            let step = match steps.next() {
+                Some(step) => step,
+                None => match states
+                    .iter()
+                    .position(|state| matches!(state, InterleavedMutationState::Held(..)))
+                {
+                    Some(index) => InterleavingStep::Run(index),
+                    None => break,
+                },
+            };
+            let index = match step {
+                InterleavingStep::Begin(index) | InterleavingStep::Run(index) => index,
+            };
+            let state = states
+                .get_mut(index)
+                .with_context(|| format!("{step:?} refers to a mutation that doesn't exist"))?;
+            // Hold the breakpoint before letting the mutation run so we can't
+            // miss it starting its next attempt.
+            waiter.get_or_insert_with(|| {
+                pause
+                    .hold(INTERLEAVING_BREAKPOINT)
+                    .wait_for_blocked()
+                    .boxed()
+            });
+            let mut handle = match (step, mem::replace(state, InterleavedMutationState::Running)) {
+                (
+                    InterleavingStep::Begin(_),
+                    InterleavedMutationState::NotStarted(InterleavedMutation { path, args }),
+                ) => {
+                    let application = self.clone();
+                    tokio_spawn("interleaved_mutation", async move {
+                        application
+                            .mutation_udf(
+                                RequestId::new(),
+                                PublicFunctionPath::Component(path),
+                                args,
+                                Identity::system(),
+                                None,
+                                FunctionCaller::Test,
+                                None,
+                                MutationOptions::default(),
+                            )
+                            .await
+                    })
+                },
+                (InterleavingStep::Run(_), InterleavedMutationState::Held(handle, guard)) => {
+                    guard.unpause();
+                    handle
+                },
+                (InterleavingStep::Begin(_), _) => {
+                    anyhow::bail!("Mutation {index} was already started")
+                },
+                (InterleavingStep::Run(_), _) => anyhow::bail!("Mutation {index} isn't held"),
+            };
+            *state = match run_until_held_or_finished(&mut waiter, &mut handle, index).await? {
+                AttemptEnd::Held(guard) => {
+                    attempts[index] += 1;
+                    InterleavedMutationState::Held(handle, guard)
+                },
+                AttemptEnd::Finished(result) => InterleavedMutationState::Finished(result),
+            };
+        }
+
+        let mut results = vec![];
+        for (index, state) in states.into_iter().enumerate() {
+            let InterleavedMutationState::Finished(result) = state else {
+                anyhow::bail!("The plan never started mutation {index}");
+            };
+            results.push(result);
+        }
+        let mut commits: Vec<_> = results
+            .iter()
+            .enumerate()
+            .filter_map(|(index, result)| match result {
+                Ok(Ok(mutation_return)) if mutation_return.committed_writes => {
+                    Some((mutation_return.ts, index))
+                },
+                _ => None,
+            })
+            .collect();
+        commits.sort();
+        Ok(InterleavingOutcome {
+            results,
+            attempts,
+            commit_order: commits.into_iter().map(|(_, index)| index).collect(),
+        })
+    }
 }
 
 impl<RT: Runtime> Application<RT> {
@@ -0,0 +1,201 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use common::{
+    components::{
+        CanonicalizedComponentFunctionPath,
+        ComponentPath,
+        PublicFunctionPath,
+    },
+    knobs::{
+        ACTION_USER_TIMEOUT,
+        V8_ACTION_SYSTEM_TIMEOUT,
+    },
+    runtime::Runtime,
+    types::{
+        FunctionCaller,
+        UdfType,
+    },
+    RequestId,
+};
+use keybroker::Identity;
+use model::modules::{
+    module_versions::FunctionTimeouts,
+    ModuleModel,
+};
+use runtime::testing::TestRuntime;
+use serde_json::json;
+use tokio::sync::oneshot;
+
+use crate::{
+    test_helpers::ApplicationTestExt,
+    Application,
+};
+
+#[convex_macro::test_runtime]
+async fn test_cancel_action(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    let (cancel_tx, cancel_rx) = oneshot::channel();
+    let start = rt.monotonic_now();
+    let action = application.action_udf_with_cancellation(
+        RequestId::new(),
+        PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+            component: ComponentPath::test_user(),
+            udf_path: "action:sleep".parse()?,
+        }),
+        vec![json!({ "ms": ACTION_USER_TIMEOUT.as_millis() as f64 * 2.0 })],
+        Identity::system(),
+        FunctionCaller::HttpEndpoint,
+        None,
+        async move {
+            _ = cancel_rx.await;
+        },
+    );
+    let cancel = async {
+        rt.wait(Duration::from_secs(1)).await;
+        _ = cancel_tx.send(());
+    };
+    let (result, ()) = tokio::join!(action, cancel);
+
+    let err = result?.expect_err("Expected the action to be cancelled");
+    assert!(err.error.to_string().contains("cancelled"), "{}", err.error);
+    assert!(rt.monotonic_now() - start < *ACTION_USER_TIMEOUT);
+
+    // The cancelled action is logged as a failed execution.
+    let (function_log, _) = application.function_log().stream(0.0).await;
+    let execution = function_log
+        .iter()
+        .find(|execution| execution.udf_type == UdfType::Action)
+        .context("Missing action execution")?;
+    assert!(execution.params.is_err());
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_per_function_timeout(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let path = |udf_path: &str| -> anyhow::Result<_> {
+        Ok(CanonicalizedComponentFunctionPath {
+            component: ComponentPath::test_user(),
+            udf_path: udf_path.parse()?,
+        })
+    };
+
+    let mut tx = application.begin(Identity::system()).await?;
+    ModuleModel::new(&mut tx)
+        .set_function_timeouts(
+            path("action:sleep")?,
+            FunctionTimeouts {
+                user: Some(Duration::from_secs(1)),
+                system: None,
+            },
+        )
+        .await?;
+    application.commit_test(tx).await?;
+
+    let sleep = |udf_path: &'static str| {
+        let application = application.clone();
+        async move {
+            application
+                .action_udf(
+                    RequestId::new(),
+                    path(udf_path)?,
+                    vec![json!({ "ms": 2000.0 })],
+                    Identity::system(),
+                    FunctionCaller::HttpEndpoint,
+                )
+                .await
+        }
+    };
+    let err = sleep("action:sleep")
+        .await?
+        .expect_err("Expected the action to time out");
+    assert!(
+        err.error
+            .to_string()
+            .contains("Function execution timed out"),
+        "{err:?}"
+    );
+    // Other functions keep the default timeout.
+    sleep("action:sleepAgain")
+        .await?
+        .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+
+    // The timeout is kept when the modules are pushed again.
+    application.load_udf_tests_modules().await?;
+    let err = sleep("action:sleep")
+        .await?
+        .expect_err("Expected the action to time out after the push");
+    assert!(
+        err.error
+            .to_string()
+            .contains("Function execution timed out"),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_per_function_timeout_clamped(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let path = CanonicalizedComponentFunctionPath {
+        component: ComponentPath::test_user(),
+        udf_path: "action:sleep".parse()?,
+    };
+
+    let mut tx = application.begin(Identity::system()).await?;
+    let too_long = *V8_ACTION_SYSTEM_TIMEOUT * 10;
+    ModuleModel::new(&mut tx)
+        .set_function_timeouts(
+            path.clone(),
+            FunctionTimeouts {
+                user: Some(too_long),
+                system: Some(too_long),
+            },
+        )
+        .await?;
+    application.commit_test(tx).await?;
+
+    let mut tx = application.begin(Identity::system()).await?;
+    let function = ModuleModel::new(&mut tx)
+        .get_analyzed_function(&path)
+        .await??;
+    assert_eq!(
+        function.timeouts,
+        FunctionTimeouts {
+            user: Some(*V8_ACTION_SYSTEM_TIMEOUT),
+            system: Some(*V8_ACTION_SYSTEM_TIMEOUT),
+        }
+    );
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_action_timing(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    let result = application
+        .action_udf(
+            RequestId::new(),
+            CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "action:sleep".parse()?,
+            },
+            vec![json!({ "ms": 500.0 })],
+            Identity::system(),
+            FunctionCaller::HttpEndpoint,
+        )
+        .await?
+        .map_err(|e| anyhow::anyhow!("{e:?}"))?;
+    assert!(result.timing.total >= Duration::from_millis(500));
+    assert_eq!(result.timing.syscalls, Duration::ZERO);
+    // The sleep is spent waiting on a timer, not running JS.
+    assert!(result.timing.async_op_wait >= Duration::from_millis(500));
+    assert!(result.timing.js < Duration::from_millis(500));
+    Ok(())
+}
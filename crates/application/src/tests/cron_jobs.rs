@@ -100,6 +100,7 @@ fn cron_log_query<RT: Runtime>(
                 common::types::MaybeValue(Some(test_cron_identifier().to_string().try_into()?)),
             )],
             order: Order::Asc,
+            collation: None,
         }),
         TableFilter::IncludePrivateSystemTables,
     )
@@ -0,0 +1,27 @@
+use common::pause::PauseController;
+use runtime::testing::TestRuntime;
+
+use crate::{
+    health_check::HealthStatus,
+    test_helpers::ApplicationTestExt,
+    Application,
+};
+
+#[convex_macro::test_runtime]
+async fn test_health_check_ok(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    assert_eq!(application.health_check().await, HealthStatus::Ok);
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_health_check_times_out_when_stalled(
+    rt: TestRuntime,
+    pause_controller: PauseController,
+) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    // Stall the probe query before it runs so it can't finish in time.
+    let _hold_guard = pause_controller.hold("run_function");
+    assert_eq!(application.health_check().await, HealthStatus::Timeout);
+    Ok(())
+}
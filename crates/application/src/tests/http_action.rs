@@ -109,6 +109,54 @@ async fn test_http_action_basic(rt: TestRuntime) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[convex_macro::test_runtime]
+async fn test_http_action_request_metadata(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application
+        .load_component_tests_modules("http_actions")
+        .await?;
+
+    let mut headers = HeaderMap::new();
+    headers.append("x-signature", "first".parse()?);
+    headers.append("x-other", "other".parse()?);
+    headers.append("x-signature", "second".parse()?);
+    let head = HttpActionRequestHead {
+        headers,
+        url: Url::parse("http://127.0.0.1:8001/requestMetadata")?,
+        method: Method::GET,
+    };
+    let http_request = HttpActionRequest { head, body: None };
+
+    let (response_sender, mut response_receiver) = mpsc::unbounded_channel();
+    let response_streamer = HttpActionResponseStreamer::new(response_sender);
+    application
+        .http_action_udf(
+            common::RequestId::new(),
+            http_request,
+            Identity::system(),
+            FunctionCaller::HttpEndpoint,
+            response_streamer,
+        )
+        .await?;
+
+    let mut body = Vec::new();
+    while let Some(part) = response_receiver.recv().await {
+        if let HttpActionResponsePart::BodyChunk(chunk) = part {
+            body.extend_from_slice(&chunk);
+        }
+    }
+    let body: serde_json::Value = serde_json::from_slice(&body)?;
+    assert_eq!(
+        body,
+        json!({
+            "method": "GET",
+            "path": "/requestMetadata",
+            "signatures": ["first", "second"],
+        })
+    );
+    Ok(())
+}
+
 #[convex_macro::test_runtime]
 async fn test_http_action_error(rt: TestRuntime) -> anyhow::Result<()> {
     let application = Application::new_for_tests(&rt).await?;
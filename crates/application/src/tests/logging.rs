@@ -10,6 +10,7 @@ use common::{
         PublicFunctionPath,
     },
     knobs,
+    log_lines::LogLevel,
     log_streaming::StructuredLogEvent,
     runtime::{
         testing::TestRuntime,
@@ -38,6 +39,7 @@ use must_let::must_let;
 use crate::{
     test_helpers::ApplicationTestExt,
     Application,
+    MutationOptions,
 };
 
 #[convex_macro::test_runtime]
@@ -110,3 +112,31 @@ async fn test_udf_logs(rt: TestRuntime) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[convex_macro::test_runtime]
+async fn test_mutation_min_log_level(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let result = application
+        .mutation_udf(
+            RequestId::new(),
+            PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "logging:logAtEachLevel".parse()?,
+            }),
+            vec![],
+            Identity::system(),
+            None,
+            FunctionCaller::HttpEndpoint,
+            None,
+            MutationOptions {
+                min_log_level: Some(LogLevel::Warn),
+                ..Default::default()
+            },
+        )
+        .await?
+        .map_err(|e| anyhow::anyhow!("Mutation failed: {e:?}"))?;
+    let log_lines: Vec<_> = result.log_lines.iter().cloned().collect();
+    assert_eq!(log_lines, vec!["[WARN] 'warn'", "[ERROR] 'error'"]);
+    Ok(())
+}
@@ -1,3 +1,4 @@
+mod action;
 mod airbyte_import;
 mod analyze;
 mod auth;
@@ -6,11 +7,13 @@ pub mod components;
 mod cron_jobs;
 mod environment_variables;
 mod fivetran_import;
+mod health_check;
 mod http_action;
 mod indexes;
 mod logging;
 mod mutation;
 mod occ_retries;
+mod paginated_query;
 mod push;
 mod query_cache;
 mod returns_validation;
@@ -1,4 +1,7 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Context;
 use common::{
@@ -7,9 +10,22 @@ use common::{
         ComponentPath,
         PublicFunctionPath,
     },
-    knobs::UDF_EXECUTOR_OCC_MAX_RETRIES,
+    knobs::{
+        TRANSACTION_MAX_NUM_USER_WRITES,
+        UDF_EXECUTOR_OCC_INITIAL_BACKOFF,
+        UDF_EXECUTOR_OCC_MAX_BACKOFF,
+        UDF_EXECUTOR_OCC_MAX_RETRIES,
+    },
     pause::PauseController,
-    types::FunctionCaller,
+    runtime::{
+        Runtime,
+        UnixTimestamp,
+    },
+    types::{
+        AllowedVisibility,
+        FunctionCaller,
+        Timestamp,
+    },
     RequestId,
 };
 use errors::ErrorMetadataAnyhowExt;
@@ -21,18 +37,43 @@ use events::{
     },
 };
 use keybroker::Identity;
+use model::session_requests::types::MAX_IDEMPOTENCY_KEY_LENGTH;
+use parking_lot::Mutex;
+use rand::RngCore;
 use runtime::testing::TestRuntime;
 use serde_json::{
     json,
     Value as JsonValue,
 };
+use value::{
+    assert_obj,
+    id_v6::DeveloperDocumentId,
+    ConvexArray,
+    ConvexValue,
+    TableNamespace,
+};
 
 use crate::{
+    application_function_runner::{
+        OccRetryObserver,
+        OccRetryOutcome,
+        OccRetryPolicy,
+        RetryPolicy,
+    },
+    function_log::OccStats,
     test_helpers::{
         ApplicationFixtureArgs,
         ApplicationTestExt,
+        InterleavedMutation,
+        InterleavingStep,
     },
     Application,
+    DryRunWriteKind,
+    MutationOptions,
+    MutationThenSubscribeReturn,
+    OccRetriesExhausted,
+    RedactedMutationError,
+    RedactedMutationReturn,
 };
 
 async fn insert_object(application: &Application<TestRuntime>) -> anyhow::Result<JsonValue> {
@@ -52,12 +93,24 @@ async fn insert_object(application: &Application<TestRuntime>) -> anyhow::Result
                 parent_execution_id: None,
             },
             None,
+            MutationOptions::default(),
         )
         .await??;
     Ok(result.value.json_value())
 }
 
 async fn insert_and_count(application: &Application<TestRuntime>) -> anyhow::Result<usize> {
+    let result = insert_and_count_return(application).await?;
+    Ok(result
+        .value
+        .json_value()
+        .as_f64()
+        .context("Expected f64 result")? as usize)
+}
+
+async fn insert_and_count_return(
+    application: &Application<TestRuntime>,
+) -> anyhow::Result<RedactedMutationReturn> {
     let obj = json!({"an": "object"});
     let result = application
         .mutation_udf(
@@ -74,13 +127,79 @@ async fn insert_and_count(application: &Application<TestRuntime>) -> anyhow::Res
                 parent_execution_id: None,
             },
             None,
+            MutationOptions::default(),
         )
         .await??;
-    Ok(result
-        .value
-        .json_value()
-        .as_f64()
-        .context("Expected f64 result")? as usize)
+    Ok(result)
+}
+
+async fn allocate_sequence_id(application: &Application<TestRuntime>) -> anyhow::Result<i64> {
+    let result = application
+        .mutation_udf(
+            RequestId::new(),
+            PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "sequences:allocate".parse()?,
+            }),
+            vec![json!({})],
+            Identity::system(),
+            None,
+            FunctionCaller::Action {
+                parent_scheduled_job: None,
+                parent_execution_id: None,
+            },
+            None,
+            MutationOptions::default(),
+        )
+        .await??;
+    match result.value.unpack() {
+        ConvexValue::Int64(id) => Ok(id),
+        v => anyhow::bail!("Expected int64 result, got {v:?}"),
+    }
+}
+
+async fn increment_counter(application: &Application<TestRuntime>) -> anyhow::Result<()> {
+    application
+        .mutation_udf(
+            RequestId::new(),
+            PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "counters:increment".parse()?,
+            }),
+            vec![json!({})],
+            Identity::system(),
+            None,
+            FunctionCaller::Action {
+                parent_scheduled_job: None,
+                parent_execution_id: None,
+            },
+            None,
+            MutationOptions::default(),
+        )
+        .await??;
+    Ok(())
+}
+
+async fn get_counter(application: &Application<TestRuntime>) -> anyhow::Result<i64> {
+    let result = application
+        .read_only_udf(
+            RequestId::new(),
+            PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "counters:get".parse()?,
+            }),
+            vec![json!({})],
+            Identity::system(),
+            FunctionCaller::HttpEndpoint,
+        )
+        .await?;
+    match result.result {
+        Ok(value) => match value.unpack() {
+            ConvexValue::Int64(value) => Ok(value),
+            v => anyhow::bail!("Expected int64 result, got {v:?}"),
+        },
+        Err(e) => anyhow::bail!("Query failed: {e:?}"),
+    }
 }
 
 #[convex_macro::test_runtime]
@@ -108,8 +227,8 @@ async fn test_mutation_occ_fail(rt: TestRuntime, pause: PauseController) -> anyh
         let mut hold_guard = hold_guard;
         for i in 0..*UDF_EXECUTOR_OCC_MAX_RETRIES + 1 {
             let guard = hold_guard
-                .wait_for_blocked()
-                .await
+                .wait_for_blocked_with_timeout(Duration::from_secs(60))
+                .await?
                 .context("Didn't hit breakpoint?")?;
 
             // Do an entire mutation while we're paused - to create an OCC conflict on
@@ -124,6 +243,28 @@ async fn test_mutation_occ_fail(rt: TestRuntime, pause: PauseController) -> anyh
     };
     let err = futures::try_join!(fut1, fut2).unwrap_err();
     assert!(err.is_occ());
+    let stats = err
+        .downcast_ref::<OccRetriesExhausted>()
+        .context("Missing retry stats")?;
+    assert_eq!(stats.occ_retries, *UDF_EXECUTOR_OCC_MAX_RETRIES);
+    // The stats don't change what the error says.
+    assert!(err
+        .to_string()
+        .starts_with("Documents read from or written to the \"objects\" table"));
+    // The last attempt lost to another run of the same function, inserting
+    // into the table it counts.
+    let occ_info = err.occ_info().context("Missing OCC info")?;
+    assert_eq!(occ_info.table_name.as_deref(), Some("objects"));
+    assert!(occ_info.document_id.is_some());
+    assert_eq!(
+        occ_info.write_source.as_deref(),
+        Some("basic.js:insertAndCount")
+    );
+    // Every attempt of the original mutation, plus each conflicting one.
+    assert_eq!(
+        pause.hit_count("retry_mutation_loop_start"),
+        (*UDF_EXECUTOR_OCC_MAX_RETRIES + 1) * 2
+    );
 
     // Test that the usage events look good.
     let function_call_events: Vec<FunctionCallUsageFields> = logger
@@ -173,6 +314,128 @@ async fn test_mutation_occ_fail(rt: TestRuntime, pause: PauseController) -> anyh
     Ok(())
 }
 
+#[convex_macro::test_runtime]
+async fn test_mutation_occ_retry_policy_override(
+    rt: TestRuntime,
+    pause: PauseController,
+) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    let hold_guard = pause.hold("retry_mutation_loop_start");
+    let fut1 = application.mutation_udf(
+        RequestId::new(),
+        PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+            component: ComponentPath::test_user(),
+            udf_path: "basic:insertAndCount".parse()?,
+        }),
+        vec![json!({"an": "object"})],
+        Identity::system(),
+        None,
+        FunctionCaller::HttpEndpoint,
+        None,
+        MutationOptions {
+            occ_retry_policy: Some(OccRetryPolicy {
+                max_attempts: 1,
+                max_total_duration: Duration::from_secs(60),
+            }),
+            ..Default::default()
+        },
+    );
+    let fut2 = async {
+        let guard = hold_guard
+            .wait_for_blocked_with_timeout(Duration::from_secs(60))
+            .await?
+            .context("Didn't hit breakpoint?")?;
+        insert_and_count(&application).await?;
+        guard.unpause();
+        Ok::<_, anyhow::Error>(())
+    };
+    let (result, conflict) = futures::join!(fut1, fut2);
+    conflict?;
+    let err = result.unwrap_err();
+    assert!(err.is_occ());
+    let stats = err
+        .downcast_ref::<OccRetriesExhausted>()
+        .context("Missing retry stats")?;
+    assert_eq!(stats.occ_retries, 0);
+    // The original mutation's only attempt, plus the conflicting one.
+    assert_eq!(pause.hit_count("retry_mutation_loop_start"), 2);
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum OccRetryEvent {
+    OccError {
+        attempt: usize,
+    },
+    Finished {
+        attempts: usize,
+        outcome: OccRetryOutcome,
+    },
+}
+
+#[derive(Default)]
+struct RecordingOccRetryObserver {
+    events: Mutex<Vec<OccRetryEvent>>,
+}
+
+impl OccRetryObserver for RecordingOccRetryObserver {
+    fn on_occ_error(&self, _path: &CanonicalizedComponentFunctionPath, attempt: usize) {
+        self.events.lock().push(OccRetryEvent::OccError { attempt });
+    }
+
+    fn on_finished(
+        &self,
+        _path: &CanonicalizedComponentFunctionPath,
+        attempts: usize,
+        outcome: OccRetryOutcome,
+    ) {
+        self.events
+            .lock()
+            .push(OccRetryEvent::Finished { attempts, outcome });
+    }
+}
+
+#[convex_macro::test_runtime]
+async fn test_occ_retry_observer(rt: TestRuntime, pause: PauseController) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let observer = Arc::new(RecordingOccRetryObserver::default());
+    application.set_occ_retry_observer(Some(observer.clone()));
+
+    let hold_guard = pause.hold("retry_mutation_loop_start");
+    let fut1 = insert_and_count(&application);
+    let fut2 = async {
+        let mut hold_guard = hold_guard;
+        for _ in 0..*UDF_EXECUTOR_OCC_MAX_RETRIES + 1 {
+            let guard = hold_guard
+                .wait_for_blocked_with_timeout(Duration::from_secs(60))
+                .await?
+                .context("Didn't hit breakpoint?")?;
+            insert_and_count(&application).await?;
+            hold_guard = pause.hold("retry_mutation_loop_start");
+            guard.unpause();
+        }
+        Ok::<_, anyhow::Error>(())
+    };
+    let err = futures::try_join!(fut1, fut2).unwrap_err();
+    assert!(err.is_occ());
+
+    // The conflicting mutations commit on their first attempt, so every event
+    // comes from the original one.
+    let attempts = *UDF_EXECUTOR_OCC_MAX_RETRIES + 1;
+    let mut expected: Vec<_> = (1..=attempts)
+        .map(|attempt| OccRetryEvent::OccError { attempt })
+        .collect();
+    expected.push(OccRetryEvent::Finished {
+        attempts,
+        outcome: OccRetryOutcome::GaveUp,
+    });
+    assert_eq!(*observer.events.lock(), expected);
+    Ok(())
+}
+
 #[convex_macro::test_runtime]
 async fn test_mutation_occ_success(rt: TestRuntime, pause: PauseController) -> anyhow::Result<()> {
     let logger = BasicTestUsageEventLogger::new();
@@ -260,34 +523,1300 @@ async fn test_mutation_occ_success(rt: TestRuntime, pause: PauseController) -> a
 }
 
 #[convex_macro::test_runtime]
-async fn test_multiple_inserts_dont_occ(
+async fn test_mutation_reports_occ_retries(
     rt: TestRuntime,
     pause: PauseController,
 ) -> anyhow::Result<()> {
     let application = Application::new_for_tests(&rt).await?;
     application.load_udf_tests_modules().await?;
 
-    // Insert an object to create the table (otherwise it'll OCC on table creation).
-    insert_object(&application).await?;
+    let result = insert_and_count_return(&application).await?;
+    assert_eq!(result.occ_retries, 0);
+    assert_eq!(result.occ_retry_time, Duration::ZERO);
+    assert!(result.committed_writes);
 
+    let num_conflicts = 2;
     let hold_guard = pause.hold("retry_mutation_loop_start");
-    let fut1 = insert_object(&application);
+    let fut1 = insert_and_count_return(&application);
     let fut2 = async {
-        let guard = hold_guard
-            .wait_for_blocked()
-            .await
-            .context("Didn't hit breakpoint?")?;
+        let mut hold_guard = hold_guard;
+        for i in 0..num_conflicts + 1 {
+            let guard = hold_guard
+                .wait_for_blocked()
+                .await
+                .context("Didn't hit breakpoint?")?;
+            // Commit a conflicting mutation while paused for all but the last
+            // attempt.
+            if i < num_conflicts {
+                insert_and_count(&application).await?;
+            }
+            hold_guard = pause.hold("retry_mutation_loop_start");
+            guard.unpause();
+        }
+        Ok::<_, anyhow::Error>(())
+    };
+    let (result, ()) = futures::try_join!(fut1, fut2)?;
+    assert_eq!(result.occ_retries, num_conflicts);
+    assert!(result.committed_writes);
+    Ok(())
+}
 
-        // Do several entire mutations while we're paused. Shouldn't OCC.
-        for _ in 0..5 {
-            let result = insert_object(&application).await?;
-            assert_eq!(result["an"], "object");
+#[convex_macro::test_runtime]
+async fn test_mutation_occ_backoff(rt: TestRuntime, pause: PauseController) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    // Record the (paused) runtime clock each time the mutation starts an
+    // attempt.
+    let hold_guard = pause.hold("retry_mutation_loop_start");
+    let fut1 = insert_and_count_return(&application);
+    let fut2 = async {
+        let mut hold_guard = hold_guard;
+        let mut attempt_starts = vec![];
+        for i in 0..*UDF_EXECUTOR_OCC_MAX_RETRIES + 1 {
+            let guard = hold_guard
+                .wait_for_blocked()
+                .await
+                .context("Didn't hit breakpoint?")?;
+            attempt_starts.push(rt.monotonic_now());
+            if i < *UDF_EXECUTOR_OCC_MAX_RETRIES {
+                insert_and_count(&application).await?;
+            }
+            hold_guard = pause.hold("retry_mutation_loop_start");
+            guard.unpause();
         }
+        Ok::<_, anyhow::Error>(attempt_starts)
+    };
+    let (result, attempt_starts) = futures::try_join!(fut1, fut2)?;
+    assert_eq!(result.occ_retries, *UDF_EXECUTOR_OCC_MAX_RETRIES);
 
-        guard.unpause();
+    // Each backoff is jittered below a cap that doubles with every conflict.
+    for (i, window) in attempt_starts.windows(2).enumerate() {
+        let cap = (*UDF_EXECUTOR_OCC_INITIAL_BACKOFF * 2u32.pow(i as u32))
+            .min(*UDF_EXECUTOR_OCC_MAX_BACKOFF);
+        let backoff = window[1] - window[0];
+        assert!(backoff <= cap, "Backoff {i} was {backoff:?}, above {cap:?}");
+    }
+    Ok(())
+}
+
+struct GiveUpAfter(usize);
+
+impl RetryPolicy for GiveUpAfter {
+    fn should_retry(
+        &self,
+        attempt: usize,
+        _elapsed: Duration,
+        _rng: &mut dyn RngCore,
+    ) -> Option<Duration> {
+        (attempt < self.0).then_some(Duration::ZERO)
+    }
+}
+
+#[convex_macro::test_runtime]
+async fn test_mutation_retry_policy(rt: TestRuntime, pause: PauseController) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    application.set_function_retry_policy(
+        CanonicalizedComponentFunctionPath {
+            component: ComponentPath::test_user(),
+            udf_path: "basic:insertAndCount".parse()?,
+        },
+        Arc::new(GiveUpAfter(2)),
+    );
+
+    // Conflict with both attempts, after which the policy gives up.
+    let hold_guard = pause.hold("retry_mutation_loop_start");
+    let fut1 = insert_and_count(&application);
+    let fut2 = async {
+        let mut hold_guard = hold_guard;
+        for i in 0..2 {
+            let guard = hold_guard
+                .wait_for_blocked()
+                .await
+                .context("Didn't hit breakpoint?")?;
+            let count = insert_and_count(&application).await?;
+            assert_eq!(count, i + 1);
+            hold_guard = pause.hold("retry_mutation_loop_start");
+            guard.unpause();
+        }
         Ok::<_, anyhow::Error>(())
     };
-    let (result, ()) = futures::try_join!(fut1, fut2)?;
-    assert_eq!(result["an"], "object");
+    let err = futures::try_join!(fut1, fut2).unwrap_err();
+    assert!(err.is_occ());
+    Ok(())
+}
+
+async fn patch_object(
+    application: &Application<TestRuntime>,
+    id: DeveloperDocumentId,
+    conflict_hint: Vec<DeveloperDocumentId>,
+) -> anyhow::Result<()> {
+    application
+        .mutation_udf(
+            RequestId::new(),
+            PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "basic:patchObject".parse()?,
+            }),
+            vec![json!({"id": id.to_string(), "obj": {"patched": true}})],
+            Identity::system(),
+            None,
+            FunctionCaller::Action {
+                parent_scheduled_job: None,
+                parent_execution_id: None,
+            },
+            None,
+            MutationOptions {
+                conflict_hint,
+                ..Default::default()
+            },
+        )
+        .await??;
+    Ok(())
+}
+
+async fn random_mutation(
+    application: &Application<TestRuntime>,
+    rng_seed: Option<[u8; 32]>,
+) -> anyhow::Result<f64> {
+    let result = application
+        .mutation_udf(
+            RequestId::new(),
+            PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "basic:randomMutation".parse()?,
+            }),
+            vec![json!({})],
+            Identity::system(),
+            None,
+            FunctionCaller::Action {
+                parent_scheduled_job: None,
+                parent_execution_id: None,
+            },
+            None,
+            MutationOptions {
+                rng_seed,
+                ..Default::default()
+            },
+        )
+        .await??;
+    result.value.json_value().as_f64().context("Expected f64")
+}
+
+async fn run_insert_and_count_with_key(
+    application: &Application<TestRuntime>,
+    identity: Identity,
+    idempotency_key: &str,
+) -> anyhow::Result<Result<RedactedMutationReturn, RedactedMutationError>> {
+    application
+        .mutation_udf(
+            RequestId::new(),
+            PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "basic:insertAndCount".parse()?,
+            }),
+            vec![json!({"an": "object"})],
+            identity,
+            None,
+            FunctionCaller::HttpEndpoint,
+            None,
+            MutationOptions {
+                idempotency_key: Some(idempotency_key.to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+}
+
+async fn insert_and_count_with_key(
+    application: &Application<TestRuntime>,
+    idempotency_key: &str,
+) -> anyhow::Result<f64> {
+    let result =
+        run_insert_and_count_with_key(application, Identity::system(), idempotency_key).await??;
+    result
+        .value
+        .json_value()
+        .as_f64()
+        .context("Expected f64 result")
+}
+
+#[convex_macro::test_runtime]
+async fn test_mutation_idempotency_key(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    assert_eq!(insert_and_count_with_key(&application, "a").await?, 1.0);
+    // A retry with the same key returns the first call's result.
+    assert_eq!(insert_and_count_with_key(&application, "a").await?, 1.0);
+    assert_eq!(insert_and_count_with_key(&application, "b").await?, 2.0);
+    assert_eq!(
+        count_objects(&application).await?,
+        ConvexValue::Float64(2.0)
+    );
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_mutation_idempotency_key_rejected(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    // Anonymous callers would all share one key scope.
+    let err = run_insert_and_count_with_key(&application, Identity::Unknown(None), "a")
+        .await?
+        .unwrap_err();
+    assert!(
+        err.error.to_string().contains("authenticated callers"),
+        "{err:?}"
+    );
+    let long_key = "k".repeat(MAX_IDEMPOTENCY_KEY_LENGTH + 1);
+    let err = run_insert_and_count_with_key(&application, Identity::system(), &long_key)
+        .await?
+        .unwrap_err();
+    assert!(err.error.to_string().contains("maximum"), "{err:?}");
+    assert_eq!(
+        count_objects(&application).await?,
+        ConvexValue::Float64(0.0)
+    );
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_mutation_idempotency_key_concurrent_calls(
+    rt: TestRuntime,
+    pause: PauseController,
+) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    let hold_guard = pause.hold("retry_mutation_loop_start");
+    let fut1 = insert_and_count_with_key(&application, "key");
+    let fut2 = async {
+        let guard = hold_guard
+            .wait_for_blocked_with_timeout(Duration::from_secs(60))
+            .await?
+            .context("Didn't hit breakpoint?")?;
+        // Commit a call with the same key while the first is paused.
+        let count = insert_and_count_with_key(&application, "key").await?;
+        guard.unpause();
+        Ok::<_, anyhow::Error>(count)
+    };
+    let (first, second) = futures::try_join!(fut1, fut2)?;
+    // The first call conflicts on the key, retries, and finds the second's
+    // result rather than inserting again.
+    assert_eq!(first, 1.0);
+    assert_eq!(second, 1.0);
+    assert_eq!(pause.hit_count("retry_mutation_loop_start"), 3);
+    assert_eq!(
+        count_objects(&application).await?,
+        ConvexValue::Float64(1.0)
+    );
+    Ok(())
+}
+
+fn batch_entry(udf_path: &str) -> anyhow::Result<(PublicFunctionPath, Vec<JsonValue>)> {
+    let path = PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+        component: ComponentPath::test_user(),
+        udf_path: udf_path.parse()?,
+    });
+    Ok((path, vec![json!({"an": "object"})]))
+}
+
+async fn count_objects(application: &Application<TestRuntime>) -> anyhow::Result<ConvexValue> {
+    Ok(application
+        .read_only_udf(
+            RequestId::new(),
+            CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "basic:count".parse()?,
+            },
+            vec![json!({})],
+            Identity::system(),
+            FunctionCaller::HttpEndpoint,
+        )
+        .await?
+        .result
+        .map_err(|e| anyhow::anyhow!("Query failed: {e:?}"))?
+        .unpack())
+}
+
+#[convex_macro::test_runtime]
+async fn test_mutation_batch_rolls_back_on_error(rt: TestRuntime) -> anyhow::Result<()> {
+    let logger = BasicTestUsageEventLogger::new();
+    let application = Application::new_for_tests_with_args(
+        &rt,
+        ApplicationFixtureArgs::with_event_logger(Arc::new(logger.clone())),
+    )
+    .await?;
+    application.load_udf_tests_modules().await?;
+    let err = application
+        .mutation_udf_batch(
+            RequestId::new(),
+            vec![
+                batch_entry("basic:insertObject")?,
+                batch_entry("basic:insertObject")?,
+                (
+                    batch_entry("custom_errors:mutationThrows")?.0,
+                    vec![json!({})],
+                ),
+            ],
+            Identity::system(),
+            AllowedVisibility::All,
+            FunctionCaller::HttpEndpoint,
+        )
+        .await?
+        .unwrap_err();
+    assert_eq!(err.index, 2);
+    assert_eq!(
+        count_objects(&application).await?,
+        ConvexValue::Float64(0.0)
+    );
+    // The mutations that ran before the failed one are still logged.
+    let udf_ids: Vec<String> = logger
+        .collect()
+        .into_iter()
+        .filter_map(|event| match event {
+            UsageEvent::FunctionCall { fields } => Some(fields.udf_id),
+            _ => None,
+        })
+        .filter(|udf_id| {
+            ["basic.js:insertObject", "custom_errors.js:mutationThrows"].contains(&udf_id.as_str())
+        })
+        .collect();
+    assert_eq!(
+        udf_ids,
+        vec![
+            "basic.js:insertObject",
+            "basic.js:insertObject",
+            "custom_errors.js:mutationThrows",
+        ]
+    );
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_mutation_batch_retries_on_occ(
+    rt: TestRuntime,
+    pause: PauseController,
+) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    let hold_guard = pause.hold("retry_mutation_loop_start");
+    let fut1 = application.mutation_udf_batch(
+        RequestId::new(),
+        vec![
+            batch_entry("basic:insertAndCount")?,
+            batch_entry("basic:insertAndCount")?,
+        ],
+        Identity::system(),
+        AllowedVisibility::All,
+        FunctionCaller::HttpEndpoint,
+    );
+    let fut2 = async {
+        let guard = hold_guard
+            .wait_for_blocked_with_timeout(Duration::from_secs(60))
+            .await?
+            .context("Didn't hit breakpoint?")?;
+        // Conflict with the batch's first attempt.
+        insert_and_count(&application).await?;
+        guard.unpause();
+        Ok::<_, anyhow::Error>(())
+    };
+    let (result, conflict) = futures::join!(fut1, fut2);
+    conflict?;
+    let result = result??;
+    assert_eq!(result.occ_retries, 1);
+    // The batch's second attempt saw the conflicting insert, and nothing from
+    // its first.
+    let counts: Vec<_> = result
+        .values
+        .iter()
+        .map(|v| v.json_value().as_f64())
+        .collect();
+    assert_eq!(counts, vec![Some(2.0), Some(3.0)]);
+    // Two attempts at the batch, plus the conflicting mutation.
+    assert_eq!(pause.hit_count("retry_mutation_loop_start"), 3);
+    assert_eq!(
+        count_objects(&application).await?,
+        ConvexValue::Float64(3.0)
+    );
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_dry_run_mutation(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let result = application
+        .dry_run_mutation_udf(
+            RequestId::new(),
+            CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "basic:insertObject".parse()?,
+            },
+            vec![json!({"an": "object"})],
+            Identity::system(),
+            FunctionCaller::HttpEndpoint,
+        )
+        .await??;
+    assert_eq!(result.value.json_value()["an"], json!("object"));
+    assert!(result.timing.is_some());
+    // The first insert also creates the table, but only the insert itself is
+    // reported.
+    let writes = result.dry_run_writes.context("Missing dry run writes")?;
+    assert_eq!(writes.len(), 1);
+    assert_eq!(writes[0].table_name.to_string(), "objects");
+    assert_eq!(writes[0].kind, DryRunWriteKind::Insert);
+    assert_eq!(writes[0].old_value, None);
+    let new_value = writes[0].new_value.as_ref().context("Missing new value")?;
+    assert_eq!(new_value["an"], json!("object"));
+    assert_eq!(new_value["_id"], json!(writes[0].document_id.encode()));
+
+    let count = application
+        .read_only_udf(
+            RequestId::new(),
+            CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "basic:count".parse()?,
+            },
+            vec![json!({})],
+            Identity::system(),
+            FunctionCaller::HttpEndpoint,
+        )
+        .await?
+        .result
+        .map_err(|e| anyhow::anyhow!("Query failed: {e:?}"))?
+        .unpack();
+    assert_eq!(count, ConvexValue::Float64(0.0));
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_pinned_unix_timestamp(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let path = |udf_path: &str| -> anyhow::Result<_> {
+        Ok(CanonicalizedComponentFunctionPath {
+            component: ComponentPath::test_user(),
+            udf_path: udf_path.parse()?,
+        })
+    };
+    let read_time_ms = || async {
+        application
+            .read_only_udf(
+                RequestId::new(),
+                path("basic:readTimeMs")?,
+                vec![json!({})],
+                Identity::system(),
+                FunctionCaller::HttpEndpoint,
+            )
+            .await?
+            .result
+            .map_err(|e| anyhow::anyhow!("Query failed: {e:?}"))
+    };
+
+    let pinned = UnixTimestamp::from_millis(1_000_000);
+    application.set_pinned_unix_timestamp(Some(pinned));
+    let result = application
+        .mutation_udf(
+            RequestId::new(),
+            path("basic:insertNow")?,
+            vec![json!({})],
+            Identity::system(),
+            None,
+            FunctionCaller::HttpEndpoint,
+            None,
+            MutationOptions::default(),
+        )
+        .await??;
+    assert_eq!(result.value.json_value()["now"], json!(1_000_000.0));
+    assert_eq!(read_time_ms().await?.json_value(), json!(1_000_000.0));
+    let result = application
+        .action_udf(
+            RequestId::new(),
+            path("action:readTimeMs")?,
+            vec![json!({})],
+            Identity::system(),
+            FunctionCaller::HttpEndpoint,
+        )
+        .await?
+        .map_err(|e| anyhow::anyhow!("Action failed: {e:?}"))?;
+    assert_eq!(result.value.json_value(), json!(1_000_000.0));
+
+    // Unpinning goes back to the runtime's clock, which is far past the pinned
+    // time, so the cached query result expires.
+    application.set_pinned_unix_timestamp(None);
+    assert_ne!(read_time_ms().await?.json_value(), json!(1_000_000.0));
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_binary_return_value(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let result = application
+        .mutation_udf(
+            RequestId::new(),
+            PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "values:bytesMutation".parse()?,
+            }),
+            vec![json!({})],
+            Identity::system(),
+            None,
+            FunctionCaller::HttpEndpoint,
+            None,
+            MutationOptions::default(),
+        )
+        .await??;
+    assert_eq!(result.value.bytes(), Some(&[0, 1, 2, 254, 255][..]));
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_mutation_commit_timestamp(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let first = insert_and_count_return(&application).await?;
+    let second = insert_and_count_return(&application).await?;
+    assert!(first.committed_writes && second.committed_writes);
+    assert!(first.ts < second.ts);
+
+    let readonly = application
+        .mutation_udf(
+            RequestId::new(),
+            PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "basic:simpleMutation".parse()?,
+            }),
+            vec![json!({})],
+            Identity::system(),
+            None,
+            FunctionCaller::HttpEndpoint,
+            None,
+            MutationOptions::default(),
+        )
+        .await??;
+    assert!(!readonly.committed_writes);
+    assert!(readonly.ts >= second.ts);
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_caller_memory_limit(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    application.set_caller_memory_limit("Cron", 16 << 20);
+    let grow_array = |caller: FunctionCaller| {
+        let application = application.clone();
+        async move {
+            application
+                .mutation_udf(
+                    RequestId::new(),
+                    PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                        component: ComponentPath::test_user(),
+                        udf_path: "basic:growArray".parse()?,
+                    }),
+                    vec![json!({ "chunks": 60.0 })],
+                    Identity::system(),
+                    None,
+                    caller,
+                    None,
+                    MutationOptions::default(),
+                )
+                .await
+        }
+    };
+
+    let err = grow_array(FunctionCaller::Cron)
+        .await?
+        .expect_err("Expected the mutation to exceed its memory limit");
+    assert!(
+        err.error.to_string().contains("MemoryLimitExceeded"),
+        "{err:?}"
+    );
+
+    // Other callers aren't limited.
+    let result = grow_array(FunctionCaller::HttpEndpoint).await??;
+    assert_eq!(result.value.json_value(), json!(60.0));
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_mutation_rng_seed(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    let first = random_mutation(&application, Some([7; 32])).await?;
+    assert_eq!(random_mutation(&application, Some([7; 32])).await?, first);
+    assert_ne!(random_mutation(&application, Some([8; 32])).await?, first);
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_mutation_occ_error_identifies_document(
+    rt: TestRuntime,
+    pause: PauseController,
+) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let object = insert_object(&application).await?;
+    let id: DeveloperDocumentId = object["_id"].as_str().context("Expected _id")?.parse()?;
+
+    // Patch the document out from under every attempt until retries run out.
+    let hold_guard = pause.hold("retry_mutation_loop_start");
+    let fut1 = patch_object(&application, id, vec![]);
+    let fut2 = async {
+        let mut hold_guard = hold_guard;
+        for _ in 0..*UDF_EXECUTOR_OCC_MAX_RETRIES + 1 {
+            let guard = hold_guard
+                .wait_for_blocked()
+                .await
+                .context("Didn't hit breakpoint?")?;
+            patch_object(&application, id, vec![]).await?;
+            hold_guard = pause.hold("retry_mutation_loop_start");
+            guard.unpause();
+        }
+        Ok::<_, anyhow::Error>(())
+    };
+    let err = futures::try_join!(fut1, fut2).unwrap_err();
+    assert!(err.is_occ());
+    let occ_info = err.occ_info().context("Missing OCC info")?;
+    assert_eq!(occ_info.table_name.as_deref(), Some("objects"));
+    assert_eq!(occ_info.document_id, Some(id.encode()));
+    assert_eq!(
+        occ_info.write_source.as_deref(),
+        Some("basic.js:patchObject")
+    );
+    Ok(())
+}
+
+/// Patch the same document from two mutations, starting the second while the
+/// first is paused, and return the most retries any mutation needed.
+async fn patch_concurrently(
+    rt: &TestRuntime,
+    pause: &PauseController,
+    application: &Application<TestRuntime>,
+    use_conflict_hint: bool,
+) -> anyhow::Result<usize> {
+    let object = insert_object(application).await?;
+    let id: DeveloperDocumentId = object["_id"].as_str().context("Expected _id")?.parse()?;
+    let conflict_hint = if use_conflict_hint { vec![id] } else { vec![] };
+
+    let hold_guard = pause.hold("retry_mutation_loop_start");
+    let fut1 = patch_object(application, id, conflict_hint.clone());
+    let fut2 = async {
+        let guard = hold_guard
+            .wait_for_blocked()
+            .await
+            .context("Didn't hit breakpoint?")?;
+        let mut second = Box::pin(patch_object(application, id, conflict_hint.clone()));
+        if use_conflict_hint {
+            // The second mutation has to wait for the first to finish.
+            tokio::select! {
+                _ = &mut second => anyhow::bail!("Second mutation didn't wait"),
+                _ = rt.wait(Duration::from_secs(1)) => {},
+            }
+            guard.unpause();
+            second.await?;
+        } else {
+            second.await?;
+            guard.unpause();
+        }
+        Ok::<_, anyhow::Error>(())
+    };
+    futures::try_join!(fut1, fut2)?;
+
+    let (function_log, _) = application.function_log().stream(0.0).await;
+    Ok(function_log
+        .iter()
+        .filter_map(|execution| execution.mutation_retry_count)
+        .max()
+        .unwrap_or(0))
+}
+
+#[convex_macro::test_runtime]
+async fn test_conflict_hint_avoids_occ_retries(
+    rt: TestRuntime,
+    pause: PauseController,
+) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let retries_without_hint = patch_concurrently(&rt, &pause, &application, false).await?;
+
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let retries_with_hint = patch_concurrently(&rt, &pause, &application, true).await?;
+
+    assert_eq!(retries_without_hint, 1);
+    assert_eq!(retries_with_hint, 0);
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_occ_stats(rt: TestRuntime, pause: PauseController) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    patch_concurrently(&rt, &pause, &application, false).await?;
+
+    let window = Duration::from_secs(60);
+    let stats = application
+        .occ_stats(Identity::system(), window, 10)
+        .await?;
+    // One insert and two patches of the inserted object, one of which
+    // conflicted and was retried once.
+    assert_eq!(stats.mutations, 3);
+    assert_eq!(stats.attempts, 4);
+    assert_eq!(stats.conflicts, 1);
+    assert_eq!(stats.conflict_rate(), 0.25);
+    assert_eq!(stats.retries_per_mutation(), 1.0 / 3.0);
+    let patch_object_path = CanonicalizedComponentFunctionPath {
+        component: ComponentPath::test_user(),
+        udf_path: "basic:patchObject".parse()?,
+    };
+    assert_eq!(
+        stats.top_conflicting_functions,
+        vec![(patch_object_path, 1)]
+    );
+
+    // The conflicts age out of the window.
+    rt.wait(window * 2).await;
+    let stats = application
+        .occ_stats(Identity::system(), window, 10)
+        .await?;
+    assert_eq!(stats, OccStats::default());
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_abort_transaction(rt: TestRuntime, pause: PauseController) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    // Hold the mutation once it's registered but before it starts running.
+    let hold_guard = pause.hold("in_flight_mutation_registered");
+    let fut1 = insert_and_count(&application);
+    let fut2 = async {
+        let guard = hold_guard
+            .wait_for_blocked()
+            .await
+            .context("Didn't hit breakpoint?")?;
+        let in_flight = application.in_flight_mutations(Identity::system()).await?;
+        assert_eq!(in_flight.len(), 1);
+        assert_eq!(
+            in_flight[0].path,
+            CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "basic:insertAndCount".parse()?,
+            }
+        );
+        application
+            .abort_transaction(Identity::system(), in_flight[0].execution_id)
+            .await?;
+        guard.unpause();
+        Ok::<_, anyhow::Error>(in_flight[0].execution_id)
+    };
+    let (result, execution_id) = futures::join!(fut1, fut2);
+    let execution_id = execution_id?;
+    let err = result.unwrap_err();
+    assert_eq!(err.short_msg(), "MutationAborted");
+    assert!(application
+        .in_flight_mutations(Identity::system())
+        .await?
+        .is_empty());
+
+    // The aborted insert was rolled back.
+    assert_eq!(insert_and_count(&application).await?, 1);
+
+    // The mutation is no longer running, so it can't be aborted again.
+    let err = application
+        .abort_transaction(Identity::system(), execution_id)
+        .await
+        .unwrap_err();
+    assert_eq!(err.short_msg(), "TransactionNotFound");
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_multiple_inserts_dont_occ(
+    rt: TestRuntime,
+    pause: PauseController,
+) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    // Insert an object to create the table (otherwise it'll OCC on table creation).
+    insert_object(&application).await?;
+
+    let hold_guard = pause.hold("retry_mutation_loop_start");
+    let fut1 = insert_object(&application);
+    let fut2 = async {
+        let guard = hold_guard
+            .wait_for_blocked()
+            .await
+            .context("Didn't hit breakpoint?")?;
+
+        // Do several entire mutations while we're paused. Shouldn't OCC.
+        for _ in 0..5 {
+            let result = insert_object(&application).await?;
+            assert_eq!(result["an"], "object");
+        }
+
+        guard.unpause();
+        Ok::<_, anyhow::Error>(())
+    };
+    let (result, ()) = futures::try_join!(fut1, fut2)?;
+    assert_eq!(result["an"], "object");
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_interleaved_mutations(rt: TestRuntime, pause: PauseController) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    insert_object(&application).await?;
+
+    let insert_and_count =
+        || InterleavedMutation::new("basic:insertAndCount", json!({"an": "object"}));
+    // Both mutations begin before either runs, so the first one to run wins
+    // and the other has to retry after it commits.
+    let outcome = application
+        .test_run_interleaved_mutations(
+            &pause,
+            vec![insert_and_count()?, insert_and_count()?],
+            &[
+                InterleavingStep::Begin(0),
+                InterleavingStep::Begin(1),
+                InterleavingStep::Run(1),
+                InterleavingStep::Run(0),
+            ],
+        )
+        .await?;
+    assert_eq!(outcome.commit_order, vec![1, 0]);
+    assert_eq!(outcome.attempts, vec![2, 1]);
+    let counts: Vec<_> = outcome
+        .results
+        .into_iter()
+        .map(|result| anyhow::Ok(result??.value.json_value().as_f64()))
+        .try_collect()?;
+    assert_eq!(counts, vec![Some(3.0), Some(2.0)]);
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_bulk_insert(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let count_at = |ts: Timestamp| {
+        let application = &application;
+        async move {
+            let result = application
+                .read_only_udf_at_ts(
+                    RequestId::new(),
+                    PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                        component: ComponentPath::test_user(),
+                        udf_path: "basic:count".parse()?,
+                    }),
+                    vec![json!({})],
+                    Identity::system(),
+                    ts,
+                    None,
+                    FunctionCaller::HttpEndpoint,
+                )
+                .await?
+                .result
+                .map_err(|e| anyhow::anyhow!("Query failed: {e:?}"))?;
+            anyhow::Ok(result.unpack())
+        }
+    };
+
+    let objects: Vec<_> = (0..50).map(|i| assert_obj!("an" => i as f64)).collect();
+    let (count, ts) = application
+        .insert_documents(
+            &Identity::system(),
+            TableNamespace::test_user(),
+            "objects".parse()?,
+            objects.clone(),
+        )
+        .await?;
+    assert_eq!(count, 50);
+    // All of the documents became visible at the one commit timestamp.
+    assert_eq!(count_at(ts.pred()?).await?, ConvexValue::Float64(0.0));
+    assert_eq!(count_at(ts).await?, ConvexValue::Float64(50.0));
+
+    // A failed insert rolls back the rest of the batch.
+    let mut objects = objects;
+    objects.push(assert_obj!("_creationTime" => 1.0));
+    let err = application
+        .insert_documents(
+            &Identity::system(),
+            TableNamespace::test_user(),
+            "objects".parse()?,
+            objects,
+        )
+        .await
+        .expect_err("Expected the batch to fail");
+    assert!(err.is_bad_request(), "{err:?}");
+    assert_eq!(
+        count_at(*application.now_ts_for_reads()).await?,
+        ConvexValue::Float64(50.0)
+    );
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_blocked_breakpoints(rt: TestRuntime, pause: PauseController) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    assert!(pause.blocked_breakpoints().is_empty());
+
+    let hold_guard = pause.hold("retry_mutation_loop_start");
+    let fut1 = insert_object(&application);
+    let fut2 = async {
+        let guard = hold_guard
+            .wait_for_blocked()
+            .await
+            .context("Didn't hit breakpoint?")?;
+        let blocked: Vec<_> = pause
+            .blocked_breakpoints()
+            .into_iter()
+            .map(|(label, _)| label)
+            .collect();
+        assert_eq!(blocked, vec!["retry_mutation_loop_start"]);
+        guard.unpause();
+        Ok::<_, anyhow::Error>(())
+    };
+    futures::try_join!(fut1, fut2)?;
+    assert!(pause.blocked_breakpoints().is_empty());
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_sequence_ids_unique_across_occ(
+    rt: TestRuntime,
+    pause: PauseController,
+) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    let hold_guard = pause.hold("retry_mutation_loop_start");
+    let fut1 = allocate_sequence_id(&application);
+    let fut2 = async {
+        let guard = hold_guard
+            .wait_for_blocked()
+            .await
+            .context("Didn't hit breakpoint?")?;
+
+        // Allocate from the same sequence while the first mutation is paused, so
+        // the first mutation OCCs and has to allocate again on retry.
+        let id = allocate_sequence_id(&application).await?;
+
+        guard.unpause();
+        Ok::<_, anyhow::Error>(id)
+    };
+    let (first, second) = futures::try_join!(fut1, fut2)?;
+    assert_eq!(second, 1);
+    assert_eq!(first, 2);
+
+    let third = allocate_sequence_id(&application).await?;
+    assert_eq!(third, 3);
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_counter_increments_dont_occ(
+    rt: TestRuntime,
+    pause: PauseController,
+) -> anyhow::Result<()> {
+    let logger = BasicTestUsageEventLogger::new();
+    let application = Application::new_for_tests_with_args(
+        &rt,
+        ApplicationFixtureArgs::with_event_logger(Arc::new(logger.clone())),
+    )
+    .await?;
+    application.load_udf_tests_modules().await?;
+
+    let hold_guard = pause.hold("retry_mutation_loop_start");
+    let fut1 = increment_counter(&application);
+    let fut2 = async {
+        let guard = hold_guard
+            .wait_for_blocked()
+            .await
+            .context("Didn't hit breakpoint?")?;
+
+        // Increment the same counter while the first mutation is paused. Unlike
+        // sequences, the increments are merged at commit time and don't OCC.
+        for _ in 0..5 {
+            increment_counter(&application).await?;
+        }
+
+        guard.unpause();
+        Ok::<_, anyhow::Error>(())
+    };
+    futures::try_join!(fut1, fut2)?;
+    assert_eq!(get_counter(&application).await?, 6);
+
+    let function_call_events: Vec<FunctionCallUsageFields> = logger
+        .collect()
+        .into_iter()
+        .filter_map(|event| match event {
+            UsageEvent::FunctionCall { fields } if fields.udf_id == "counters.js:increment" => {
+                Some(fields)
+            },
+            _ => None,
+        })
+        .collect();
+    assert_eq!(function_call_events.len(), 6);
+    assert!(function_call_events.iter().all(|event| !event.is_occ));
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_mutation_then_subscribe(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    increment_counter(&application).await?;
+
+    let counter_path = |udf_path: &str| -> anyhow::Result<PublicFunctionPath> {
+        Ok(PublicFunctionPath::Component(
+            CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: udf_path.parse()?,
+            },
+        ))
+    };
+    let MutationThenSubscribeReturn {
+        mutation,
+        query,
+        subscription,
+    } = application
+        .mutation_then_subscribe(
+            RequestId::new(),
+            counter_path("counters:increment")?,
+            vec![json!({})],
+            counter_path("counters:get")?,
+            vec![json!({})],
+            Identity::system(),
+            FunctionCaller::HttpEndpoint,
+        )
+        .await??;
+
+    // The first result reflects the mutation's write.
+    assert_eq!(query.token.ts(), mutation.ts);
+    let value = query
+        .result
+        .map_err(|e| anyhow::anyhow!("Query failed: {e:?}"))?
+        .unpack();
+    assert_eq!(value, ConvexValue::Int64(2));
+    assert_eq!(subscription.invalid_ts(), None);
+
+    // A later write invalidates the subscription.
+    increment_counter(&application).await?;
+    subscription.wait_for_invalidation().await;
+    assert_eq!(get_counter(&application).await?, 3);
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_mutation_read_set_size(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    for _ in 0..5 {
+        insert_object(&application).await?;
+    }
+
+    let run = |udf_path: &'static str, report_read_set_size: bool| {
+        let application = application.clone();
+        async move {
+            let result = application
+                .mutation_udf(
+                    RequestId::new(),
+                    PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                        component: ComponentPath::test_user(),
+                        udf_path: udf_path.parse()?,
+                    }),
+                    vec![json!({"an": "object"})],
+                    Identity::system(),
+                    None,
+                    FunctionCaller::HttpEndpoint,
+                    None,
+                    MutationOptions {
+                        report_read_set_size,
+                        ..Default::default()
+                    },
+                )
+                .await??;
+            anyhow::Ok(result.read_set_size)
+        }
+    };
+    // The read set is only reported when asked for.
+    assert_eq!(run("basic:insertAndCollect", false).await?, None);
+
+    // `insertObject` only reads back the document it inserted, while
+    // `insertAndCollect` reads every document already in the table.
+    let narrow = run("basic:insertObject", true)
+        .await?
+        .context("Missing read set size")?;
+    let broad = run("basic:insertAndCollect", true)
+        .await?
+        .context("Missing read set size")?;
+    assert!(narrow.num_documents <= 1, "{narrow:?}");
+    assert!(
+        broad.num_documents >= narrow.num_documents + 6,
+        "narrow: {narrow:?}, broad: {broad:?}"
+    );
+    assert!(
+        broad.document_bytes > narrow.document_bytes,
+        "narrow: {narrow:?}, broad: {broad:?}"
+    );
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_mutation_read_write_sets(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let first = insert_and_count_return(&application)
+        .await?
+        .read_write_sets
+        .context("Missing read/write sets")?;
+    let second = insert_and_count_return(&application)
+        .await?
+        .read_write_sets
+        .context("Missing read/write sets")?;
+    assert_eq!(second.writes.len(), 1);
+    // Both mutations count the whole table, so each would have conflicted with
+    // the other's insert had they run concurrently.
+    let (_, conflicting_id) = first
+        .overlapping_read(&second)
+        .context("Expected the first mutation's reads to overlap")?;
+    assert!(second.writes.contains_key(&conflicting_id));
+    assert!(second.overlapping_read(&first).is_some());
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_table_count(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    let mut ids = vec![];
+    for _ in 0..3 {
+        let object = insert_object(&application).await?;
+        ids.push(object["_id"].clone());
+    }
+    let table_count = || {
+        application.table_count(
+            Identity::system(),
+            TableNamespace::test_user(),
+            "objects".parse().unwrap(),
+        )
+    };
+    assert_eq!(table_count().await?, 3);
+
+    let result = application
+        .mutation_udf(
+            RequestId::new(),
+            PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "basic:deleteAndCount".parse()?,
+            }),
+            vec![json!({ "id": ids[0] })],
+            Identity::system(),
+            None,
+            FunctionCaller::Test,
+            None,
+            MutationOptions::default(),
+        )
+        .await??;
+    // The maintained count agrees with counting from within the mutation.
+    assert_eq!(result.value.json_value(), json!(2.0));
+    assert_eq!(table_count().await?, 2);
+
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_mutation_and_query_with_values(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    // Neither of these survives a round trip through a plain JSON number or
+    // string, so the functions only echo them back intact if they received them
+    // as values.
+    let args = ConvexValue::Object(assert_obj!(
+        "big" => (1i64 << 60) + 1,
+        "bytes" => vec![0u8, 1, 254, 255],
+    ));
+    let result = application
+        .mutation_udf_with_values(
+            RequestId::new(),
+            PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "basic:echoArgs".parse()?,
+            }),
+            ConvexArray::try_from(vec![args.clone()])?,
+            Identity::system(),
+            None,
+            FunctionCaller::Test,
+            None,
+            MutationOptions::default(),
+        )
+        .await??;
+    assert_eq!(result.value.unpack(), args);
+
+    let result = application
+        .read_only_udf_with_values(
+            RequestId::new(),
+            PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "basic:echoArgsQuery".parse()?,
+            }),
+            ConvexArray::try_from(vec![args.clone()])?,
+            Identity::system(),
+            FunctionCaller::Test,
+        )
+        .await?;
+    match result.result {
+        Ok(value) => assert_eq!(value.unpack(), args),
+        Err(e) => anyhow::bail!("Query failed: {e:?}"),
+    }
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_counter_increments_count_against_write_limit(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    // Each distinct counter is a separate write at commit time.
+    let count = *TRANSACTION_MAX_NUM_USER_WRITES + 1;
+    let result = application
+        .mutation_udf(
+            RequestId::new(),
+            PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: ComponentPath::test_user(),
+                udf_path: "counters:incrementMany".parse()?,
+            }),
+            vec![json!({ "count": count })],
+            Identity::system(),
+            None,
+            FunctionCaller::Action {
+                parent_scheduled_job: None,
+                parent_execution_id: None,
+            },
+            None,
+            vec![],
+        )
+        .await;
+    let error = match result {
+        Ok(Ok(_)) => anyhow::bail!("Mutation over the write limit succeeded"),
+        Ok(Err(e)) => e.error.to_string(),
+        Err(e) => format!("{e:#}"),
+    };
+    assert!(error.contains("Too many writes"), "{error}");
     Ok(())
 }
@@ -0,0 +1,108 @@
+use common::{
+    components::{
+        CanonicalizedComponentFunctionPath,
+        ComponentPath,
+    },
+    types::FunctionCaller,
+    RequestId,
+};
+use errors::ErrorMetadataAnyhowExt;
+use keybroker::Identity;
+use runtime::testing::TestRuntime;
+use serde_json::json;
+
+use crate::{
+    test_helpers::ApplicationTestExt,
+    Application,
+    MutationOptions,
+};
+
+fn path(udf_path: &str) -> anyhow::Result<CanonicalizedComponentFunctionPath> {
+    Ok(CanonicalizedComponentFunctionPath {
+        component: ComponentPath::test_user(),
+        udf_path: udf_path.parse()?,
+    })
+}
+
+#[convex_macro::test_runtime]
+async fn test_paginated_query(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let insert_objects = |objects: Vec<_>| {
+        let application = application.clone();
+        async move {
+            application
+                .mutation_udf(
+                    RequestId::new(),
+                    path("basic:insertObjects")?,
+                    vec![json!({ "objects": objects })],
+                    Identity::system(),
+                    None,
+                    FunctionCaller::HttpEndpoint,
+                    None,
+                    MutationOptions::default(),
+                )
+                .await?
+                .map_err(|e| anyhow::anyhow!("{e:?}"))
+        }
+    };
+    insert_objects((0..30).map(|i| json!({ "an": i })).collect()).await?;
+
+    let get_page = |udf_path: &'static str, cursor: Option<String>| {
+        let application = application.clone();
+        async move {
+            application
+                .paginated_query_udf(
+                    RequestId::new(),
+                    path(udf_path)?,
+                    json!({}),
+                    10,
+                    cursor,
+                    Identity::system(),
+                    FunctionCaller::HttpEndpoint,
+                )
+                .await?
+                .map_err(|e| anyhow::anyhow!("{e:?}"))
+        }
+    };
+
+    let mut seen = vec![];
+    let mut cursor = None;
+    let mut num_pages = 0;
+    loop {
+        let page = get_page("basic:paginateObjects", cursor).await?;
+        num_pages += 1;
+        assert!(page.page.len() <= 10);
+        seen.extend(page.page.iter().map(|doc| doc["an"].as_f64().unwrap()));
+        cursor = page.continue_cursor;
+        if cursor.is_none() {
+            break;
+        }
+        // A cursor from one function can't be used with another, or after
+        // it's been tampered with.
+        let err = get_page("query:paginateTableScan", cursor.clone())
+            .await
+            .unwrap_err();
+        assert_eq!(err.short_msg(), "InvalidCursor");
+        let mut tampered: Vec<char> = cursor.clone().unwrap().chars().collect();
+        let mid = tampered.len() / 2;
+        tampered[mid] = if tampered[mid] == 'A' { 'B' } else { 'A' };
+        let err = get_page(
+            "basic:paginateObjects",
+            Some(tampered.into_iter().collect()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.short_msg(), "InvalidCursor");
+        // Later pages read the first page's snapshot, so they don't see
+        // objects inserted since.
+        if num_pages == 1 {
+            insert_objects((30..35).map(|i| json!({ "an": i })).collect()).await?;
+        }
+    }
+    assert!(num_pages >= 3);
+    seen.sort_by(f64::total_cmp);
+    let expected: Vec<_> = (0..30).map(f64::from).collect();
+    assert_eq!(seen, expected);
+    Ok(())
+}
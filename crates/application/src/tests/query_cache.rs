@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use anyhow::Context;
 use common::{
     bootstrap_model::index::{
         database_index::IndexedFields,
@@ -11,9 +12,14 @@ use common::{
         PublicFunctionPath,
     },
     pause::PauseController,
-    types::FunctionCaller,
+    types::{
+        FunctionCaller,
+        IndexName,
+        Timestamp,
+    },
     RequestId,
 };
+use errors::ErrorMetadataAnyhowExt;
 use keybroker::{
     testing::TestUserIdentity,
     Identity,
@@ -33,6 +39,7 @@ use value::{
 use crate::{
     test_helpers::ApplicationTestExt,
     Application,
+    MutationOptions,
 };
 
 fn udf_path(path: &str) -> PublicFunctionPath {
@@ -101,6 +108,7 @@ async fn insert_object(application: &Application<TestRuntime>) -> anyhow::Result
                 parent_execution_id: None,
             },
             None,
+            MutationOptions::default(),
         )
         .await??;
     Ok(result.value.unpack())
@@ -141,6 +149,33 @@ async fn test_query_cache(rt: TestRuntime) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[convex_macro::test_runtime]
+async fn test_warm_query(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    application
+        .warm_query(
+            RequestId::new(),
+            udf_path("basic:listAllObjects"),
+            vec![json!({})],
+            Identity::system(),
+            FunctionCaller::Test,
+        )
+        .await?;
+    // The first real request is served from the cache.
+    run_query(
+        &application,
+        "basic:listAllObjects",
+        json!({}),
+        Identity::system(),
+        true,
+    )
+    .await?;
+
+    Ok(())
+}
+
 #[convex_macro::test_runtime]
 async fn test_query_cache_data_invalidation(rt: TestRuntime) -> anyhow::Result<()> {
     let application = Application::new_for_tests(&rt).await?;
@@ -622,7 +657,8 @@ async fn test_cache_paginated_query(rt: TestRuntime) -> anyhow::Result<()> {
                 Identity::system(),
                 None,
                 FunctionCaller::Test,
-                None
+                None,
+                MutationOptions::default(),
             )
             .await?
             .is_ok());
@@ -695,7 +731,8 @@ async fn test_cache_paginated_query(rt: TestRuntime) -> anyhow::Result<()> {
             Identity::system(),
             None,
             FunctionCaller::Test,
-            None
+            None,
+            MutationOptions::default(),
         )
         .await?
         .is_ok());
@@ -730,3 +767,148 @@ async fn test_cache_paginated_query(rt: TestRuntime) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[convex_macro::test_runtime]
+async fn test_query_udf_batch(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    insert_object(&application).await?;
+
+    let results = application
+        .query_udf_batch(
+            RequestId::new(),
+            vec![
+                (udf_path("basic:count"), vec![json!({})]),
+                (udf_path("http_action:erroringQuery"), vec![json!({})]),
+                (udf_path("basic:listAllObjects"), vec![json!({})]),
+            ],
+            Identity::system(),
+            FunctionCaller::Action {
+                parent_scheduled_job: None,
+                parent_execution_id: None,
+            },
+        )
+        .await
+        .into_iter()
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    assert_eq!(results.len(), 3);
+    // Every query ran against the same snapshot.
+    assert!(results
+        .iter()
+        .all(|result| result.token.ts() == results[0].token.ts()));
+
+    // The failing query doesn't stop the ones around it from returning.
+    let count = results[0].result.as_ref().unwrap().unpack();
+    assert_eq!(count, ConvexValue::from(1.0));
+    assert!(results[1].result.is_err());
+    must_let!(let ConvexValue::Array(objects) = results[2].result.as_ref().unwrap().unpack());
+    assert_eq!(objects.len(), 1);
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_query_udf_at(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    insert_object(&application).await?;
+    let ts = *application.now_ts_for_reads();
+    insert_object(&application).await?;
+
+    let caller = FunctionCaller::Action {
+        parent_scheduled_job: None,
+        parent_execution_id: None,
+    };
+    let query_at = |ts| {
+        application.query_udf_at(
+            RequestId::new(),
+            udf_path("basic:count"),
+            vec![json!({})],
+            Identity::system(),
+            ts,
+            caller.clone(),
+        )
+    };
+
+    // The query doesn't see the second insert.
+    let result = query_at(ts).await?;
+    assert_eq!(result.result?.unpack(), ConvexValue::from(1.0));
+    assert_eq!(result.token.ts(), ts);
+
+    let future_ts = application.now_ts_for_reads().succ()?;
+    let err = query_at(future_ts).await.unwrap_err();
+    assert_eq!(err.short_msg(), "SnapshotTooNew");
+
+    let err = query_at(Timestamp::MIN).await.unwrap_err();
+    assert!(err.is_out_of_retention());
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_query_timing(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    insert_object(&application).await?;
+
+    let query = || {
+        application.read_only_udf(
+            RequestId::new(),
+            udf_path("basic:count"),
+            vec![json!({})],
+            Identity::system(),
+            FunctionCaller::HttpEndpoint,
+        )
+    };
+    let timing = query()
+        .await?
+        .timing
+        .context("Uncached query has no timing")?;
+    assert_eq!(timing.js + timing.syscalls, timing.total);
+    // Nothing ran for a cache hit.
+    assert_eq!(query().await?.timing, None);
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_query_index_reads(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let wide_field = "x".repeat(10_000);
+    for _ in 0..3 {
+        application
+            .mutation_udf(
+                RequestId::new(),
+                udf_path("basic:insertObject"),
+                vec![json!({ "wide": wide_field })],
+                Identity::system(),
+                None,
+                FunctionCaller::HttpEndpoint,
+                None,
+                MutationOptions::default(),
+            )
+            .await??;
+    }
+
+    let query = || {
+        application.read_only_udf(
+            RequestId::new(),
+            udf_path("basic:listAllObjects"),
+            vec![json!({})],
+            Identity::system(),
+            FunctionCaller::HttpEndpoint,
+        )
+    };
+    let index_reads = query()
+        .await?
+        .index_reads
+        .context("Uncached query has no index reads")?;
+    let by_creation_time: IndexName = "objects.by_creation_time".parse()?;
+    let stats = index_reads
+        .get(&by_creation_time)
+        .context("Missing table scan index")?;
+    assert_eq!(stats.rows_read, 3);
+    // The documents dominate the bytes read, not the index keys.
+    assert!(stats.document_bytes_read >= 3 * 10_000);
+    assert!(stats.index_bytes_read < stats.document_bytes_read);
+    assert_eq!(query().await?.index_reads, None);
+    Ok(())
+}
@@ -18,6 +18,7 @@ use serde_json::json;
 use crate::{
     test_helpers::ApplicationTestExt,
     Application,
+    MutationOptions,
     RedactedActionError,
     RedactedActionReturn,
     RedactedMutationError,
@@ -42,6 +43,7 @@ async fn run_zero_arg_mutation(
             None,
             FunctionCaller::HttpEndpoint,
             None,
+            MutationOptions::default(),
         )
         .await
 }
@@ -1,4 +1,7 @@
-use std::str::FromStr;
+use std::{
+    str::FromStr,
+    time::Duration,
+};
 
 use common::{
     components::{
@@ -13,12 +16,17 @@ use common::{
         HoldGuard,
         PauseController,
     },
+    query::{
+        Order,
+        Query,
+    },
     runtime::Runtime,
     types::FunctionCaller,
     RequestId,
 };
 use database::{
     BootstrapComponentsModel,
+    ResolvedQuery,
     TableModel,
     Transaction,
 };
@@ -35,7 +43,10 @@ use model::{
     },
 };
 use runtime::testing::TestRuntime;
-use serde_json::Value as JsonValue;
+use serde_json::{
+    json,
+    Value as JsonValue,
+};
 use sync_types::CanonicalizedUdfPath;
 use udf::helpers::parse_udf_args;
 use value::{
@@ -54,6 +65,7 @@ use crate::{
         OBJECTS_TABLE_COMPONENT,
     },
     Application,
+    MutationOptions,
 };
 
 fn insert_object_path() -> CanonicalizedComponentFunctionPath {
@@ -153,6 +165,54 @@ async fn test_scheduled_jobs_canceled(rt: TestRuntime) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[convex_macro::test_runtime]
+async fn test_schedule_if_absent(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    let path = insert_object_path();
+    let schedule = async |key: &str| {
+        let mut tx = application.begin(Identity::system()).await?;
+        let job_id = SchedulerModel::new(&mut tx, TableNamespace::test_user())
+            .schedule_if_absent(
+                path.clone(),
+                parse_udf_args(&path.udf_path, vec![])?,
+                // Far enough out that the job stays pending for the test.
+                rt.unix_timestamp() + Duration::from_secs(3600),
+                ExecutionContext::new_for_test(),
+                key.to_string(),
+            )
+            .await?;
+        application.commit_test(tx).await?;
+        anyhow::Ok(job_id)
+    };
+
+    let first = schedule("debounce").await?;
+    let second = schedule("debounce").await?;
+    assert_eq!(first, second);
+
+    let mut tx = application.begin(Identity::system()).await?;
+    let jobs = SchedulerModel::new(&mut tx, TableNamespace::test_user())
+        .list()
+        .await?;
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].dedup_key.as_deref(), Some("debounce"));
+
+    // A different key schedules a separate job.
+    let other = schedule("other").await?;
+    assert_ne!(first, other);
+
+    // Once the pending job is canceled, the key is free again.
+    let mut tx = application.begin(Identity::system()).await?;
+    SchedulerModel::new(&mut tx, TableNamespace::test_user())
+        .cancel(first)
+        .await?;
+    application.commit_test(tx).await?;
+    let third = schedule("debounce").await?;
+    assert_ne!(first, third);
+    Ok(())
+}
+
 #[convex_macro::test_runtime]
 async fn test_scheduled_jobs_race_condition(rt: TestRuntime) -> anyhow::Result<()> {
     let application = Application::new_for_tests(&rt).await?;
@@ -324,6 +384,7 @@ async fn test_cancel_recursively_scheduled_job(rt: TestRuntime) -> anyhow::Resul
                 parent_execution_id: None,
             },
             None,
+            MutationOptions::default(),
         )
         .await??;
 
@@ -397,3 +458,74 @@ async fn test_scheduled_job_retry(
     assert_eq!(state, ScheduledJobState::Success);
     Ok(())
 }
+
+#[convex_macro::test_runtime]
+async fn test_replay_scheduled_job_order(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+
+    // Pause the backend so the executor doesn't pick up the jobs itself.
+    let mut tx = application.begin(Identity::system()).await?;
+    BackendStateModel::new(&mut tx)
+        .toggle_backend_state(BackendState::Paused)
+        .await?;
+    application.commit_test(tx).await?;
+
+    // Schedule three jobs that are all due at the same time.
+    let schedule_jobs = || async {
+        let mut tx = application.begin(Identity::system()).await?;
+        let path = insert_object_path();
+        let mut model = SchedulerModel::new(&mut tx, TableNamespace::test_user());
+        let ts = rt.unix_timestamp();
+        for key in ["a", "b", "c"] {
+            model
+                .schedule(
+                    path.clone(),
+                    parse_udf_args(&path.udf_path, vec![json!({ "key": key })])?,
+                    ts,
+                    ExecutionContext::new_for_test(),
+                )
+                .await?;
+        }
+        application.commit_test(tx).await?;
+        anyhow::Ok(())
+    };
+
+    schedule_jobs().await?;
+    let recorded = application.test_run_due_scheduled_jobs(None).await?;
+    assert_eq!(recorded.len(), 3);
+
+    // Replaying runs the same jobs in the recorded order, even when it
+    // disagrees with the scheduler's.
+    schedule_jobs().await?;
+    let replay: Vec<_> = recorded.iter().rev().cloned().collect();
+    let replayed = application
+        .test_run_due_scheduled_jobs(Some(&replay))
+        .await?;
+    assert_eq!(replayed, replay);
+
+    let mut tx = application.begin(Identity::system()).await?;
+    let mut query_stream = ResolvedQuery::new(
+        &mut tx,
+        TableNamespace::test_user(),
+        Query::full_table_scan(OBJECTS_TABLE.clone(), Order::Asc),
+    )?;
+    let mut keys = vec![];
+    while let Some(doc) = query_stream.next(&mut tx, None).await? {
+        keys.push(doc.value().get("key").cloned());
+    }
+    assert_eq!(keys.len(), 6);
+    let (first_run, second_run) = keys.split_at(3);
+    assert_eq!(
+        second_run,
+        first_run.iter().rev().cloned().collect::<Vec<_>>()
+    );
+
+    // Running a replay that doesn't match the due jobs fails.
+    schedule_jobs().await?;
+    assert!(application
+        .test_run_due_scheduled_jobs(Some(&replay[..1]))
+        .await
+        .is_err());
+    Ok(())
+}
@@ -1,19 +1,55 @@
 use common::{
+    assert_obj,
     bootstrap_model::index::database_index::IndexedFields,
+    db_schema_with_indexes,
+    object_validator,
+    schemas::{
+        json::DatabaseSchemaJson,
+        validator::{
+            FieldValidator,
+            Validator,
+        },
+        DatabaseSchema,
+        DocumentSchema,
+        TableDefinition,
+    },
     types::{
         IndexDescriptor,
         ModuleEnvironment,
     },
 };
+use database::{
+    SchemaModel,
+    UserFacingModel,
+};
 use errors::ErrorMetadataAnyhowExt;
+use keybroker::{
+    testing::TestUserIdentity,
+    Identity,
+    UserIdentity,
+};
 use model::config::types::ModuleConfig;
 use runtime::testing::TestRuntime;
+use value::TableNamespace;
 
 use crate::{
     test_helpers::ApplicationTestExt,
     Application,
 };
 
+async fn activate_schema(
+    application: &Application<TestRuntime>,
+    schema: DatabaseSchema,
+) -> anyhow::Result<()> {
+    let mut tx = application.begin(Identity::system()).await?;
+    let mut model = SchemaModel::new_root_for_test(&mut tx);
+    let (schema_id, _) = model.submit_pending(schema).await?;
+    model.mark_validated(schema_id).await?;
+    model.mark_active(schema_id).await?;
+    application.commit_test(tx).await?;
+    Ok(())
+}
+
 #[convex_macro::test_runtime]
 async fn test_evaluate_schema_append_creation_time(rt: TestRuntime) -> anyhow::Result<()> {
     let source = r#"
@@ -211,3 +247,69 @@ export default new SchemaDefinition()
 
     Ok(())
 }
+
+#[convex_macro::test_runtime]
+async fn test_export_and_reimport_schema(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    let schema = db_schema_with_indexes!("messages" => {
+        indexes: ("by_channel", vec!["channel"]),
+        document_schema: DocumentSchema::Union(vec![object_validator!(
+            "channel" => FieldValidator::required_field_type(Validator::String)
+        )]),
+    });
+    activate_schema(&application, schema.clone()).await?;
+
+    // A table that exists but isn't in the schema.
+    let mut tx = application.begin(Identity::system()).await?;
+    UserFacingModel::new_root_for_test(&mut tx)
+        .insert("notes".parse()?, assert_obj!("text" => "hi"))
+        .await?;
+    application.commit_test(tx).await?;
+
+    let exported = application
+        .export_schema(TableNamespace::test_user(), &Identity::system())
+        .await?;
+    let serialized = serde_json::to_string(&exported)?;
+    let reimported =
+        DatabaseSchema::try_from(serde_json::from_str::<DatabaseSchemaJson>(&serialized)?)?;
+
+    let mut expected = schema;
+    expected.tables.insert(
+        "notes".parse()?,
+        TableDefinition {
+            table_name: "notes".parse()?,
+            indexes: Default::default(),
+            staged_db_indexes: Default::default(),
+            text_indexes: Default::default(),
+            staged_text_indexes: Default::default(),
+            vector_indexes: Default::default(),
+            staged_vector_indexes: Default::default(),
+            document_type: None,
+        },
+    );
+    assert_eq!(reimported, expected);
+
+    // Importing the export into a fresh deployment and exporting again gives
+    // back the same schema.
+    let other = Application::new_for_tests(&rt).await?;
+    activate_schema(&other, reimported.clone()).await?;
+    let reexported = other
+        .export_schema(TableNamespace::test_user(), &Identity::system())
+        .await?;
+    assert_eq!(DatabaseSchema::try_from(reexported)?, reimported);
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_export_schema_requires_admin(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    let err = application
+        .export_schema(
+            TableNamespace::test_user(),
+            &Identity::user(UserIdentity::test()),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.is_forbidden(), "{err:?}");
+    Ok(())
+}
@@ -237,6 +237,24 @@ impl PublicFunctionPath {
     }
 }
 
+impl From<ExportPath> for PublicFunctionPath {
+    fn from(path: ExportPath) -> Self {
+        PublicFunctionPath::RootExport(path)
+    }
+}
+
+impl From<CanonicalizedComponentFunctionPath> for PublicFunctionPath {
+    fn from(path: CanonicalizedComponentFunctionPath) -> Self {
+        PublicFunctionPath::Component(path)
+    }
+}
+
+impl From<ResolvedComponentFunctionPath> for PublicFunctionPath {
+    fn from(path: ResolvedComponentFunctionPath) -> Self {
+        PublicFunctionPath::ResolvedComponent(path)
+    }
+}
+
 impl HeapSize for PublicFunctionPath {
     fn heap_size(&self) -> usize {
         match self {
@@ -21,6 +21,7 @@ use metrics::{
     SERVICE_NAME,
 };
 use pb::common::{
+    ArgumentValidationError as ArgumentValidationErrorProto,
     FrameData as FrameDataProto,
     JsError as JsErrorProto,
     JsFrames as JsFramesProto,
@@ -483,12 +484,69 @@ pub struct JsError {
     pub message: String,
     pub custom_data: Option<ConvexValue>,
     pub frames: Option<JsFrames>,
+    /// Set when the function's arguments didn't match its `args` validator, in
+    /// which case none of the function's code ran.
+    pub argument_validation: Option<ArgumentValidationError>,
+}
+
+/// Which part of a function's arguments failed its `args` validator.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    any(test, feature = "testing"),
+    derive(proptest_derive::Arbitrary, PartialEq)
+)]
+pub struct ArgumentValidationError {
+    /// Path to the offending field, like `.user.name`, or `None` if the
+    /// arguments weren't a single object.
+    pub field_path: Option<String>,
+    /// The validator the field should have matched, or `None` if the field
+    /// isn't allowed at all.
+    pub expected: Option<String>,
+}
+
+impl From<ArgumentValidationError> for ArgumentValidationErrorProto {
+    fn from(
+        ArgumentValidationError {
+            field_path,
+            expected,
+        }: ArgumentValidationError,
+    ) -> Self {
+        Self {
+            field_path,
+            expected,
+        }
+    }
+}
+
+impl From<ArgumentValidationErrorProto> for ArgumentValidationError {
+    fn from(
+        ArgumentValidationErrorProto {
+            field_path,
+            expected,
+        }: ArgumentValidationErrorProto,
+    ) -> Self {
+        Self {
+            field_path,
+            expected,
+        }
+    }
+}
+
+impl HeapSize for ArgumentValidationError {
+    fn heap_size(&self) -> usize {
+        self.field_path.heap_size() + self.expected.heap_size()
+    }
 }
 
 impl From<JsError> for anyhow::Error {
     fn from(js_error: JsError) -> anyhow::Error {
+        let short_msg = if js_error.argument_validation.is_some() {
+            "ArgumentValidationError"
+        } else {
+            "Error"
+        };
         let msg = js_error.to_string();
-        anyhow::anyhow!(ErrorMetadata::bad_request("Error", msg)).context(js_error)
+        anyhow::anyhow!(ErrorMetadata::bad_request(short_msg, msg)).context(js_error)
     }
 }
 
@@ -500,6 +558,7 @@ impl TryFrom<JsError> for JsErrorProto {
             message,
             custom_data,
             frames,
+            argument_validation,
         }: JsError,
     ) -> anyhow::Result<Self> {
         Ok(Self {
@@ -508,6 +567,7 @@ impl TryFrom<JsError> for JsErrorProto {
                 .map(|v| anyhow::Ok(v.json_serialize()?.into_bytes()))
                 .transpose()?,
             frames: frames.map(JsFramesProto::from),
+            argument_validation: argument_validation.map(ArgumentValidationErrorProto::from),
         })
     }
 }
@@ -520,6 +580,7 @@ impl TryFrom<JsErrorProto> for JsError {
             message,
             custom_data,
             frames,
+            argument_validation,
         }: JsErrorProto,
     ) -> anyhow::Result<Self> {
         Ok(Self {
@@ -532,13 +593,14 @@ impl TryFrom<JsErrorProto> for JsError {
                 })
                 .transpose()?,
             frames: frames.map(JsFrames::try_from).transpose()?,
+            argument_validation: argument_validation.map(ArgumentValidationError::from),
         })
     }
 }
 
 impl HeapSize for JsError {
     fn heap_size(&self) -> usize {
-        self.message.heap_size() + self.frames.heap_size()
+        self.message.heap_size() + self.frames.heap_size() + self.argument_validation.heap_size()
     }
 }
 
@@ -593,6 +655,7 @@ impl JsError {
             message,
             custom_data: None,
             frames: None,
+            argument_validation: None,
         }
     }
 
@@ -601,6 +664,16 @@ impl JsError {
             message,
             custom_data: Some(data),
             frames: None,
+            argument_validation: None,
+        }
+    }
+
+    pub fn argument_validation_error(message: String, error: ArgumentValidationError) -> Self {
+        Self {
+            message,
+            custom_data: None,
+            frames: None,
+            argument_validation: Some(error),
         }
     }
 
@@ -681,6 +754,7 @@ impl JsError {
             message,
             custom_data,
             frames: Some(JsFrames(mapped_frames.into())),
+            argument_validation: None,
         }
     }
 
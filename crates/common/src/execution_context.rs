@@ -26,6 +26,8 @@ use value::{
 
 use crate::{
     components::ComponentId,
+    log_lines::LogLevel,
+    runtime::UnixTimestamp,
     types::FunctionCaller,
 };
 
@@ -44,6 +46,20 @@ pub struct ExecutionContext {
     /// version of this would be something like parent_execution_id:
     /// Option<ExecutionId>
     is_root: bool,
+    /// Seed for the function's RNG. Functions are seeded from fresh entropy
+    /// when this is `None`, which is always the case outside of tests.
+    pub rng_seed: Option<[u8; 32]>,
+    /// What the function sees as the current time. Functions read the
+    /// runtime's clock when this is `None`. Only tests pin it, through
+    /// `Application::set_pinned_unix_timestamp`, which is compiled only with
+    /// the `testing` feature.
+    pub unix_timestamp: Option<UnixTimestamp>,
+    /// Console messages below this level are dropped. Defaults to
+    /// `UDF_MIN_LOG_LEVEL`.
+    pub min_log_level: Option<LogLevel>,
+    /// Bytes of memory the function may use before it's terminated. Defaults
+    /// to `ISOLATE_INVOCATION_MEMORY_LIMIT`.
+    pub memory_limit: Option<usize>,
 }
 
 impl ExecutionContext {
@@ -53,6 +69,10 @@ impl ExecutionContext {
             execution_id: ExecutionId::new(),
             parent_scheduled_job: caller.parent_scheduled_job(),
             is_root: caller.is_root(),
+            rng_seed: None,
+            unix_timestamp: None,
+            min_log_level: None,
+            memory_limit: None,
         }
     }
 
@@ -67,6 +87,10 @@ impl ExecutionContext {
             execution_id,
             parent_scheduled_job,
             is_root,
+            rng_seed: None,
+            unix_timestamp: None,
+            min_log_level: None,
+            memory_limit: None,
         }
     }
 
@@ -81,6 +105,10 @@ impl ExecutionContext {
             execution_id: ExecutionId::new(),
             parent_scheduled_job: None,
             is_root: true,
+            rng_seed: None,
+            unix_timestamp: None,
+            min_log_level: None,
+            memory_limit: None,
         }
     }
 
@@ -263,6 +291,10 @@ impl From<ExecutionContext> for pb::common::ExecutionContext {
                 .and_then(|id| id.serialize_to_string()),
             parent_scheduled_job: parent_document_id.map(Into::into),
             is_root: Some(value.is_root),
+            rng_seed: value.rng_seed.map(|seed| seed.to_vec()),
+            unix_timestamp: value.unix_timestamp.map(Into::into),
+            min_log_level: value.min_log_level.map(|level| level.to_string()),
+            memory_limit: value.memory_limit.map(|bytes| bytes as u64),
         }
     }
 }
@@ -283,6 +315,15 @@ impl TryFrom<pb::common::ExecutionContext> for ExecutionContext {
             },
             parent_scheduled_job: parent_document_id.map(|id| (parent_component_id, id)),
             is_root: value.is_root.unwrap_or_default(),
+            rng_seed: value
+                .rng_seed
+                .map(|seed| {
+                    <[u8; 32]>::try_from(seed).map_err(|_| anyhow::anyhow!("Invalid RNG seed"))
+                })
+                .transpose()?,
+            unix_timestamp: value.unix_timestamp.map(TryInto::try_into).transpose()?,
+            min_log_level: value.min_log_level.map(|level| level.parse()).transpose()?,
+            memory_limit: value.memory_limit.map(|bytes| bytes as usize),
         })
     }
 }
@@ -29,9 +29,12 @@ use reqwest::{
 };
 use tokio::select;
 
-use crate::http::{
-    HttpRequestStream,
-    HttpResponseStream,
+use crate::{
+    http::{
+        HttpRequestStream,
+        HttpResponseStream,
+    },
+    knobs::FETCH_DEFAULT_USER_AGENT,
 };
 
 /// Http client used for fetch syscall.
@@ -62,7 +65,7 @@ impl ProxiedFetchClient {
                         );
                     builder = builder.proxy(proxy);
                 }
-                builder = builder.user_agent("Convex/1.0");
+                builder = builder.user_agent(FETCH_DEFAULT_USER_AGENT.as_str());
                 builder.build().expect("Failed to build reqwest client")
             })),
         }
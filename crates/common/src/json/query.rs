@@ -61,6 +61,9 @@ struct JsonQueryIndexRange {
     index_name: String,
     range: Vec<JsonIndexRangeExpression>,
     order: Option<String>,
+    // Omitted when unset so existing query fingerprints don't change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    collation: Option<String>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -241,6 +244,10 @@ impl TryFrom<JsonQuerySource> for QuerySource {
                     index_name: IndexName::from_str(&json_index_range.index_name)?,
                     range: range_exprs,
                     order: try_order_from_string(json_index_range.order)?,
+                    collation: json_index_range
+                        .collation
+                        .map(|locale| locale.parse())
+                        .transpose()?,
                 })
             },
             JsonQuerySource::Search(json_search) => {
@@ -274,6 +281,7 @@ impl From<QuerySource> for JsonQuerySource {
                 index_name,
                 range,
                 order,
+                collation,
             }) => JsonQuerySource::IndexRange(JsonQueryIndexRange {
                 index_name: index_name.to_string(),
                 range: range
@@ -281,6 +289,7 @@ impl From<QuerySource> for JsonQuerySource {
                     .map(|range_expr| range_expr.into())
                     .collect(),
                 order: Some(order.into()),
+                collation: collation.map(|locale| locale.to_string()),
             }),
             QuerySource::Search(Search {
                 index_name,
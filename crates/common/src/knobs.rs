@@ -13,6 +13,7 @@
 #![deny(missing_docs)]
 
 use std::{
+    collections::BTreeMap,
     num::{
         NonZeroU32,
         NonZeroUsize,
@@ -23,7 +24,11 @@ use std::{
 
 use cmd_util::env::env_config;
 
-use crate::fastrace_helpers::SamplingConfig;
+use crate::{
+    fastrace_helpers::SamplingConfig,
+    log_lines::LogLevel,
+    types::ConflictGranularity,
+};
 
 /// This exists solely to allow knobs to have separate defaults for local
 /// execution and prod (running in Nomad). Don't export this outside of
@@ -60,6 +65,13 @@ pub static RUNTIME_WORKER_THREADS: LazyLock<usize> =
 pub static RUNTIME_DISABLE_LIFO_SLOT: LazyLock<bool> =
     LazyLock::new(|| env_config("RUNTIME_DISABLE_LIFO_SLOT", true));
 
+/// Console messages from functions below this level (one of `DEBUG`, `LOG`,
+/// `INFO`, `WARN` or `ERROR`) are dropped. Mutations and actions can override
+/// this per request. Queries can't, since their cached log lines are shared
+/// between requests.
+pub static UDF_MIN_LOG_LEVEL: LazyLock<LogLevel> =
+    LazyLock::new(|| env_config("UDF_MIN_LOG_LEVEL", LogLevel::Debug));
+
 /// Maximum size of the UDF cache. Default 100MiB.
 pub static UDF_CACHE_MAX_SIZE: LazyLock<usize> =
     LazyLock::new(|| env_config("UDF_CACHE_MAX_SIZE", 104857600));
@@ -139,11 +151,13 @@ pub static LOG_MANAGER_AGGREGATION_INTERVAL_MILLIS: LazyLock<u64> =
 pub static UDF_EXECUTOR_OCC_MAX_RETRIES: LazyLock<usize> =
     LazyLock::new(|| env_config("UDF_EXECUTOR_OCC_MAX_RETRIES", 4));
 
-/// Initial backoff when we encounter an OCC conflict.
+/// Initial backoff when we encounter an OCC conflict. Backoffs double with
+/// each conflict up to `UDF_EXECUTOR_OCC_MAX_BACKOFF`, are jittered, and are
+/// skipped if they'd extend the mutation past `DATABASE_UDF_SYSTEM_TIMEOUT`.
 pub static UDF_EXECUTOR_OCC_INITIAL_BACKOFF: LazyLock<Duration> =
     LazyLock::new(|| Duration::from_millis(env_config("UDF_EXECUTOR_OCC_INITIAL_BACKOFF_MS", 10)));
 
-/// Maximum expontial backoff when facing repeated OCC conflicts.
+/// Maximum exponential backoff when facing repeated OCC conflicts.
 pub static UDF_EXECUTOR_OCC_MAX_BACKOFF: LazyLock<Duration> =
     LazyLock::new(|| Duration::from_millis(env_config("UDF_EXECUTOR_OCC_MAX_BACKOFF_MS", 2000)));
 
@@ -196,6 +210,30 @@ pub static HTTP_SERVER_MAX_CONCURRENT_REQUESTS: LazyLock<usize> =
 pub static TRANSACTION_MAX_NUM_USER_WRITES: LazyLock<usize> =
     LazyLock::new(|| env_config("TRANSACTION_MAX_NUM_USER_WRITES", 16000));
 
+/// Max size of a single user document, in bytes. Writes of larger documents
+/// fail with `ValueTooLargeError`. This can lower, but not raise, the limit of
+/// `::value::MAX_USER_SIZE`.
+pub static MAX_USER_DOCUMENT_SIZE_BYTES: LazyLock<usize> = LazyLock::new(|| {
+    env_config("MAX_USER_DOCUMENT_SIZE_BYTES", value::MAX_USER_SIZE).min(value::MAX_USER_SIZE)
+});
+
+/// Top-level `bytes` fields at least this large are stored in the
+/// `document_blobs` storage rather than in the document's row, which holds a
+/// reference instead. Zero (the default) keeps all fields inline. Only applies
+/// to fields written while it's set; reads work either way.
+pub static DOCUMENT_BLOB_THRESHOLD_BYTES: LazyLock<usize> =
+    LazyLock::new(|| env_config("DOCUMENT_BLOB_THRESHOLD_BYTES", 0));
+
+/// Max number of out-of-line blobs fetched concurrently while reading
+/// documents.
+pub static DOCUMENT_BLOB_FETCH_CONCURRENCY: LazyLock<usize> =
+    LazyLock::new(|| env_config("DOCUMENT_BLOB_FETCH_CONCURRENCY", 8));
+
+/// How precisely transactions record their reads for OCC conflict
+/// detection. One of `row`, `range` or `table`; see `ConflictGranularity`.
+pub static CONFLICT_GRANULARITY: LazyLock<ConflictGranularity> =
+    LazyLock::new(|| env_config("CONFLICT_GRANULARITY", ConflictGranularity::Range));
+
 /// Max size of user writes in a transaction, in bytes
 pub static TRANSACTION_MAX_USER_WRITE_SIZE_BYTES: LazyLock<usize> = LazyLock::new(|| {
     env_config("TRANSACTION_MAX_USER_WRITE_SIZE_BYTES", 1 << 24) // 16 MiB
@@ -328,6 +366,11 @@ pub static MAX_REACTOR_CALL_DEPTH: LazyLock<usize> =
 pub static DEFAULT_QUERY_PREFETCH: LazyLock<usize> =
     LazyLock::new(|| env_config("DEFAULT_QUERY_PREFETCH", 100));
 
+/// Largest page `Application::paginated_query_udf` returns. Larger page sizes
+/// are clamped to this.
+pub static PAGINATED_QUERY_MAX_PAGE_SIZE: LazyLock<usize> =
+    LazyLock::new(|| env_config("PAGINATED_QUERY_MAX_PAGE_SIZE", 8192));
+
 /// Number of rows that can be read in a transaction.
 pub static TRANSACTION_MAX_READ_SIZE_ROWS: LazyLock<usize> =
     LazyLock::new(|| env_config("TRANSACTION_MAX_READ_SIZE_ROWS", 32000));
@@ -654,6 +697,29 @@ pub static VECTOR_INDEX_WORKER_PAGE_SIZE: LazyLock<usize> =
 pub static DATABASE_UDF_USER_TIMEOUT: LazyLock<Duration> =
     LazyLock::new(|| Duration::from_secs(env_config("DATABASE_UDF_USER_TIMEOUT_SECONDS", 1)));
 
+/// How often a function's isolate is interrupted to count an op. V8 can't
+/// count the operations it executes, and only services interrupts while it's
+/// running JavaScript, so each serviced interrupt counts as one op of
+/// JavaScript execution. `ISOLATE_OPS_PER_YIELD` and `ISOLATE_CPU_BUDGET_OPS`
+/// are measured in these ops.
+pub static ISOLATE_OP_INTERVAL: LazyLock<Duration> =
+    LazyLock::new(|| Duration::from_millis(env_config("ISOLATE_OP_INTERVAL_MILLIS", 10)));
+
+/// After this many ops, a function gives up its concurrency permit to the
+/// functions waiting for one and carries on once it's rescheduled, so
+/// CPU-heavy functions can't starve the others. Time spent waiting to be
+/// rescheduled doesn't count against the function's timeout. Zero disables
+/// yielding.
+pub static ISOLATE_OPS_PER_YIELD: LazyLock<u64> =
+    LazyLock::new(|| env_config("ISOLATE_OPS_PER_YIELD", 10));
+
+/// Functions that run more than this many ops of JavaScript are terminated
+/// with `CpuBudgetExceeded`. The default is ten minutes of JavaScript at the
+/// default `ISOLATE_OP_INTERVAL`, matching `ACTION_USER_TIMEOUT`. Zero
+/// disables the budget.
+pub static ISOLATE_CPU_BUDGET_OPS: LazyLock<u64> =
+    LazyLock::new(|| env_config("ISOLATE_CPU_BUDGET_OPS", 60_000));
+
 /// Timeout on the "system time" during a UDF -- i.e. syscalls.
 // The user limits are not very tight, which requires us to have a high
 // syscall timeout. When the database is healthy, we should never have UDF
@@ -811,6 +877,46 @@ pub static APPLICATION_MAX_CONCURRENT_UPLOADS: LazyLock<usize> =
 pub static ISOLATE_MAX_USER_HEAP_SIZE: LazyLock<usize> =
     LazyLock::new(|| env_config("ISOLATE_MAX_USER_HEAP_SIZE", 1 << 26));
 
+/// Memory a single function invocation may have in use, across the V8 heap
+/// and the blobs, streams and array buffers it holds, before it's terminated
+/// with `MemoryLimitExceeded`. Zero disables the limit.
+///
+/// This is only checked when the function yields, e.g. to await a syscall. A
+/// function that allocates without yielding, such as one building a giant
+/// array in a synchronous loop, isn't stopped by this limit and instead runs
+/// until it hits `ISOLATE_MAX_USER_HEAP_SIZE`, which fails it with an
+/// out-of-memory error.
+pub static ISOLATE_INVOCATION_MEMORY_LIMIT: LazyLock<Option<usize>> = LazyLock::new(|| {
+    let bytes = env_config("ISOLATE_INVOCATION_MEMORY_LIMIT_BYTES", 0);
+    (bytes > 0).then_some(bytes)
+});
+
+/// Comma-separated `caller=bytes` overrides of
+/// `ISOLATE_INVOCATION_MEMORY_LIMIT` for functions run by particular callers,
+/// e.g. `Cron=16777216,Scheduler=33554432`. Callers are named as in
+/// `FunctionCaller`'s `Display` impl.
+pub static ISOLATE_INVOCATION_MEMORY_LIMIT_BY_CALLER: LazyLock<BTreeMap<String, usize>> =
+    LazyLock::new(|| {
+        caller_limits(env_config(
+            "ISOLATE_INVOCATION_MEMORY_LIMIT_BY_CALLER",
+            String::new(),
+        ))
+    });
+
+fn caller_limits(limits: String) -> BTreeMap<String, usize> {
+    limits
+        .split(',')
+        .filter_map(|limit| {
+            let (caller, bytes) = limit.split_once('=')?;
+            let bytes = bytes
+                .trim()
+                .parse()
+                .unwrap_or_else(|e| panic!("Invalid memory limit for {caller}: {e}"));
+            Some((caller.trim().to_string(), bytes))
+        })
+        .collect()
+}
+
 /// Allow for some objects to persist between contexts, not necessarily created
 /// by the UDF.
 pub static ISOLATE_MAX_HEAP_EXTRA_SIZE: LazyLock<usize> =
@@ -892,10 +998,25 @@ pub static POSTGRES_INACTIVE_CONNECTION_LIFETIME: LazyLock<Duration> = LazyLock:
     Duration::from_secs(env_config("POSTGRES_INACTIVE_CONNECTION_LIFETIME_SECS", 90))
 });
 
-/// How many actions "ops" (e.g. syscalls) can execute concurrently.
+/// How many actions "ops" (e.g. syscalls) can execute concurrently. This
+/// bounds the task executor's parallelism, unlike
+/// `MAX_OUTSTANDING_ACTION_ASYNC_OPS`, which bounds how many async ops the
+/// action's JS can have waiting on results.
 pub static MAX_CONCURRENT_ACTION_OPS: LazyLock<usize> =
     LazyLock::new(|| env_config("MAX_CONCURRENT_ACTION_OPS", 8));
 
+/// How many async ops (e.g. `fetch()` or `setTimeout()`) an action can start
+/// over its entire execution.
+pub static MAX_TOTAL_ACTION_ASYNC_OPS: LazyLock<usize> =
+    LazyLock::new(|| env_config("MAX_TOTAL_ACTION_ASYNC_OPS", 100_000));
+
+/// How many async ops an action can have outstanding at once. Starting more
+/// fails the new op rather than queueing it. Not to be confused with
+/// `MAX_CONCURRENT_ACTION_OPS`, which limits how many of an action's
+/// syscalls the task executor runs in parallel.
+pub static MAX_OUTSTANDING_ACTION_ASYNC_OPS: LazyLock<usize> =
+    LazyLock::new(|| env_config("MAX_OUTSTANDING_ACTION_ASYNC_OPS", 1000));
+
 /// Maximum count of transitions within the web socket server message buffer.
 /// When this limit is reached, the web socket worker will temporary stop
 /// computing and sending transition messages to the client.
@@ -987,6 +1108,31 @@ pub static FUNRUN_CODE_CACHE_SIZE: LazyLock<u64> =
 pub static FUNRUN_FETCH_CLIENT_CACHE_SIZE: LazyLock<usize> =
     LazyLock::new(|| env_config("FUNRUN_FETCH_CLIENT_CACHE_SIZE", 100));
 
+/// The `User-Agent` header sent with `fetch()` requests from actions, unless
+/// the function sets its own.
+pub static FETCH_DEFAULT_USER_AGENT: LazyLock<String> =
+    LazyLock::new(|| env_config("FETCH_DEFAULT_USER_AGENT", String::from("Convex/1.0")));
+
+/// Comma-separated hosts that `fetch()` requests from actions are restricted
+/// to. A host also allows its subdomains. If empty, any host not in
+/// `FETCH_HOST_DENYLIST` is allowed.
+pub static FETCH_HOST_ALLOWLIST: LazyLock<Vec<String>> =
+    LazyLock::new(|| host_list(env_config("FETCH_HOST_ALLOWLIST", String::new())));
+
+/// Comma-separated hosts that `fetch()` requests from actions may never be
+/// made to, even if they're in `FETCH_HOST_ALLOWLIST`. A host also denies its
+/// subdomains.
+pub static FETCH_HOST_DENYLIST: LazyLock<Vec<String>> =
+    LazyLock::new(|| host_list(env_config("FETCH_HOST_DENYLIST", String::new())));
+
+fn host_list(hosts: String) -> Vec<String> {
+    hosts
+        .split(',')
+        .map(|host| host.trim().to_ascii_lowercase())
+        .filter(|host| !host.is_empty())
+        .collect()
+}
+
 /// The maximum number of concurrent requests a single client can make to a
 /// single Funrun server.
 /// NOTE: When changing this value, ensure that the following parameters
@@ -1323,6 +1469,11 @@ pub static MIGRATION_REWRITE_BATCH_SIZE: LazyLock<usize> =
 pub static MAX_IMPORT_AGE: LazyLock<Duration> =
     LazyLock::new(|| Duration::from_secs(env_config("MAX_IMPORT_AGE_SECONDS", 7 * 24 * 60 * 60)));
 
+/// How long a schema change waits for in-progress imports to finish before
+/// giving up.
+pub static SCHEMA_LOCK_TIMEOUT: LazyLock<Duration> =
+    LazyLock::new(|| Duration::from_secs(env_config("SCHEMA_LOCK_TIMEOUT_SECONDS", 60)));
+
 /// Max staleness in seconds of a partition loader result before we allow
 /// refreshing. If a request tries to update the partition loader and this
 /// duration has not passed since the last refresh, a stale value will be used.
@@ -1445,3 +1596,13 @@ pub static INDEX_BACKFILL_CONCURRENCY: LazyLock<usize> =
 /// getting auth metadata right now.
 pub static HTTP_CACHE_SIZE: LazyLock<u64> =
     LazyLock::new(|| env_config("HTTP_CACHE_SIZE", 16 * 1024 * 1024));
+
+/// How long `Application::health_check` waits for its probe query before
+/// reporting a timeout.
+pub static HEALTH_CHECK_TIMEOUT: LazyLock<Duration> =
+    LazyLock::new(|| Duration::from_millis(env_config("HEALTH_CHECK_TIMEOUT_MS", 5000)));
+
+/// Probe queries that take longer than this (but finish within
+/// `HEALTH_CHECK_TIMEOUT`) report the instance as degraded.
+pub static HEALTH_CHECK_DEGRADED_THRESHOLD: LazyLock<Duration> =
+    LazyLock::new(|| Duration::from_millis(env_config("HEALTH_CHECK_DEGRADED_THRESHOLD_MS", 1000)));
@@ -27,6 +27,7 @@ pub mod bootstrap_model;
 pub mod bounds;
 pub mod client_pool;
 pub mod codel_queue;
+pub mod collation;
 pub mod comparators;
 pub mod components;
 pub mod deleted_bitset;
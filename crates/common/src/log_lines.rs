@@ -53,7 +53,7 @@ pub const MAX_LOG_LINE_LENGTH: usize = 32768;
 pub struct LogLines(WithHeapSize<Vec<LogLine>>);
 pub type RawLogLines = WithHeapSize<Vec<String>>;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(any(test, feature = "testing"), derive(proptest_derive::Arbitrary))]
 pub enum LogLevel {
     Debug,
@@ -75,6 +75,24 @@ impl Display for LogLevel {
     }
 }
 
+impl LogLevel {
+    fn severity(&self) -> u8 {
+        match self {
+            LogLevel::Debug => 0,
+            LogLevel::Log | LogLevel::Info => 1,
+            LogLevel::Warn => 2,
+            LogLevel::Error => 3,
+        }
+    }
+
+    /// Whether to keep a message at this level when only messages at
+    /// `threshold` or above are wanted. Errors are always kept, even if the
+    /// threshold is set above them.
+    pub fn meets_threshold(&self, threshold: &LogLevel) -> bool {
+        *self == LogLevel::Error || self.severity() >= threshold.severity()
+    }
+}
+
 impl FromStr for LogLevel {
     type Err = anyhow::Error;
 
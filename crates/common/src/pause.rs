@@ -4,6 +4,10 @@ mod test_pause {
         collections::BTreeMap,
         mem,
         sync::Arc,
+        time::{
+            Duration,
+            Instant,
+        },
     };
 
     use parking_lot::Mutex;
@@ -19,6 +23,12 @@ mod test_pause {
     #[derive(Default, Clone)]
     pub struct PauseClient {
         channels: Arc<Mutex<BTreeMap<&'static str, RendezvousReceiver<oneshot::Receiver<Fault>>>>>,
+        // Breakpoints the tested code is currently paused on, along with when
+        // it started waiting.
+        blocked: Arc<Mutex<BTreeMap<&'static str, Instant>>>,
+        // How many times the tested code has reached each breakpoint, whether
+        // or not it was held.
+        hits: Arc<Mutex<BTreeMap<&'static str, usize>>>,
     }
 
     impl PauseClient {
@@ -27,12 +37,15 @@ mod test_pause {
         pub fn new() -> Self {
             Self {
                 channels: Arc::new(Mutex::new(BTreeMap::new())),
+                blocked: Arc::new(Mutex::new(BTreeMap::new())),
+                hits: Arc::new(Mutex::new(BTreeMap::new())),
             }
         }
 
         /// Wait for the named breakpoint, blocking until the controller
         /// `unpause`s it.
         pub async fn wait(&self, label: &'static str) -> Fault {
+            *self.hits.lock().entry(label).or_default() += 1;
             let mut rendezvous = match self.channels.lock().remove(&label) {
                 Some(r) => r,
                 None => {
@@ -41,9 +54,11 @@ mod test_pause {
                 },
             };
             tracing::info!("PauseClient waiting on {label}");
+            self.blocked.lock().insert(label, Instant::now());
             // Start waiting on the channel to signal to the controller that we're paused.
             let Some(rx) = rendezvous.recv().await else {
                 tracing::info!("Rendezvous disconnected for {label:?}, continuing...");
+                self.blocked.lock().remove(&label);
                 return Fault::Noop;
             };
             tracing::info!("PauseClient successfully paused {label}");
@@ -52,6 +67,7 @@ mod test_pause {
                 tracing::info!("Rendezvous disconnected after pause for {label:?}, continuing...");
                 Fault::Noop
             });
+            self.blocked.lock().remove(&label);
             tracing::info!("PauseClient successfully unpaused {label}");
             fault
         }
@@ -85,6 +101,18 @@ mod test_pause {
                 fault: Fault::Noop,
             })
         }
+
+        /// Like `wait_for_blocked`, but fails if the tested code doesn't hit
+        /// the breakpoint within `timeout`, rather than hanging the test.
+        pub async fn wait_for_blocked_with_timeout(
+            self,
+            timeout: Duration,
+        ) -> anyhow::Result<Option<PauseGuard>> {
+            let label = self.label;
+            tokio::time::timeout(timeout, self.wait_for_blocked())
+                .await
+                .map_err(|_| anyhow::anyhow!("Timed out after {timeout:?} waiting for {label}"))
+        }
     }
 
     pub struct PauseGuard {
@@ -125,9 +153,7 @@ mod test_pause {
     /// and then install the returned `PauseClient` in your tested code.
     impl PauseController {
         pub fn new() -> (Self, PauseClient) {
-            let client = PauseClient {
-                channels: Default::default(),
-            };
+            let client = PauseClient::new();
             let controller = Self {
                 client: client.clone(),
             };
@@ -141,6 +167,24 @@ mod test_pause {
             }
             HoldGuard { label, sender: tx }
         }
+
+        /// How many times the tested code has reached the named breakpoint,
+        /// including times it wasn't held.
+        pub fn hit_count(&self, label: &'static str) -> usize {
+            self.client.hits.lock().get(label).copied().unwrap_or(0)
+        }
+
+        /// Returns the breakpoints the tested code is currently blocked on and
+        /// how long it has been waiting on each, sorted by label. Useful for
+        /// figuring out where a hung test is stuck.
+        pub fn blocked_breakpoints(&self) -> Vec<(&'static str, Duration)> {
+            self.client
+                .blocked
+                .lock()
+                .iter()
+                .map(|(label, since)| (*label, since.elapsed()))
+                .collect()
+        }
     }
 }
 #[cfg(any(test, feature = "testing"))]
@@ -41,6 +41,7 @@ use value::{
 
 use crate::{
     bootstrap_model::index::database_index::IndexedFields,
+    collation::Locale,
     document::ID_FIELD_PATH,
     index::IndexKeyBytes,
     interval::{
@@ -181,6 +182,10 @@ pub struct IndexRange {
     pub range: Vec<IndexRangeExpression>,
     /// The order to scan in.
     pub order: Order,
+    /// Order strings by this locale's collation rather than by code point.
+    /// The range is still read in index order and re-sorted afterwards, so
+    /// these queries can't be paginated.
+    pub collation: Option<Locale>,
 }
 
 impl IndexRange {
@@ -807,11 +812,13 @@ mod proptest {
             (
                 prop::collection::vec(any::<IndexRangeExpression>(), 0..4),
                 any::<(IndexName, Order)>(),
+                prop::option::of(prop_oneof![Just("en"), Just("sv-SE")]),
             )
-                .prop_map(|(range, (index_name, order))| IndexRange {
+                .prop_map(|(range, (index_name, order), collation)| IndexRange {
                     range,
                     index_name,
                     order,
+                    collation: collation.map(|locale| locale.parse().unwrap()),
                 })
         }
     }
@@ -1095,6 +1102,7 @@ impl Query {
                 MaybeValue(Some(ConvexValue::from(id))),
             )],
             order: Order::Asc,
+            collation: None,
         })
     }
 
@@ -1375,7 +1383,8 @@ mod tests {
                     "channel".parse()?,
                     maybe_val!("#general")
                 )],
-                order: Order::Desc
+                order: Order::Desc,
+                collation: None,
             })
             .fingerprint(&indexed_fields)?,
             vec![
@@ -548,6 +548,13 @@ impl TryFrom<ValidatorJson> for DocumentSchema {
 
     fn try_from(json: ValidatorJson) -> Result<Self, Self::Error> {
         let schema_type = Validator::try_from(json)?;
+        // Inserts don't fill in defaults, so don't accept them on table fields.
+        if schema_type.has_field_defaults() {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidDefault",
+                "Table fields can't have default values; only function arguments can"
+            ));
+        }
         match schema_type {
             Validator::Any => Ok(DocumentSchema::Any),
             Validator::Union(value) => {
@@ -603,6 +610,8 @@ impl TryFrom<DocumentSchema> for ValidatorJson {
 pub struct FieldTypeJson {
     field_type: ValidatorJson,
     optional: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default: Option<JsonValue>,
 }
 
 impl JsonForm for FieldValidator {
@@ -613,9 +622,29 @@ impl TryFrom<FieldTypeJson> for FieldValidator {
     type Error = anyhow::Error;
 
     fn try_from(field_type_json: FieldTypeJson) -> anyhow::Result<Self> {
+        let default = field_type_json
+            .default
+            .map(ConvexValue::try_from)
+            .transpose()?;
+        if default.is_some() && !field_type_json.optional {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidDefault",
+                "Only optional fields can have a default value"
+            ));
+        }
+        let validator: Validator = field_type_json.field_type.try_into()?;
+        if let Some(default) = &default
+            && let Err(e) = validator.check_default(default)
+        {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "InvalidDefault",
+                format!("Default value {default} doesn't match its validator {validator}: {e}")
+            ));
+        }
         Ok(FieldValidator {
-            validator: field_type_json.field_type.try_into()?,
+            validator,
             optional: field_type_json.optional,
+            default,
         })
     }
 }
@@ -627,6 +656,7 @@ impl TryFrom<FieldValidator> for FieldTypeJson {
         Ok(FieldTypeJson {
             field_type: ValidatorJson::try_from(f.validator)?,
             optional: f.optional,
+            default: f.default.map(JsonValue::from),
         })
     }
 }
@@ -753,6 +783,7 @@ impl TryFrom<Validator> for ValidatorJson {
                 values: Box::new(FieldTypeJson::try_from(FieldValidator {
                     optional: false,
                     validator: *v,
+                    default: None,
                 })?),
             },
             Validator::Object(o) => ValidatorJson::Object {
@@ -843,7 +874,11 @@ mod tests {
 
     use crate::schemas::{
         json::ValidatorJson,
-        validator::LiteralValidator,
+        validator::{
+            LiteralValidator,
+            Validator,
+        },
+        DocumentSchema,
     };
 
     #[test]
@@ -878,4 +913,36 @@ mod tests {
         );
         Ok(())
     }
+
+    fn object_with_default(default: JsonValue) -> JsonValue {
+        json!({
+            "type": "object",
+            "value": {
+                "field": {
+                    "fieldType": {"type": "string"},
+                    "optional": true,
+                    "default": default,
+                },
+            },
+        })
+    }
+
+    #[test]
+    fn test_default_must_match_validator() -> anyhow::Result<()> {
+        let json: ValidatorJson = serde_json::from_value(object_with_default(json!("ok")))?;
+        Validator::try_from(json)?;
+
+        let json: ValidatorJson = serde_json::from_value(object_with_default(json!(1)))?;
+        let error = Validator::try_from(json).unwrap_err();
+        assert_eq!(error.short_msg(), "InvalidDefault");
+        Ok(())
+    }
+
+    #[test]
+    fn test_table_fields_cannot_have_defaults() -> anyhow::Result<()> {
+        let json: ValidatorJson = serde_json::from_value(object_with_default(json!("ok")))?;
+        let error = DocumentSchema::try_from(json).unwrap_err();
+        assert_eq!(error.short_msg(), "InvalidDefault");
+        Ok(())
+    }
 }
@@ -111,6 +111,7 @@ impl proptest::arbitrary::Arbitrary for Validator {
                         FieldValidator {
                             validator,
                             optional,
+                            default: None,
                         }
                     }),
                     0..8
@@ -347,6 +348,7 @@ impl Validator {
                                     virtual_system_mapping,
                                 ),
                                 optional: v.optional,
+                                default: None,
                             },
                         )
                     })
@@ -663,6 +665,38 @@ impl Validator {
         ))
     }
 
+    /// Does any object field within this validator declare a default value?
+    pub fn has_field_defaults(&self) -> bool {
+        match self {
+            Self::Object(object) => object
+                .0
+                .values()
+                .any(|field| field.default.is_some() || field.validator.has_field_defaults()),
+            Self::Array(item) => item.has_field_defaults(),
+            Self::Union(options) => options.iter().any(|option| option.has_field_defaults()),
+            Self::Record(key, value) => key.has_field_defaults() || value.has_field_defaults(),
+            Self::Any
+            | Self::Boolean
+            | Self::Bytes
+            | Self::String
+            | Self::Id(_)
+            | Self::Literal(_)
+            | Self::Null
+            | Self::Float64
+            | Self::Int64 => false,
+        }
+    }
+
+    /// Check a default value declared in a schema against this validator.
+    /// There's no table mapping when a schema is parsed, so IDs never match.
+    pub fn check_default(&self, value: &ConvexValue) -> Result<(), ValidationError> {
+        self.check_value_internal(
+            value,
+            &|_| anyhow::bail!("Defaults can't reference tables"),
+            ValidationContext::new(),
+        )
+    }
+
     // Filter out `_id` and `_creationTime` at the top level
     pub fn filter_top_level_system_fields(self) -> Self {
         match self {
@@ -722,6 +756,12 @@ impl ValidationContext {
             None => Self(Some(new_context)),
         }
     }
+
+    /// Path to the value being validated from the outermost value, like
+    /// `.user.tags[0]`, or `None` for the outermost value itself.
+    pub fn path(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
 }
 
 impl Display for ValidationContext {
@@ -907,6 +947,10 @@ pub struct FieldValidator {
     )]
     pub validator: Validator,
     pub optional: bool,
+    /// Value to use when an optional function argument is omitted. Only
+    /// function arguments can have defaults, not table fields.
+    #[cfg_attr(any(test, feature = "testing"), proptest(value = "None"))]
+    pub default: Option<ConvexValue>,
 }
 
 impl FieldValidator {
@@ -918,6 +962,7 @@ impl FieldValidator {
         Self {
             validator,
             optional: false,
+            default: None,
         }
     }
 
@@ -925,6 +970,7 @@ impl FieldValidator {
         Self {
             validator,
             optional: true,
+            default: None,
         }
     }
 }
@@ -1006,6 +1052,61 @@ Validator: {validator}"
     },
 }
 
+impl ValidationError {
+    /// Path to the offending value, like `.user.name`. For missing and extra
+    /// fields this includes the field itself.
+    pub fn field_path(&self) -> Option<String> {
+        match self {
+            ValidationError::TableNamesDoNotMatch { context, .. }
+            | ValidationError::SystemTableReference { context, .. }
+            | ValidationError::LiteralValuesDoNotMatch { context, .. }
+            | ValidationError::NoMatch { context, .. } => context.path().map(str::to_string),
+            ValidationError::MissingRequiredField {
+                field_name,
+                context,
+                ..
+            } => context
+                .with(format!(".{field_name}"))
+                .path()
+                .map(str::to_string),
+            ValidationError::ExtraField {
+                field_name,
+                context,
+                ..
+            } => context
+                .with(format!(".{field_name}"))
+                .path()
+                .map(str::to_string),
+        }
+    }
+
+    /// The validator the offending value should have matched, or `None` if
+    /// the value shouldn't have been there at all.
+    pub fn expected(&self) -> Option<String> {
+        match self {
+            ValidationError::TableNamesDoNotMatch {
+                validator_table, ..
+            }
+            | ValidationError::SystemTableReference {
+                validator_table, ..
+            } => Some(Validator::Id(validator_table.clone()).to_string()),
+            ValidationError::LiteralValuesDoNotMatch {
+                literal_validator, ..
+            } => Some(Validator::Literal(literal_validator.clone()).to_string()),
+            ValidationError::MissingRequiredField {
+                field_name,
+                object_validator,
+                ..
+            } => object_validator
+                .0
+                .get(field_name)
+                .map(|field| field.to_string()),
+            ValidationError::ExtraField { .. } => None,
+            ValidationError::NoMatch { validator, .. } => Some(validator.to_string()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{
@@ -0,0 +1,19 @@
+/// How precisely a transaction's reads are recorded for OCC conflict
+/// detection. Coarser granularities keep read sets small and cheap to
+/// validate, at the cost of conflicting with writes the transaction never
+/// observed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum ConflictGranularity {
+    /// Record only the documents that were read, each as a single key. Index
+    /// range scans don't record the gaps between the documents they return,
+    /// so a transaction doesn't conflict with inserts into a range it
+    /// scanned.
+    Row,
+    /// Record exactly the index key intervals that were read.
+    #[default]
+    Range,
+    /// Widen every read to its entire index, so a transaction conflicts with
+    /// any write to a table it read from.
+    Table,
+}
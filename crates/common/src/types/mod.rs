@@ -18,6 +18,7 @@ mod actions;
 mod admin_key;
 mod backend_info;
 mod backend_state;
+mod conflict_granularity;
 mod deployment_type;
 mod environment_variables;
 mod file_storage;
@@ -51,6 +52,7 @@ pub use backend_info::{
     DEFAULT_PROVISION_CONCURRENCY,
 };
 pub use backend_state::BackendState;
+pub use conflict_granularity::ConflictGranularity;
 pub use deployment_type::DeploymentType;
 pub use environment_variables::{
     env_var_limit_met,
@@ -90,7 +92,10 @@ pub use object_key::{
     ObjectKey,
 };
 pub use snapshot_export::SetExportExpirationRequest;
-pub use table::TableStats;
+pub use table::{
+    IndexReadStats,
+    TableStats,
+};
 #[cfg(any(test, feature = "testing"))]
 pub use timestamp::unchecked_repeatable_ts;
 pub use timestamp::{
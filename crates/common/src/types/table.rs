@@ -9,6 +9,16 @@ pub struct TableStats {
     pub rows_deleted: u64,
 }
 
+/// Rows and bytes a transaction has read through a single index.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct IndexReadStats {
+    pub rows_read: u64,
+    /// Total size of the index keys scanned.
+    pub index_bytes_read: u64,
+    /// Total size of the documents the scanned index entries point to.
+    pub document_bytes_read: u64,
+}
+
 impl HeapSize for TableStats {
     fn heap_size(&self) -> usize {
         0
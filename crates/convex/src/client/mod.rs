@@ -33,6 +33,8 @@ use crate::{
     },
     client::{
         subscription::{
+            BufferedQuerySubscription,
+            OverflowPolicy,
             QuerySetSubscription,
             QuerySubscription,
         },
@@ -212,6 +214,56 @@ impl ConvexClient {
         Ok(res)
     }
 
+    /// Subscribe to the results of query `name` called with `args`, buffering
+    /// up to `capacity` results if the consumer falls behind.
+    ///
+    /// Returns a [`BufferedQuerySubscription`] which implements [`Stream`]<
+    /// [`FunctionResult`]>. Once `capacity` results are waiting to be
+    /// consumed, `policy` decides what happens to the next one.
+    ///
+    /// ```no_run
+    /// # use convex::{ConvexClient, OverflowPolicy};
+    /// # use futures::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> anyhow::Result<()> {
+    /// let mut client = ConvexClient::new("https://cool-music-123.convex.cloud").await?;
+    /// let mut sub = client
+    ///     .subscribe_buffered("listMessages", maplit::btreemap!{}, 16, OverflowPolicy::DropOldest)
+    ///     .await?;
+    /// while let Some(result) = sub.next().await {
+    ///     println!("{result:?}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn subscribe_buffered(
+        &mut self,
+        name: &str,
+        args: BTreeMap<String, Value>,
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> anyhow::Result<BufferedQuerySubscription> {
+        anyhow::ensure!(
+            capacity > 0,
+            "Subscription buffer capacity must be positive"
+        );
+        let (tx, rx) = oneshot::channel();
+
+        let udf_path = name.parse()?;
+        let request = SubscribeRequest { udf_path, args };
+        let (buffer, buffer_sender) = BufferedQuerySubscription::buffer(capacity, policy);
+
+        self.request_sender
+            .send(ClientRequest::SubscribeBuffered(request, buffer_sender, tx))?;
+
+        let subscriber_id = rx.await?;
+        Ok(BufferedQuerySubscription::new(
+            subscriber_id,
+            self.request_sender.clone(),
+            buffer,
+        ))
+    }
+
     /// Make a oneshot request to a query `name` with `args`.
     ///
     /// Returns a [`FunctionResult`] representing the result of the query.
@@ -485,6 +537,7 @@ pub mod tests {
             SyncProtocol,
         },
         value::Value,
+        OverflowPolicy,
         QuerySubscription,
     };
 
@@ -771,6 +824,101 @@ pub mod tests {
         Ok(())
     }
 
+    /// Deliver results 1 through 4 to a buffered subscription with capacity 2
+    /// whose consumer isn't reading, then return what the consumer sees.
+    async fn slow_consumer_results(
+        policy: OverflowPolicy,
+        expected_dropped: usize,
+    ) -> anyhow::Result<Vec<FunctionResult>> {
+        let (mut client, mut test_protocol) = ConvexClient::with_test_protocol().await?;
+        let mut subscription = client
+            .subscribe_buffered("getValue1", btreemap! {}, 2, policy)
+            .await?;
+        let mut version = StateVersion::initial();
+        for i in 1..=4 {
+            let (transition, end_version) =
+                fake_transition(version, vec![(subscription.query_id(), Value::from(i))]);
+            test_protocol.fake_server_response(transition).await?;
+            version = end_version;
+        }
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while subscription.dropped() < expected_dropped {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        })
+        .await?;
+        // Stop receiving so the streams that don't close can be collected.
+        drop(client);
+        let mut results = vec![];
+        while let Ok(Some(result)) =
+            tokio::time::timeout(Duration::from_millis(50), subscription.next()).await
+        {
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    #[tokio::test]
+    async fn test_buffered_subscription_overflow() -> anyhow::Result<()> {
+        let values = |values: &[i64]| -> Vec<FunctionResult> {
+            values
+                .iter()
+                .map(|v| FunctionResult::Value(Value::from(*v)))
+                .collect()
+        };
+        assert_eq!(
+            slow_consumer_results(OverflowPolicy::DropOldest, 2).await?,
+            values(&[3, 4])
+        );
+        assert_eq!(
+            slow_consumer_results(OverflowPolicy::DropNewest, 2).await?,
+            values(&[1, 2])
+        );
+        // The third result closes the stream, and the fourth is never
+        // received.
+        assert_eq!(
+            slow_consumer_results(OverflowPolicy::Close, 1).await?,
+            values(&[1, 2])
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_buffered_subscription_skips_unchanged_results() -> anyhow::Result<()> {
+        let (mut client, mut test_protocol) = ConvexClient::with_test_protocol().await?;
+        let mut changing = client
+            .subscribe_buffered("getValue1", btreemap! {}, 8, OverflowPolicy::DropOldest)
+            .await?;
+        let mut unchanged = client
+            .subscribe_buffered("getValue2", btreemap! {}, 8, OverflowPolicy::DropOldest)
+            .await?;
+        let (transition, version) = fake_transition(
+            StateVersion::initial(),
+            vec![
+                (changing.query_id(), Value::from(1)),
+                (unchanged.query_id(), Value::from(10)),
+            ],
+        );
+        test_protocol.fake_server_response(transition).await?;
+        let (transition, _) = fake_transition(version, vec![(changing.query_id(), Value::from(2))]);
+        test_protocol.fake_server_response(transition).await?;
+
+        assert_eq!(changing.next().await, Some(FunctionResult::Value(1.into())));
+        assert_eq!(changing.next().await, Some(FunctionResult::Value(2.into())));
+        assert_eq!(
+            unchanged.next().await,
+            Some(FunctionResult::Value(10.into()))
+        );
+        // The second transition didn't change this query's result, so it
+        // isn't buffered again.
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), unchanged.next())
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_client_subscribe_unsubscribe_subscribe() -> anyhow::Result<()> {
         let (mut client, mut test_protocol) = ConvexClient::with_test_protocol().await?;
@@ -1,10 +1,18 @@
 use std::{
+    collections::VecDeque,
     ops::Deref,
     pin::Pin,
+    sync::{
+        Arc,
+        Mutex,
+    },
 };
 
 use futures::{
-    task,
+    task::{
+        self,
+        AtomicWaker,
+    },
     Stream,
     StreamExt,
 };
@@ -147,3 +155,169 @@ impl Stream for QuerySetSubscription {
         }
     }
 }
+
+/// What a [`BufferedQuerySubscription`] does with a new result when its
+/// consumer has fallen behind and the buffer is full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Discard the oldest buffered result to make room for the new one.
+    DropOldest,
+    /// Discard the new result, keeping the buffered ones.
+    DropNewest,
+    /// Stop receiving results. The stream ends once the buffered results have
+    /// been consumed.
+    Close,
+}
+
+#[derive(Default)]
+struct BufferState {
+    results: VecDeque<FunctionResult>,
+    dropped: usize,
+    closed: bool,
+}
+
+pub(super) struct SubscriptionBuffer {
+    capacity: usize,
+    policy: OverflowPolicy,
+    state: Mutex<BufferState>,
+    waker: AtomicWaker,
+}
+
+impl SubscriptionBuffer {
+    fn close(&self) {
+        self.state.lock().expect("buffer lock poisoned").closed = true;
+        self.waker.wake();
+    }
+}
+
+/// The worker's half of a [`BufferedQuerySubscription`]. Dropping it ends the
+/// stream.
+pub(super) struct SubscriptionBufferSender {
+    buffer: Arc<SubscriptionBuffer>,
+}
+
+impl SubscriptionBufferSender {
+    pub(super) fn push(&self, result: FunctionResult) {
+        let mut state = self.buffer.state.lock().expect("buffer lock poisoned");
+        if state.closed {
+            return;
+        }
+        if state.results.len() < self.buffer.capacity {
+            state.results.push_back(result);
+        } else {
+            state.dropped += 1;
+            match self.buffer.policy {
+                OverflowPolicy::DropOldest => {
+                    state.results.pop_front();
+                    state.results.push_back(result);
+                },
+                OverflowPolicy::DropNewest => (),
+                OverflowPolicy::Close => state.closed = true,
+            }
+        }
+        drop(state);
+        self.buffer.waker.wake();
+    }
+}
+
+impl Drop for SubscriptionBufferSender {
+    fn drop(&mut self) {
+        self.buffer.close();
+    }
+}
+
+/// A subscription to a query with args that buffers at most a fixed number of
+/// results for a consumer that can't keep up, applying an [`OverflowPolicy`]
+/// once the buffer is full.
+///
+/// Unlike [`QuerySubscription`], which only ever yields the latest result,
+/// this yields every result the client receives for the query, up to the
+/// buffer's capacity. It is returned by [`ConvexClient::subscribe_buffered`]
+/// and unsubscribes when dropped.
+pub struct BufferedQuerySubscription {
+    pub(super) subscriber_id: SubscriberId,
+    pub(super) request_sender: mpsc::UnboundedSender<ClientRequest>,
+    buffer: Arc<SubscriptionBuffer>,
+}
+impl BufferedQuerySubscription {
+    pub(super) fn buffer(
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> (Arc<SubscriptionBuffer>, SubscriptionBufferSender) {
+        let buffer = Arc::new(SubscriptionBuffer {
+            capacity,
+            policy,
+            state: Mutex::new(BufferState::default()),
+            waker: AtomicWaker::new(),
+        });
+        (buffer.clone(), SubscriptionBufferSender { buffer })
+    }
+
+    pub(super) fn new(
+        subscriber_id: SubscriberId,
+        request_sender: mpsc::UnboundedSender<ClientRequest>,
+        buffer: Arc<SubscriptionBuffer>,
+    ) -> Self {
+        Self {
+            subscriber_id,
+            request_sender,
+            buffer,
+        }
+    }
+
+    /// Returns an identifier for this subscription based on its query and args.
+    pub fn id(&self) -> &SubscriberId {
+        &self.subscriber_id
+    }
+
+    /// The number of results discarded because the buffer was full.
+    pub fn dropped(&self) -> usize {
+        self.buffer
+            .state
+            .lock()
+            .expect("buffer lock poisoned")
+            .dropped
+    }
+}
+impl std::fmt::Debug for BufferedQuerySubscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BufferedQuerySubscription")
+            .field("subscriber_id", &self.subscriber_id)
+            .field("policy", &self.buffer.policy)
+            .finish()
+    }
+}
+impl Deref for BufferedQuerySubscription {
+    type Target = SubscriberId;
+
+    fn deref(&self) -> &SubscriberId {
+        &self.subscriber_id
+    }
+}
+impl Drop for BufferedQuerySubscription {
+    fn drop(&mut self) {
+        let _ = self
+            .request_sender
+            .send(ClientRequest::Unsubscribe(UnsubscribeRequest {
+                subscriber_id: self.subscriber_id,
+            }));
+    }
+}
+impl Stream for BufferedQuerySubscription {
+    type Item = FunctionResult;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> task::Poll<Option<Self::Item>> {
+        // Register before checking the buffer so a concurrent push can't be
+        // missed.
+        self.buffer.waker.register(cx.waker());
+        let mut state = self.buffer.state.lock().expect("buffer lock poisoned");
+        match state.results.pop_front() {
+            Some(result) => task::Poll::Ready(Some(result)),
+            None if state.closed => task::Poll::Ready(None),
+            None => task::Poll::Pending,
+        }
+    }
+}
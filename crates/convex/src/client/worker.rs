@@ -30,6 +30,7 @@ use crate::{
         SubscriberId,
     },
     client::{
+        subscription::SubscriptionBufferSender,
         QueryResults,
         QuerySubscription,
     },
@@ -60,6 +61,11 @@ pub enum ClientRequest {
         oneshot::Sender<QuerySubscription>,
         mpsc::UnboundedSender<ClientRequest>,
     ),
+    SubscribeBuffered(
+        SubscribeRequest,
+        SubscriptionBufferSender,
+        oneshot::Sender<SubscriberId>,
+    ),
     Unsubscribe(UnsubscribeRequest),
     Authenticate(AuthenticateRequest),
 }
@@ -98,12 +104,14 @@ pub async fn worker<T: SyncProtocol>(
     let mut backoff = Backoff::new(INITIAL_BACKOFF, MAX_BACKOFF);
     let mut protocol_response_stream = ReceiverStream::new(protocol_response_receiver).fuse();
     let mut client_request_stream = UnboundedReceiverStream::new(client_request_receiver).fuse();
+    let mut buffers = BTreeMap::new();
     loop {
         let e = loop {
             match _worker_once(
                 &mut protocol_response_stream,
                 &mut client_request_stream,
                 &mut watch_sender,
+                &mut buffers,
                 &mut base_client,
                 &mut protocol_manager,
             )
@@ -137,6 +145,7 @@ async fn _worker_once<T: SyncProtocol>(
     protocol_response_stream: impl FusedStream<Item = ProtocolResponse>,
     client_request_stream: impl FusedStream<Item = ClientRequest>,
     watch_sender: &mut broadcast::Sender<QueryResults>,
+    buffers: &mut BTreeMap<SubscriberId, SubscriptionBufferSender>,
     base_client: &mut BaseConvexClient,
     protocol_manager: &mut T,
 ) -> Result<(), ReconnectProtocolReason> {
@@ -147,7 +156,16 @@ async fn _worker_once<T: SyncProtocol>(
         if !protocol_response_stream.is_terminated() => {
             match protocol_response {
                 ProtocolResponse::ServerMessage(msg) => {
+                    let previous_results = base_client.latest_results().clone();
                     if let Some(subscriber_id_to_latest_value) = base_client.receive_message(msg)? {
+                        // Only buffer the results this transition changed.
+                        for (subscriber_id, buffer) in buffers.iter() {
+                            if let Some(result) = subscriber_id_to_latest_value.get(subscriber_id) {
+                                if previous_results.get(subscriber_id) != Some(result) {
+                                    buffer.push(result.clone());
+                                }
+                            }
+                        }
                         // Notify watchers of the new consistent query results at new timestamp
                         let _ = watch_sender.send(subscriber_id_to_latest_value);
                     }
@@ -165,7 +183,7 @@ async fn _worker_once<T: SyncProtocol>(
                     let SubscribeRequest {
                         udf_path,
                         args,
-                    } =  query;
+                    } = query;
                     let subscriber_id = base_client.subscribe(udf_path, args);
                     flush_messages(base_client, protocol_manager).await;
 
@@ -178,6 +196,20 @@ async fn _worker_once<T: SyncProtocol>(
                     };
                     let _ = tx.send(subscription);
                 },
+                ClientRequest::SubscribeBuffered(query, buffer, tx) => {
+                    let SubscribeRequest {
+                        udf_path,
+                        args,
+                    } = query;
+                    let subscriber_id = base_client.subscribe(udf_path, args);
+                    flush_messages(base_client, protocol_manager).await;
+
+                    if let Some(initial) = base_client.latest_results().get(&subscriber_id) {
+                        buffer.push(initial.clone());
+                    }
+                    buffers.insert(subscriber_id, buffer);
+                    let _ = tx.send(subscriber_id);
+                },
                 ClientRequest::Mutation(mutation, tx) => {
                     let MutationRequest {
                         udf_path,
@@ -200,6 +232,7 @@ async fn _worker_once<T: SyncProtocol>(
                 },
                 ClientRequest::Unsubscribe(unsubscribe) => {
                     let UnsubscribeRequest {subscriber_id} = unsubscribe;
+                    buffers.remove(&subscriber_id);
                     base_client.unsubscribe(subscriber_id);
                     flush_messages(base_client, protocol_manager).await;
                 },
@@ -52,6 +52,8 @@ pub use value::{
 mod client;
 pub use client::{
     subscription::{
+        BufferedQuerySubscription,
+        OverflowPolicy,
         QuerySetSubscription,
         QuerySubscription,
     },
@@ -0,0 +1,578 @@
+//! Stores large binary fields out-of-line.
+//!
+//! [`BlobPersistence`] wraps another [`Persistence`] and, on write, uploads any
+//! top-level `bytes` field at least `threshold` bytes long to object storage.
+//! The row written to the underlying persistence keeps everything else, plus a
+//! `_blobs` system field mapping each externalized field to its object key.
+//! Users can't write top-level system fields, so there's no ambiguity with
+//! user data.
+//!
+//! Readers put the fields back as documents are pulled from the underlying
+//! persistence, so blobs are only fetched for the documents a caller actually
+//! reads, and never for index entries. Index keys are computed above the
+//! persistence layer and are unaffected. When retention deletes a revision,
+//! its blobs are deleted too.
+
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    sync::{
+        Arc,
+        LazyLock,
+    },
+};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use common::{
+    document::ResolvedDocument,
+    index::{
+        IndexEntry,
+        IndexKey,
+    },
+    interval::Interval,
+    knobs::DOCUMENT_BLOB_FETCH_CONCURRENCY,
+    persistence::{
+        ConflictStrategy,
+        DocumentLogEntry,
+        DocumentPrevTsQuery,
+        DocumentStream,
+        IndexStream,
+        LatestDocument,
+        NoopRetentionValidator,
+        Persistence,
+        PersistenceGlobalKey,
+        PersistenceIndexEntry,
+        PersistenceReader,
+        PersistenceTableSize,
+        RetentionValidator,
+        TimestampRange,
+    },
+    query::Order,
+    types::{
+        IndexId,
+        ObjectKey,
+        PersistenceVersion,
+        Timestamp,
+    },
+};
+use futures::{
+    future,
+    stream,
+    StreamExt,
+    TryStreamExt,
+};
+use serde_json::Value as JsonValue;
+use storage::{
+    Storage,
+    StorageExt,
+};
+use value::{
+    ConvexObject,
+    ConvexValue,
+    FieldName,
+    InternalDocumentId,
+    TabletId,
+};
+
+static BLOBS_FIELD: LazyLock<FieldName> =
+    LazyLock::new(|| "_blobs".parse().expect("invalid _blobs field"));
+
+pub struct BlobPersistence {
+    inner: Arc<dyn Persistence>,
+    storage: Arc<dyn Storage>,
+    threshold: usize,
+}
+
+impl BlobPersistence {
+    /// Store `bytes` fields of at least `threshold` bytes in `storage` rather
+    /// than in `inner`.
+    pub fn new(inner: Arc<dyn Persistence>, storage: Arc<dyn Storage>, threshold: usize) -> Self {
+        Self {
+            inner,
+            storage,
+            threshold,
+        }
+    }
+
+    async fn externalize(&self, document: &ResolvedDocument) -> anyhow::Result<ResolvedDocument> {
+        let value: &ConvexObject = document.value();
+        let mut fields = BTreeMap::new();
+        let mut blobs = BTreeMap::new();
+        for (field, value) in value.iter() {
+            match value {
+                ConvexValue::Bytes(bytes)
+                    if !field.is_system() && bytes.len() >= self.threshold =>
+                {
+                    let mut upload = self.storage.start_upload().await?;
+                    upload.write(Vec::<u8>::from(bytes.clone()).into()).await?;
+                    let key = upload.complete().await?;
+                    blobs.insert(field.clone(), ConvexValue::try_from(String::from(key))?);
+                },
+                _ => {
+                    fields.insert(field.clone(), value.clone());
+                },
+            }
+        }
+        if blobs.is_empty() {
+            return Ok(document.clone());
+        }
+        fields.insert(
+            BLOBS_FIELD.clone(),
+            ConvexValue::Object(ConvexObject::try_from(blobs)?),
+        );
+        // `_blobs` is a system field, so this skips document validation.
+        ResolvedDocument::from_database(
+            document.id().tablet_id,
+            ConvexValue::Object(ConvexObject::try_from(fields)?),
+        )
+    }
+}
+
+#[async_trait]
+impl Persistence for BlobPersistence {
+    fn is_fresh(&self) -> bool {
+        self.inner.is_fresh()
+    }
+
+    fn reader(&self) -> Arc<dyn PersistenceReader> {
+        Arc::new(BlobPersistenceReader {
+            inner: self.inner.reader(),
+            storage: self.storage.clone(),
+        })
+    }
+
+    async fn write<'a>(
+        &self,
+        documents: &'a [DocumentLogEntry],
+        indexes: &'a [PersistenceIndexEntry],
+        conflict_strategy: ConflictStrategy,
+    ) -> anyhow::Result<()> {
+        // Blobs are uploaded before the write, so a failed write can leave
+        // unreferenced objects behind, but never a dangling reference.
+        let mut externalized = Vec::with_capacity(documents.len());
+        for entry in documents {
+            let value = match &entry.value {
+                Some(document) => Some(self.externalize(document).await?),
+                None => None,
+            };
+            externalized.push(DocumentLogEntry {
+                ts: entry.ts,
+                id: entry.id,
+                value,
+                prev_ts: entry.prev_ts,
+            });
+        }
+        self.inner
+            .write(&externalized, indexes, conflict_strategy)
+            .await
+    }
+
+    async fn write_persistence_global(
+        &self,
+        key: PersistenceGlobalKey,
+        value: JsonValue,
+    ) -> anyhow::Result<()> {
+        self.inner.write_persistence_global(key, value).await
+    }
+
+    async fn load_index_chunk(
+        &self,
+        cursor: Option<IndexEntry>,
+        chunk_size: usize,
+    ) -> anyhow::Result<Vec<IndexEntry>> {
+        self.inner.load_index_chunk(cursor, chunk_size).await
+    }
+
+    async fn delete_index_entries(&self, entries: Vec<IndexEntry>) -> anyhow::Result<usize> {
+        self.inner.delete_index_entries(entries).await
+    }
+
+    async fn delete(
+        &self,
+        documents: Vec<(Timestamp, InternalDocumentId)>,
+    ) -> anyhow::Result<usize> {
+        // Delete the blobs before the rows that reference them, so a failure in
+        // between leaves dangling references in revisions that are already
+        // expired rather than blobs that nothing references.
+        let queries = documents
+            .iter()
+            .map(|(ts, id)| DocumentPrevTsQuery {
+                id: *id,
+                ts: *ts,
+                prev_ts: *ts,
+            })
+            .collect();
+        // The revisions are being deleted because they've left retention, so
+        // don't validate the read against it.
+        let revisions = self
+            .inner
+            .reader()
+            .previous_revisions_of_documents(queries, Arc::new(NoopRetentionValidator))
+            .await?;
+        for entry in revisions.into_values() {
+            let Some(document) = entry.value else {
+                continue;
+            };
+            for key in blob_keys(&document)? {
+                // A retried delete may have already deleted the blob.
+                if self.storage.get_object_attributes(&key).await?.is_some() {
+                    self.storage.delete_object(&key).await?;
+                }
+            }
+        }
+        self.inner.delete(documents).await
+    }
+
+    async fn shutdown(&self) -> anyhow::Result<()> {
+        self.inner.shutdown().await
+    }
+
+    async fn finish_loading(&self) -> anyhow::Result<()> {
+        self.inner.finish_loading().await
+    }
+}
+
+/// The object keys of `document`'s externalized fields.
+fn blob_keys(document: &ResolvedDocument) -> anyhow::Result<Vec<ObjectKey>> {
+    let value: &ConvexObject = document.value();
+    let Some(blobs) = value.get(&*BLOBS_FIELD) else {
+        return Ok(vec![]);
+    };
+    let ConvexValue::Object(blobs) = blobs else {
+        anyhow::bail!("Invalid {} field: {blobs}", *BLOBS_FIELD);
+    };
+    blobs
+        .iter()
+        .map(|(field, key)| {
+            let ConvexValue::String(key) = key else {
+                anyhow::bail!("Invalid blob reference for {field}: {key}");
+            };
+            ObjectKey::try_from(key.clone())
+        })
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct BlobPersistenceReader {
+    inner: Arc<dyn PersistenceReader>,
+    storage: Arc<dyn Storage>,
+}
+
+impl BlobPersistenceReader {
+    async fn internalize(&self, document: ResolvedDocument) -> anyhow::Result<ResolvedDocument> {
+        let tablet_id = document.id().tablet_id;
+        let mut fields: BTreeMap<_, _> = document.into_value().0.into();
+        let Some(blobs) = fields.remove(&*BLOBS_FIELD) else {
+            return ResolvedDocument::from_database(
+                tablet_id,
+                ConvexValue::Object(fields.try_into()?),
+            );
+        };
+        let ConvexValue::Object(blobs) = blobs else {
+            anyhow::bail!("Invalid {} field: {blobs}", *BLOBS_FIELD);
+        };
+        let storage = &self.storage;
+        let fetched = future::try_join_all(blobs.into_iter().map(|(field, key)| async move {
+            let ConvexValue::String(key) = key else {
+                anyhow::bail!("Invalid blob reference for {field}: {key}");
+            };
+            let key = ObjectKey::try_from(key)?;
+            let bytes = storage
+                .get(&key)
+                .await?
+                .with_context(|| format!("Blob {key:?} for field {field} not found"))?
+                .collect_as_bytes()
+                .await?;
+            anyhow::Ok((field, ConvexValue::Bytes(bytes.to_vec().try_into()?)))
+        }))
+        .await?;
+        fields.extend(fetched);
+        ResolvedDocument::from_database(tablet_id, ConvexValue::Object(fields.try_into()?))
+    }
+
+    async fn internalize_entry(&self, entry: DocumentLogEntry) -> anyhow::Result<DocumentLogEntry> {
+        let value = match entry.value {
+            Some(document) => Some(self.internalize(document).await?),
+            None => None,
+        };
+        Ok(DocumentLogEntry { value, ..entry })
+    }
+
+    async fn internalize_latest(&self, latest: LatestDocument) -> anyhow::Result<LatestDocument> {
+        Ok(LatestDocument {
+            value: self.internalize(latest.value).await?,
+            ..latest
+        })
+    }
+
+    fn internalize_stream<'a>(&'a self, stream: DocumentStream<'a>) -> DocumentStream<'a> {
+        stream
+            .map_ok(move |entry| self.internalize_entry(entry))
+            .try_buffered(*DOCUMENT_BLOB_FETCH_CONCURRENCY)
+            .boxed()
+    }
+
+    async fn internalize_entries<K: Ord>(
+        &self,
+        entries: BTreeMap<K, DocumentLogEntry>,
+    ) -> anyhow::Result<BTreeMap<K, DocumentLogEntry>> {
+        stream::iter(entries)
+            .map(|(key, entry)| async move {
+                anyhow::Ok((key, self.internalize_entry(entry).await?))
+            })
+            .buffer_unordered(*DOCUMENT_BLOB_FETCH_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+}
+
+#[async_trait]
+impl PersistenceReader for BlobPersistenceReader {
+    fn load_documents(
+        &self,
+        range: TimestampRange,
+        order: Order,
+        page_size: u32,
+        retention_validator: Arc<dyn RetentionValidator>,
+    ) -> DocumentStream<'_> {
+        self.internalize_stream(self.inner.load_documents(
+            range,
+            order,
+            page_size,
+            retention_validator,
+        ))
+    }
+
+    fn load_documents_from_table(
+        &self,
+        tablet_id: TabletId,
+        range: TimestampRange,
+        order: Order,
+        page_size: u32,
+        retention_validator: Arc<dyn RetentionValidator>,
+    ) -> DocumentStream<'_> {
+        self.internalize_stream(self.inner.load_documents_from_table(
+            tablet_id,
+            range,
+            order,
+            page_size,
+            retention_validator,
+        ))
+    }
+
+    async fn previous_revisions(
+        &self,
+        ids: BTreeSet<(InternalDocumentId, Timestamp)>,
+        retention_validator: Arc<dyn RetentionValidator>,
+    ) -> anyhow::Result<BTreeMap<(InternalDocumentId, Timestamp), DocumentLogEntry>> {
+        let entries = self
+            .inner
+            .previous_revisions(ids, retention_validator)
+            .await?;
+        self.internalize_entries(entries).await
+    }
+
+    async fn previous_revisions_of_documents(
+        &self,
+        ids: BTreeSet<DocumentPrevTsQuery>,
+        retention_validator: Arc<dyn RetentionValidator>,
+    ) -> anyhow::Result<BTreeMap<DocumentPrevTsQuery, DocumentLogEntry>> {
+        let entries = self
+            .inner
+            .previous_revisions_of_documents(ids, retention_validator)
+            .await?;
+        self.internalize_entries(entries).await
+    }
+
+    fn index_scan(
+        &self,
+        index_id: IndexId,
+        tablet_id: TabletId,
+        read_timestamp: Timestamp,
+        range: &Interval,
+        order: Order,
+        size_hint: usize,
+        retention_validator: Arc<dyn RetentionValidator>,
+    ) -> IndexStream<'_> {
+        self.inner
+            .index_scan(
+                index_id,
+                tablet_id,
+                read_timestamp,
+                range,
+                order,
+                size_hint,
+                retention_validator,
+            )
+            .map_ok(move |(key, latest)| async move {
+                anyhow::Ok((key, self.internalize_latest(latest).await?))
+            })
+            .try_buffered(*DOCUMENT_BLOB_FETCH_CONCURRENCY)
+            .boxed()
+    }
+
+    async fn get_persistence_global(
+        &self,
+        key: PersistenceGlobalKey,
+    ) -> anyhow::Result<Option<JsonValue>> {
+        self.inner.get_persistence_global(key).await
+    }
+
+    async fn index_get(
+        &self,
+        index_id: IndexId,
+        tablet_id: TabletId,
+        read_timestamp: Timestamp,
+        key: IndexKey,
+        retention_validator: Arc<dyn RetentionValidator>,
+    ) -> anyhow::Result<Option<LatestDocument>> {
+        match self
+            .inner
+            .index_get(
+                index_id,
+                tablet_id,
+                read_timestamp,
+                key,
+                retention_validator,
+            )
+            .await?
+        {
+            Some(latest) => Ok(Some(self.internalize_latest(latest).await?)),
+            None => Ok(None),
+        }
+    }
+
+    // Only needs timestamps, so skip fetching the latest document's blobs.
+    async fn max_ts(&self) -> anyhow::Result<Option<Timestamp>> {
+        self.inner.max_ts().await
+    }
+
+    fn version(&self) -> PersistenceVersion {
+        self.inner.version()
+    }
+
+    async fn table_size_stats(&self) -> anyhow::Result<Vec<PersistenceTableSize>> {
+        self.inner.table_size_stats().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common::{
+        assert_obj,
+        document::{
+            CreationTime,
+            ResolvedDocument,
+        },
+        persistence::{
+            ConflictStrategy,
+            DocumentLogEntry,
+            Persistence,
+            PersistenceReader,
+        },
+        testing::{
+            TestIdGenerator,
+            TestPersistence,
+        },
+        types::Timestamp,
+    };
+    use futures::TryStreamExt;
+    use runtime::testing::TestRuntime;
+    use storage::{
+        LocalDirStorage,
+        Storage,
+    };
+    use value::{
+        ConvexObject,
+        ConvexValue,
+        Size,
+        TableName,
+    };
+
+    use super::{
+        blob_keys,
+        BlobPersistence,
+    };
+
+    async fn write_large_document(
+        persistence: &BlobPersistence,
+    ) -> anyhow::Result<ResolvedDocument> {
+        let mut id_generator = TestIdGenerator::new();
+        let table: TableName = "files".parse()?;
+        let document = ResolvedDocument::new(
+            id_generator.user_generate(&table),
+            CreationTime::ONE,
+            assert_obj!(
+                "name" => "big.bin",
+                "contents" => ConvexValue::Bytes(vec![7u8; 1 << 20].try_into()?),
+                "thumbnail" => ConvexValue::Bytes(vec![1u8; 16].try_into()?),
+            ),
+        )?;
+        persistence
+            .write(
+                &[DocumentLogEntry {
+                    ts: Timestamp::must(1),
+                    id: document.id_with_table_id(),
+                    value: Some(document.clone()),
+                    prev_ts: None,
+                }],
+                &[],
+                ConflictStrategy::Error,
+            )
+            .await?;
+        Ok(document)
+    }
+
+    #[convex_macro::test_runtime]
+    async fn test_large_bytes_field_stored_out_of_line(rt: TestRuntime) -> anyhow::Result<()> {
+        let inner = Arc::new(TestPersistence::new());
+        let storage = Arc::new(LocalDirStorage::new(rt)?);
+        let persistence = BlobPersistence::new(inner.clone(), storage, 1024);
+        let document = write_large_document(&persistence).await?;
+
+        // The row in the underlying persistence only holds a reference to the
+        // large field. Small fields stay inline.
+        let rows: Vec<_> = inner.reader().load_all_documents().try_collect().await?;
+        assert_eq!(rows.len(), 1);
+        let row = rows[0].value.as_ref().unwrap();
+        let row_value: &ConvexObject = row.value();
+        assert!(row_value.size() < 1024, "row is {} bytes", row_value.size());
+        assert!(row_value.get("contents").is_none());
+        assert!(row_value.get("thumbnail").is_some());
+
+        // Reading through the wrapper restores the original document.
+        let loaded: Vec<_> = persistence
+            .reader()
+            .load_all_documents()
+            .try_collect()
+            .await?;
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].value.as_ref(), Some(&document));
+        Ok(())
+    }
+
+    #[convex_macro::test_runtime]
+    async fn test_deleting_revision_deletes_blob(rt: TestRuntime) -> anyhow::Result<()> {
+        let inner = Arc::new(TestPersistence::new());
+        let storage = Arc::new(LocalDirStorage::new(rt)?);
+        let persistence = BlobPersistence::new(inner.clone(), storage.clone(), 1024);
+        let document = write_large_document(&persistence).await?;
+
+        let rows: Vec<_> = inner.reader().load_all_documents().try_collect().await?;
+        let keys = blob_keys(rows[0].value.as_ref().unwrap())?;
+        assert_eq!(keys.len(), 1);
+        assert!(storage.get_object_attributes(&keys[0]).await?.is_some());
+
+        let deleted = persistence
+            .delete(vec![(Timestamp::must(1), document.id_with_table_id())])
+            .await?;
+        assert_eq!(deleted, 1);
+        assert!(storage.get_object_attributes(&keys[0]).await?.is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+//! Contention-free counters for UDFs.
+//!
+//! Unlike sequences, incrementing a counter doesn't read its document.
+//! Instead the transaction records the increment and the committer merges it
+//! into the latest version of the counter at commit time. Since increments
+//! commute, concurrent increments to the same counter never conflict with
+//! each other. Reading a counter is a regular read, though, so it does
+//! conflict with concurrent increments.
+//!
+//! The committer looks counters up in memory, so `_counters` must be one of
+//! the tables loaded into memory.
+pub mod types;
+
+use std::sync::LazyLock;
+
+use common::{
+    document::CREATION_TIME_FIELD_PATH,
+    runtime::Runtime,
+};
+use errors::ErrorMetadata;
+use value::{
+    FieldPath,
+    TableName,
+    TableNamespace,
+};
+
+use self::types::CounterMetadata;
+use crate::{
+    system_tables::{
+        SystemIndex,
+        SystemTable,
+    },
+    Transaction,
+};
+
+pub static COUNTERS_TABLE: LazyLock<TableName> = LazyLock::new(|| {
+    "_counters"
+        .parse()
+        .expect("Invalid built-in counters table")
+});
+
+pub static COUNTERS_INDEX_BY_NAME: LazyLock<SystemIndex<CountersTable>> = LazyLock::new(|| {
+    SystemIndex::new("by_name", [&NAME_FIELD, &CREATION_TIME_FIELD_PATH]).unwrap()
+});
+static NAME_FIELD: LazyLock<FieldPath> =
+    LazyLock::new(|| "name".parse().expect("invalid name field"));
+
+pub struct CountersTable;
+impl SystemTable for CountersTable {
+    type Metadata = CounterMetadata;
+
+    fn table_name() -> &'static TableName {
+        &COUNTERS_TABLE
+    }
+
+    fn indexes() -> Vec<SystemIndex<Self>> {
+        vec![COUNTERS_INDEX_BY_NAME.clone()]
+    }
+}
+
+pub fn counter_overflow_error(name: &str) -> ErrorMetadata {
+    ErrorMetadata::bad_request(
+        "CounterOverflow",
+        format!("Counter {name:?} overflowed a 64-bit integer"),
+    )
+}
+
+pub struct CountersModel<'a, RT: Runtime> {
+    tx: &'a mut Transaction<RT>,
+    namespace: TableNamespace,
+}
+
+impl<'a, RT: Runtime> CountersModel<'a, RT> {
+    pub fn new(tx: &'a mut Transaction<RT>, namespace: TableNamespace) -> Self {
+        Self { tx, namespace }
+    }
+
+    /// Add `delta` to the counter `name`, creating it if needed. Counters
+    /// start at 0.
+    pub fn increment(&mut self, name: &str, delta: i64) -> anyhow::Result<()> {
+        self.tx.increment_counter(self.namespace, name, delta)
+    }
+
+    /// Read the current value of the counter `name`, including increments
+    /// made earlier in this transaction.
+    pub async fn get(&mut self, name: &str) -> anyhow::Result<i64> {
+        let committed = self
+            .tx
+            .query_system(self.namespace, &COUNTERS_INDEX_BY_NAME)?
+            .eq(&[name])?
+            .unique()
+            .await?
+            .map(|doc| doc.value)
+            .unwrap_or(0);
+        let pending = self.tx.pending_counter_delta(self.namespace, name)?;
+        committed
+            .checked_add(pending)
+            .ok_or_else(|| counter_overflow_error(name).into())
+    }
+}
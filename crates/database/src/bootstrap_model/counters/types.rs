@@ -0,0 +1,41 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use value::codegen_convex_serialization;
+
+/// A named counter. Increments are merged into `value` by the committer, so
+/// they never conflict with each other.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "testing"), derive(proptest_derive::Arbitrary))]
+pub struct CounterMetadata {
+    pub name: String,
+    pub value: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializedCounterMetadata {
+    name: String,
+    value: i64,
+}
+
+impl From<CounterMetadata> for SerializedCounterMetadata {
+    fn from(value: CounterMetadata) -> Self {
+        Self {
+            name: value.name,
+            value: value.value,
+        }
+    }
+}
+
+impl From<SerializedCounterMetadata> for CounterMetadata {
+    fn from(value: SerializedCounterMetadata) -> Self {
+        Self {
+            name: value.name,
+            value: value.value,
+        }
+    }
+}
+
+codegen_convex_serialization!(CounterMetadata, SerializedCounterMetadata);
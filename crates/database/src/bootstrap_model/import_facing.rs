@@ -10,7 +10,6 @@ use common::{
 };
 use errors::ErrorMetadata;
 use value::{
-    check_user_size,
     ConvexObject,
     ConvexValue,
     DeveloperDocumentId,
@@ -23,6 +22,7 @@ use value::{
 };
 
 use crate::{
+    bootstrap_model::user_facing::check_document_size,
     defaults::bootstrap_system_tables,
     SchemaModel,
     Transaction,
@@ -77,7 +77,7 @@ impl<'a, RT: Runtime> ImportFacingModel<'a, RT> {
         }
 
         if !table_name.is_system() {
-            check_user_size(value.size())?;
+            check_document_size(value.size())?;
         }
         self.tx.retention_validator.fail_if_falling_behind()?;
         let id_field = FieldName::from(ID_FIELD.clone());
@@ -159,7 +159,7 @@ impl<'a, RT: Runtime> ImportFacingModel<'a, RT> {
         }
 
         if !table_name.is_system() {
-            check_user_size(value.size())?;
+            check_document_size(value.size())?;
         }
         let id_field = FieldName::from(ID_FIELD.clone());
         let developer_id = if let Some(ConvexValue::String(s)) = value.get(&id_field) {
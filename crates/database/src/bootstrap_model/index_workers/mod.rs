@@ -102,6 +102,7 @@ impl<'a, RT: Runtime> IndexWorkerMetadataModel<'a, RT> {
             index_name: INDEX_DOC_ID_INDEX.name(),
             range,
             order: Order::Asc,
+            collation: None,
         });
         let mut query_stream = ResolvedQuery::new(self.tx, TableNamespace::Global, query)?;
         let result = query_stream.next(self.tx, None).await?;
@@ -3,6 +3,7 @@
 //!
 //! Higher level tables belong in the model crate, layered above the database.
 pub mod components;
+pub mod counters;
 pub mod defaults;
 pub mod import_facing;
 pub mod index;
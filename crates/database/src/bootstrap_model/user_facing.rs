@@ -1,6 +1,7 @@
 use std::{
     cmp,
     collections::BTreeMap,
+    fmt,
 };
 
 use anyhow::Context;
@@ -11,6 +12,7 @@ use common::{
         DeveloperDocument,
         ResolvedDocument,
     },
+    knobs::MAX_USER_DOCUMENT_SIZE_BYTES,
     query::CursorPosition,
     runtime::Runtime,
     types::{
@@ -20,19 +22,23 @@ use common::{
     version::Version,
 };
 use errors::ErrorMetadata;
+use humansize::{
+    FormatSize,
+    BINARY,
+};
 use indexing::backend_in_memory_indexes::{
     BatchKey,
     RangeRequest,
 };
 use itertools::Itertools;
 use value::{
-    check_user_size,
     ConvexObject,
     DeveloperDocumentId,
     ResolvedDocumentId,
     Size,
     TableName,
     TableNamespace,
+    VALUE_TOO_LARGE_SHORT_MSG,
 };
 
 use crate::{
@@ -191,7 +197,7 @@ impl<'a, RT: Runtime> UserFacingModel<'a, RT> {
             ));
         }
 
-        check_user_size(value.size())?;
+        check_document_size(value.size())?;
         self.tx.retention_validator.fail_if_falling_behind()?;
         let internal_id = self.tx.id_generator.generate_internal();
 
@@ -246,6 +252,22 @@ impl<'a, RT: Runtime> UserFacingModel<'a, RT> {
         id: DeveloperDocumentId,
         value: PatchValue,
     ) -> anyhow::Result<DeveloperDocument> {
+        let (_, new_document) = self.patch_with_previous(id, value, false).await?;
+        Ok(new_document)
+    }
+
+    /// Like `patch`, but if `return_previous` is set, also returns the
+    /// document as it was before the patch. The previous version is read as
+    /// part of the write, so this doesn't read anything beyond what `patch`
+    /// does.
+    #[fastrace::trace]
+    #[convex_macro::instrument_future]
+    pub async fn patch_with_previous(
+        &mut self,
+        id: DeveloperDocumentId,
+        value: PatchValue,
+        return_previous: bool,
+    ) -> anyhow::Result<(Option<DeveloperDocument>, DeveloperDocument)> {
         if self.tx.is_system(self.namespace, id.table())
             && !(self.tx.identity.is_admin() || self.tx.identity.is_system())
         {
@@ -256,15 +278,20 @@ impl<'a, RT: Runtime> UserFacingModel<'a, RT> {
 
         let id_ = self.tx.resolve_developer_id(&id, self.namespace)?;
 
-        let new_document = self.tx.patch_inner(id_, value).await?;
+        let (old_document, new_document) = self
+            .tx
+            .patch_inner_with_previous(id_, value, return_previous)
+            .await?;
 
         // Check the size of the patched document.
         if !self.tx.is_system(self.namespace, id.table()) {
-            check_user_size(new_document.size())?;
+            check_document_size(new_document.size())?;
         }
 
-        let developer_document = new_document.to_developer();
-        Ok(developer_document)
+        Ok((
+            old_document.map(|d| d.to_developer()),
+            new_document.to_developer(),
+        ))
     }
 
     /// Replace the document with the given value.
@@ -275,6 +302,20 @@ impl<'a, RT: Runtime> UserFacingModel<'a, RT> {
         id: DeveloperDocumentId,
         value: ConvexObject,
     ) -> anyhow::Result<DeveloperDocument> {
+        let (_, new_document) = self.replace_with_previous(id, value, false).await?;
+        Ok(new_document)
+    }
+
+    /// Like `replace`, but if `return_previous` is set, also returns the
+    /// document as it was before the replace.
+    #[fastrace::trace]
+    #[convex_macro::instrument_future]
+    pub async fn replace_with_previous(
+        &mut self,
+        id: DeveloperDocumentId,
+        value: ConvexObject,
+        return_previous: bool,
+    ) -> anyhow::Result<(Option<DeveloperDocument>, DeveloperDocument)> {
         if self.tx.is_system(self.namespace, id.table())
             && !(self.tx.identity.is_admin() || self.tx.identity.is_system())
         {
@@ -282,14 +323,19 @@ impl<'a, RT: Runtime> UserFacingModel<'a, RT> {
         }
         self.require_active_component().await?;
         if !self.tx.is_system(self.namespace, id.table()) {
-            check_user_size(value.size())?;
+            check_document_size(value.size())?;
         }
         self.tx.retention_validator.fail_if_falling_behind()?;
         let id_ = self.tx.resolve_developer_id(&id, self.namespace)?;
 
-        let new_document = self.tx.replace_inner(id_, value).await?;
-        let developer_document = new_document.to_developer();
-        Ok(developer_document)
+        let (old_document, new_document) = self
+            .tx
+            .replace_inner_with_previous(id_, value, return_previous)
+            .await?;
+        Ok((
+            old_document.map(|d| d.to_developer()),
+            new_document.to_developer(),
+        ))
     }
 
     /// Delete the document at the given path -- called from user facing APIs
@@ -436,3 +482,45 @@ pub async fn index_range_batch<RT: Runtime>(
     assert_eq!(results.len(), batch_size);
     results
 }
+
+/// Size and limit of a document rejected by [`check_document_size`]. Attached
+/// to the error under its `ValueTooLargeError` metadata, so callers can
+/// `downcast_ref` it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentTooLarge {
+    pub size: usize,
+    pub limit: usize,
+}
+
+impl fmt::Display for DocumentTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "document of {} bytes exceeds the limit of {} bytes",
+            self.size, self.limit
+        )
+    }
+}
+
+impl std::error::Error for DocumentTooLarge {}
+
+/// Reject user documents larger than `MAX_USER_DOCUMENT_SIZE_BYTES` before
+/// they're written to the transaction.
+pub fn check_document_size(size: usize) -> anyhow::Result<()> {
+    let limit = *MAX_USER_DOCUMENT_SIZE_BYTES;
+    if size > limit {
+        return Err(
+            anyhow::Error::new(DocumentTooLarge { size, limit }).context(
+                ErrorMetadata::bad_request(
+                    VALUE_TOO_LARGE_SHORT_MSG,
+                    format!(
+                        "Value is too large ({} > maximum document size {})",
+                        size.format_size(BINARY),
+                        limit.format_size(BINARY),
+                    ),
+                ),
+            ),
+        );
+    }
+    Ok(())
+}
@@ -37,6 +37,7 @@ use common::{
         initialize_root_from_parent,
         EncodedSpan,
     },
+    interval::Interval,
     knobs::{
         COMMITTER_QUEUE_SIZE,
         COMMIT_TRACE_THRESHOLD,
@@ -55,6 +56,7 @@ use common::{
         RetentionValidator,
         TimestampRange,
     },
+    query::Order,
     runtime::{
         block_in_place,
         tokio_spawn,
@@ -70,10 +72,14 @@ use common::{
         DatabaseIndexUpdate,
         DatabaseIndexValue,
         RepeatableTimestamp,
+        TabletIndexName,
         Timestamp,
         WriteTimestamp,
     },
-    value::ResolvedDocumentId,
+    value::{
+        ResolvedDocumentId,
+        Size,
+    },
 };
 use errors::ErrorMetadata;
 use fastrace::prelude::*;
@@ -108,6 +114,8 @@ use value::{
         WithHeapSize,
     },
     id_v6::DeveloperDocumentId,
+    val,
+    values_to_bytes,
     InternalDocumentId,
     TableMapping,
     TableName,
@@ -115,7 +123,14 @@ use value::{
 use vector::DocInVectorIndex;
 
 use crate::{
-    bootstrap_model::defaults::BootstrapTableIds,
+    bootstrap_model::{
+        counters::{
+            counter_overflow_error,
+            types::CounterMetadata,
+            COUNTERS_INDEX_BY_NAME,
+        },
+        defaults::BootstrapTableIds,
+    },
     database::ConflictingReadWithWriteSource,
     metrics::{
         self,
@@ -142,7 +157,10 @@ use crate::{
         PendingWrites,
         WriteSource,
     },
-    writes::DocumentWrite,
+    writes::{
+        DocumentWrite,
+        Writes,
+    },
     ComponentRegistry,
     Snapshot,
     Transaction,
@@ -682,16 +700,35 @@ impl<RT: Runtime> Committer<RT> {
     ) -> anyhow::Result<ValidatedCommit> {
         let commit_ts = self.next_commit_ts()?;
         let timer = metrics::commit_is_stale_timer();
-        if let Some(conflicting_read) = self.commit_has_conflict(
-            transaction.reads.read_set(),
-            *transaction.begin_timestamp,
-            commit_ts,
-        )? {
-            anyhow::bail!(conflicting_read.into_error(&transaction.table_mapping, &write_source));
+        if self
+            .commit_has_conflict(
+                transaction.reads.read_set(),
+                *transaction.begin_timestamp,
+                commit_ts,
+            )?
+            .is_some()
+        {
+            // Conflicts are rare, so only pay for finding all of them once we
+            // know there's at least one.
+            let conflicts = self.commit_conflicts(
+                transaction.reads.read_set(),
+                *transaction.begin_timestamp,
+                commit_ts,
+            )?;
+            anyhow::bail!(ConflictingReadWithWriteSource::into_combined_error(
+                conflicts,
+                &transaction.table_mapping,
+                &write_source
+            ));
         }
         timer.finish();
 
-        let updates: Vec<_> = transaction.writes.coalesced_writes().collect();
+        let counter_updates = self.resolve_counter_increments(&transaction.writes)?;
+        let updates: Vec<_> = transaction
+            .writes
+            .coalesced_writes()
+            .chain(counter_updates.iter().map(|update| (&update.id, update)))
+            .collect();
         // The updates are ordered using table_dependency_sort_key,
         // which is the same order they should be applied to database metadata
         // and index data structures
@@ -732,6 +769,94 @@ impl<RT: Runtime> Committer<RT> {
         })
     }
 
+    /// Turn the transaction's counter increments into document updates against
+    /// the latest version of each counter, including pending writes. This
+    /// runs after the conflict check, so increments never conflict with
+    /// each other. The counters' writes count against the transaction's user
+    /// write limits, since the function chose how many counters to touch.
+    #[fastrace::trace]
+    fn resolve_counter_increments(
+        &self,
+        writes: &Writes,
+    ) -> anyhow::Result<Vec<DocumentUpdateWithPrevTs>> {
+        let counter_increments = writes.counter_increments();
+        if counter_increments.is_empty() {
+            return Ok(vec![]);
+        }
+        let latest_pending_snapshot = self
+            .pending_writes
+            .latest_snapshot()
+            .unwrap_or_else(|| self.snapshot_manager.read().latest_snapshot());
+        let mut updates = Vec::with_capacity(counter_increments.len());
+        let mut tx_size = writes.user_size().clone();
+        for (&(tablet_id, ref name), increment) in counter_increments {
+            let index_name =
+                TabletIndexName::new(tablet_id, COUNTERS_INDEX_BY_NAME.descriptor().clone())?;
+            let index = latest_pending_snapshot
+                .index_registry
+                .get_enabled(&index_name)
+                .with_context(|| format!("Missing counters index {index_name}"))?;
+            let interval = Interval::prefix(values_to_bytes(&[Some(val!(name.clone()))]).into());
+            let Some(existing) = latest_pending_snapshot.in_memory_indexes.range(
+                index.id(),
+                &interval,
+                Order::Asc,
+            )?
+            else {
+                anyhow::bail!("Counters index {index_name} is not loaded in memory");
+            };
+            let update = match existing.into_iter().next() {
+                Some((_, ts, document)) => {
+                    let old_document = document.packed_document.unpack();
+                    let metadata = CounterMetadata::try_from(old_document.clone().into_value().0)?;
+                    let value = metadata
+                        .value
+                        .checked_add(increment.delta)
+                        .ok_or_else(|| counter_overflow_error(name))?;
+                    let new_document = old_document.replace_value(
+                        CounterMetadata {
+                            name: name.clone(),
+                            value,
+                        }
+                        .try_into()?,
+                    )?;
+                    DocumentUpdateWithPrevTs {
+                        id: old_document.id(),
+                        old_document: Some((old_document, ts)),
+                        new_document: Some(new_document),
+                    }
+                },
+                None => DocumentUpdateWithPrevTs {
+                    id: increment.new_id,
+                    old_document: None,
+                    new_document: Some(ResolvedDocument::new(
+                        increment.new_id,
+                        increment.creation_time,
+                        CounterMetadata {
+                            name: name.clone(),
+                            value: increment.delta,
+                        }
+                        .try_into()?,
+                    )?),
+                },
+            };
+            anyhow::ensure!(
+                !writes.updates_document(&update.id),
+                "Counter {name:?} was both written and incremented in the same transaction"
+            );
+            tx_size.num_writes += 1;
+            tx_size.size += update.id.size()
+                + update
+                    .new_document
+                    .as_ref()
+                    .map(|d| d.value().size())
+                    .unwrap_or(0);
+            tx_size.check_user_limits()?;
+            updates.push(update);
+        }
+        Ok(updates)
+    }
+
     #[fastrace::trace]
     fn compute_writes(
         &self,
@@ -794,6 +919,21 @@ impl<RT: Runtime> Committer<RT> {
         Ok(None)
     }
 
+    /// Every write, committed or pending, that conflicts with `reads`.
+    fn commit_conflicts(
+        &self,
+        reads: &ReadSet,
+        reads_ts: Timestamp,
+        commit_ts: Timestamp,
+    ) -> anyhow::Result<Vec<ConflictingReadWithWriteSource>> {
+        let mut conflicts = self.log.all_conflicts(reads, reads_ts, commit_ts)?;
+        conflicts.extend(
+            self.pending_writes
+                .all_conflicts(reads, reads_ts, commit_ts)?,
+        );
+        Ok(conflicts)
+    }
+
     /// Commit the transaction to persistence (without the lock held).
     /// This is the commit point of a transaction. If this succeeds, the
     /// transaction must be published and made visible. If we are unsure whether
@@ -4,6 +4,7 @@ use std::{
         BTreeMap,
         BTreeSet,
     },
+    fmt,
     ops::Bound,
     sync::{
         atomic::{
@@ -58,6 +59,7 @@ use common::{
     knobs::{
         DEFAULT_DOCUMENTS_PAGE_SIZE,
         LIST_SNAPSHOT_MAX_AGE_SECS,
+        SCHEMA_LOCK_TIMEOUT,
     },
     persistence::{
         new_idle_repeatable_ts,
@@ -168,6 +170,7 @@ use crate::{
         bootstrap_system_tables,
         DEFAULT_BOOTSTRAP_TABLE_NUMBERS,
     },
+    derived_fields::DerivedFields,
     metrics::{
         self,
         load_indexes_into_memory_timer,
@@ -175,6 +178,11 @@ use crate::{
         verify_invariants_timer,
     },
     retention::LeaderRetentionManager,
+    schema_lock::{
+        SchemaChangeGuard,
+        SchemaFreezeGuard,
+        SchemaLock,
+    },
     schema_registry::SchemaRegistry,
     search_index_bootstrap::SearchIndexBootstrapWorker,
     snapshot_manager::{
@@ -278,6 +286,8 @@ pub struct Database<RT: Runtime> {
     pub search_storage: Arc<OnceLock<Arc<dyn Storage>>>,
     usage_counter: UsageCounter,
     virtual_system_mapping: VirtualSystemMapping,
+    schema_lock: SchemaLock,
+    derived_fields: DerivedFields,
     pub bootstrap_metadata: BootstrapMetadata,
     // Caches of snapshot TableMapping and by_id index ids, which are used repeatedly by
     // /api/list_snapshot.
@@ -1035,6 +1045,8 @@ impl<RT: Runtime> Database<RT> {
             search_storage: Arc::new(OnceLock::new()),
             usage_counter,
             virtual_system_mapping,
+            schema_lock: SchemaLock::new(),
+            derived_fields: DerivedFields::new(),
             bootstrap_metadata,
             table_mapping_snapshot_cache,
             by_id_indexes_snapshot_cache,
@@ -1741,6 +1753,9 @@ impl<RT: Runtime> Database<RT> {
     ) -> anyhow::Result<Timestamp> {
         task::consume_budget().await;
         let readonly = transaction.is_readonly();
+        if !readonly {
+            self.derived_fields.validate(&transaction)?;
+        }
         let result = self
             .committer
             .commit(transaction, write_source.into())
@@ -1773,6 +1788,18 @@ impl<RT: Runtime> Database<RT> {
         self.subscriptions.subscribe(token)
     }
 
+    /// Like [`Database::subscribe`], but the subscription is only invalidated
+    /// by writes to one of `tablet_ids`. Changes to any other table the token
+    /// read from are ignored.
+    pub async fn subscribe_with_table_filter(
+        &self,
+        token: Token,
+        tablet_ids: &BTreeSet<TabletId>,
+    ) -> anyhow::Result<Subscription> {
+        self.subscriptions
+            .subscribe(token.filter_tables(tablet_ids))
+    }
+
     fn streaming_export_table_filter(
         filter: &StreamingExportFilter,
         tablet_id: TabletId,
@@ -2137,6 +2164,25 @@ impl<RT: Runtime> Database<RT> {
             .is_some()
     }
 
+    /// Derived fields that are validated on every commit.
+    pub fn derived_fields(&self) -> &DerivedFields {
+        &self.derived_fields
+    }
+
+    /// Prevent schema changes until the returned guard is dropped. Used by
+    /// snapshot imports.
+    pub async fn freeze_schema(&self) -> SchemaFreezeGuard {
+        self.schema_lock.freeze().await
+    }
+
+    /// Wait for any in-progress snapshot imports to finish before changing the
+    /// schema. New imports can't start until the returned guard is dropped.
+    pub async fn lock_schema_for_change(&self) -> anyhow::Result<SchemaChangeGuard> {
+        self.schema_lock
+            .lock_for_change(&self.runtime, *SCHEMA_LOCK_TIMEOUT)
+            .await
+    }
+
     pub fn usage_counter(&self) -> UsageCounter {
         self.usage_counter.clone()
     }
@@ -2307,6 +2353,22 @@ fn occ_write_source_string(
     format!("{preamble} changed the document with ID \"{document_id}\"")
 }
 
+/// How many conflicting document IDs an OCC error lists in its message.
+const MAX_CONFLICTING_IDS_IN_MESSAGE: usize = 5;
+
+/// Every document changed by the writes an OCC error conflicted with. Attached
+/// to the error under its OCC metadata, so callers can `downcast_ref` it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictingDocumentIds(pub Vec<String>);
+
+impl fmt::Display for ConflictingDocumentIds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "conflicting writes changed {}", self.0.join(", "))
+    }
+}
+
+impl std::error::Error for ConflictingDocumentIds {}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct ConflictingReadWithWriteSource {
     pub(crate) read: ConflictingRead,
@@ -2319,11 +2381,57 @@ pub struct ConflictingReadWithWriteSource {
 }
 
 impl ConflictingReadWithWriteSource {
-    pub fn into_error(self, mapping: &TableMapping, current_writer: &WriteSource) -> anyhow::Error {
+    /// Build an OCC error for the first of `conflicts` that also lists the
+    /// documents changed by every other conflicting write.
+    pub fn into_combined_error(
+        conflicts: Vec<Self>,
+        mapping: &TableMapping,
+        current_writer: &WriteSource,
+    ) -> anyhow::Error {
+        let mut conflicts = conflicts.into_iter();
+        let Some(first) = conflicts.next() else {
+            return anyhow::anyhow!(ErrorMetadata::user_occ(None, None, None, None));
+        };
+        let mut document_ids = vec![first.read.id.to_string()];
+        for conflict in conflicts {
+            let document_id = conflict.read.id.to_string();
+            if !document_ids.contains(&document_id) {
+                document_ids.push(document_id);
+            }
+        }
+        let num_documents = document_ids.len();
+        let note = (num_documents > 1).then(|| {
+            let mut listed = document_ids
+                .iter()
+                .take(MAX_CONFLICTING_IDS_IN_MESSAGE)
+                .map(|id| format!("\"{id}\""))
+                .join(", ");
+            if num_documents > MAX_CONFLICTING_IDS_IN_MESSAGE {
+                let num_more = num_documents - MAX_CONFLICTING_IDS_IN_MESSAGE;
+                listed = format!("{listed} and {num_more} more");
+            }
+            format!("Conflicting writes changed {num_documents} documents, with IDs {listed}")
+        });
+        first.into_error(
+            mapping,
+            current_writer,
+            note,
+            ConflictingDocumentIds(document_ids),
+        )
+    }
+
+    fn into_error(
+        self,
+        mapping: &TableMapping,
+        current_writer: &WriteSource,
+        note: Option<String>,
+        document_ids: ConflictingDocumentIds,
+    ) -> anyhow::Error {
         let table_name = mapping.tablet_name(*self.read.index.table());
+        let document_ids = anyhow::Error::new(document_ids);
 
         let Ok(table_name) = table_name else {
-            return anyhow::anyhow!(ErrorMetadata::user_occ(None, None, None, None));
+            return document_ids.context(ErrorMetadata::user_occ(None, None, None, None));
         };
 
         // We want to show the document's ID only if we know which mutation changed it,
@@ -2335,9 +2443,13 @@ impl ConflictingReadWithWriteSource {
                 *current_writer == self.write_source,
             )
         });
+        let occ_msg = match (occ_msg, note) {
+            (Some(occ_msg), Some(note)) => Some(format!("{occ_msg}. {note}")),
+            (occ_msg, note) => occ_msg.or(note),
+        };
 
         if !table_name.is_system() {
-            return anyhow::anyhow!(ErrorMetadata::user_occ(
+            return document_ids.context(ErrorMetadata::user_occ(
                 Some(table_name.into()),
                 Some(self.read.id.developer_id.encode()),
                 self.write_source.0.as_ref().map(|s| s.to_string()),
@@ -2371,7 +2483,9 @@ impl ConflictingReadWithWriteSource {
                 tracing::error!("Read of {index} occurred at {stack_trace}");
             }
         };
-        anyhow::anyhow!(formatted).context(ErrorMetadata::system_occ())
+        document_ids
+            .context(formatted)
+            .context(ErrorMetadata::system_occ())
     }
 }
 
@@ -0,0 +1,114 @@
+//! Fields whose values are computed from the rest of the document.
+//!
+//! A derived field is registered per table along with a function that computes
+//! its expected value. Every write to the table is checked at commit time, and
+//! the transaction is rejected if the document's value for the field doesn't
+//! match. Checking at commit (rather than in `insert`/`replace`) means writes
+//! made by other transactions merged into this one are checked too.
+
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+};
+
+use common::runtime::Runtime;
+use errors::ErrorMetadata;
+use parking_lot::RwLock;
+use value::{
+    ConvexObject,
+    ConvexValue,
+    FieldName,
+    TableName,
+    TableNamespace,
+};
+
+use crate::Transaction;
+
+/// Computes the expected value of a derived field from a document, or `None`
+/// if the field should be absent.
+pub type DeriveFieldFn =
+    Arc<dyn Fn(&ConvexObject) -> anyhow::Result<Option<ConvexValue>> + Send + Sync>;
+
+#[derive(Clone, Default)]
+pub struct DerivedFields {
+    fields: Arc<RwLock<BTreeMap<(TableNamespace, TableName), BTreeMap<FieldName, DeriveFieldFn>>>>,
+}
+
+impl DerivedFields {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require `field` in `table` to always equal `derive` applied to the
+    /// document, replacing any existing derivation for the field.
+    pub fn register(
+        &self,
+        namespace: TableNamespace,
+        table: TableName,
+        field: FieldName,
+        derive: DeriveFieldFn,
+    ) {
+        self.fields
+            .write()
+            .entry((namespace, table))
+            .or_default()
+            .insert(field, derive);
+    }
+
+    pub fn unregister(&self, namespace: TableNamespace, table: &TableName, field: &FieldName) {
+        let mut fields = self.fields.write();
+        let key = (namespace, table.clone());
+        if let Some(table_fields) = fields.get_mut(&key) {
+            table_fields.remove(field);
+            if table_fields.is_empty() {
+                fields.remove(&key);
+            }
+        }
+    }
+
+    /// Check every document written by `transaction` against the derivations
+    /// registered for its table.
+    pub(crate) fn validate<RT: Runtime>(
+        &self,
+        transaction: &Transaction<RT>,
+    ) -> anyhow::Result<()> {
+        let fields = self.fields.read();
+        if fields.is_empty() {
+            return Ok(());
+        }
+        let table_mapping = transaction.metadata.table_mapping();
+        for (id, update) in transaction.writes().as_flat()?.coalesced_writes() {
+            let Some(new_document) = &update.new_document else {
+                continue;
+            };
+            let namespace = table_mapping.tablet_namespace(id.tablet_id)?;
+            let table_name = table_mapping.tablet_name(id.tablet_id)?;
+            let Some(table_fields) = fields.get(&(namespace, table_name.clone())) else {
+                continue;
+            };
+            let value: &ConvexObject = new_document.value();
+            for (field, derive) in table_fields {
+                let expected = derive(value)?;
+                if value.get(field) != expected.as_ref() {
+                    anyhow::bail!(ErrorMetadata::bad_request(
+                        "DerivedFieldMismatch",
+                        format!(
+                            "Field `{field}` in table \"{table_name}\" is derived from other \
+                             fields and must be {}, but the document has {}",
+                            display_field(expected.as_ref()),
+                            display_field(value.get(field)),
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn display_field(value: Option<&ConvexValue>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "absent".to_string(),
+    }
+}
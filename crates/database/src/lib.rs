@@ -11,10 +11,12 @@
 #![feature(try_find)]
 #![feature(once_cell_try)]
 
+mod blob_persistence;
 mod bootstrap_model;
 mod committer;
 mod database;
 mod database_index_workers;
+mod derived_fields;
 mod execution_size;
 mod metrics;
 pub mod patch;
@@ -23,6 +25,7 @@ mod preloaded;
 pub mod query;
 pub mod reads;
 mod retention;
+mod schema_lock;
 mod search_index_bootstrap;
 mod search_index_workers;
 mod snapshot_manager;
@@ -55,6 +58,10 @@ pub mod test_helpers;
 #[cfg(test)]
 pub mod tests;
 pub mod text_index_worker;
+pub use blob_persistence::{
+    BlobPersistence,
+    BlobPersistenceReader,
+};
 pub use component_registry::ComponentRegistry;
 pub use database_index_workers::{
     index_writer::{
@@ -63,15 +70,25 @@ pub use database_index_workers::{
     },
     IndexWorker,
 };
+pub use derived_fields::{
+    DeriveFieldFn,
+    DerivedFields,
+};
 pub use execution_size::FunctionExecutionSize;
 pub use patch::PatchValue;
 pub use preloaded::PreloadedIndexRange;
 pub use reads::{
     ReadSet,
+    ReadSetSize,
     TransactionReadSet,
     TransactionReadSize,
     OVER_LIMIT_HELP,
 };
+pub use schema_lock::{
+    SchemaChangeGuard,
+    SchemaFreezeGuard,
+    SchemaLock,
+};
 pub use schema_registry::SchemaRegistry;
 pub use table_registry::TableRegistry;
 pub use token::{
@@ -79,6 +96,7 @@ pub use token::{
     Token,
 };
 pub use transaction::{
+    ReadWriteSets,
     TableCountSnapshot,
     Transaction,
 };
@@ -94,6 +112,8 @@ pub use write_log::{
     WriteSource,
 };
 pub use writes::{
+    CounterIncrement,
+    CounterIncrements,
     DocumentWrite,
     TransactionWriteSize,
     Writes,
@@ -111,6 +131,13 @@ pub use self::{
             COMPONENTS_BY_PARENT_INDEX,
             COMPONENTS_TABLE,
         },
+        counters::{
+            types::CounterMetadata,
+            CountersModel,
+            CountersTable,
+            COUNTERS_INDEX_BY_NAME,
+            COUNTERS_TABLE,
+        },
         defaults,
         import_facing::ImportFacingModel,
         index::{
@@ -159,6 +186,7 @@ pub use self::{
     database::{
         unauthorized_error,
         BootstrapMetadata,
+        ConflictingDocumentIds,
         Database,
         DatabaseSnapshot,
         DocumentDeltas,
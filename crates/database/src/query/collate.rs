@@ -0,0 +1,132 @@
+use std::{
+    cmp::Ordering,
+    mem,
+    vec,
+};
+
+use async_trait::async_trait;
+use common::{
+    bootstrap_model::index::database_index::IndexedFields,
+    collation::{
+        Collator,
+        Locale,
+    },
+    document::DeveloperDocument,
+    query::{
+        CursorPosition,
+        Order,
+    },
+    runtime::Runtime,
+    types::{
+        IndexName,
+        TabletIndexName,
+        WriteTimestamp,
+    },
+};
+
+use super::{
+    DeveloperIndexRangeResponse,
+    QueryNode,
+    QueryStream,
+    QueryStreamNext,
+    MAX_QUERY_FETCH,
+};
+use crate::Transaction;
+
+/// Re-sorts an index range by a locale's collation. The underlying range is
+/// read to completion before the first row is returned, so it's still subject
+/// to the transaction's read limits.
+pub(super) struct Collate {
+    inner: QueryNode,
+    indexed_fields: IndexedFields,
+    locale: Locale,
+    order: Order,
+    scanned: Vec<(DeveloperDocument, WriteTimestamp)>,
+    sorted: Option<vec::IntoIter<(DeveloperDocument, WriteTimestamp)>>,
+}
+
+impl Collate {
+    pub fn new(
+        inner: QueryNode,
+        indexed_fields: IndexedFields,
+        locale: Locale,
+        order: Order,
+    ) -> Self {
+        Self {
+            inner,
+            indexed_fields,
+            locale,
+            order,
+            scanned: vec![],
+            sorted: None,
+        }
+    }
+
+    fn sort(&mut self) -> anyhow::Result<vec::IntoIter<(DeveloperDocument, WriteTimestamp)>> {
+        let collator = Collator::new(&self.locale)?;
+        let mut rows = mem::take(&mut self.scanned);
+        // The sort is stable, so rows that collate equally stay in index order.
+        rows.sort_by(|(left, _), (right, _)| {
+            let ordering = self
+                .indexed_fields
+                .iter()
+                .map(|field| {
+                    collator
+                        .compare_values(left.value().get_path(field), right.value().get_path(field))
+                })
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or(Ordering::Equal);
+            match self.order {
+                Order::Asc => ordering,
+                Order::Desc => ordering.reverse(),
+            }
+        });
+        Ok(rows.into_iter())
+    }
+}
+
+#[async_trait]
+impl QueryStream for Collate {
+    fn cursor_position(&self) -> &Option<CursorPosition> {
+        self.inner.cursor_position()
+    }
+
+    fn split_cursor_position(&self) -> Option<&CursorPosition> {
+        self.inner.split_cursor_position()
+    }
+
+    fn is_approaching_data_limit(&self) -> bool {
+        self.inner.is_approaching_data_limit()
+    }
+
+    async fn next<RT: Runtime>(
+        &mut self,
+        tx: &mut Transaction<RT>,
+        _prefetch_hint: Option<usize>,
+    ) -> anyhow::Result<QueryStreamNext> {
+        loop {
+            if let Some(ref mut sorted) = self.sorted {
+                return Ok(QueryStreamNext::Ready(sorted.next()));
+            }
+            match self.inner.next(tx, Some(MAX_QUERY_FETCH)).await? {
+                QueryStreamNext::Ready(Some(row)) => self.scanned.push(row),
+                QueryStreamNext::Ready(None) => self.sorted = Some(self.sort()?),
+                QueryStreamNext::WaitingOn(request) => {
+                    return Ok(QueryStreamNext::WaitingOn(request))
+                },
+            }
+        }
+    }
+
+    fn feed(&mut self, index_range_response: DeveloperIndexRangeResponse) -> anyhow::Result<()> {
+        self.inner.feed(index_range_response)
+    }
+
+    fn tablet_index_name(&self) -> Option<&TabletIndexName> {
+        self.inner.tablet_index_name()
+    }
+
+    fn printable_index_name(&self) -> &IndexName {
+        self.inner.printable_index_name()
+    }
+}
@@ -198,16 +198,18 @@ impl IndexRange {
             if let Some(intermediate_cursors) = &mut self.intermediate_cursors {
                 intermediate_cursors.push(CursorPosition::After(index_position.clone()));
             }
-            let cursor_position = CursorPosition::After(index_position);
+            let cursor_position = CursorPosition::After(index_position.clone());
             self.cursor_interval.curr_exclusive = Some(cursor_position.clone());
             self.returned_results += 1;
             let (used_interval, _) = self
                 .initial_unfetched_interval
                 .split(cursor_position, self.order);
 
-            tx.reads.record_indexed_directly(
+            tx.record_index_read(&tablet_index_name, index_bytes, v.size());
+            tx.reads.record_indexed_scan_result(
                 tablet_index_name,
                 self.indexed_fields.clone(),
+                &index_position,
                 used_interval,
             )?;
             UserFacingModel::new(tx, self.namespace)
@@ -225,7 +227,7 @@ impl IndexRange {
             return Ok(QueryStreamNext::Ready(Some((v, timestamp))));
         }
         if let Some(CursorPosition::End) = self.cursor_interval.curr_exclusive {
-            tx.reads.record_indexed_directly(
+            tx.reads.record_indexed_scan(
                 tablet_index_name,
                 self.indexed_fields.clone(),
                 self.initial_unfetched_interval.clone(),
@@ -233,7 +235,7 @@ impl IndexRange {
             return Ok(QueryStreamNext::Ready(None));
         }
         if self.unfetched_interval.is_empty() {
-            tx.reads.record_indexed_directly(
+            tx.reads.record_indexed_scan(
                 tablet_index_name,
                 self.indexed_fields.clone(),
                 self.initial_unfetched_interval.clone(),
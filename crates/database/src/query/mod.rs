@@ -47,6 +47,7 @@ use value::{
 };
 
 use self::{
+    collate::Collate,
     filter::Filter,
     index_range::{
         CursorInterval,
@@ -62,6 +63,7 @@ use crate::{
     Transaction,
 };
 
+mod collate;
 mod filter;
 mod index_range;
 mod limit;
@@ -428,20 +430,41 @@ impl<RT: Runtime> DeveloperQuery<RT> {
             )),
             QuerySource::IndexRange(index_range) => {
                 let order = index_range.order;
+                let collation = index_range.collation.clone();
                 let interval = index_range.compile(indexed_fields.clone())?;
-                QueryNode::IndexRange(IndexRange::new(
+                let node = QueryNode::IndexRange(IndexRange::new(
                     namespace,
                     stable_index_name,
                     index_name,
                     interval,
                     order,
-                    indexed_fields,
+                    indexed_fields.clone(),
                     cursor_interval,
                     maximum_rows_read,
                     maximum_bytes_read,
                     should_compute_split_cursor,
                     version,
-                ))
+                ));
+                match collation {
+                    Some(locale) => {
+                        // Cursors are index positions, which don't mean anything once the
+                        // rows have been re-sorted.
+                        anyhow::ensure!(
+                            fingerprint.is_none(),
+                            ErrorMetadata::bad_request(
+                                "CollationWithPagination",
+                                "Queries with a collation can't be paginated.",
+                            )
+                        );
+                        QueryNode::Collate(Box::new(Collate::new(
+                            node,
+                            indexed_fields,
+                            locale,
+                            order,
+                        )))
+                    },
+                    None => node,
+                }
             },
             QuerySource::Search(search) => QueryNode::Search(SearchQuery::new(
                 stable_index_name,
@@ -698,6 +721,7 @@ enum QueryNode {
     Search(SearchQuery),
     Filter(Box<Filter>),
     Limit(Box<Limit>),
+    Collate(Box<Collate>),
 }
 
 #[async_trait]
@@ -708,6 +732,7 @@ impl QueryStream for QueryNode {
             QueryNode::Search(r) => r.cursor_position(),
             QueryNode::Filter(r) => r.cursor_position(),
             QueryNode::Limit(r) => r.cursor_position(),
+            QueryNode::Collate(r) => r.cursor_position(),
         }
     }
 
@@ -717,6 +742,7 @@ impl QueryStream for QueryNode {
             QueryNode::Search(r) => r.split_cursor_position(),
             QueryNode::Filter(r) => r.split_cursor_position(),
             QueryNode::Limit(r) => r.split_cursor_position(),
+            QueryNode::Collate(r) => r.split_cursor_position(),
         }
     }
 
@@ -726,6 +752,7 @@ impl QueryStream for QueryNode {
             Self::Search(r) => r.is_approaching_data_limit(),
             Self::Filter(r) => r.is_approaching_data_limit(),
             Self::Limit(r) => r.is_approaching_data_limit(),
+            Self::Collate(r) => r.is_approaching_data_limit(),
         }
     }
 
@@ -739,6 +766,7 @@ impl QueryStream for QueryNode {
             QueryNode::Search(r) => r.next(tx, prefetch_hint).await,
             QueryNode::Filter(r) => r.next(tx, prefetch_hint).await,
             QueryNode::Limit(r) => r.next(tx, prefetch_hint).await,
+            QueryNode::Collate(r) => r.next(tx, prefetch_hint).await,
         }
     }
 
@@ -748,6 +776,7 @@ impl QueryStream for QueryNode {
             QueryNode::Search(r) => r.feed(index_range_response),
             QueryNode::Filter(r) => r.feed(index_range_response),
             QueryNode::Limit(r) => r.feed(index_range_response),
+            QueryNode::Collate(r) => r.feed(index_range_response),
         }
     }
 
@@ -757,6 +786,7 @@ impl QueryStream for QueryNode {
             QueryNode::Search(r) => r.tablet_index_name(),
             QueryNode::Filter(r) => r.tablet_index_name(),
             QueryNode::Limit(r) => r.tablet_index_name(),
+            QueryNode::Collate(r) => r.tablet_index_name(),
         }
     }
 
@@ -766,6 +796,7 @@ impl QueryStream for QueryNode {
             QueryNode::Search(r) => r.printable_index_name(),
             QueryNode::Filter(r) => r.printable_index_name(),
             QueryNode::Limit(r) => r.printable_index_name(),
+            QueryNode::Collate(r) => r.printable_index_name(),
         }
     }
 }
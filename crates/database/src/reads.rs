@@ -1,6 +1,10 @@
 //! Read set tracking for an active transaction
 use std::{
-    collections::BTreeMap,
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    ops::ControlFlow,
     sync::LazyLock,
 };
 
@@ -16,17 +20,20 @@ use common::{
         DocumentIndexKeyValue,
         DocumentIndexKeys,
     },
+    index::IndexKeyBytes,
     interval::{
         Interval,
         IntervalSet,
     },
     knobs::{
+        CONFLICT_GRANULARITY,
         TRANSACTION_MAX_READ_SET_INTERVALS,
         TRANSACTION_MAX_READ_SIZE_BYTES,
         TRANSACTION_MAX_READ_SIZE_ROWS,
     },
     static_span,
     types::{
+        ConflictGranularity,
         PersistenceVersion,
         TabletIndexName,
         Timestamp,
@@ -133,6 +140,23 @@ impl ReadSet {
         self.search.iter()
     }
 
+    /// Returns a copy of this read set that only includes reads of the given
+    /// tables.
+    pub fn filter_tables(&self, tablet_ids: &BTreeSet<TabletId>) -> Self {
+        Self::new(
+            self.indexed
+                .iter()
+                .filter(|(index, _)| tablet_ids.contains(index.table()))
+                .map(|(index, reads)| (index.clone(), reads.clone()))
+                .collect(),
+            self.search
+                .iter()
+                .filter(|(index, _)| tablet_ids.contains(index.table()))
+                .map(|(index, reads)| (index.clone(), reads.clone()))
+                .collect(),
+        )
+    }
+
     pub fn consume(
         self,
     ) -> (
@@ -282,34 +306,75 @@ impl ReadSet {
         >,
         persistence_version: PersistenceVersion,
     ) -> Option<ConflictingReadWithWriteSource> {
+        let mut first = None;
+        self.for_each_doc_conflict(updates, persistence_version, |conflict| {
+            first = Some(conflict);
+            ControlFlow::Break(())
+        });
+        first
+    }
+
+    /// Like `writes_overlap_docs`, but returns every conflicting write rather
+    /// than stopping at the first.
+    pub fn all_writes_overlapping_docs<'a>(
+        &self,
+        updates: impl Iterator<
+            Item = (
+                &'a Timestamp,
+                impl Iterator<Item = &'a (ResolvedDocumentId, PackedDocumentUpdate)>,
+                &'a WriteSource,
+            ),
+        >,
+        persistence_version: PersistenceVersion,
+    ) -> Vec<ConflictingReadWithWriteSource> {
+        let mut conflicts = vec![];
+        self.for_each_doc_conflict(updates, persistence_version, |conflict| {
+            conflicts.push(conflict);
+            ControlFlow::Continue(())
+        });
+        conflicts
+    }
+
+    fn for_each_doc_conflict<'a>(
+        &self,
+        updates: impl Iterator<
+            Item = (
+                &'a Timestamp,
+                impl Iterator<Item = &'a (ResolvedDocumentId, PackedDocumentUpdate)>,
+                &'a WriteSource,
+            ),
+        >,
+        persistence_version: PersistenceVersion,
+        mut f: impl FnMut(ConflictingReadWithWriteSource) -> ControlFlow<()>,
+    ) {
         let mut buffer = IndexKeyBuffer::new();
         for (update_ts, updates, write_source) in updates {
             for (_, update) in updates {
-                if let Some(ref document) = update.new_document {
-                    if let Some(conflicting_read) =
+                // A document that conflicts in both its old and new versions
+                // is only reported once.
+                let conflicting_read = update
+                    .new_document
+                    .as_ref()
+                    .and_then(|document| {
                         self.overlaps_document(document, persistence_version, &mut buffer)
-                    {
-                        return Some(ConflictingReadWithWriteSource {
-                            read: conflicting_read,
-                            write_source: write_source.clone(),
-                            write_ts: *update_ts,
-                        });
-                    }
-                }
-                if let Some(ref prev_value) = update.old_document {
-                    if let Some(conflicting_read) =
-                        self.overlaps_document(prev_value, persistence_version, &mut buffer)
-                    {
-                        return Some(ConflictingReadWithWriteSource {
-                            read: conflicting_read,
-                            write_source: write_source.clone(),
-                            write_ts: *update_ts,
-                        });
+                    })
+                    .or_else(|| {
+                        update.old_document.as_ref().and_then(|prev_value| {
+                            self.overlaps_document(prev_value, persistence_version, &mut buffer)
+                        })
+                    });
+                if let Some(conflicting_read) = conflicting_read {
+                    let conflict = ConflictingReadWithWriteSource {
+                        read: conflicting_read,
+                        write_source: write_source.clone(),
+                        write_ts: *update_ts,
+                    };
+                    if f(conflict).is_break() {
+                        return;
                     }
                 }
             }
         }
-        None
     }
 
     /// Equivalent to `writes_overlap_docs` but does not need to read the full
@@ -325,29 +390,69 @@ impl ReadSet {
             ),
         >,
     ) -> Option<ConflictingReadWithWriteSource> {
+        let mut first = None;
+        self.for_each_index_keys_conflict(updates, |conflict| {
+            first = Some(conflict);
+            ControlFlow::Break(())
+        });
+        first
+    }
+
+    /// Like `writes_overlap_index_keys`, but returns every conflicting write
+    /// rather than stopping at the first.
+    pub fn all_writes_overlapping_index_keys<'a>(
+        &self,
+        updates: impl Iterator<
+            Item = (
+                &'a Timestamp,
+                impl Iterator<Item = &'a (ResolvedDocumentId, DocumentIndexKeysUpdate)>,
+                &'a WriteSource,
+            ),
+        >,
+    ) -> Vec<ConflictingReadWithWriteSource> {
+        let mut conflicts = vec![];
+        self.for_each_index_keys_conflict(updates, |conflict| {
+            conflicts.push(conflict);
+            ControlFlow::Continue(())
+        });
+        conflicts
+    }
+
+    fn for_each_index_keys_conflict<'a>(
+        &self,
+        updates: impl Iterator<
+            Item = (
+                &'a Timestamp,
+                impl Iterator<Item = &'a (ResolvedDocumentId, DocumentIndexKeysUpdate)>,
+                &'a WriteSource,
+            ),
+        >,
+        mut f: impl FnMut(ConflictingReadWithWriteSource) -> ControlFlow<()>,
+    ) {
         for (update_ts, updates, write_source) in updates {
             for (id, update) in updates {
-                if let Some(ref document) = update.new_document_keys {
-                    if let Some(conflicting_read) = self.overlaps_index_keys(*id, document) {
-                        return Some(ConflictingReadWithWriteSource {
-                            read: conflicting_read,
-                            write_source: write_source.clone(),
-                            write_ts: *update_ts,
-                        });
-                    }
-                }
-                if let Some(ref document) = update.old_document_keys {
-                    if let Some(conflicting_read) = self.overlaps_index_keys(*id, document) {
-                        return Some(ConflictingReadWithWriteSource {
-                            read: conflicting_read,
-                            write_source: write_source.clone(),
-                            write_ts: *update_ts,
-                        });
+                let conflicting_read = update
+                    .new_document_keys
+                    .as_ref()
+                    .and_then(|document| self.overlaps_index_keys(*id, document))
+                    .or_else(|| {
+                        update
+                            .old_document_keys
+                            .as_ref()
+                            .and_then(|document| self.overlaps_index_keys(*id, document))
+                    });
+                if let Some(conflicting_read) = conflicting_read {
+                    let conflict = ConflictingReadWithWriteSource {
+                        read: conflicting_read,
+                        write_source: write_source.clone(),
+                        write_ts: *update_ts,
+                    };
+                    if f(conflict).is_break() {
+                        return;
                     }
                 }
             }
         }
-        None
     }
 }
 
@@ -378,6 +483,8 @@ pub struct TransactionReadSet {
 
     user_tx_size: TransactionReadSize,
     system_tx_size: TransactionReadSize,
+
+    granularity: ConflictGranularity,
 }
 
 #[cfg(any(test, feature = "testing"))]
@@ -399,6 +506,18 @@ pub struct TransactionReadSize {
     pub total_document_count: usize,
 }
 
+/// How much a transaction has read. A larger read set gives concurrent
+/// writes more to conflict with.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ReadSetSize {
+    /// Number of index intervals read.
+    pub num_intervals: usize,
+    /// Number of user documents read.
+    pub num_documents: usize,
+    /// Total size of the user documents read.
+    pub document_bytes: usize,
+}
+
 impl TransactionReadSet {
     /// Create a read-set at the given timestamp.
     pub fn new() -> Self {
@@ -407,9 +526,16 @@ impl TransactionReadSet {
             num_intervals: 0,
             user_tx_size: TransactionReadSize::default(),
             system_tx_size: TransactionReadSize::default(),
+            granularity: *CONFLICT_GRANULARITY,
         }
     }
 
+    /// Record subsequent direct reads at `granularity` rather than the one
+    /// configured by `CONFLICT_GRANULARITY`.
+    pub fn set_granularity(&mut self, granularity: ConflictGranularity) {
+        self.granularity = granularity;
+    }
+
     pub fn into_read_set(self) -> ReadSet {
         self.read_set
     }
@@ -565,6 +691,12 @@ impl TransactionReadSet {
     ) -> anyhow::Result<()> {
         let _s = static_span!();
 
+        let interval = match self.granularity {
+            // Direct reads outside of range scans, like `db.get`, are already
+            // point reads of a single document.
+            ConflictGranularity::Row | ConflictGranularity::Range => interval,
+            ConflictGranularity::Table => Interval::all(),
+        };
         let (num_intervals_before, num_intervals_after) =
             self._record_indexed(index_name, fields, [interval]);
 
@@ -587,6 +719,38 @@ impl TransactionReadSet {
         Ok(())
     }
 
+    /// Record a document at `key` returned by a range scan, which has so far
+    /// covered `scanned`. At row granularity only the document's key is
+    /// recorded.
+    pub fn record_indexed_scan_result(
+        &mut self,
+        index_name: TabletIndexName,
+        fields: IndexedFields,
+        key: &IndexKeyBytes,
+        scanned: Interval,
+    ) -> anyhow::Result<()> {
+        let interval = match self.granularity {
+            ConflictGranularity::Row => Interval::prefix(key.clone().into()),
+            ConflictGranularity::Range | ConflictGranularity::Table => scanned,
+        };
+        self.record_indexed_directly(index_name, fields, interval)
+    }
+
+    /// Record the whole interval of a finished range scan. At row
+    /// granularity the scan's documents were already recorded one at a time,
+    /// so this records nothing.
+    pub fn record_indexed_scan(
+        &mut self,
+        index_name: TabletIndexName,
+        fields: IndexedFields,
+        scanned: Interval,
+    ) -> anyhow::Result<()> {
+        if self.granularity == ConflictGranularity::Row {
+            return Ok(());
+        }
+        self.record_indexed_directly(index_name, fields, scanned)
+    }
+
     pub fn top_three_intervals(&self) -> String {
         let mut intervals: Vec<_> = self
             .read_set
@@ -620,6 +784,14 @@ impl TransactionReadSet {
         &self.user_tx_size
     }
 
+    pub fn size(&self) -> ReadSetSize {
+        ReadSetSize {
+            num_intervals: self.num_intervals,
+            num_documents: self.user_tx_size.total_document_count,
+            document_bytes: self.user_tx_size.total_document_size,
+        }
+    }
+
     pub fn system_tx_size(&self) -> &TransactionReadSize {
         &self.system_tx_size
     }
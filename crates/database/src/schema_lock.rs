@@ -0,0 +1,70 @@
+//! Coordinates snapshot imports with schema changes.
+//!
+//! Imports validate documents against the schemas that exist when they start,
+//! and fail at the end if any schema changed in the meantime. To avoid losing
+//! the work of a long import, an import holds a shared "freeze" on the schema
+//! for its duration, and schema changes wait for all freezes to be released
+//! before proceeding.
+
+use std::{
+    sync::Arc,
+    time::Duration,
+};
+
+use common::runtime::Runtime;
+use errors::ErrorMetadata;
+use futures::FutureExt;
+use tokio::sync::{
+    OwnedRwLockReadGuard,
+    OwnedRwLockWriteGuard,
+    RwLock,
+};
+
+#[derive(Clone, Default)]
+pub struct SchemaLock {
+    lock: Arc<RwLock<()>>,
+}
+
+/// Held by an import while it's running. Schema changes are blocked until
+/// every outstanding freeze is dropped.
+pub struct SchemaFreezeGuard {
+    _guard: OwnedRwLockReadGuard<()>,
+}
+
+/// Held while changing the schema. New imports wait for it to be dropped.
+pub struct SchemaChangeGuard {
+    _guard: OwnedRwLockWriteGuard<()>,
+}
+
+impl SchemaLock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn freeze(&self) -> SchemaFreezeGuard {
+        SchemaFreezeGuard {
+            _guard: self.lock.clone().read_owned().await,
+        }
+    }
+
+    /// Wait for any in-progress imports to finish, failing if they don't
+    /// finish within `timeout`.
+    pub async fn lock_for_change<RT: Runtime>(
+        &self,
+        rt: &RT,
+        timeout: Duration,
+    ) -> anyhow::Result<SchemaChangeGuard> {
+        futures::select_biased! {
+            guard = self.lock.clone().write_owned().fuse() => {
+                Ok(SchemaChangeGuard { _guard: guard })
+            },
+            _ = rt.wait(timeout) => {
+                anyhow::bail!(ErrorMetadata::bad_request(
+                    "SchemaLockedByImport",
+                    "Cannot change the schema while an import is in progress. Try again once \
+                     the import completes.",
+                ))
+            },
+        }
+    }
+}
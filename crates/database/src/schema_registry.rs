@@ -223,6 +223,7 @@ impl SchemaRegistry {
                 state_value.into(),
             )],
             order: Order::Asc,
+            collation: None,
         };
         let fields = IndexedFields::try_from(vec![SCHEMA_STATE_FIELD.clone()])?;
         let interval = index_range.compile(fields.clone())?;
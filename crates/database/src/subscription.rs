@@ -25,6 +25,7 @@ use common::{
         DocumentIndexKeys,
     },
     errors::report_error,
+    interval::Interval,
     knobs::{
         SUBSCRIPTIONS_WORKER_QUEUE_SIZE,
         SUBSCRIPTION_ADVANCE_LOG_TRACING_THRESHOLD,
@@ -578,6 +579,7 @@ pub struct Subscription {
     validity: Arc<Validity>,
     // May lag behind `validity` in case of subscription splaying
     valid: watch::Receiver<SubscriptionState>,
+    reads: Arc<ReadSet>,
     _timer: Timer<VMHistogram>,
 }
 
@@ -588,6 +590,7 @@ impl Subscription {
         let subscription = Subscription {
             validity: validity.clone(),
             valid: valid_rx,
+            reads: token.reads_owned(),
             _timer: metrics::subscription_timer(),
         };
         (subscription, SubscriptionSender { validity, valid_tx })
@@ -598,10 +601,20 @@ impl Subscription {
         Subscription {
             validity: Arc::new(Validity::invalid(invalid_ts)),
             valid: receiver,
+            reads: Arc::new(ReadSet::empty()),
             _timer: metrics::subscription_timer(),
         }
     }
 
+    /// The index ranges this subscription depends on: a write to any of them
+    /// invalidates it. Text search reads aren't included.
+    pub fn index_ranges(&self) -> BTreeMap<TabletIndexName, Vec<Interval>> {
+        self.reads
+            .iter_indexed()
+            .map(|(index, reads)| (index.clone(), reads.intervals.iter().collect()))
+            .collect()
+    }
+
     pub fn current_ts(&self) -> Option<Timestamp> {
         self.validity.valid_ts()
     }
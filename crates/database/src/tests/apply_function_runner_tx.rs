@@ -81,6 +81,11 @@ async fn test_apply_function_runner_tx_new_table(rt: TestRuntime) -> anyhow::Res
         .map(|(table, stats)| (*table, stats.rows_read))
         .collect();
     let updates = function_runner_tx.writes.as_flat()?.clone().into_updates();
+    let counter_increments = function_runner_tx
+        .writes
+        .as_flat()?
+        .counter_increments()
+        .clone();
     backend_tx.apply_function_runner_tx(
         *begin_timestamp,
         reads,
@@ -88,6 +93,7 @@ async fn test_apply_function_runner_tx_new_table(rt: TestRuntime) -> anyhow::Res
         user_tx_size,
         system_tx_size,
         updates,
+        counter_increments,
         rows_read_by_tablet,
     )?;
     assert_eq!(
@@ -134,6 +140,11 @@ async fn test_apply_function_runner_tx_read_only(rt: TestRuntime) -> anyhow::Res
         .map(|(table, stats)| (*table, stats.rows_read))
         .collect();
     let updates = function_runner_tx.writes.as_flat()?.clone().into_updates();
+    let counter_increments = function_runner_tx
+        .writes
+        .as_flat()?
+        .counter_increments()
+        .clone();
     backend_tx.apply_function_runner_tx(
         *begin_timestamp,
         reads,
@@ -141,6 +152,7 @@ async fn test_apply_function_runner_tx_read_only(rt: TestRuntime) -> anyhow::Res
         user_tx_size,
         system_tx_size,
         updates,
+        counter_increments,
         rows_read_by_tablet,
     )?;
 
@@ -184,6 +196,11 @@ async fn test_apply_function_runner_tx_replace(rt: TestRuntime) -> anyhow::Resul
         .map(|(table, stats)| (*table, stats.rows_read))
         .collect();
     let updates = function_runner_tx.writes.as_flat()?.clone().into_updates();
+    let counter_increments = function_runner_tx
+        .writes
+        .as_flat()?
+        .counter_increments()
+        .clone();
     backend_tx.apply_function_runner_tx(
         *begin_timestamp,
         reads,
@@ -191,6 +208,7 @@ async fn test_apply_function_runner_tx_replace(rt: TestRuntime) -> anyhow::Resul
         user_tx_size,
         system_tx_size,
         updates,
+        counter_increments,
         rows_read_by_tablet,
     )?;
 
@@ -237,6 +255,11 @@ async fn test_apply_function_runner_tx_merge_existing_writes(
         .map(|(table, stats)| (*table, stats.rows_read))
         .collect();
     let updates = function_runner_tx.writes.as_flat()?.clone().into_updates();
+    let counter_increments = function_runner_tx
+        .writes
+        .as_flat()?
+        .counter_increments()
+        .clone();
     backend_tx.apply_function_runner_tx(
         *begin_timestamp,
         reads,
@@ -244,6 +267,7 @@ async fn test_apply_function_runner_tx_merge_existing_writes(
         user_tx_size,
         system_tx_size,
         updates,
+        counter_increments,
         rows_read_by_tablet,
     )?;
 
@@ -289,6 +313,11 @@ async fn test_apply_function_runner_tx_merge_existing_writes_bad(
         .map(|(table, stats)| (*table, stats.rows_read))
         .collect();
     let updates = function_runner_tx.writes.as_flat()?.clone().into_updates();
+    let counter_increments = function_runner_tx
+        .writes
+        .as_flat()?
+        .counter_increments()
+        .clone();
     assert!(backend_tx
         .apply_function_runner_tx(
             *begin_timestamp,
@@ -297,6 +326,7 @@ async fn test_apply_function_runner_tx_merge_existing_writes_bad(
             user_tx_size,
             system_tx_size,
             updates,
+            counter_increments,
             rows_read_by_tablet,
         )
         .is_err());
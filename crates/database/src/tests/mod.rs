@@ -11,6 +11,7 @@ use std::{
 };
 
 use ::usage_tracking::FunctionUsageTracker;
+use anyhow::Context;
 use cmd_util::env::env_config;
 use common::{
     assert_obj,
@@ -31,6 +32,8 @@ use common::{
         PackedDocument,
         ResolvedDocument,
     },
+    index::IndexKey,
+    interval::Interval,
     maybe_val,
     object_validator,
     pause::PauseController,
@@ -62,11 +65,13 @@ use common::{
     },
     types::{
         unchecked_repeatable_ts,
+        ConflictGranularity,
         IndexDescriptor,
         IndexName,
         PersistenceVersion,
         RepeatableTimestamp,
         TableName,
+        TabletIndexName,
         WriteTimestamp,
     },
     value::{
@@ -104,9 +109,12 @@ use value::{
 };
 
 use crate::{
-    bootstrap_model::index_backfills::{
-        types::BackfillCursor,
-        IndexBackfillModel,
+    bootstrap_model::{
+        index_backfills::{
+            types::BackfillCursor,
+            IndexBackfillModel,
+        },
+        user_facing::DocumentTooLarge,
     },
     database_index_workers::index_writer::{
         IndexSelector,
@@ -129,6 +137,7 @@ use crate::{
         DbFixturesArgs,
     },
     write_log::WriteSource,
+    ConflictingDocumentIds,
     Database,
     DatabaseSnapshot,
     ImportFacingModel,
@@ -453,6 +462,187 @@ async fn test_delete_conflict(rt: TestRuntime) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[convex_macro::test_runtime]
+async fn test_conflict_lists_conflicting_documents(rt: TestRuntime) -> anyhow::Result<()> {
+    let database = new_test_database(rt).await;
+    let mut tx = database.begin(Identity::system()).await?;
+    let mut ids = vec![];
+    for _ in 0..7 {
+        ids.push(
+            TestFacingModel::new(&mut tx)
+                .insert(&"key".parse()?, ConvexObject::empty())
+                .await?,
+        );
+    }
+    database.commit(tx).await?;
+
+    let mut tx1 = database.begin(Identity::system()).await?;
+    for id in &ids {
+        assert!(tx1.get(*id).await?.is_some());
+    }
+    TestFacingModel::new(&mut tx1)
+        .insert(&"key2".parse()?, ConvexObject::empty())
+        .await?;
+
+    // Separate writers each change one of the documents tx1 read.
+    for (i, id) in ids.iter().enumerate() {
+        let mut tx = database.begin(Identity::system()).await?;
+        UserFacingModel::new_root_for_test(&mut tx)
+            .delete((*id).into())
+            .await?;
+        database
+            .commit_with_write_source(tx, format!("foo/bar:writer{i}"))
+            .await?;
+    }
+
+    must_let!(let Err(e) = database.commit(tx1).await);
+    assert!(e.is_occ());
+    // The message only lists the first few IDs...
+    let msg = format!("{e}");
+    assert!(
+        msg.contains("Conflicting writes changed 7 documents, with IDs "),
+        "Got:\n\n{msg}"
+    );
+    assert!(msg.contains(" and 2 more"), "Got:\n\n{msg}");
+    let num_listed = ids
+        .iter()
+        .filter(|id| msg.contains(&format!("\"{id}\", ")) || msg.contains(&format!("\"{id}\" and")))
+        .count();
+    assert_eq!(num_listed, 5, "Got:\n\n{msg}");
+    // ...but all of them are attached to the error.
+    let document_ids = e
+        .downcast_ref::<ConflictingDocumentIds>()
+        .expect("missing conflicting document IDs");
+    let mut attached = document_ids.0.clone();
+    attached.sort();
+    let mut expected: Vec<_> = ids.iter().map(|id| id.to_string()).collect();
+    expected.sort();
+    assert_eq!(attached, expected);
+
+    Ok(())
+}
+
+/// Run `num_writers` concurrent transactions that each read their own
+/// document and then write, returning how many of them failed with an OCC.
+async fn count_conflicts(
+    database: &Database<TestRuntime>,
+    granularity: ConflictGranularity,
+    num_writers: usize,
+) -> anyhow::Result<usize> {
+    let mut tx = database.begin(Identity::system()).await?;
+    let mut ids = vec![];
+    for _ in 0..num_writers {
+        ids.push(
+            TestFacingModel::new(&mut tx)
+                .insert(&"key".parse()?, assert_obj!())
+                .await?,
+        );
+    }
+    database.commit(tx).await?;
+
+    let mut txs = vec![];
+    for id in ids {
+        let mut tx = database.begin(Identity::system()).await?;
+        tx.set_conflict_granularity(granularity);
+        assert!(tx.get(id).await?.is_some());
+        UserFacingModel::new_root_for_test(&mut tx)
+            .patch(
+                id.into(),
+                crate::patch_value!("touched" => Some(true.into()))?,
+            )
+            .await?;
+        txs.push(tx);
+    }
+    let mut conflicts = 0;
+    for tx in txs {
+        match database.commit(tx).await {
+            Ok(_) => (),
+            Err(e) if e.is_occ() => conflicts += 1,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Run `num_writers` concurrent transactions that each scan a whole table and
+/// then insert into it, returning how many of them failed with an OCC.
+async fn count_insert_conflicts(
+    database: &Database<TestRuntime>,
+    granularity: ConflictGranularity,
+    num_writers: usize,
+) -> anyhow::Result<usize> {
+    let table_name: TableName = format!("scanned_{granularity}").parse()?;
+    let mut tx = database.begin(Identity::system()).await?;
+    TestFacingModel::new(&mut tx)
+        .insert(&table_name, assert_obj!())
+        .await?;
+    database.commit(tx).await?;
+
+    let mut txs = vec![];
+    for _ in 0..num_writers {
+        let mut tx = database.begin(Identity::system()).await?;
+        tx.set_conflict_granularity(granularity);
+        let query = Query::full_table_scan(table_name.clone(), Order::Asc);
+        let mut query_stream = ResolvedQuery::new(&mut tx, TableNamespace::test_user(), query)?;
+        let mut num_read = 0;
+        while query_stream.next(&mut tx, None).await?.is_some() {
+            num_read += 1;
+        }
+        assert_eq!(num_read, 1);
+        TestFacingModel::new(&mut tx)
+            .insert(&table_name, assert_obj!())
+            .await?;
+        txs.push(tx);
+    }
+    let mut conflicts = 0;
+    for tx in txs {
+        match database.commit(tx).await {
+            Ok(_) => (),
+            Err(e) if e.is_occ() => conflicts += 1,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(conflicts)
+}
+
+#[convex_macro::test_runtime]
+async fn test_conflict_granularity(rt: TestRuntime) -> anyhow::Result<()> {
+    let database = new_test_database(rt).await;
+    // Each transaction only read its own row, so none of them conflict.
+    assert_eq!(
+        count_conflicts(&database, ConflictGranularity::Row, 4).await?,
+        0
+    );
+    assert_eq!(
+        count_conflicts(&database, ConflictGranularity::Range, 4).await?,
+        0
+    );
+    // Table-level reads conflict with every other write to the table, so only
+    // the first writer commits.
+    assert_eq!(
+        count_conflicts(&database, ConflictGranularity::Table, 4).await?,
+        3
+    );
+
+    // Row-level reads only cover the document each scan returned, so inserts
+    // into the scanned table don't conflict.
+    assert_eq!(
+        count_insert_conflicts(&database, ConflictGranularity::Row, 4).await?,
+        0
+    );
+    // The scanned range covers the whole table, so every insert after the
+    // first lands in the other transactions' read sets.
+    assert_eq!(
+        count_insert_conflicts(&database, ConflictGranularity::Range, 4).await?,
+        3
+    );
+    assert_eq!(
+        count_insert_conflicts(&database, ConflictGranularity::Table, 4).await?,
+        3
+    );
+    Ok(())
+}
+
 #[convex_macro::test_runtime]
 async fn test_creation_time_success(rt: TestRuntime) -> anyhow::Result<()> {
     let database = new_test_database(rt.clone()).await;
@@ -777,6 +967,7 @@ where
             index_name,
             range,
             order,
+            collation: None,
         }),
         operators: vec![],
     };
@@ -886,6 +1077,152 @@ async fn test_query_index_range_half_bounded(rt: TestRuntime) -> anyhow::Result<
     .await
 }
 
+#[convex_macro::test_runtime]
+async fn test_query_index_range_collation(rt: TestRuntime) -> anyhow::Result<()> {
+    let DbFixtures {
+        db: database, tp, ..
+    } = DbFixtures::new(&rt).await?;
+    let table_name: TableName = str::parse("words")?;
+    let namespace = TableNamespace::test_user();
+    let index_name = IndexName::new(table_name.clone(), IndexDescriptor::new("by_word")?)?;
+
+    let mut tx = database.begin(Identity::system()).await?;
+    let begin_ts = tx.begin_timestamp();
+    IndexModel::new(&mut tx)
+        .add_application_index(
+            namespace,
+            IndexMetadata::new_backfilling(
+                *begin_ts,
+                index_name.clone(),
+                vec![str::parse("word")?].try_into()?,
+            ),
+        )
+        .await?;
+    database.commit(tx).await?;
+
+    let mut tx = database.begin(Identity::system()).await?;
+    for word in [
+        "zebra",
+        "Äpple",
+        "apple",
+        "éclair",
+        "eclair",
+        "Zoo",
+        "ångström",
+    ] {
+        TestFacingModel::new(&mut tx)
+            .insert(&table_name, assert_obj!("word" => word))
+            .await?;
+    }
+    database.commit(tx).await?;
+
+    IndexWorker::new_terminating(rt, tp, Arc::new(NoopRetentionValidator), database.clone())
+        .await?;
+    let mut tx = database.begin_system().await?;
+    IndexModel::new(&mut tx)
+        .enable_index_for_testing(namespace, &index_name)
+        .await?;
+    database.commit(tx).await?;
+
+    let query_words = |order: Order, collation: Option<&str>| {
+        let database = database.clone();
+        let query = Query::index_range(IndexRange {
+            index_name: index_name.clone(),
+            range: vec![],
+            order,
+            collation: collation.map(|locale| locale.parse().unwrap()),
+        });
+        async move {
+            let words = run_query(database, namespace, query)
+                .await?
+                .into_iter()
+                .map(|doc| {
+                    must_let!(let Some(ConvexValue::String(word)) = doc.value().get("word"));
+                    word.to_string()
+                })
+                .collect::<Vec<_>>();
+            anyhow::Ok(words)
+        }
+    };
+
+    // Without a collation, strings sort by code point.
+    assert_eq!(
+        query_words(Order::Asc, None).await?,
+        [
+            "Zoo",
+            "apple",
+            "eclair",
+            "zebra",
+            "Äpple",
+            "ångström",
+            "éclair"
+        ]
+    );
+    assert_eq!(
+        query_words(Order::Asc, Some("en")).await?,
+        [
+            "ångström",
+            "apple",
+            "Äpple",
+            "eclair",
+            "éclair",
+            "zebra",
+            "Zoo"
+        ]
+    );
+    assert_eq!(
+        query_words(Order::Desc, Some("en")).await?,
+        [
+            "Zoo",
+            "zebra",
+            "éclair",
+            "eclair",
+            "Äpple",
+            "apple",
+            "ångström"
+        ]
+    );
+    // Swedish sorts "å" and "ä" as separate letters after "z".
+    assert_eq!(
+        query_words(Order::Asc, Some("sv")).await?,
+        [
+            "apple",
+            "eclair",
+            "éclair",
+            "zebra",
+            "Zoo",
+            "ångström",
+            "Äpple"
+        ]
+    );
+
+    // Collated results can't be paginated, since cursors are index positions.
+    let mut tx = database.begin(Identity::system()).await?;
+    let err = ResolvedQuery::<TestRuntime>::new_bounded(
+        &mut tx,
+        namespace,
+        Query::index_range(IndexRange {
+            index_name,
+            range: vec![],
+            order: Order::Asc,
+            collation: Some("en".parse()?),
+        }),
+        PaginationOptions::ManualPagination {
+            start_cursor: None,
+            maximum_rows_read: None,
+            maximum_bytes_read: None,
+        },
+        None,
+        TableFilter::IncludePrivateSystemTables,
+    )
+    .err()
+    .unwrap();
+    assert!(err.is_bad_request());
+    assert_eq!(err.short_msg(), "CollationWithPagination");
+
+    Ok(())
+}
+
 proptest! {
     #![proptest_config(
             ProptestConfig { cases: 256 * env_config("CONVEX_PROPTEST_MULTIPLIER", 1), failure_persistence: None, ..ProptestConfig::default() }
@@ -1016,6 +1353,46 @@ async fn test_too_large_values(rt: TestRuntime) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[convex_macro::test_runtime]
+async fn test_oversized_document_rejected_before_commit(rt: TestRuntime) -> anyhow::Result<()> {
+    let huge_obj = assert_obj!("huge" => vec![0; 1 << 22]);
+    let database = new_test_database(rt).await;
+    let table_name: TableName = "table".parse()?;
+
+    let mut tx = database.begin(Identity::system()).await?;
+    UserFacingModel::new_root_for_test(&mut tx)
+        .insert(table_name.clone(), assert_obj!())
+        .await?;
+    database.commit(tx).await?;
+
+    let mut tx = database.begin(Identity::system()).await?;
+    let err = UserFacingModel::new_root_for_test(&mut tx)
+        .insert(table_name.clone(), huge_obj)
+        .await
+        .unwrap_err();
+    assert!(err.is_bad_request());
+    assert_eq!(err.short_msg(), "ValueTooLargeError");
+    let too_large = err
+        .downcast_ref::<DocumentTooLarge>()
+        .expect("missing DocumentTooLarge data");
+    assert_eq!(too_large.limit, 1 << 20);
+    assert!(too_large.size > too_large.limit);
+    assert!(
+        format!("{err}").contains("> maximum document size 1 MiB"),
+        "{err}"
+    );
+    // The failed write left the transaction untouched.
+    database.commit(tx).await?;
+
+    let mut tx = database.begin(Identity::system()).await?;
+    let count = TableModel::new(&mut tx)
+        .count(TableNamespace::test_user(), &table_name)
+        .await?;
+    assert_eq!(count, Some(1));
+
+    Ok(())
+}
+
 #[convex_macro::test_runtime]
 async fn test_too_nested_values(rt: TestRuntime) -> anyhow::Result<()> {
     let mut deeply_nested_but_still_ok = assert_val!(false);
@@ -1864,6 +2241,7 @@ async fn check_index_is_correct(
                 index_name: index_name.clone(),
                 range,
                 order,
+                collation: None,
             }),
             operators: vec![],
         };
@@ -2322,6 +2700,7 @@ async fn test_query_filter_readset(rt: TestRuntime) -> anyhow::Result<()> {
                 ConvexValue::Float64(0.0).into(),
             )],
             order: Order::Asc,
+            collation: None,
         }),
         operators: vec![QueryOperator::Filter(Expression::Eq(
             Box::new(Expression::Literal(maybe_val!("eng"))),
@@ -2418,6 +2797,7 @@ async fn test_query_readset_empty_query(rt: TestRuntime) -> anyhow::Result<()> {
                 ConvexValue::Float64(0.0).into(),
             )],
             order: Order::Asc,
+            collation: None,
         }),
         operators: vec![],
     };
@@ -2612,3 +2992,273 @@ async fn test_schema_registry_takes_read_dependency(rt: TestRuntime) -> anyhow::
     );
     Ok(())
 }
+
+#[convex_macro::test_runtime]
+async fn test_subscribe_with_table_filter(rt: TestRuntime) -> anyhow::Result<()> {
+    let database = new_test_database(rt).await;
+    let mut tx = database.begin(Identity::system()).await?;
+    let a_id = TestFacingModel::new(&mut tx)
+        .insert(&"a".parse()?, ConvexObject::empty())
+        .await?;
+    let b_id = TestFacingModel::new(&mut tx)
+        .insert(&"b".parse()?, ConvexObject::empty())
+        .await?;
+    database.commit(tx).await?;
+
+    let mut tx = database.begin(Identity::system()).await?;
+    assert!(tx.get(a_id).await?.is_some());
+    assert!(tx.get(b_id).await?.is_some());
+    let token = tx.into_token()?;
+
+    let unfiltered = database.subscribe(token.clone()).await?;
+    let filtered = database
+        .subscribe_with_table_filter(token, &BTreeSet::from([a_id.tablet_id]))
+        .await?;
+
+    // A write to the ignored table only invalidates the unfiltered subscription.
+    let mut tx = database.begin(Identity::system()).await?;
+    UserFacingModel::new_root_for_test(&mut tx)
+        .delete(b_id.into())
+        .await?;
+    let b_ts = database.commit(tx).await?;
+    assert_eq!(unfiltered.wait_for_invalidation().await, Some(b_ts));
+    assert_eq!(filtered.invalid_ts(), None);
+
+    // A write to the filtered table still invalidates it.
+    let mut tx = database.begin(Identity::system()).await?;
+    UserFacingModel::new_root_for_test(&mut tx)
+        .delete(a_id.into())
+        .await?;
+    let a_ts = database.commit(tx).await?;
+    assert_eq!(filtered.wait_for_invalidation().await, Some(a_ts));
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_subscription_index_ranges(rt: TestRuntime) -> anyhow::Result<()> {
+    let database = new_test_database(rt).await;
+    let mut tx = database.begin(Identity::system()).await?;
+    let a_id = TestFacingModel::new(&mut tx)
+        .insert(&"a".parse()?, ConvexObject::empty())
+        .await?;
+    let b_id = TestFacingModel::new(&mut tx)
+        .insert(&"b".parse()?, ConvexObject::empty())
+        .await?;
+    database.commit(tx).await?;
+
+    let mut tx = database.begin(Identity::system()).await?;
+    assert!(tx.get(a_id).await?.is_some());
+    let query = Query::full_table_scan("b".parse()?, Order::Asc);
+    let mut compiled_query = ResolvedQuery::new(&mut tx, TableNamespace::test_user(), query)?;
+    while compiled_query.next(&mut tx, None).await?.is_some() {}
+    let subscription = database.subscribe(tx.into_token()?).await?;
+
+    // Getting `a` by id reads a single point in its `by_id` index, and the
+    // full table scan of `b` reads all of its `by_creation_time` index.
+    let ranges = subscription.index_ranges();
+    let a_ranges: Vec<_> = ranges
+        .iter()
+        .filter(|(index, _)| *index.table() == a_id.tablet_id)
+        .collect();
+    assert_eq!(
+        a_ranges,
+        vec![(
+            &TabletIndexName::by_id(a_id.tablet_id),
+            &vec![Interval::prefix(
+                IndexKey::new(vec![], a_id.into()).to_bytes().into()
+            )]
+        )]
+    );
+    let b_ranges: Vec<_> = ranges
+        .iter()
+        .filter(|(index, _)| *index.table() == b_id.tablet_id)
+        .collect();
+    assert_eq!(
+        b_ranges,
+        vec![(
+            &TabletIndexName::by_creation_time(b_id.tablet_id),
+            &vec![Interval::all()]
+        )]
+    );
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_index_read_stats_wide_documents(rt: TestRuntime) -> anyhow::Result<()> {
+    let database = new_test_database(rt).await;
+    let namespace = TableNamespace::test_user();
+    let table_name: TableName = "wide".parse()?;
+    let mut tx = database.begin(Identity::system()).await?;
+    for i in 0..3i64 {
+        TestFacingModel::new(&mut tx)
+            .insert(
+                &table_name,
+                assert_obj!("i" => i, "padding" => "x".repeat(10_000)),
+            )
+            .await?;
+    }
+    database.commit(tx).await?;
+
+    let mut tx = database.begin(Identity::system()).await?;
+    let query = Query::full_table_scan(table_name.clone(), Order::Asc);
+    let mut compiled_query = ResolvedQuery::new(&mut tx, namespace, query)?;
+    let mut document_bytes = 0;
+    while let Some(document) = compiled_query.next(&mut tx, None).await? {
+        document_bytes += document.size() as u64;
+    }
+
+    // The scan only touches the table's `by_creation_time` index, and while it
+    // only reads a handful of rows, they're expensive.
+    let stats: Vec<_> = tx.index_read_stats().values().copied().collect();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].rows_read, 3);
+    assert_eq!(stats[0].document_bytes_read, document_bytes);
+    assert!(stats[0].document_bytes_read > 30_000);
+    assert!(stats[0].index_bytes_read > 0);
+    assert!(stats[0].index_bytes_read < stats[0].document_bytes_read / 100);
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_derived_field_mismatch_rejected(rt: TestRuntime) -> anyhow::Result<()> {
+    let database = new_test_database(rt).await;
+    let table_name: TableName = "users".parse()?;
+    database.derived_fields().register(
+        TableNamespace::test_user(),
+        table_name.clone(),
+        "fullName".parse()?,
+        Arc::new(|object: &ConvexObject| {
+            let (Some(ConvexValue::String(first)), Some(ConvexValue::String(last))) =
+                (object.get("first"), object.get("last"))
+            else {
+                return Ok(None);
+            };
+            Ok(Some(ConvexValue::try_from(format!(
+                "{} {}",
+                &first[..],
+                &last[..]
+            ))?))
+        }),
+    );
+
+    let mut tx = database.begin(Identity::system()).await?;
+    TestFacingModel::new(&mut tx)
+        .insert(
+            &table_name,
+            assert_obj!("first" => "Ada", "last" => "Lovelace", "fullName" => "Ada Lovelace"),
+        )
+        .await?;
+    database.commit(tx).await?;
+
+    let mut tx = database.begin(Identity::system()).await?;
+    TestFacingModel::new(&mut tx)
+        .insert(
+            &table_name,
+            assert_obj!("first" => "Alan", "last" => "Turing", "fullName" => "Ada Lovelace"),
+        )
+        .await?;
+    let err = database.commit(tx).await.unwrap_err();
+    assert!(err.is_bad_request());
+    assert_eq!(err.short_msg(), "DerivedFieldMismatch");
+
+    // Other tables aren't affected.
+    let mut tx = database.begin(Identity::system()).await?;
+    TestFacingModel::new(&mut tx)
+        .insert(&"other".parse()?, assert_obj!("fullName" => "anything"))
+        .await?;
+    database.commit(tx).await?;
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_patch_rewrites_only_affected_index_keys(rt: TestRuntime) -> anyhow::Result<()> {
+    let DbFixtures { db, tp, .. } = DbFixtures::new(&rt).await?;
+    let table_name: TableName = "messages".parse()?;
+    let namespace = TableNamespace::test_user();
+    let by_author = IndexName::new(table_name.clone(), IndexDescriptor::new("by_author")?)?;
+    let by_channel = IndexName::new(table_name.clone(), IndexDescriptor::new("by_channel")?)?;
+
+    let mut tx = db.begin(Identity::system()).await?;
+    let begin_ts = tx.begin_timestamp();
+    for (index_name, field) in [(&by_author, "author"), (&by_channel, "channel")] {
+        IndexModel::new(&mut tx)
+            .add_application_index(
+                namespace,
+                IndexMetadata::new_backfilling(
+                    *begin_ts,
+                    index_name.clone(),
+                    vec![field.parse()?].try_into()?,
+                ),
+            )
+            .await?;
+    }
+    db.commit(tx).await?;
+    IndexWorker::new_terminating(
+        rt.clone(),
+        tp.clone(),
+        Arc::new(NoopRetentionValidator),
+        db.clone(),
+    )
+    .await?;
+    let mut tx = db.begin_system().await?;
+    for index_name in [&by_author, &by_channel] {
+        IndexModel::new(&mut tx)
+            .enable_index_for_testing(namespace, index_name)
+            .await?;
+    }
+    db.commit(tx).await?;
+
+    let mut tx = db.begin(Identity::system()).await?;
+    let id = TestFacingModel::new(&mut tx)
+        .insert(
+            &table_name,
+            assert_obj!("author" => "sarah", "channel" => "general", "body" => "hi"),
+        )
+        .await?;
+    db.commit(tx).await?;
+
+    // Patch a field that's only in `by_author`.
+    let mut tx = db.begin(Identity::system()).await?;
+    UserFacingModel::new_root_for_test(&mut tx)
+        .patch(
+            id.into(),
+            crate::patch_value!("author" => Some("lee".try_into()?))?,
+        )
+        .await?;
+    let index_registry = tx.index.index_registry().clone();
+    let patch_ts = db.commit(tx).await?;
+
+    let index_id = |index_name: &IndexName| {
+        let tablet_index_name =
+            TabletIndexName::new(id.tablet_id, index_name.descriptor().clone())?;
+        anyhow::Ok(
+            index_registry
+                .get_enabled(&tablet_index_name)
+                .context("Missing index")?
+                .id(),
+        )
+    };
+    let by_author_id = index_id(&by_author)?;
+    let by_channel_id = index_id(&by_channel)?;
+    let entries_at_patch: Vec<_> = tp
+        .load_index_chunk(None, 1000)
+        .await?
+        .into_iter()
+        .filter(|entry| entry.ts == patch_ts)
+        .collect();
+    let entries_for = |index_id| {
+        entries_at_patch
+            .iter()
+            .filter(|entry| entry.index_id == index_id)
+            .map(|entry| entry.deleted)
+            .collect::<Vec<_>>()
+    };
+
+    // The changed key in `by_author` is tombstoned and replaced, while
+    // `by_channel` keeps its key and is only pointed at the new revision.
+    let mut by_author_entries = entries_for(by_author_id);
+    by_author_entries.sort();
+    assert_eq!(by_author_entries, vec![false, true]);
+    assert_eq!(entries_for(by_channel_id), vec![false]);
+    Ok(())
+}
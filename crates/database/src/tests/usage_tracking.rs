@@ -446,6 +446,7 @@ async fn test_usage_tracking_insert_with_index(rt: TestRuntime) -> anyhow::Resul
         index_name,
         range: vec![IndexRangeExpression::Eq("key".parse()?, maybe_val!(1))],
         order: Order::Asc,
+        collation: None,
     });
     let mut query_stream = ResolvedQuery::new(&mut tx, namespace, index_query)?;
     while query_stream.next(&mut tx, None).await?.is_some() {}
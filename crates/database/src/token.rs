@@ -1,16 +1,22 @@
 //! Externalizable tokens that record the currently-observed state within a
 //! transaction.
 
-use std::sync::Arc;
+use std::{
+    collections::BTreeSet,
+    sync::Arc,
+};
 
 #[cfg(any(test, feature = "testing"))]
 use common::types::TabletIndexName;
 use common::types::Timestamp;
 #[cfg(any(test, feature = "testing"))]
 use search::query::TextQueryTerm;
-use value::heap_size::HeapSize;
 #[cfg(any(test, feature = "testing"))]
 use value::FieldPath;
+use value::{
+    heap_size::HeapSize,
+    TabletId,
+};
 
 use crate::reads::ReadSet;
 
@@ -96,6 +102,12 @@ impl Token {
         self.read_set.clone()
     }
 
+    /// Returns a token at the same timestamp that only depends on reads from
+    /// the given tables, so writes to any other table won't invalidate it.
+    pub fn filter_tables(&self, tablet_ids: &BTreeSet<TabletId>) -> Self {
+        Self::new(Arc::new(self.read_set.filter_tables(tablet_ids)), self.ts)
+    }
+
     /// Advance the token's timestamp to a new timestamp.
     pub fn advance_ts(&mut self, ts: Timestamp) {
         assert!(self.ts < ts);
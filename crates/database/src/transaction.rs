@@ -32,6 +32,8 @@ use common::{
     document::{
         CreationTime,
         DocumentUpdateWithPrevTs,
+        IndexKeyBuffer,
+        PackedDocument,
         ResolvedDocument,
     },
     identity::InertIdentity,
@@ -58,6 +60,7 @@ use common::{
         GenericIndexName,
         IndexId,
         IndexName,
+        IndexReadStats,
         PersistenceVersion,
         RepeatableTimestamp,
         StableIndexName,
@@ -119,7 +122,10 @@ use crate::{
         IndexRangeResponse,
         TableFilter,
     },
-    reads::TransactionReadSet,
+    reads::{
+        ReadSetSize,
+        TransactionReadSet,
+    },
     schema_registry::SchemaRegistry,
     snapshot_manager::{
         Snapshot,
@@ -131,6 +137,7 @@ use crate::{
     transaction_index::TransactionIndex,
     write_limits::BiggestDocumentWrites,
     writes::{
+        CounterIncrements,
         NestedWriteToken,
         NestedWrites,
         TransactionWriteSize,
@@ -143,6 +150,7 @@ use crate::{
     SystemMetadataModel,
     TableModel,
     TableRegistry,
+    COUNTERS_TABLE,
     SCHEMAS_TABLE,
 };
 
@@ -174,6 +182,7 @@ pub struct Transaction<RT: Runtime> {
     pub(crate) table_count_deltas: BTreeMap<TabletId, i64>,
 
     pub(crate) stats: BTreeMap<TabletId, TableStats>,
+    pub(crate) index_read_stats: BTreeMap<TabletIndexName, IndexReadStats>,
 
     pub(crate) retention_validator: Arc<dyn RetentionValidator>,
 
@@ -200,6 +209,40 @@ pub trait TableCountSnapshot: Send + Sync + 'static {
     async fn count(&self, table: TabletId) -> anyhow::Result<Option<u64>>;
 }
 
+/// The reads and writes of a transaction, for working out which other
+/// transactions it conflicts with.
+#[derive(Clone, Debug)]
+pub struct ReadWriteSets {
+    pub reads: ReadSet,
+    /// The previous and new version of each document the transaction wrote.
+    pub writes: BTreeMap<ResolvedDocumentId, (Option<ResolvedDocument>, Option<ResolvedDocument>)>,
+    persistence_version: PersistenceVersion,
+}
+
+impl ReadWriteSets {
+    /// Returns the index and document of the first write in `other` that
+    /// overlaps a read in `self`, i.e. the write that would make `self` fail
+    /// with an OCC error if `other` committed first.
+    pub fn overlapping_read(
+        &self,
+        other: &ReadWriteSets,
+    ) -> Option<(TabletIndexName, ResolvedDocumentId)> {
+        let mut buffer = IndexKeyBuffer::new();
+        other
+            .writes
+            .values()
+            .flat_map(|(old_document, new_document)| old_document.iter().chain(new_document))
+            .find_map(|document| {
+                let read = self.reads.overlaps_document(
+                    &PackedDocument::pack(document),
+                    self.persistence_version,
+                    &mut buffer,
+                )?;
+                Some((read.index, read.id))
+            })
+    }
+}
+
 pub struct SubtransactionToken {
     writes: NestedWriteToken,
     index: NestedWriteToken,
@@ -237,6 +280,7 @@ impl<RT: Runtime> Transaction<RT> {
             count_snapshot: count,
             table_count_deltas: BTreeMap::new(),
             stats: BTreeMap::new(),
+            index_read_stats: BTreeMap::new(),
             runtime,
             retention_validator,
             usage_tracker,
@@ -337,6 +381,17 @@ impl<RT: Runtime> Transaction<RT> {
         self.index.base_snapshot().timestamp()
     }
 
+    pub fn read_set_size(&self) -> ReadSetSize {
+        self.reads.size()
+    }
+
+    /// Record this transaction's subsequent reads at `granularity` rather than
+    /// the one configured by the `CONFLICT_GRANULARITY` knob.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn set_conflict_granularity(&mut self, granularity: common::types::ConflictGranularity) {
+        self.reads.set_granularity(granularity);
+    }
+
     pub fn is_readonly(&self) -> bool {
         self.writes.is_empty()
     }
@@ -385,6 +440,22 @@ impl<RT: Runtime> Transaction<RT> {
         &self.writes
     }
 
+    pub fn read_write_sets(&self) -> ReadWriteSets {
+        let writes = self
+            .writes
+            .coalesced_writes()
+            .map(|(id, update)| {
+                let old_document = update.old_document.as_ref().map(|(d, _)| d.clone());
+                (*id, (old_document, update.new_document.clone()))
+            })
+            .collect();
+        ReadWriteSets {
+            reads: self.reads.read_set().clone(),
+            writes,
+            persistence_version: self.persistence_version(),
+        }
+    }
+
     pub fn into_reads_and_writes(self) -> (TransactionReadSet, NestedWrites<Writes>) {
         (self.reads, self.writes)
     }
@@ -441,6 +512,7 @@ impl<RT: Runtime> Transaction<RT> {
         user_tx_size: crate::reads::TransactionReadSize,
         system_tx_size: crate::reads::TransactionReadSize,
         updates: OrdMap<ResolvedDocumentId, DocumentUpdateWithPrevTs>,
+        counter_increments: CounterIncrements,
         rows_read_by_tablet: BTreeMap<TabletId, u64>,
     ) -> anyhow::Result<()> {
         anyhow::ensure!(
@@ -452,6 +524,7 @@ impl<RT: Runtime> Transaction<RT> {
             .merge(reads, num_intervals, user_tx_size, system_tx_size);
 
         self.merge_writes(updates)?;
+        self.merge_counter_increments(counter_increments)?;
 
         for (tablet_id, rows_read) in rows_read_by_tablet {
             self.stats.entry(tablet_id).or_default().rows_read += rows_read;
@@ -518,6 +591,66 @@ impl<RT: Runtime> Transaction<RT> {
         Ok(())
     }
 
+    /// Like `merge_writes`, but for counter increments. The passed in
+    /// `counter_increments` must include any increments already made in this
+    /// transaction.
+    pub fn merge_counter_increments(
+        &mut self,
+        counter_increments: CounterIncrements,
+    ) -> anyhow::Result<()> {
+        self.writes.merge_counter_increments(counter_increments)
+    }
+
+    /// Add `delta` to the counter `name` in `namespace` without reading it.
+    /// The committer applies the increment to the counter's latest value, so
+    /// concurrent increments don't conflict.
+    pub fn increment_counter(
+        &mut self,
+        namespace: TableNamespace,
+        name: &str,
+        delta: i64,
+    ) -> anyhow::Result<()> {
+        let table_id = self
+            .table_mapping()
+            .namespace(namespace)
+            .id(&COUNTERS_TABLE)?;
+        let bootstrap_tables = self.bootstrap_tables();
+        let id_generator = &mut self.id_generator;
+        let next_creation_time = &mut self.next_creation_time;
+        self.writes.increment_counter(
+            bootstrap_tables,
+            &mut self.reads,
+            table_id.tablet_id,
+            name,
+            delta,
+            || {
+                let new_id = id_generator.generate_resolved(table_id);
+                let creation_time = next_creation_time.increment()?;
+                Ok((new_id, creation_time))
+            },
+        )
+    }
+
+    /// The sum of increments to the counter `name` in `namespace` made so far
+    /// in this transaction.
+    pub fn pending_counter_delta(
+        &mut self,
+        namespace: TableNamespace,
+        name: &str,
+    ) -> anyhow::Result<i64> {
+        let tablet_id = self
+            .table_mapping()
+            .namespace(namespace)
+            .id(&COUNTERS_TABLE)?
+            .tablet_id;
+        Ok(self
+            .writes
+            .counter_increments()
+            .get(&(tablet_id, name.to_string()))
+            .map(|increment| increment.delta)
+            .unwrap_or(0))
+    }
+
     /// Return the document with the given `id` or None if document doesn't
     /// exist.
     pub async fn get(
@@ -550,6 +683,19 @@ impl<RT: Runtime> Transaction<RT> {
         id: ResolvedDocumentId,
         value: PatchValue,
     ) -> anyhow::Result<ResolvedDocument> {
+        let (_, new_document) = self.patch_inner_with_previous(id, value, false).await?;
+        Ok(new_document)
+    }
+
+    /// Like `patch_inner`, but if `return_previous` is set, also returns the
+    /// document as it was before the patch.
+    #[convex_macro::instrument_future]
+    pub(crate) async fn patch_inner_with_previous(
+        &mut self,
+        id: ResolvedDocumentId,
+        value: PatchValue,
+        return_previous: bool,
+    ) -> anyhow::Result<(Option<ResolvedDocument>, ResolvedDocument)> {
         task::consume_budget().await;
 
         let table_name = self.table_mapping().tablet_name(id.tablet_id)?;
@@ -564,17 +710,16 @@ impl<RT: Runtime> Transaction<RT> {
                 ))?;
 
         let new_document = {
-            let patched_value = value
-                .clone()
-                .apply(old_document.value().clone().into_value())?;
+            let patched_value = value.apply(old_document.value().clone().into_value())?;
             old_document.replace_value(patched_value)?
         };
         SchemaModel::new(self, namespace)
             .enforce(&new_document)
             .await?;
 
+        let previous = return_previous.then(|| old_document.clone());
         self.apply_validated_write(id, Some((old_document, old_ts)), Some(new_document.clone()))?;
-        Ok(new_document)
+        Ok((previous, new_document))
     }
 
     pub fn is_system(&mut self, namespace: TableNamespace, table_number: TableNumber) -> bool {
@@ -589,6 +734,19 @@ impl<RT: Runtime> Transaction<RT> {
         id: ResolvedDocumentId,
         value: ConvexObject,
     ) -> anyhow::Result<ResolvedDocument> {
+        let (_, new_document) = self.replace_inner_with_previous(id, value, false).await?;
+        Ok(new_document)
+    }
+
+    /// Like `replace_inner`, but if `return_previous` is set, also returns the
+    /// document as it was before the replace.
+    #[convex_macro::instrument_future]
+    pub(crate) async fn replace_inner_with_previous(
+        &mut self,
+        id: ResolvedDocumentId,
+        value: ConvexObject,
+        return_previous: bool,
+    ) -> anyhow::Result<(Option<ResolvedDocument>, ResolvedDocument)> {
         task::consume_budget().await;
 
         let table_name = self.table_mapping().tablet_name(id.tablet_id)?;
@@ -608,12 +766,13 @@ impl<RT: Runtime> Transaction<RT> {
             .enforce(&new_document)
             .await?;
 
+        let previous = return_previous.then(|| old_document.clone());
         self.apply_validated_write(
             new_document.id(),
             Some((old_document, old_ts)),
             Some(new_document.clone()),
         )?;
-        Ok(new_document)
+        Ok((previous, new_document))
     }
 
     #[convex_macro::instrument_future]
@@ -710,6 +869,40 @@ impl<RT: Runtime> Transaction<RT> {
         &self.stats
     }
 
+    /// Rows and bytes read through each index so far in this transaction.
+    pub fn index_read_stats(&self) -> &BTreeMap<TabletIndexName, IndexReadStats> {
+        &self.index_read_stats
+    }
+
+    /// Like `take_stats`, but for the per-index read stats, keyed by
+    /// developer-facing index name.
+    pub fn take_index_read_stats(&mut self) -> BTreeMap<IndexName, IndexReadStats> {
+        let stats = mem::take(&mut self.index_read_stats);
+        stats
+            .into_iter()
+            .filter_map(|(index_name, stats)| {
+                // As in `take_stats`, skip tablets from rolled back
+                // subtransactions.
+                let index_name = index_name
+                    .map_table(&self.table_mapping().tablet_to_name())
+                    .ok()?;
+                Some((index_name, stats))
+            })
+            .collect()
+    }
+
+    pub(crate) fn record_index_read(
+        &mut self,
+        index_name: &TabletIndexName,
+        index_bytes: usize,
+        document_bytes: usize,
+    ) {
+        let stats = self.index_read_stats.entry(index_name.clone()).or_default();
+        stats.rows_read += 1;
+        stats.index_bytes_read += index_bytes as u64;
+        stats.document_bytes_read += document_bytes as u64;
+    }
+
     fn take_table_mapping_dep(&mut self) {
         let tables_by_id = TabletIndexName::by_id(
             self.metadata
@@ -929,7 +1122,12 @@ impl<RT: Runtime> Transaction<RT> {
             ))?;
         }
         let result = match range_results.into_iter().next() {
-            Some((_, doc, timestamp)) => {
+            Some((index_key, doc, timestamp)) => {
+                self.record_index_read(
+                    &TabletIndexName::by_id(id.tablet_id),
+                    index_key.len(),
+                    doc.size(),
+                );
                 let is_virtual_table = self.virtual_system_mapping().is_virtual_table(&table_name);
                 let component_path = self
                     .component_path_for_document_id(doc.id())?
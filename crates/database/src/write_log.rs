@@ -326,6 +326,18 @@ impl WriteLog {
         })
     }
 
+    fn all_conflicts(
+        &self,
+        reads: &ReadSet,
+        reads_ts: Timestamp,
+        ts: Timestamp,
+    ) -> anyhow::Result<Vec<ConflictingReadWithWriteSource>> {
+        block_in_place(|| {
+            let log_range = self.iter(reads_ts.succ()?, ts)?;
+            Ok(reads.all_writes_overlapping_index_keys(log_range))
+        })
+    }
+
     /// Returns Err(write_ts) if the token could not be refreshed, where
     /// write_ts is the timestamp of a conflicting write (if known)
     fn refresh_token(
@@ -484,6 +496,17 @@ impl LogWriter {
         let snapshot = { self.inner.lock().log.clone() };
         block_in_place(|| snapshot.is_stale(reads, reads_ts, ts))
     }
+
+    /// Like `is_stale`, but returns every write that conflicts with `reads`.
+    pub fn all_conflicts(
+        &self,
+        reads: &ReadSet,
+        reads_ts: Timestamp,
+        ts: Timestamp,
+    ) -> anyhow::Result<Vec<ConflictingReadWithWriteSource>> {
+        let snapshot = { self.inner.lock().log.clone() };
+        block_in_place(|| snapshot.all_conflicts(reads, reads_ts, ts))
+    }
 }
 
 /// Pending writes are used by the committer to detect conflicts between a new
@@ -564,6 +587,17 @@ impl PendingWrites {
         Ok(reads.writes_overlap_docs(self.iter(reads_ts.succ()?, ts), self.persistence_version))
     }
 
+    /// Like `is_stale`, but returns every write that conflicts with `reads`.
+    pub fn all_conflicts(
+        &self,
+        reads: &ReadSet,
+        reads_ts: Timestamp,
+        ts: Timestamp,
+    ) -> anyhow::Result<Vec<ConflictingReadWithWriteSource>> {
+        Ok(reads
+            .all_writes_overlapping_docs(self.iter(reads_ts.succ()?, ts), self.persistence_version))
+    }
+
     pub fn pop_first(
         &mut self,
         mut handle: PendingWriteHandle,
@@ -1,6 +1,9 @@
 //! Write set tracking for an active transaction
 use std::{
-    collections::BTreeSet,
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
     ops::{
         Deref,
         DerefMut,
@@ -16,6 +19,7 @@ use common::{
         TABLE_ID_FIELD_PATH,
     },
     document::{
+        CreationTime,
         DocumentUpdateWithPrevTs,
         ResolvedDocument,
     },
@@ -48,7 +52,10 @@ use value::{
 };
 
 use crate::{
-    bootstrap_model::defaults::BootstrapTableIds,
+    bootstrap_model::{
+        counters::counter_overflow_error,
+        defaults::BootstrapTableIds,
+    },
     reads::TransactionReadSet,
     schema_registry::SchemaRegistry,
     ComponentRegistry,
@@ -165,10 +172,27 @@ impl<W: PendingWrites> NestedWrites<W> {
     }
 }
 
+/// An increment to a counter in `_counters` that the committer merges into
+/// the counter's latest value at commit time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CounterIncrement {
+    pub name: String,
+    pub delta: i64,
+    /// Id and creation time to use if the counter doesn't exist yet at commit
+    /// time.
+    pub new_id: ResolvedDocumentId,
+    pub creation_time: CreationTime,
+}
+
+/// Pending counter increments, keyed by the `_counters` tablet and counter
+/// name.
+pub type CounterIncrements = BTreeMap<(TabletId, String), CounterIncrement>;
+
 /// The write set for a transaction, maintained by `TransactionState`
 #[derive(Debug, Clone, PartialEq)]
 pub struct Writes {
     updates: OrdMap<ResolvedDocumentId, DocumentUpdateWithPrevTs>,
+    counter_increments: CounterIncrements,
 
     // Fields below can be recomputed from `updates`.
 
@@ -187,11 +211,40 @@ pub struct TransactionWriteSize {
     pub size: usize,
 }
 
+impl TransactionWriteSize {
+    /// Fail with a developer error if these writes to user tables are over
+    /// the transaction limits.
+    pub(crate) fn check_user_limits(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.num_writes <= *TRANSACTION_MAX_NUM_USER_WRITES,
+            ErrorMetadata::pagination_limit(
+                "TooManyWrites",
+                format!(
+                    "Too many writes in a single function execution (limit: {})",
+                    *TRANSACTION_MAX_NUM_USER_WRITES,
+                )
+            ),
+        );
+        anyhow::ensure!(
+            self.size <= *TRANSACTION_MAX_USER_WRITE_SIZE_BYTES,
+            ErrorMetadata::pagination_limit(
+                "TooManyBytesWritten",
+                format!(
+                    "Too many bytes written in a single function execution (limit: {} bytes)",
+                    *TRANSACTION_MAX_USER_WRITE_SIZE_BYTES,
+                )
+            ),
+        );
+        Ok(())
+    }
+}
+
 impl Writes {
     /// Create an empty write set.
     pub fn new() -> Self {
         Self {
             updates: OrdMap::new(),
+            counter_increments: BTreeMap::new(),
             user_tx_size: TransactionWriteSize::default(),
             system_tx_size: TransactionWriteSize::default(),
         }
@@ -199,7 +252,73 @@ impl Writes {
 
     /// Are there any writes in the active transaction?
     pub fn is_empty(&self) -> bool {
-        self.updates.is_empty()
+        self.updates.is_empty() && self.counter_increments.is_empty()
+    }
+
+    /// Record an increment of the counter `name` in the `_counters` tablet
+    /// `tablet_id`. Increments don't read the counter, so they only depend on
+    /// the table and its indexes staying the same. `new_id` is only called
+    /// for the first increment of each counter.
+    pub fn increment_counter(
+        &mut self,
+        bootstrap_tables: BootstrapTableIds,
+        reads: &mut TransactionReadSet,
+        tablet_id: TabletId,
+        name: &str,
+        delta: i64,
+        new_id: impl FnOnce() -> anyhow::Result<(ResolvedDocumentId, CreationTime)>,
+    ) -> anyhow::Result<()> {
+        Self::record_reads_for_write(bootstrap_tables, reads, tablet_id)?;
+        match self
+            .counter_increments
+            .get_mut(&(tablet_id, name.to_string()))
+        {
+            Some(increment) => {
+                increment.delta = increment
+                    .delta
+                    .checked_add(delta)
+                    .ok_or_else(|| counter_overflow_error(name))?;
+            },
+            None => {
+                let (new_id, creation_time) = new_id()?;
+                self.counter_increments.insert(
+                    (tablet_id, name.to_string()),
+                    CounterIncrement {
+                        name: name.to_string(),
+                        delta,
+                        new_id,
+                        creation_time,
+                    },
+                );
+            },
+        }
+        Ok(())
+    }
+
+    pub(crate) fn updates_document(&self, id: &ResolvedDocumentId) -> bool {
+        self.updates.contains_key(id)
+    }
+
+    pub fn counter_increments(&self) -> &CounterIncrements {
+        &self.counter_increments
+    }
+
+    /// Replace this write set's counter increments with `counter_increments`,
+    /// which must include every counter this write set has already
+    /// incremented.
+    pub fn merge_counter_increments(
+        &mut self,
+        counter_increments: CounterIncrements,
+    ) -> anyhow::Result<()> {
+        for key in self.counter_increments.keys() {
+            anyhow::ensure!(
+                counter_increments.contains_key(key),
+                "Existing counter increment for {:?} was not preserved",
+                key.1
+            );
+        }
+        self.counter_increments = counter_increments;
+        Ok(())
     }
 
     pub fn update(
@@ -250,26 +369,7 @@ impl Writes {
             tx_size
         } else {
             let tx_size = &self.user_tx_size;
-            anyhow::ensure!(
-                tx_size.num_writes <= *TRANSACTION_MAX_NUM_USER_WRITES,
-                ErrorMetadata::pagination_limit(
-                    "TooManyWrites",
-                    format!(
-                        "Too many writes in a single function execution (limit: {})",
-                        *TRANSACTION_MAX_NUM_USER_WRITES,
-                    )
-                ),
-            );
-            anyhow::ensure!(
-                tx_size.size <= *TRANSACTION_MAX_USER_WRITE_SIZE_BYTES,
-                ErrorMetadata::pagination_limit(
-                    "TooManyBytesWritten",
-                    format!(
-                        "Too many bytes written in a single function execution (limit: {} bytes)",
-                        *TRANSACTION_MAX_USER_WRITE_SIZE_BYTES,
-                    )
-                ),
-            );
+            tx_size.check_user_limits()?;
             tx_size
         };
 
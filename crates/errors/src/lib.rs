@@ -420,6 +420,22 @@ impl ErrorMetadata {
         matches!(self.code, ErrorCode::OCC { .. })
     }
 
+    pub fn occ_info(&self) -> Option<OccInfo> {
+        match &self.code {
+            ErrorCode::OCC {
+                table_name,
+                document_id,
+                write_source,
+                is_system: _,
+            } => Some(OccInfo {
+                table_name: table_name.clone(),
+                document_id: document_id.clone(),
+                write_source: write_source.clone(),
+            }),
+            _ => None,
+        }
+    }
+
     pub fn is_pagination_limit(&self) -> bool {
         self.code == ErrorCode::PaginationLimit
     }
@@ -685,9 +701,22 @@ impl ErrorCode {
     }
 }
 
+/// What an OCC error conflicted with, as far as the commit that detected it
+/// knows.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OccInfo {
+    /// The table the conflicting write was to. Unset for OCCs on system
+    /// tables.
+    pub table_name: Option<String>,
+    /// The document whose write overlapped this transaction's reads.
+    pub document_id: Option<String>,
+    /// The function that made the conflicting write, if known.
+    pub write_source: Option<String>,
+}
+
 pub trait ErrorMetadataAnyhowExt {
     fn is_occ(&self) -> bool;
-    fn occ_info(&self) -> Option<(Option<String>, Option<String>, Option<String>)>;
+    fn occ_info(&self) -> Option<OccInfo>;
     fn is_pagination_limit(&self) -> bool;
     fn is_unauthenticated(&self) -> bool;
     fn is_auth_update_failed(&self) -> bool;
@@ -725,21 +754,9 @@ impl ErrorMetadataAnyhowExt for anyhow::Error {
         false
     }
 
-    fn occ_info(&self) -> Option<(Option<String>, Option<String>, Option<String>)> {
+    fn occ_info(&self) -> Option<OccInfo> {
         if let Some(e) = self.downcast_ref::<ErrorMetadata>() {
-            return match &e.code {
-                ErrorCode::OCC {
-                    table_name,
-                    document_id,
-                    write_source,
-                    is_system: _,
-                } => Some((
-                    table_name.clone(),
-                    document_id.clone(),
-                    write_source.clone(),
-                )),
-                _ => None,
-            };
+            return e.occ_info();
         }
         None
     }
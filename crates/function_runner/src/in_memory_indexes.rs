@@ -657,6 +657,7 @@ impl<RT: Runtime> InMemoryIndexCache<RT> {
             usage_tracker,
         )?;
         tx.merge_writes(existing_writes.updates)?;
+        tx.merge_counter_increments(existing_writes.counter_increments)?;
         Ok(tx)
     }
 }
@@ -47,7 +47,10 @@ use futures::{
     FutureExt,
     StreamExt,
 };
-use isolate::ActionCallbacks;
+use isolate::{
+    ActionCallbacks,
+    ConcurrencyPermitStats,
+};
 use keybroker::{
     FunctionRunnerKeyBroker,
     Identity,
@@ -381,4 +384,8 @@ impl<RT: Runtime> FunctionRunner<RT> for InProcessFunctionRunner<RT> {
     fn set_action_callbacks(&self, action_callbacks: Arc<dyn ActionCallbacks>) {
         *self.action_callbacks.write() = Some(Arc::downgrade(&action_callbacks));
     }
+
+    fn concurrency_permit_stats(&self) -> Option<ConcurrencyPermitStats> {
+        Some(self.server.concurrency_permit_stats())
+    }
 }
@@ -34,6 +34,7 @@ use common::{
     },
 };
 use database::{
+    CounterIncrements,
     ReadSet,
     Transaction,
     TransactionReadSet,
@@ -41,7 +42,10 @@ use database::{
     Writes,
 };
 use imbl::OrdMap;
-use isolate::ActionCallbacks;
+use isolate::{
+    ActionCallbacks,
+    ConcurrencyPermitStats,
+};
 use keybroker::Identity;
 pub use metrics::record_module_sizes;
 use model::{
@@ -150,6 +154,12 @@ pub trait FunctionRunner<RT: Runtime>: Send + Sync + 'static {
     /// a reference cycle between ApplicationFunctionRunner and dyn
     /// FunctionRunner.
     fn set_action_callbacks(&self, action_callbacks: Arc<dyn ActionCallbacks>);
+
+    /// How many of the runner's isolate concurrency permits are held, if it
+    /// runs functions in this process.
+    fn concurrency_permit_stats(&self) -> Option<ConcurrencyPermitStats> {
+        None
+    }
 }
 
 /// Reads and writes from a UDF that executed in Funrun
@@ -217,6 +227,7 @@ impl From<TransactionReadSet> for FunctionReads {
 #[derive(Clone, Default)]
 pub struct FunctionWrites {
     pub updates: OrdMap<ResolvedDocumentId, DocumentUpdateWithPrevTs>,
+    pub counter_increments: CounterIncrements,
 }
 
 #[cfg(any(test, feature = "testing"))]
@@ -229,6 +240,7 @@ impl proptest::arbitrary::Arbitrary for FunctionWrites {
         proptest::collection::vec(proptest::prelude::any::<DocumentUpdateWithPrevTs>(), 0..4)
             .prop_map(|updates| Self {
                 updates: updates.into_iter().map(|u| (u.id, u)).collect(),
+                counter_increments: CounterIncrements::new(),
             })
             .boxed()
     }
@@ -236,8 +248,10 @@ impl proptest::arbitrary::Arbitrary for FunctionWrites {
 
 impl From<Writes> for FunctionWrites {
     fn from(writes: Writes) -> Self {
+        let counter_increments = writes.counter_increments().clone();
         Self {
             updates: writes.into_updates(),
+            counter_increments,
         }
     }
 }
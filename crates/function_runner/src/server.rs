@@ -55,6 +55,7 @@ use futures::FutureExt;
 use isolate::{
     client::EnvironmentData,
     ActionCallbacks,
+    ConcurrencyPermitStats,
     IsolateClient,
 };
 use keybroker::{
@@ -259,6 +260,10 @@ impl<RT: Runtime, S: StorageForInstance<RT>> FunctionRunnerCore<RT, S> {
         self.isolate_client.shutdown().await
     }
 
+    pub fn concurrency_permit_stats(&self) -> ConcurrencyPermitStats {
+        self.isolate_client.concurrency_permit_stats()
+    }
+
     // Runs a function given the information for the backend as well as arguments
     // to the function itself.
     // NOTE: The caller of this is responsible of checking retention by calling
@@ -213,6 +213,11 @@ impl IndexRegistry {
         )
     }
 
+    /// Compute the index entries to write when replacing `deletion` with
+    /// `insertion`. Only indexes whose key changed get a tombstone for the old
+    /// key, so a patch that leaves an index's fields alone doesn't churn it.
+    /// Every index still gets an entry for the new revision since persisted
+    /// index entries point at a specific document revision.
     pub fn index_updates<'a>(
         &'a self,
         deletion: Option<&'a ResolvedDocument>,
@@ -84,6 +84,10 @@ use deno_core::{
     v8,
     v8::V8,
 };
+use derive_more::{
+    Add,
+    AddAssign,
+};
 use errors::{
     ErrorMetadata,
     ErrorMetadataAnyhowExt,
@@ -153,7 +157,11 @@ use value::{
 use vector::PublicVectorSearchQueryResult;
 
 use crate::{
-    concurrency_limiter::ConcurrencyLimiter,
+    concurrency_limiter::{
+        ConcurrencyLimiter,
+        ConcurrencyPermitStats,
+    },
+    cpu_budget::CpuBudget,
     isolate::{
         Isolate,
         IsolateHeapStats,
@@ -188,6 +196,10 @@ pub struct IsolateConfig {
     // allows us to set an upper bound to it that we use for tests.
     max_user_timeout: Option<Duration>,
 
+    // Defaults to the `ISOLATE_OP_INTERVAL`, `ISOLATE_OPS_PER_YIELD` and
+    // `ISOLATE_CPU_BUDGET_OPS` knobs.
+    cpu_budget: CpuBudget,
+
     limiter: ConcurrencyLimiter,
 }
 
@@ -196,6 +208,7 @@ impl IsolateConfig {
         Self {
             name,
             max_user_timeout: None,
+            cpu_budget: CpuBudget::from_knobs(),
             limiter,
         }
     }
@@ -209,9 +222,16 @@ impl IsolateConfig {
         Self {
             name,
             max_user_timeout,
+            cpu_budget: CpuBudget::from_knobs(),
             limiter,
         }
     }
+
+    #[cfg(any(test, feature = "testing"))]
+    pub fn with_cpu_budget(mut self, cpu_budget: CpuBudget) -> Self {
+        self.cpu_budget = cpu_budget;
+        self
+    }
 }
 
 #[cfg(any(test, feature = "testing"))]
@@ -220,6 +240,7 @@ impl Default for IsolateConfig {
         Self {
             name: "test",
             max_user_timeout: None,
+            cpu_budget: CpuBudget::from_knobs(),
             limiter: ConcurrencyLimiter::unlimited(),
         }
     }
@@ -535,6 +556,7 @@ impl<RT: Runtime> Clone for IsolateClient<RT> {
             scheduler: self.scheduler.clone(),
             sender: self.sender.clone(),
             concurrency_logger: self.concurrency_logger.clone(),
+            concurrency_limiter: self.concurrency_limiter.clone(),
         }
     }
 }
@@ -618,6 +640,7 @@ pub struct IsolateClient<RT: Runtime> {
     scheduler: Arc<Mutex<Option<Box<dyn SpawnHandle>>>>,
     sender: CoDelQueueSender<RT, Request<RT>>,
     concurrency_logger: Arc<Mutex<Option<Box<dyn SpawnHandle>>>>,
+    concurrency_limiter: ConcurrencyLimiter,
 }
 
 impl<RT: Runtime> IsolateClient<RT> {
@@ -638,6 +661,7 @@ impl<RT: Runtime> IsolateClient<RT> {
         );
         let isolate_config =
             isolate_config.unwrap_or(IsolateConfig::new("funrun", concurrency_limit));
+        let concurrency_limiter = isolate_config.limiter.clone();
 
         initialize_v8();
         // NB: We don't call V8::Dispose or V8::ShutdownPlatform since we just assume a
@@ -668,9 +692,15 @@ impl<RT: Runtime> IsolateClient<RT> {
             scheduler: Arc::new(Mutex::new(Some(scheduler))),
             concurrency_logger: Arc::new(Mutex::new(Some(concurrency_logger))),
             handles,
+            concurrency_limiter,
         })
     }
 
+    /// How many concurrency permits the isolates are holding right now.
+    pub fn concurrency_permit_stats(&self) -> ConcurrencyPermitStats {
+        self.concurrency_limiter.permit_stats()
+    }
+
     pub fn aggregate_heap_stats(&self) -> IsolateHeapStats {
         let mut total = IsolateHeapStats::default();
         for handle in self.handles.lock().iter() {
@@ -679,6 +709,16 @@ impl<RT: Runtime> IsolateClient<RT> {
         total
     }
 
+    /// How many requests have run on a newly created isolate versus a reused
+    /// one, across all workers.
+    pub fn aggregate_start_stats(&self) -> IsolateStartStats {
+        let mut total = IsolateStartStats::default();
+        for handle in self.handles.lock().iter() {
+            total += handle.start_stats.get();
+        }
+        total
+    }
+
     #[fastrace::trace]
     pub async fn execute_udf(
         &self,
@@ -1256,14 +1296,18 @@ impl<RT: Runtime, W: IsolateWorker<RT>> SharedIsolateScheduler<RT, W> {
             let new_worker = self.worker.clone();
             let heap_stats = SharedIsolateHeapStats::new();
             let heap_stats_ = heap_stats.clone();
+            let start_stats = SharedIsolateStartStats::new();
+            let start_stats_ = start_stats.clone();
             let (work_sender, work_receiver) = mpsc::channel(1);
             let handle = self.rt.spawn_thread("isolate", move || {
-                new_worker.service_requests(work_receiver, heap_stats_)
+                new_worker.service_requests(work_receiver, heap_stats_, start_stats_)
             });
             self.worker_senders.push(work_sender);
-            self.handles
-                .lock()
-                .push(IsolateWorkerHandle { handle, heap_stats });
+            self.handles.lock().push(IsolateWorkerHandle {
+                handle,
+                heap_stats,
+                start_stats,
+            });
             tracing::info!(
                 "Created {} isolate worker {}",
                 self.worker.config().name,
@@ -1324,6 +1368,7 @@ impl<RT: Runtime, W: IsolateWorker<RT>> SharedIsolateScheduler<RT, W> {
 pub struct IsolateWorkerHandle {
     pub handle: Box<dyn SpawnHandle>,
     heap_stats: SharedIsolateHeapStats,
+    start_stats: SharedIsolateStartStats,
 }
 
 #[derive(Clone)]
@@ -1343,15 +1388,48 @@ impl SharedIsolateHeapStats {
     }
 }
 
+/// Counts of requests that paid the cost of starting on a newly created
+/// isolate (cold) versus ones that ran on an isolate reused from a previous
+/// request (warm).
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Add, AddAssign)]
+pub struct IsolateStartStats {
+    pub cold_starts: u64,
+    pub warm_starts: u64,
+}
+
+#[derive(Clone)]
+pub struct SharedIsolateStartStats(Arc<Mutex<IsolateStartStats>>);
+
+impl SharedIsolateStartStats {
+    pub(crate) fn new() -> Self {
+        Self(Arc::new(Mutex::new(IsolateStartStats::default())))
+    }
+
+    pub(crate) fn get(&self) -> IsolateStartStats {
+        *self.0.lock()
+    }
+
+    fn record(&self, reused: bool) {
+        let mut stats = self.0.lock();
+        if reused {
+            stats.warm_starts += 1;
+        } else {
+            stats.cold_starts += 1;
+        }
+    }
+}
+
 #[async_trait(?Send)]
 pub trait IsolateWorker<RT: Runtime>: Clone + Send + 'static {
     async fn service_requests<T>(
         self,
         reqs: mpsc::Receiver<(Request<RT>, oneshot::Sender<T>, T)>,
         heap_stats: SharedIsolateHeapStats,
+        start_stats: SharedIsolateStartStats,
     ) {
         let IsolateConfig {
             max_user_timeout,
+            cpu_budget,
             limiter,
             ..
         } = self.config();
@@ -1360,7 +1438,8 @@ pub trait IsolateWorker<RT: Runtime>: Clone + Send + 'static {
         'recreate_isolate: loop {
             let mut last_client_id: Option<String> = None;
             let mut last_request: Option<String> = None;
-            let mut isolate = Isolate::new(self.rt(), *max_user_timeout, limiter.clone());
+            let mut isolate = Isolate::new(self.rt(), *max_user_timeout, limiter.clone())
+                .with_cpu_budget(*cpu_budget);
             heap_stats.store(isolate.heap_stats());
             loop {
                 let v8_context = {
@@ -1420,6 +1499,10 @@ pub trait IsolateWorker<RT: Runtime>: Clone + Send + 'static {
                             req.parent_trace.clone(),
                         );
                         root.add_property(|| ("reused_isolate", reused.as_label()));
+                        // Record the start before handling the request so it's visible
+                        // by the time the caller gets a response.
+                        start_stats.record(reused);
+                        let request_timer = metrics::isolate_request_timer(reused);
                         // Require the layer below to opt into isolate reuse by setting `isolate_clean`.
                         let mut isolate_clean = false;
                         let debug_str = self
@@ -1432,6 +1515,7 @@ pub trait IsolateWorker<RT: Runtime>: Clone + Send + 'static {
                             )
                             .in_span(root)
                             .await;
+                        drop(request_timer);
                         if !isolate_clean || should_recreate_isolate(&mut isolate, &debug_str) {
                             continue 'recreate_isolate;
                         }
@@ -50,6 +50,14 @@ impl ConcurrencyLimiter {
         Self { tx, rx, tracker }
     }
 
+    pub fn permit_stats(&self) -> ConcurrencyPermitStats {
+        let held = self.tx.len();
+        ConcurrencyPermitStats {
+            held,
+            available: self.tx.capacity().map(|capacity| capacity - held),
+        }
+    }
+
     // TODO(presley): Replace this when we have isolate_v2.
     // If a client uses a thread for too long. We still want to log periodically.
     pub fn go_log<RT: Runtime>(
@@ -92,6 +100,15 @@ impl ConcurrencyLimiter {
     }
 }
 
+/// How many of a [`ConcurrencyLimiter`]'s permits are in use, for sizing the
+/// isolate pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConcurrencyPermitStats {
+    pub held: usize,
+    /// `None` if the limiter is unlimited.
+    pub available: Option<usize>,
+}
+
 #[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq)]
 struct PermitId(usize);
 
@@ -0,0 +1,170 @@
+use std::{
+    ffi::c_void,
+    sync::{
+        atomic::{
+            AtomicBool,
+            AtomicU64,
+            Ordering,
+        },
+        Arc,
+    },
+    time::Duration,
+};
+
+use common::{
+    knobs::{
+        ISOLATE_CPU_BUDGET_OPS,
+        ISOLATE_OPS_PER_YIELD,
+        ISOLATE_OP_INTERVAL,
+    },
+    runtime::{
+        Runtime,
+        SpawnHandle,
+    },
+};
+use deno_core::v8;
+
+use crate::{
+    environment::IsolateEnvironment,
+    metrics,
+    request_scope::RequestState,
+    termination::{
+        ContextHandle,
+        TerminationReason,
+    },
+};
+
+/// Limits on how much JavaScript a request may run between yields, and in
+/// total.
+///
+/// V8 doesn't expose a per-instruction hook, so we count "ops" by requesting
+/// an interrupt every `op_interval`. V8 only services interrupts while it's
+/// executing JavaScript, so time spent in async syscalls doesn't count.
+#[derive(Clone, Copy, Debug)]
+pub struct CpuBudget {
+    /// How often to request an op-counting interrupt.
+    pub op_interval: Duration,
+    /// Give up the concurrency permit every this many ops so other requests
+    /// can be scheduled. Zero disables yielding.
+    pub ops_per_yield: u64,
+    /// Terminate the request with `CpuBudgetExceeded` after this many ops.
+    pub max_ops: Option<u64>,
+}
+
+impl CpuBudget {
+    pub fn from_knobs() -> Self {
+        Self {
+            op_interval: *ISOLATE_OP_INTERVAL,
+            ops_per_yield: *ISOLATE_OPS_PER_YIELD,
+            max_ops: Some(*ISOLATE_CPU_BUDGET_OPS).filter(|ops| *ops > 0),
+        }
+    }
+}
+
+/// Per-request op counter. The background task stops when this is dropped.
+pub struct OpCounter {
+    handle: Box<dyn SpawnHandle>,
+    inner: Arc<OpCounterInner>,
+}
+
+struct OpCounterInner {
+    context_handle: ContextHandle,
+    budget: CpuBudget,
+    ops: AtomicU64,
+    // Set while an interrupt has been requested but not yet run, so a
+    // request blocked on a syscall doesn't queue up interrupts.
+    interrupt_pending: AtomicBool,
+    finished: AtomicBool,
+}
+
+impl OpCounter {
+    pub fn start<RT: Runtime, E: IsolateEnvironment<RT>>(
+        rt: &RT,
+        context_handle: ContextHandle,
+        budget: CpuBudget,
+    ) -> Self {
+        let inner = Arc::new(OpCounterInner {
+            context_handle,
+            budget,
+            ops: AtomicU64::new(0),
+            interrupt_pending: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+        });
+        let handle = rt.spawn(
+            "isolate_op_counter",
+            Self::go::<RT, E>(rt.clone(), inner.clone()),
+        );
+        Self { handle, inner }
+    }
+
+    async fn go<RT: Runtime, E: IsolateEnvironment<RT>>(rt: RT, inner: Arc<OpCounterInner>) {
+        if inner.budget.max_ops.is_none() && inner.budget.ops_per_yield == 0 {
+            return;
+        }
+        while !inner.finished.load(Ordering::Acquire) {
+            rt.wait(inner.budget.op_interval).await;
+            if inner.interrupt_pending.swap(true, Ordering::AcqRel) {
+                continue;
+            }
+            // The interrupt callback takes ownership of this reference.
+            let data = Arc::into_raw(inner.clone()) as *mut c_void;
+            if !inner
+                .context_handle
+                .request_interrupt(op_interrupt::<RT, E>, data)
+            {
+                // The isolate is gone, so the callback will never run.
+                drop(unsafe { Arc::from_raw(data as *const OpCounterInner) });
+                return;
+            }
+        }
+    }
+}
+
+impl Drop for OpCounter {
+    fn drop(&mut self) {
+        self.inner.finished.store(true, Ordering::Release);
+        self.handle.shutdown();
+    }
+}
+
+extern "C" fn op_interrupt<RT: Runtime, E: IsolateEnvironment<RT>>(
+    isolate: &mut v8::Isolate,
+    data: *mut c_void,
+) {
+    // SAFETY: `data` came from `Arc::into_raw` in `OpCounter::go`, and each
+    // requested interrupt runs at most once.
+    let inner = unsafe { Arc::from_raw(data as *const OpCounterInner) };
+    inner.interrupt_pending.store(false, Ordering::Release);
+    // The request may have completed (and another started) since the
+    // interrupt was requested.
+    if inner.finished.load(Ordering::Acquire) {
+        return;
+    }
+    let ops = inner.ops.fetch_add(1, Ordering::Relaxed) + 1;
+    if let Some(max_ops) = inner.budget.max_ops
+        && ops > max_ops
+    {
+        metrics::log_cpu_budget_exceeded();
+        inner
+            .context_handle
+            .terminate(TerminationReason::CpuBudgetExceeded(max_ops));
+        return;
+    }
+    if inner.budget.ops_per_yield == 0 || ops % inner.budget.ops_per_yield != 0 {
+        return;
+    }
+    let Some(state) = isolate.get_slot_mut::<RequestState<RT, E>>() else {
+        return;
+    };
+    // Let another request have our permit while we wait to be rescheduled.
+    // Waiting for a permit isn't our fault, so don't count it as user time.
+    let Some(permit) = state.permit.take() else {
+        return;
+    };
+    let regain = permit.suspend();
+    let pause_guard = state.timeout.try_pause();
+    let permit = futures::executor::block_on(regain.acquire());
+    drop(pause_guard);
+    state.permit = Some(permit);
+    metrics::log_cpu_yield();
+}
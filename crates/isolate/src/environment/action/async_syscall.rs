@@ -40,6 +40,7 @@ use vector::{
 use super::task_executor::TaskExecutor;
 use crate::{
     environment::helpers::{
+        identity_claims,
         remove_rejected_before_execution,
         with_argument_error,
         ArgName,
@@ -68,6 +69,7 @@ impl<RT: Runtime> TaskExecutor<RT> {
                 "1.0/actions/cancel_job" => self.async_syscall_cancel_job(args).await?.into(),
                 "1.0/actions/vectorSearch" => self.async_syscall_vectorSearch(args).await?.into(),
                 "1.0/getUserIdentity" => self.async_syscall_getUserIdentity(args).await?.into(),
+                "1.0/getIdentityClaims" => self.async_syscall_getIdentityClaims(args).await?.into(),
                 "1.0/storageDelete" => self.async_syscall_storageDelete(args).await?.into(),
                 "1.0/storageGetMetadata" => {
                     self.async_syscall_storageGetMetadata(args).await?.into()
@@ -349,6 +351,11 @@ impl<RT: Runtime> TaskExecutor<RT> {
         self.user_identity()
     }
 
+    #[convex_macro::instrument_future]
+    async fn async_syscall_getIdentityClaims(&self, args: JsonValue) -> anyhow::Result<JsonValue> {
+        identity_claims(self.user_identity()?, args)
+    }
+
     async fn async_syscall_storageGenerateUploadUrl(
         &self,
         _args: JsonValue,
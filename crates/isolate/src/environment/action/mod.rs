@@ -12,6 +12,7 @@ use std::{
     cmp::Ordering,
     collections::BTreeMap,
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::anyhow;
@@ -28,6 +29,10 @@ use common::{
         ACTION_USER_TIMEOUT,
         FUNCTION_MAX_ARGS_SIZE,
         FUNCTION_MAX_RESULT_SIZE,
+        ISOLATE_INVOCATION_MEMORY_LIMIT,
+        MAX_OUTSTANDING_ACTION_ASYNC_OPS,
+        MAX_TOTAL_ACTION_ASYNC_OPS,
+        UDF_MIN_LOG_LEVEL,
         V8_ACTION_SYSTEM_TIMEOUT,
     },
     log_lines::{
@@ -71,7 +76,10 @@ use model::{
         EnvVarValue,
     },
     modules::{
-        module_versions::FullModuleSource,
+        module_versions::{
+            FullModuleSource,
+            FunctionTimeouts,
+        },
         user_error::FunctionNotFoundError,
     },
 };
@@ -97,6 +105,7 @@ use udf::{
     HttpActionResponsePart,
     HttpActionResponseStreamer,
     HttpActionResult,
+    HttpRequestMetadata,
     SyscallTrace,
     HTTP_ACTION_BODY_LIMIT,
 };
@@ -148,7 +157,10 @@ use crate::{
             resolve_promise_allow_all_errors,
             MAX_LOG_LINES,
         },
+        AsyncOpBudget,
+        AsyncOpConcurrencyLimit,
         AsyncOpRequest,
+        FetchHostPolicy,
         IsolateEnvironment,
     },
     execution_scope::ExecutionScope,
@@ -220,6 +232,9 @@ pub struct ActionEnvironment<RT: Runtime> {
 
     next_task_id: TaskId,
     pending_task_sender: spsc::UnboundedSender<TaskRequest>,
+    async_op_budget: AsyncOpBudget,
+    async_op_concurrency_limit: AsyncOpConcurrencyLimit,
+    fetch_host_policy: FetchHostPolicy,
 
     running_tasks: Option<Box<dyn SpawnHandle>>,
 
@@ -229,7 +244,15 @@ pub struct ActionEnvironment<RT: Runtime> {
     task_responses: mpsc::UnboundedReceiver<TaskResponse>,
     phase: ActionPhase<RT>,
     syscall_trace: Arc<Mutex<SyscallTrace>>,
+    // How long the action's JS has sat idle waiting for async ops to resolve.
+    async_op_wait: Duration,
     heap_stats: SharedIsolateHeapStats,
+    // Set from the action's metadata once we know which action is running.
+    timeouts: FunctionTimeouts,
+    min_log_level: LogLevel,
+    memory_limit: Option<usize>,
+    /// The request being handled, if this is an HTTP action.
+    http_request: Option<HttpRequestMetadata>,
 }
 
 impl<RT: Runtime> Drop for ActionEnvironment<RT> {
@@ -261,6 +284,11 @@ impl<RT: Runtime> ActionEnvironment<RT> {
         context: ExecutionContext,
     ) -> Self {
         let syscall_trace = Arc::new(Mutex::new(SyscallTrace::new()));
+        let min_log_level = context
+            .min_log_level
+            .clone()
+            .unwrap_or_else(|| UDF_MIN_LOG_LEVEL.clone());
+        let memory_limit = context.memory_limit.or(*ISOLATE_INVOCATION_MEMORY_LIMIT);
         let (task_retval_sender, task_responses) = mpsc::unbounded_channel();
         let resources = Arc::new(Mutex::new(BTreeMap::new()));
         let convex_origin_override = Arc::new(Mutex::new(None));
@@ -292,6 +320,11 @@ impl<RT: Runtime> ActionEnvironment<RT> {
 
             next_task_id: TaskId(0),
             pending_task_sender,
+            async_op_budget: AsyncOpBudget::new(*MAX_TOTAL_ACTION_ASYNC_OPS),
+            async_op_concurrency_limit: AsyncOpConcurrencyLimit::new(
+                *MAX_OUTSTANDING_ACTION_ASYNC_OPS,
+            ),
+            fetch_host_policy: FetchHostPolicy::from_knobs(),
             task_responses,
             running_tasks: Some(running_tasks),
             task_promise_resolvers: BTreeMap::new(),
@@ -303,9 +336,15 @@ impl<RT: Runtime> ActionEnvironment<RT> {
                 default_system_env_vars,
                 resources,
                 convex_origin_override,
+                context.unix_timestamp,
             ),
             syscall_trace,
+            async_op_wait: Duration::ZERO,
             heap_stats,
+            timeouts: FunctionTimeouts::default(),
+            min_log_level,
+            memory_limit,
+            http_request: None,
         }
     }
 
@@ -331,6 +370,7 @@ impl<RT: Runtime> ActionEnvironment<RT> {
         let udf_path = &component_function_path.udf_path;
 
         let heap_stats = self.heap_stats.clone();
+        self.http_request = Some(request.head.metadata());
         // See Isolate::with_context for an explanation of this setup code. We can't use
         // that method directly since we want an `await` below, and passing in a
         // generic async closure to `Isolate` is currently difficult.
@@ -411,7 +451,7 @@ impl<RT: Runtime> ActionEnvironment<RT> {
             state
                 .environment
                 .phase
-                .initialize(&mut state.timeout, &mut state.permit)
+                .initialize(&mut state.timeout, &mut state.permit, http_module_path)
                 .await?;
         }
 
@@ -661,6 +701,7 @@ impl<RT: Runtime> ActionEnvironment<RT> {
     ) -> anyhow::Result<ActionOutcome> {
         let start_unix_timestamp = self.rt.unix_timestamp();
         let heap_stats = self.heap_stats.clone();
+        self.timeouts = request_params.path_and_args.timeouts();
 
         // See Isolate::with_context for an explanation of this setup code. We can't use
         // that method directly since we want an `await` below, and passing in a
@@ -719,6 +760,7 @@ impl<RT: Runtime> ActionEnvironment<RT> {
                 Err(e) => Err(e),
             },
             syscall_trace: self.syscall_trace.lock().clone(),
+            async_op_wait: self.async_op_wait,
             udf_server_version,
         };
         Ok(outcome)
@@ -738,7 +780,11 @@ impl<RT: Runtime> ActionEnvironment<RT> {
             state
                 .environment
                 .phase
-                .initialize(&mut state.timeout, &mut state.permit)
+                .initialize(
+                    &mut state.timeout,
+                    &mut state.permit,
+                    &request_params.path_and_args.path().udf_path,
+                )
                 .await?;
         }
         let (path, arguments, _) = request_params.path_and_args.consume();
@@ -1114,6 +1160,7 @@ impl<RT: Runtime> ActionEnvironment<RT> {
             let regain_permit = permit.suspend();
 
             let environment = &mut scope.state_mut()?.environment;
+            let wait_start = environment.rt.monotonic_now();
             select_biased! {
                 result = collecting_result.result_stream.next().fuse() => {
                     match result {
@@ -1172,6 +1219,8 @@ impl<RT: Runtime> ActionEnvironment<RT> {
                     anyhow::bail!("Cancelled");
                 },
             }
+            let environment = &mut scope.state_mut()?.environment;
+            environment.async_op_wait += environment.rt.monotonic_now() - wait_start;
             let permit_acquire = scope
                 .with_state_mut(|state| state.timeout.with_timeout(regain_permit.acquire()))?;
             let permit = permit_acquire.await?;
@@ -1323,6 +1372,9 @@ impl<RT: Runtime> ActionEnvironment<RT> {
 
 impl<RT: Runtime> IsolateEnvironment<RT> for ActionEnvironment<RT> {
     fn trace(&mut self, level: LogLevel, messages: Vec<String>) -> anyhow::Result<()> {
+        if !level.meets_threshold(&self.min_log_level) {
+            return Ok(());
+        }
         // - 1 to reserve for the [ERROR] log line
 
         match self.total_log_lines.cmp(&(MAX_LOG_LINES - 1)) {
@@ -1372,6 +1424,10 @@ impl<RT: Runtime> IsolateEnvironment<RT> for ActionEnvironment<RT> {
         self.phase.get_environment_variable(name)
     }
 
+    fn get_http_request(&mut self) -> anyhow::Result<Option<&HttpRequestMetadata>> {
+        Ok(self.http_request.as_ref())
+    }
+
     fn get_all_table_mappings(&mut self) -> anyhow::Result<NamespacedTableMapping> {
         anyhow::bail!("get_all_table_mappings unsupported in actions")
     }
@@ -1389,6 +1445,14 @@ impl<RT: Runtime> IsolateEnvironment<RT> for ActionEnvironment<RT> {
         Ok(result)
     }
 
+    async fn module_paths(
+        &mut self,
+        _timeout: &mut Timeout<RT>,
+        _permit: &mut Option<ConcurrencyPermit>,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        Ok(Some(self.phase.module_paths()?))
+    }
+
     fn syscall(&mut self, name: &str, args: JsonValue) -> anyhow::Result<JsonValue> {
         self.syscall_impl(name, args)
     }
@@ -1407,6 +1471,14 @@ impl<RT: Runtime> IsolateEnvironment<RT> for ActionEnvironment<RT> {
         request: AsyncOpRequest,
         resolver: v8::Global<v8::PromiseResolver>,
     ) -> anyhow::Result<()> {
+        self.fetch_host_policy.check(&request)?;
+        let in_flight = self
+            .task_promise_resolvers
+            .values()
+            .filter(|(_, task_type)| !matches!(task_type, TaskType::Syscall(_)))
+            .count();
+        self.async_op_concurrency_limit.check(&request, in_flight)?;
+        self.async_op_budget.start(&request)?;
         self.start_task(TaskRequestEnum::AsyncOp(request), resolver)
     }
 
@@ -1416,11 +1488,15 @@ impl<RT: Runtime> IsolateEnvironment<RT> for ActionEnvironment<RT> {
         self.heap_stats.store(isolate_stats);
     }
 
+    fn memory_limit(&self) -> Option<usize> {
+        self.memory_limit
+    }
+
     fn user_timeout(&self) -> std::time::Duration {
-        *ACTION_USER_TIMEOUT
+        self.timeouts.user.unwrap_or(*ACTION_USER_TIMEOUT)
     }
 
     fn system_timeout(&self) -> std::time::Duration {
-        *V8_ACTION_SYSTEM_TIMEOUT
+        self.timeouts.system.unwrap_or(*V8_ACTION_SYSTEM_TIMEOUT)
     }
 }
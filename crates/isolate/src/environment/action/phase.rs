@@ -52,6 +52,7 @@ use rand::{
 use rand_chacha::ChaCha12Rng;
 use sync_types::{
     CanonicalizedModulePath,
+    CanonicalizedUdfPath,
     ModulePath,
 };
 use udf::environment::parse_system_env_var_overrides;
@@ -86,6 +87,9 @@ pub struct ActionPhase<RT: Runtime> {
     phase: Phase,
     pub rt: RT,
     preloaded: ActionPreloaded<RT>,
+    /// What the action sees as the current time once it's executing, if the
+    /// caller pinned it.
+    pinned_unix_timestamp: Option<UnixTimestamp>,
 }
 
 enum ActionPreloaded<RT: Runtime> {
@@ -116,11 +120,13 @@ impl<RT: Runtime> ActionPhase<RT> {
         default_system_env_vars: BTreeMap<EnvVarName, EnvVarValue>,
         resources: Arc<Mutex<BTreeMap<Reference, Resource>>>,
         convex_origin_override: Arc<Mutex<Option<ConvexOrigin>>>,
+        pinned_unix_timestamp: Option<UnixTimestamp>,
     ) -> Self {
         Self {
             component,
             phase: Phase::Importing,
             rt,
+            pinned_unix_timestamp,
             preloaded: ActionPreloaded::Created {
                 tx,
                 module_loader,
@@ -136,6 +142,7 @@ impl<RT: Runtime> ActionPhase<RT> {
         &mut self,
         timeout: &mut Timeout<RT>,
         permit_slot: &mut Option<ConcurrencyPermit>,
+        udf_path: &CanonicalizedUdfPath,
     ) -> anyhow::Result<()> {
         anyhow::ensure!(self.phase == Phase::Importing);
 
@@ -218,7 +225,7 @@ impl<RT: Runtime> ActionPhase<RT> {
             let user_env_vars = with_release_permit(
                 timeout,
                 permit_slot,
-                EnvironmentVariablesModel::new(&mut tx).get_all(),
+                EnvironmentVariablesModel::new(&mut tx).get_all_for_function(udf_path),
             )
             .await?;
             env_vars.extend(user_env_vars);
@@ -287,6 +294,18 @@ impl<RT: Runtime> ActionPhase<RT> {
         Ok(Some((source.clone(), code_cache_result)))
     }
 
+    /// The paths of the preloaded user modules.
+    pub fn module_paths(&self) -> anyhow::Result<Vec<String>> {
+        let ActionPreloaded::Ready { ref modules, .. } = self.preloaded else {
+            anyhow::bail!("Phase not initialized");
+        };
+        Ok(modules
+            .keys()
+            .filter(|path| !path.is_system())
+            .map(|path| path.as_str().to_owned())
+            .collect())
+    }
+
     pub fn begin_execution(&mut self) -> anyhow::Result<()> {
         if self.phase != Phase::Importing {
             anyhow::bail!("Phase was already {:?}", self.phase)
@@ -365,7 +384,8 @@ impl<RT: Runtime> ActionPhase<RT> {
             };
             unix_timestamp
         } else {
-            self.rt.unix_timestamp()
+            self.pinned_unix_timestamp
+                .unwrap_or_else(|| self.rt.unix_timestamp())
         };
         Ok(timestamp)
     }
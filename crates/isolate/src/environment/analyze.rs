@@ -193,6 +193,19 @@ impl<RT: Runtime> IsolateEnvironment<RT> for AnalyzeEnvironment {
         Ok(result.map(|m| (m, ModuleCodeCacheResult::noop())))
     }
 
+    async fn module_paths(
+        &mut self,
+        _timeout: &mut Timeout<RT>,
+        _permit: &mut Option<ConcurrencyPermit>,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        let paths = self
+            .modules
+            .keys()
+            .map(|path| path.as_str().to_owned())
+            .collect();
+        Ok(Some(paths))
+    }
+
     fn syscall(&mut self, name: &str, _args: JsonValue) -> anyhow::Result<JsonValue> {
         match name {
             "count" | "get" | "insert" | "update" | "replace" | "queryStreamNext" | "queryPage"
@@ -398,6 +411,7 @@ impl AnalyzeEnvironment {
                                 ),
                                 custom_data: None,
                                 frames: e.frames,
+                                argument_validation: None,
                             }))
                         },
                         Err(e) => return Err(e),
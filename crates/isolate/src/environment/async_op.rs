@@ -2,9 +2,14 @@ use std::fmt;
 
 use common::{
     http::HttpRequestStream,
+    knobs::{
+        FETCH_HOST_ALLOWLIST,
+        FETCH_HOST_DENYLIST,
+    },
     runtime::UnixTimestamp,
     sync::spsc,
 };
+use errors::ErrorMetadata;
 use futures::stream::BoxStream;
 
 pub enum AsyncOpRequest {
@@ -59,6 +64,114 @@ impl AsyncOpRequest {
     }
 }
 
+/// Tracks how many async ops an environment has started over its lifetime,
+/// independently of how many are running concurrently.
+pub struct AsyncOpBudget {
+    limit: usize,
+    started: usize,
+}
+
+impl AsyncOpBudget {
+    pub fn new(limit: usize) -> Self {
+        Self { limit, started: 0 }
+    }
+
+    /// Account for starting `request`, failing if doing so would exceed the
+    /// budget.
+    pub fn start(&mut self, request: &AsyncOpRequest) -> anyhow::Result<()> {
+        if self.started >= self.limit {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "AsyncOpBudgetExceeded",
+                format!(
+                    "{} failed: a function may start at most {} async operations",
+                    request.description_for_error(),
+                    self.limit,
+                ),
+            ));
+        }
+        self.started += 1;
+        Ok(())
+    }
+}
+
+/// Caps how many async ops an environment may have outstanding at once.
+pub struct AsyncOpConcurrencyLimit {
+    limit: usize,
+}
+
+impl AsyncOpConcurrencyLimit {
+    pub fn new(limit: usize) -> Self {
+        Self { limit }
+    }
+
+    /// Fail if starting `request` while `in_flight` other async ops are
+    /// outstanding would exceed the limit.
+    pub fn check(&self, request: &AsyncOpRequest, in_flight: usize) -> anyhow::Result<()> {
+        if in_flight >= self.limit {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "TooManyConcurrentAsyncOps",
+                format!(
+                    "{} failed: too many concurrent async operations, a function may have at most \
+                     {} in flight",
+                    request.description_for_error(),
+                    self.limit,
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Restricts which hosts `fetch()` may make requests to, so functions can't
+/// reach internal services. A host in either list also matches its
+/// subdomains.
+#[derive(Clone, Debug, Default)]
+pub struct FetchHostPolicy {
+    allowlist: Vec<String>,
+    denylist: Vec<String>,
+}
+
+impl FetchHostPolicy {
+    /// An empty `allowlist` allows every host that isn't in `denylist`. Hosts
+    /// match case-insensitively and ignoring a trailing dot.
+    pub fn new(allowlist: Vec<String>, denylist: Vec<String>) -> Self {
+        let normalize = |hosts: Vec<String>| hosts.iter().map(|h| Self::normalize(h)).collect();
+        Self {
+            allowlist: normalize(allowlist),
+            denylist: normalize(denylist),
+        }
+    }
+
+    // `example.com.` is the fully qualified form of `example.com`, so strip
+    // the trailing dot rather than letting it slip past the denylist.
+    fn normalize(host: &str) -> String {
+        host.strip_suffix('.').unwrap_or(host).to_ascii_lowercase()
+    }
+
+    pub fn from_knobs() -> Self {
+        Self::new(FETCH_HOST_ALLOWLIST.clone(), FETCH_HOST_DENYLIST.clone())
+    }
+
+    /// Fail if `request` is a fetch to a host the policy doesn't allow. Other
+    /// async ops are always allowed.
+    pub fn check(&self, request: &AsyncOpRequest) -> anyhow::Result<()> {
+        let AsyncOpRequest::Fetch { request, .. } = request else {
+            return Ok(());
+        };
+        let host = Self::normalize(request.url.host_str().unwrap_or_default());
+        let matches = |pattern: &String| host == *pattern || host.ends_with(&format!(".{pattern}"));
+        let allowed = (self.allowlist.is_empty() || self.allowlist.iter().any(matches))
+            && !self.denylist.iter().any(matches);
+        if !allowed {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "FetchNotAllowed",
+                format!("fetch() to host \"{host}\" is not allowed"),
+            ));
+        }
+        Ok(())
+    }
+}
+
 impl fmt::Debug for AsyncOpRequest {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.name_for_error().fmt(f)
@@ -15,6 +15,7 @@ use errors::{
     ErrorCode,
     ErrorMetadata,
 };
+use serde::Deserialize;
 use serde_json::Value as JsonValue;
 use value::TableName;
 
@@ -102,6 +103,33 @@ pub fn check_table_name(
     Ok(())
 }
 
+/// Implements `1.0/getIdentityClaims`: pick the claims named in `args` (or
+/// all of them) out of the JSON form of the caller's identity, as returned by
+/// `1.0/getUserIdentity`. Claims the identity doesn't have are left out, and
+/// unauthenticated callers get `null`.
+pub fn identity_claims(user_identity: JsonValue, args: JsonValue) -> anyhow::Result<JsonValue> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct IdentityClaimsArgs {
+        names: Option<Vec<String>>,
+    }
+    let names = with_argument_error("auth.getIdentityClaims", || {
+        let args: IdentityClaimsArgs = serde_json::from_value(args).context(ArgName("names"))?;
+        Ok(args.names)
+    })?;
+    let JsonValue::Object(claims) = user_identity else {
+        return Ok(JsonValue::Null);
+    };
+    let claims = match names {
+        None => claims,
+        Some(names) => claims
+            .into_iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect(),
+    };
+    Ok(JsonValue::Object(claims))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -34,9 +34,15 @@ use common::{
 use deno_core::v8;
 use rand_chacha::ChaCha12Rng;
 use serde_json::Value as JsonValue;
+use udf::HttpRequestMetadata;
 use value::NamespacedTableMapping;
 
-pub use self::async_op::AsyncOpRequest;
+pub use self::async_op::{
+    AsyncOpBudget,
+    AsyncOpConcurrencyLimit,
+    AsyncOpRequest,
+    FetchHostPolicy,
+};
 use crate::{
     concurrency_limiter::ConcurrencyPermit,
     isolate::IsolateHeapStats,
@@ -69,6 +75,17 @@ pub trait IsolateEnvironment<RT: Runtime>: 'static {
         permit: &mut Option<ConcurrencyPermit>,
     ) -> anyhow::Result<Option<(Arc<FullModuleSource>, ModuleCodeCacheResult)>>;
 
+    /// Paths `lookup_source` would find, for suggesting alternatives when a
+    /// module is missing. `None` if they can't be listed.
+    #[allow(async_fn_in_trait)]
+    async fn module_paths(
+        &mut self,
+        _timeout: &mut Timeout<RT>,
+        _permit: &mut Option<ConcurrencyPermit>,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        Ok(None)
+    }
+
     fn syscall(&mut self, name: &str, args: JsonValue) -> anyhow::Result<JsonValue>;
     fn start_async_syscall(
         &mut self,
@@ -85,6 +102,12 @@ pub trait IsolateEnvironment<RT: Runtime>: 'static {
     fn get_environment_variable(&mut self, name: EnvVarName)
         -> anyhow::Result<Option<EnvVarValue>>;
 
+    /// The HTTP request that triggered the function, if it was called as an
+    /// `HttpAction`.
+    fn get_http_request(&mut self) -> anyhow::Result<Option<&HttpRequestMetadata>> {
+        Ok(None)
+    }
+
     fn get_all_table_mappings(&mut self) -> anyhow::Result<NamespacedTableMapping>;
 
     fn start_async_op(
@@ -95,6 +118,12 @@ pub trait IsolateEnvironment<RT: Runtime>: 'static {
 
     fn record_heap_stats(&self, _heap_size: IsolateHeapStats) {}
 
+    /// Bytes of memory the invocation may use before it's terminated with
+    /// `MemoryLimitExceeded`, checked each time it yields.
+    fn memory_limit(&self) -> Option<usize> {
+        None
+    }
+
     fn user_timeout(&self) -> Duration;
     fn system_timeout(&self) -> Duration;
     fn is_nested_function(&self) -> bool {
@@ -50,6 +50,7 @@ use database::{
     soft_data_limit,
     table_summary::table_summary_bootstrapping_error,
     BootstrapComponentsModel,
+    CountersModel,
     DeveloperQuery,
     PatchValue,
     Transaction,
@@ -73,6 +74,7 @@ use model::{
         FileStorageId,
     },
     scheduled_jobs::VirtualSchedulerModel,
+    sequences::SequencesModel,
     virtual_system_mapping,
 };
 use serde::{
@@ -107,6 +109,7 @@ use crate::{
         action::parse_name_or_reference,
         helpers::{
             check_table_name,
+            identity_claims,
             parse_version,
             remove_rejected_before_execution,
             with_argument_error,
@@ -151,6 +154,21 @@ pub fn system_table_guard(name: &TableName, expect_system_table: bool) -> anyhow
     Ok(())
 }
 
+// Patches and replaces return the updated document, or, if the caller asked
+// for `returnPrevious`, both the document before the write and after it.
+fn update_result_json(
+    previous: Option<DeveloperDocument>,
+    document: DeveloperDocument,
+) -> JsonValue {
+    match previous {
+        Some(previous) => json!({
+            "previous": previous.to_internal_json(),
+            "value": document.to_internal_json(),
+        }),
+        None => document.to_internal_json(),
+    }
+}
+
 /// A batch of async syscalls that can run "in parallel", where they actually
 /// execute in a batch for determinism, but as far as the js promises are
 /// concerned, they're running in parallel.
@@ -588,6 +606,7 @@ impl<RT: Runtime> AsyncSyscallProvider<RT> for DatabaseUdfEnvironment<RT> {
             unix_timestamp: _,
             rng_seed: _,
             memory_in_mb: _,
+            permit_wait: _,
         } = outcome;
 
         log_run_udf(
@@ -698,6 +717,9 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
                     "1.0/getUserIdentity" => {
                         Box::pin(Self::get_user_identity(provider, args)).await
                     },
+                    "1.0/getIdentityClaims" => {
+                        Box::pin(Self::get_identity_claims(provider, args)).await
+                    },
                     // Storage
                     "1.0/storageDelete" => Box::pin(Self::storage_delete(provider, args)).await,
                     "1.0/storageGetMetadata" => {
@@ -709,6 +731,13 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
                     // Scheduling
                     "1.0/schedule" => Box::pin(Self::schedule(provider, args)).await,
                     "1.0/cancel_job" => Box::pin(Self::cancel_job(provider, args)).await,
+                    // Sequences
+                    "1.0/sequenceNext" => Box::pin(Self::sequence_next(provider, args)).await,
+                    // Counters
+                    "1.0/counterIncrement" => {
+                        Box::pin(Self::counter_increment(provider, args)).await
+                    },
+                    "1.0/counterGet" => Box::pin(Self::counter_get(provider, args)).await,
 
                     // Components
                     "1.0/runUdf" => Box::pin(Self::run_udf(provider, args)).await,
@@ -790,6 +819,12 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
         Ok(JsonValue::Null)
     }
 
+    #[convex_macro::instrument_future]
+    async fn get_identity_claims(provider: &mut P, args: JsonValue) -> anyhow::Result<JsonValue> {
+        let user_identity = Self::get_user_identity(provider, JsonValue::Null).await?;
+        identity_claims(user_identity, args)
+    }
+
     #[convex_macro::instrument_future]
     async fn storage_generate_upload_url(
         provider: &mut P,
@@ -904,6 +939,7 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
             function_handle: Option<String>,
             ts: f64,
             args: UdfArgsJson,
+            dedup_key: Option<String>,
         }
 
         let ScheduleArgs {
@@ -912,6 +948,7 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
             function_handle,
             ts,
             args,
+            dedup_key,
         }: ScheduleArgs = with_argument_error("scheduler", || Ok(serde_json::from_value(args)?))?;
 
         let path = match function_handle {
@@ -952,9 +989,19 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
 
         let context = provider.context().clone();
         let tx = provider.tx()?;
-        let virtual_id = VirtualSchedulerModel::new(tx, scheduling_component.into())
-            .schedule(path, udf_args, scheduled_ts, context)
-            .await?;
+        let mut model = VirtualSchedulerModel::new(tx, scheduling_component.into());
+        let virtual_id = match dedup_key {
+            Some(dedup_key) => {
+                model
+                    .schedule_if_absent(path, udf_args, scheduled_ts, context, dedup_key)
+                    .await?
+            },
+            None => {
+                model
+                    .schedule(path, udf_args, scheduled_ts, context)
+                    .await?
+            },
+        };
 
         Ok(JsonValue::from(virtual_id))
     }
@@ -982,6 +1029,60 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
         Ok(JsonValue::Null)
     }
 
+    #[convex_macro::instrument_future]
+    async fn sequence_next(provider: &mut P, args: JsonValue) -> anyhow::Result<JsonValue> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct SequenceNextArgs {
+            name: String,
+        }
+        let name = with_argument_error("sequence.next", || {
+            let args: SequenceNextArgs = serde_json::from_value(args)?;
+            Ok(args.name)
+        })?;
+        let component = provider.component()?;
+        let tx = provider.tx()?;
+        let value = SequencesModel::new(tx, component.into())
+            .next(&name)
+            .await?;
+        Ok(ConvexValue::from(value).to_internal_json())
+    }
+
+    #[convex_macro::instrument_future]
+    async fn counter_increment(provider: &mut P, args: JsonValue) -> anyhow::Result<JsonValue> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct CounterIncrementArgs {
+            name: String,
+            delta: i64,
+        }
+        let (name, delta) = with_argument_error("counter.increment", || {
+            let args: CounterIncrementArgs = serde_json::from_value(args)?;
+            Ok((args.name, args.delta))
+        })?;
+        let component = provider.component()?;
+        let tx = provider.tx()?;
+        CountersModel::new(tx, component.into()).increment(&name, delta)?;
+        Ok(JsonValue::Null)
+    }
+
+    #[convex_macro::instrument_future]
+    async fn counter_get(provider: &mut P, args: JsonValue) -> anyhow::Result<JsonValue> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct CounterGetArgs {
+            name: String,
+        }
+        let name = with_argument_error("counter.get", || {
+            let args: CounterGetArgs = serde_json::from_value(args)?;
+            Ok(args.name)
+        })?;
+        let component = provider.component()?;
+        let tx = provider.tx()?;
+        let value = CountersModel::new(tx, component.into()).get(&name).await?;
+        Ok(ConvexValue::from(value).to_internal_json())
+    }
+
     #[fastrace::trace]
     #[convex_macro::instrument_future]
     async fn insert(provider: &mut P, args: JsonValue) -> anyhow::Result<JsonValue> {
@@ -1022,11 +1123,13 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
             table: Option<String>,
             id: String,
             value: JsonValue,
+            #[serde(default)]
+            return_previous: bool,
         }
         let table_filter = provider.table_filter();
         let component = provider.component()?;
         let tx = provider.tx()?;
-        let (id, value, table_name) = with_argument_error("db.patch", || {
+        let (id, value, table_name, return_previous) = with_argument_error("db.patch", || {
             let args: UpdateArgs = serde_json::from_value(args)?;
 
             let id = DeveloperDocumentId::decode(&args.id).context(ArgName("id"))?;
@@ -1036,15 +1139,15 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
             check_table_name(&args.table, &actual_table_name)?;
 
             let value = PatchValue::try_from(args.value).context(ArgName("value"))?;
-            Ok((id, value, actual_table_name))
+            Ok((id, value, actual_table_name, args.return_previous))
         })?;
 
         system_table_guard(&table_name, false)?;
 
-        let document = UserFacingModel::new(tx, component.into())
-            .patch(id, value)
+        let (previous, document) = UserFacingModel::new(tx, component.into())
+            .patch_with_previous(id, value, return_previous)
             .await?;
-        Ok(document.to_internal_json())
+        Ok(update_result_json(previous, document))
     }
 
     #[fastrace::trace]
@@ -1057,11 +1160,13 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
             table: Option<String>,
             id: String,
             value: JsonValue,
+            #[serde(default)]
+            return_previous: bool,
         }
         let table_filter = provider.table_filter();
         let component = provider.component()?;
         let tx = provider.tx()?;
-        let (id, value, table_name) = with_argument_error("db.replace", || {
+        let (id, value, table_name, return_previous) = with_argument_error("db.replace", || {
             let args: ReplaceArgs = serde_json::from_value(args)?;
 
             let id = DeveloperDocumentId::decode(&args.id).context(ArgName("id"))?;
@@ -1075,15 +1180,16 @@ impl<RT: Runtime, P: AsyncSyscallProvider<RT>> DatabaseSyscallsV1<RT, P> {
                 id,
                 value.try_into().context(ArgName("value"))?,
                 actual_table_name,
+                args.return_previous,
             ))
         })?;
 
         system_table_guard(&table_name, false)?;
 
-        let document = UserFacingModel::new(tx, component.into())
-            .replace(id, value)
+        let (previous, document) = UserFacingModel::new(tx, component.into())
+            .replace_with_previous(id, value, return_previous)
             .await?;
-        Ok(document.to_internal_json())
+        Ok(update_result_json(previous, document))
     }
 
     #[fastrace::trace]
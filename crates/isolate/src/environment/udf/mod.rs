@@ -17,7 +17,10 @@ use model::{
         EnvVarValue,
     },
     modules::{
-        module_versions::FullModuleSource,
+        module_versions::{
+            FullModuleSource,
+            FunctionTimeouts,
+        },
         user_error::FunctionNotFoundError,
     },
 };
@@ -46,6 +49,8 @@ use common::{
         DATABASE_UDF_USER_TIMEOUT,
         FUNCTION_MAX_ARGS_SIZE,
         FUNCTION_MAX_RESULT_SIZE,
+        ISOLATE_INVOCATION_MEMORY_LIMIT,
+        MAX_USER_DOCUMENT_SIZE_BYTES,
         TRANSACTION_MAX_NUM_SCHEDULED,
         TRANSACTION_MAX_NUM_USER_WRITES,
         TRANSACTION_MAX_READ_SET_INTERVALS,
@@ -53,6 +58,7 @@ use common::{
         TRANSACTION_MAX_READ_SIZE_ROWS,
         TRANSACTION_MAX_SCHEDULED_TOTAL_ARGUMENT_SIZE_BYTES,
         TRANSACTION_MAX_USER_WRITE_SIZE_BYTES,
+        UDF_MIN_LOG_LEVEL,
     },
     log_lines::{
         LogLevel,
@@ -99,7 +105,6 @@ use value::{
     NamespacedTableMapping,
     Size,
     MAX_DOCUMENT_NESTING,
-    MAX_USER_SIZE,
     VALUE_TOO_LARGE_SHORT_MSG,
 };
 
@@ -194,9 +199,12 @@ pub struct DatabaseUdfEnvironment<RT: Runtime> {
     heap_stats: SharedIsolateHeapStats,
 
     context: ExecutionContext,
+    min_log_level: LogLevel,
 
     reactor_depth: usize,
     udf_callback: Box<dyn UdfCallback<RT>>,
+
+    timeouts: FunctionTimeouts,
 }
 
 fn not_allowed_in_udf(name: &str, description: &str) -> ErrorMetadata {
@@ -211,6 +219,9 @@ fn not_allowed_in_udf(name: &str, description: &str) -> ErrorMetadata {
 
 impl<RT: Runtime> IsolateEnvironment<RT> for DatabaseUdfEnvironment<RT> {
     fn trace(&mut self, level: LogLevel, messages: Vec<String>) -> anyhow::Result<()> {
+        if !level.meets_threshold(&self.min_log_level) {
+            return Ok(());
+        }
         self.emit_log_line(LogLine::new_developer_log_line(
             level,
             messages,
@@ -260,6 +271,15 @@ impl<RT: Runtime> IsolateEnvironment<RT> for DatabaseUdfEnvironment<RT> {
         Ok(result)
     }
 
+    async fn module_paths(
+        &mut self,
+        timeout: &mut Timeout<RT>,
+        permit: &mut Option<ConcurrencyPermit>,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        let paths = self.phase.module_paths(timeout, permit).await?;
+        Ok(Some(paths))
+    }
+
     fn syscall(&mut self, name: &str, args: JsonValue) -> anyhow::Result<JsonValue> {
         syscall_impl(self, name, args)
     }
@@ -296,12 +316,18 @@ impl<RT: Runtime> IsolateEnvironment<RT> for DatabaseUdfEnvironment<RT> {
         self.heap_stats.store(isolate_stats);
     }
 
+    fn memory_limit(&self) -> Option<usize> {
+        self.context
+            .memory_limit
+            .or(*ISOLATE_INVOCATION_MEMORY_LIMIT)
+    }
+
     fn user_timeout(&self) -> std::time::Duration {
-        *DATABASE_UDF_USER_TIMEOUT
+        self.timeouts.user.unwrap_or(*DATABASE_UDF_USER_TIMEOUT)
     }
 
     fn system_timeout(&self) -> std::time::Duration {
-        *DATABASE_UDF_SYSTEM_TIMEOUT
+        self.timeouts.system.unwrap_or(*DATABASE_UDF_SYSTEM_TIMEOUT)
     }
 
     fn is_nested_function(&self) -> bool {
@@ -331,8 +357,14 @@ impl<RT: Runtime> DatabaseUdfEnvironment<RT> {
         client_id: String,
     ) -> Self {
         let persistence_version = transaction.persistence_version();
+        let timeouts = path_and_args.timeouts();
         let (path, arguments, udf_server_version) = path_and_args.consume();
         let component = path.component;
+        let udf_path = path.udf_path.clone();
+        let min_log_level = context
+            .min_log_level
+            .clone()
+            .unwrap_or_else(|| UDF_MIN_LOG_LEVEL.clone());
         Self {
             rt: rt.clone(),
             udf_type,
@@ -347,6 +379,7 @@ impl<RT: Runtime> DatabaseUdfEnvironment<RT> {
                 module_loader.clone(),
                 default_system_env_vars,
                 component,
+                udf_path,
             ),
             file_storage,
 
@@ -362,10 +395,13 @@ impl<RT: Runtime> DatabaseUdfEnvironment<RT> {
             syscall_trace: SyscallTrace::new(),
             heap_stats,
             context,
+            min_log_level,
 
             reactor_depth,
             udf_callback,
             client_id,
+
+            timeouts,
         }
     }
 
@@ -379,11 +415,17 @@ impl<RT: Runtime> DatabaseUdfEnvironment<RT> {
         cancellation: BoxFuture<'_, ()>,
         function_started: Option<oneshot::Sender<()>>,
     ) -> anyhow::Result<(Transaction<RT>, FunctionOutcome)> {
-        // Initialize the UDF's RNG from some high-quality entropy. As with
-        // `unix_timestamp` below, the UDF is only deterministic modulo this
-        // system-generated input.
-        let rng_seed = self.rt.rng().random();
-        let unix_timestamp = self.rt.unix_timestamp();
+        // Initialize the UDF's RNG from some high-quality entropy and read the
+        // current time, unless the caller pinned them. The UDF is only
+        // deterministic modulo these system-generated inputs.
+        let rng_seed = self
+            .context
+            .rng_seed
+            .unwrap_or_else(|| self.rt.rng().random());
+        let unix_timestamp = self
+            .context
+            .unix_timestamp
+            .unwrap_or_else(|| self.rt.unix_timestamp());
         let heap_stats = self.heap_stats.clone();
 
         // See Isolate::with_context for an explanation of this setup code. We can't use
@@ -430,6 +472,7 @@ impl<RT: Runtime> DatabaseUdfEnvironment<RT> {
         // environment.
         let result = result?;
 
+        let permit_wait = isolate_context.permit_wait();
         let execution_time;
         (self, execution_time) = isolate_context.take_environment();
         let success_result_value = result.as_ref().ok();
@@ -473,6 +516,7 @@ impl<RT: Runtime> DatabaseUdfEnvironment<RT> {
                 syscall_trace: self.syscall_trace,
                 udf_server_version: self.udf_server_version,
                 memory_in_mb,
+                permit_wait,
             }),
             // TODO: Add num_writes and write_bandwidth to UdfOutcome,
             // and use them in log_mutation.
@@ -494,6 +538,7 @@ impl<RT: Runtime> DatabaseUdfEnvironment<RT> {
                 syscall_trace: self.syscall_trace,
                 udf_server_version: self.udf_server_version,
                 memory_in_mb,
+                permit_wait,
             }),
             _ => anyhow::bail!("UdfEnvironment should only run queries and mutations"),
         };
@@ -955,7 +1000,7 @@ impl<RT: Runtime> DatabaseUdfEnvironment<RT> {
             let (max_size_document_id, max_size) = biggest_writes.max_size;
             if let Some(warning) = approaching_limit_warning(
                 max_size,
-                MAX_USER_SIZE,
+                *MAX_USER_DOCUMENT_SIZE_BYTES,
                 VALUE_TOO_LARGE_SHORT_MSG,
                 || format!("Large document written with ID \"{max_size_document_id}\""),
                 None,
@@ -40,7 +40,10 @@ use model::{
 };
 use rand::SeedableRng;
 use rand_chacha::ChaCha12Rng;
-use sync_types::ModulePath;
+use sync_types::{
+    CanonicalizedUdfPath,
+    ModulePath,
+};
 use udf::environment::system_env_vars;
 use value::{
     identifier::Identifier,
@@ -82,6 +85,7 @@ pub struct UdfPhase<RT: Runtime> {
     module_loader: Arc<dyn ModuleCache<RT>>,
     preloaded: UdfPreloaded,
     component: ComponentId,
+    udf_path: CanonicalizedUdfPath,
 }
 
 enum UdfPreloaded {
@@ -108,6 +112,7 @@ impl<RT: Runtime> UdfPhase<RT> {
         module_loader: Arc<dyn ModuleCache<RT>>,
         default_system_env_vars: BTreeMap<EnvVarName, EnvVarValue>,
         component: ComponentId,
+        udf_path: CanonicalizedUdfPath,
     ) -> Self {
         Self {
             phase: Phase::Importing,
@@ -118,6 +123,7 @@ impl<RT: Runtime> UdfPhase<RT> {
                 default_system_env_vars,
             },
             component,
+            udf_path,
         }
     }
 
@@ -164,11 +170,12 @@ impl<RT: Runtime> UdfPhase<RT> {
         let unix_timestamp = udf_config.as_ref().map(|c| c.import_phase_unix_timestamp);
 
         let env_vars = if component.is_root() {
+            let udf_path = self.udf_path.clone();
             Some(
                 with_release_permit(
                     timeout,
                     permit_slot,
-                    EnvironmentVariablesModel::new(self.tx_mut()?).preload(),
+                    EnvironmentVariablesModel::new(self.tx_mut()?).preload_for_function(&udf_path),
                 )
                 .await?,
             )
@@ -287,6 +294,28 @@ impl<RT: Runtime> UdfPhase<RT> {
         Ok(Some((module_source, code_cache_result)))
     }
 
+    /// The paths of the component's user modules.
+    pub async fn module_paths(
+        &mut self,
+        timeout: &mut Timeout<RT>,
+        permit_slot: &mut Option<ConcurrencyPermit>,
+    ) -> anyhow::Result<Vec<String>> {
+        let UdfPreloaded::Ready { component, .. } = &self.preloaded else {
+            anyhow::bail!("Phase not initialized");
+        };
+        let component = *component;
+        let modules = with_release_permit(timeout, permit_slot, async {
+            ModuleModel::new(self.tx_mut()?)
+                .get_application_metadata(component)
+                .await
+        })
+        .await?;
+        Ok(modules
+            .into_iter()
+            .map(|module| module.path.as_str().to_owned())
+            .collect())
+    }
+
     pub fn tx(&mut self) -> anyhow::Result<&mut Transaction<RT>> {
         if self.phase != Phase::Executing {
             anyhow::bail!(ErrorMetadata::bad_request(
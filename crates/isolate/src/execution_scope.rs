@@ -64,7 +64,10 @@ use crate::{
         ModuleMap,
     },
     request_scope::RequestState,
-    termination::IsolateHandle,
+    termination::{
+        IsolateHandle,
+        TerminationReason,
+    },
     IsolateHeapStats,
 };
 
@@ -213,16 +216,26 @@ impl<'a, 'b: 'a, RT: Runtime, E: IsolateEnvironment<RT>> ExecutionScope<'a, 'b,
             .get_slot::<Arc<ArrayBufferMemoryLimit>>()
             .context("missing ArrayBufferMemoryLimit?")?
             .used();
-        self.with_state_mut(|state| {
+        let (used, memory_limit) = self.with_state_mut(|state| {
             let blobs_heap_size = state.blob_parts.heap_size();
             let streams_heap_size = state.streams.heap_size() + state.stream_listeners.heap_size();
-            state.environment.record_heap_stats(IsolateHeapStats::new(
-                stats,
-                blobs_heap_size,
-                streams_heap_size,
-                array_buffer_size,
-            ));
-        })
+            let heap_stats =
+                IsolateHeapStats::new(stats, blobs_heap_size, streams_heap_size, array_buffer_size);
+            state.environment.record_heap_stats(heap_stats);
+            let used = heap_stats.v8_used_heap_size
+                + heap_stats.env_heap_size()
+                + heap_stats.array_buffer_size;
+            (used, state.environment.memory_limit())
+        })?;
+        if let Some(limit) = memory_limit
+            && used > limit
+        {
+            let handle = self.handle();
+            if handle.is_not_clean().is_none() {
+                handle.terminate(TerminationReason::MemoryLimitExceeded(limit));
+            }
+        }
+        Ok(())
     }
 
     pub fn module_map(&mut self) -> &ModuleMap {
@@ -490,11 +503,25 @@ impl<'a, 'b: 'a, RT: Runtime, E: IsolateEnvironment<RT>> ExecutionScope<'a, 'b,
         }
 
         let state = self.state_mut()?;
-        let result = state
+        let Some(result) = state
             .environment
             .lookup_source(module_path, &mut state.timeout, &mut state.permit)
             .await?
-            .ok_or_else(|| ModuleNotFoundError::new(module_path))?;
+        else {
+            let available = state
+                .environment
+                .module_paths(&mut state.timeout, &mut state.permit)
+                .await?;
+            let error = match available {
+                Some(available) => {
+                    ModuleNotFoundError::with_available_modules(module_path, &available)
+                },
+                None => ModuleNotFoundError::new(module_path),
+            };
+            let msg = error.to_string();
+            return Err(anyhow::Error::new(error)
+                .context(ErrorMetadata::bad_request("ModuleNotFound", msg)));
+        };
 
         timer.finish();
 
@@ -34,6 +34,10 @@ use value::heap_size::WithHeapSize;
 use crate::{
     array_buffer_allocator::ArrayBufferMemoryLimit,
     concurrency_limiter::ConcurrencyLimiter,
+    cpu_budget::{
+        CpuBudget,
+        OpCounter,
+    },
     environment::IsolateEnvironment,
     helpers::pump_message_loop,
     metrics::{
@@ -62,6 +66,9 @@ pub struct Isolate<RT: Runtime> {
     // Typically, the user timeout is configured based on environment. This
     // allows us to set an upper bound to it that we use for tests.
     max_user_timeout: Option<Duration>,
+    // How often requests yield their concurrency permit, and when they're
+    // stopped for running too much JavaScript.
+    cpu_budget: CpuBudget,
     // The heap limit callback takes ownership of this `Box` allocation, which
     // we reclaim after removing the callback.
     heap_ctx_ptr: *mut HeapContext,
@@ -83,6 +90,10 @@ pub enum IsolateNotClean {
     UserTimeout,
     #[error("Isolate hit system timeout")]
     SystemTimeout,
+    #[error("Isolate exceeded its CPU budget")]
+    CpuBudgetExceeded,
+    #[error("Isolate exceeded its invocation memory limit")]
+    MemoryLimitExceeded,
     #[error("Isolate ran out of memory")]
     OutOfMemory,
 
@@ -102,6 +113,8 @@ impl IsolateNotClean {
             Self::UnhandledPromiseRejection => "unhandled_promise_rejection",
             Self::UserTimeout => "user_timeout",
             Self::SystemTimeout => "system_timeout",
+            Self::CpuBudgetExceeded => "cpu_budget_exceeded",
+            Self::MemoryLimitExceeded => "memory_limit_exceeded",
             Self::OutOfMemory => "out_of_memory",
             Self::TooMuchMemoryCarryOver(..) => "memory_carry_over",
             Self::DetachedContext(_) => "detached_context",
@@ -210,11 +223,19 @@ impl<RT: Runtime> Isolate<RT> {
             handle,
             heap_ctx_ptr,
             max_user_timeout,
+            cpu_budget: CpuBudget::from_knobs(),
             limiter,
             array_buffer_memory_limit,
         }
     }
 
+    /// Override the `ISOLATE_OP_INTERVAL`, `ISOLATE_OPS_PER_YIELD` and
+    /// `ISOLATE_CPU_BUDGET_OPS` knobs for requests run in this isolate.
+    pub fn with_cpu_budget(mut self, cpu_budget: CpuBudget) -> Self {
+        self.cpu_budget = cpu_budget;
+        self
+    }
+
     extern "C" fn import_meta_callback(
         context: v8::Local<v8::Context>,
         _module: v8::Local<v8::Module>,
@@ -297,6 +318,7 @@ impl<RT: Runtime> Isolate<RT> {
                 "Selected isolate was not clean",
             ))?;
         // Acquire a concurrency permit without counting it against the timeout.
+        let permit_wait_start = self.rt.monotonic_now();
         let permit = tokio::select! {
             biased;
             permit = self.limiter.acquire(client_id) => permit,
@@ -318,21 +340,24 @@ impl<RT: Runtime> Isolate<RT> {
         }
         let timeout = Timeout::new(
             self.rt.clone(),
-            context_handle,
+            context_handle.clone(),
             Some(user_timeout),
             Some(environment.system_timeout()),
         );
+        let op_counter = OpCounter::start::<RT, E>(&self.rt, context_handle, self.cpu_budget);
         let state = RequestState {
             rt: self.rt.clone(),
             environment,
             timeout,
             permit: Some(permit),
+            permit_wait: permit_wait_start.elapsed(),
             blob_parts: WithHeapSize::default(),
             streams: WithHeapSize::default(),
             stream_listeners: WithHeapSize::default(),
             request_stream_state: None,
             console_timers: WithHeapSize::default(),
             text_decoders: BTreeMap::new(),
+            op_counter,
         };
         Ok((self.handle.clone(), state))
     }
@@ -458,6 +458,7 @@ mod op_provider {
     };
     use rand_chacha::ChaCha12Rng;
     use sourcemap::SourceMap;
+    use udf::HttpRequestMetadata;
     use uuid::Uuid;
     use value::{
         heap_size::WithHeapSize,
@@ -580,6 +581,10 @@ mod op_provider {
             todo!()
         }
 
+        fn get_http_request(&mut self) -> anyhow::Result<Option<HttpRequestMetadata>> {
+            Ok(None)
+        }
+
         fn get_all_table_mappings(&mut self) -> anyhow::Result<NamespacedTableMapping> {
             self.context_state()?.environment.get_all_table_mappings()
         }
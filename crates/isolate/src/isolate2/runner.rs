@@ -555,6 +555,7 @@ async fn run_request<RT: Runtime>(
             syscall_trace: SyscallTrace::new(),
             udf_server_version,
             memory_in_mb: 0,
+            permit_wait: Duration::ZERO,
         };
         return Ok(outcome);
     }
@@ -701,6 +702,7 @@ async fn run_request<RT: Runtime>(
         memory_in_mb: (*ISOLATE_MAX_USER_HEAP_SIZE / (1 << 20))
             .try_into()
             .unwrap(),
+        permit_wait: Duration::ZERO,
     };
     Ok(outcome)
 }
@@ -14,6 +14,7 @@ mod array_buffer_allocator;
 pub mod bundled_js;
 pub mod client;
 mod concurrency_limiter;
+pub mod cpu_budget;
 pub mod environment;
 pub mod error;
 mod execution_scope;
@@ -44,11 +45,13 @@ pub use self::{
         ActionRequestParams,
         IsolateClient,
         IsolateConfig,
+        IsolateStartStats,
         UdfCallback,
     },
     concurrency_limiter::{
         ConcurrencyLimiter,
         ConcurrencyPermit,
+        ConcurrencyPermitStats,
     },
     execution_scope::ExecutionScope,
     helpers::{
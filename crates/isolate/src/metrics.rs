@@ -352,6 +352,22 @@ pub fn log_user_timeout() {
     log_counter(&UDF_USER_TIMEOUT_TOTAL, 1);
 }
 
+register_convex_counter!(
+    UDF_CPU_BUDGET_EXCEEDED_TOTAL,
+    "Number of UDFs terminated for exceeding their CPU budget"
+);
+pub fn log_cpu_budget_exceeded() {
+    log_counter(&UDF_CPU_BUDGET_EXCEEDED_TOTAL, 1);
+}
+
+register_convex_counter!(
+    UDF_CPU_YIELD_TOTAL,
+    "Number of times a UDF yielded its concurrency permit after running its ops per yield"
+);
+pub fn log_cpu_yield() {
+    log_counter(&UDF_CPU_YIELD_TOTAL, 1);
+}
+
 register_convex_counter!(UDF_SYSTEM_TIMEOUT_TOTAL, "Number of UDF system timeouts");
 pub fn log_system_timeout() {
     log_counter(&UDF_SYSTEM_TIMEOUT_TOTAL, 1);
@@ -555,6 +571,19 @@ pub fn create_isolate_timer() -> Timer<prometheus::VMHistogram> {
     Timer::new(&CREATE_ISOLATE_SECONDS)
 }
 
+register_convex_histogram!(
+    ISOLATE_REQUEST_SECONDS,
+    "Time to handle a request on an isolate worker, split by whether the request ran on a newly \
+     created isolate (cold) or one reused from a previous request (warm)",
+    &["start"]
+);
+pub fn isolate_request_timer(reused: bool) -> Timer<VMHistogramVec> {
+    let mut timer = Timer::new_with_labels(&ISOLATE_REQUEST_SECONDS);
+    let start = if reused { "warm" } else { "cold" };
+    timer.add_label(StaticMetricLabel::new("start", start));
+    timer
+}
+
 register_convex_histogram!(CREATE_CONTEXT_SECONDS, "Time to create a new V8 context");
 pub fn create_context_timer() -> Timer<prometheus::VMHistogram> {
     Timer::new(&CREATE_CONTEXT_SECONDS)
@@ -1,7 +1,10 @@
 use std::str::FromStr;
 
 use anyhow::Context;
-use common::sync::spsc;
+use common::{
+    knobs::FETCH_DEFAULT_USER_AGENT,
+    sync::spsc,
+};
 use deno_core::{
     serde_v8,
     v8::{
@@ -9,7 +12,11 @@ use deno_core::{
     },
 };
 use errors::ErrorMetadata;
-use headers::HeaderName;
+use headers::{
+    HeaderName,
+    HeaderValue,
+};
+use http::header::USER_AGENT;
 use serde::{
     Deserialize,
     Serialize,
@@ -41,7 +48,13 @@ pub fn async_op_fetch<'b, P: OpProvider<'b>>(
 ) -> anyhow::Result<()> {
     let arg: HttpRequestV8 = serde_v8::from_v8(provider.scope(), args.get(1))?;
 
-    let request = with_argument_error("fetch", || HttpRequestV8::into_stream(arg, provider))?;
+    let mut request = with_argument_error("fetch", || HttpRequestV8::into_stream(arg, provider))?;
+    if !request.headers.contains_key(USER_AGENT) {
+        request.headers.insert(
+            USER_AGENT,
+            HeaderValue::from_str(&FETCH_DEFAULT_USER_AGENT)?,
+        );
+    }
     let response_body_stream_id = provider.create_stream()?;
     provider.start_async_op(
         AsyncOpRequest::Fetch {
@@ -11,6 +11,7 @@ mod environment_variables;
 mod errors;
 mod http;
 mod random;
+mod request_metadata;
 mod storage;
 mod stream;
 mod structured_clone;
@@ -49,6 +50,7 @@ use deno_core::{
 use rand_chacha::ChaCha12Rng;
 use sourcemap::SourceMap;
 use structured_clone::op_structured_clone;
+use udf::HttpRequestMetadata;
 use uuid::Uuid;
 use validate_returns::op_validate_returns;
 use value::{
@@ -110,6 +112,10 @@ use self::{
         op_url_stringify_url_search_params,
         op_url_update_url_info,
     },
+    request_metadata::{
+        op_request_metadata_get,
+        op_request_metadata_get_header,
+    },
     storage::{
         async_op_storage_get,
         async_op_storage_store,
@@ -200,6 +206,8 @@ pub trait OpProvider<'b> {
     fn get_environment_variable(&mut self, name: EnvVarName)
         -> anyhow::Result<Option<EnvVarValue>>;
 
+    fn get_http_request(&mut self) -> anyhow::Result<Option<HttpRequestMetadata>>;
+
     fn get_all_table_mappings(&mut self) -> anyhow::Result<NamespacedTableMapping>;
 }
 
@@ -346,6 +354,11 @@ impl<'a, 'b: 'a, RT: Runtime, E: IsolateEnvironment<RT>> OpProvider<'b>
         state.environment.get_environment_variable(name)
     }
 
+    fn get_http_request(&mut self) -> anyhow::Result<Option<HttpRequestMetadata>> {
+        let state = self.state_mut()?;
+        Ok(state.environment.get_http_request()?.cloned())
+    }
+
     fn get_all_table_mappings(&mut self) -> anyhow::Result<NamespacedTableMapping> {
         let state = self.state_mut()?;
         state.environment.get_all_table_mappings()
@@ -399,6 +412,8 @@ pub fn run_op<'b, P: OpProvider<'b>>(
         "btoa" => op_btoa(provider, args, rv)?,
         "structuredClone" => op_structured_clone(provider, args.get(1), rv)?,
         "environmentVariables/get" => op_environment_variables_get(provider, args, rv)?,
+        "requestMetadata/get" => op_request_metadata_get(provider, args, rv)?,
+        "requestMetadata/getHeader" => op_request_metadata_get_header(provider, args, rv)?,
         "getTableMapping" => op_get_table_mapping(provider, args, rv)?,
         "validateArgs" => op_validate_args(provider, args, rv)?,
         "validateReturns" => op_validate_returns(provider, args, rv)?,
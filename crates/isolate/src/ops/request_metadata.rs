@@ -0,0 +1,35 @@
+use serde_json::{
+    json,
+    Value as JsonValue,
+};
+
+use super::OpProvider;
+
+#[convex_macro::v8_op]
+pub fn op_request_metadata_get<'b, P: OpProvider<'b>>(
+    provider: &mut P,
+) -> anyhow::Result<Option<JsonValue>> {
+    let Some(request) = provider.get_http_request()? else {
+        return Ok(None);
+    };
+    Ok(Some(json!({
+        "method": request.method,
+        "path": request.path,
+        "headers": request.headers,
+    })))
+}
+
+#[convex_macro::v8_op]
+pub fn op_request_metadata_get_header<'b, P: OpProvider<'b>>(
+    provider: &mut P,
+    name: String,
+) -> anyhow::Result<Vec<String>> {
+    let Some(request) = provider.get_http_request()? else {
+        return Ok(vec![]);
+    };
+    Ok(request
+        .header_values(&name)
+        .into_iter()
+        .map(|value| value.to_string())
+        .collect())
+}
@@ -4,6 +4,7 @@ use std::{
         VecDeque,
     },
     marker::PhantomData,
+    time::Duration,
 };
 
 use anyhow::{
@@ -33,6 +34,7 @@ use value::heap_size::{
 
 use crate::{
     concurrency_limiter::ConcurrencyPermit,
+    cpu_budget::OpCounter,
     environment::{
         IsolateEnvironment,
         UncatchableDeveloperError,
@@ -90,6 +92,8 @@ pub struct RequestState<RT: Runtime, E: IsolateEnvironment<RT>> {
     pub rt: RT,
     pub timeout: Timeout<RT>,
     pub permit: Option<ConcurrencyPermit>,
+    /// How long the request waited for its first concurrency permit.
+    pub permit_wait: Duration,
     pub environment: E,
 
     pub blob_parts: WithHeapSize<BTreeMap<uuid::Uuid, bytes::Bytes>>,
@@ -101,6 +105,7 @@ pub struct RequestState<RT: Runtime, E: IsolateEnvironment<RT>> {
     // This is not wrapped in `WithHeapSize` so we can return `&mut TextDecoderStream`.
     // Additionally, `TextDecoderResource` should have a fairly small heap size.
     pub text_decoders: BTreeMap<uuid::Uuid, TextDecoderResource>,
+    pub op_counter: OpCounter,
 }
 
 pub struct RequestStreamState {
@@ -442,6 +447,12 @@ impl<'a, 'b: 'a, RT: Runtime, E: IsolateEnvironment<RT>> RequestScope<'a, 'b, RT
         self.scope.remove_slot()
     }
 
+    /// How long the request waited for its first concurrency permit.
+    pub fn permit_wait(&self) -> Duration {
+        let state: &RequestState<RT, E> = self.scope.get_slot().expect("Lost ContextState?");
+        state.permit_wait
+    }
+
     pub fn take_environment(mut self) -> (E, FunctionExecutionTime) {
         let state = self.take_state().expect("Lost ContextState?");
         (
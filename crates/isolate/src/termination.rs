@@ -1,4 +1,5 @@
 use std::{
+    ffi::c_void,
     sync::Arc,
     time::Duration,
 };
@@ -26,6 +27,8 @@ pub enum TerminationReason {
     UnhandledPromiseRejection(JsError),
     UserTimeout(Duration),
     SystemTimeout(Duration),
+    CpuBudgetExceeded(u64),
+    MemoryLimitExceeded(usize),
     OutOfMemory,
 }
 
@@ -37,6 +40,8 @@ impl TerminationReason {
             Self::UnhandledPromiseRejection(e) => Self::UnhandledPromiseRejection(e.clone()),
             Self::UserTimeout(d) => Self::UserTimeout(*d),
             Self::SystemTimeout(d) => Self::SystemTimeout(*d),
+            Self::CpuBudgetExceeded(ops) => Self::CpuBudgetExceeded(*ops),
+            Self::MemoryLimitExceeded(limit) => Self::MemoryLimitExceeded(*limit),
             Self::OutOfMemory => Self::OutOfMemory,
         }
     }
@@ -48,6 +53,8 @@ impl TerminationReason {
             Self::UnhandledPromiseRejection(_) => IsolateNotClean::UnhandledPromiseRejection,
             Self::UserTimeout(_) => IsolateNotClean::UserTimeout,
             Self::SystemTimeout(_) => IsolateNotClean::SystemTimeout,
+            Self::CpuBudgetExceeded(_) => IsolateNotClean::CpuBudgetExceeded,
+            Self::MemoryLimitExceeded(_) => IsolateNotClean::MemoryLimitExceeded,
             Self::OutOfMemory => IsolateNotClean::OutOfMemory,
         }
     }
@@ -151,6 +158,12 @@ impl IsolateHandle {
                     TerminationReason::UserTimeout(max_duration) => Ok(Err(JsError::from_message(
                         format!("{}", UserTimeoutError(max_duration)),
                     ))),
+                    TerminationReason::CpuBudgetExceeded(ops) => Ok(Err(JsError::from_message(
+                        format!("{}", CpuBudgetExceededError(ops)),
+                    ))),
+                    TerminationReason::MemoryLimitExceeded(limit) => Ok(Err(
+                        JsError::from_message(format!("{}", MemoryLimitExceededError(limit))),
+                    )),
                     TerminationReason::OutOfMemory => {
                         log_isolate_out_of_memory();
                         // We report this error here because otherwise it is only surfaced to users
@@ -211,6 +224,14 @@ impl ContextHandle {
         }
         self.isolate_handle.terminate(reason)
     }
+
+    /// Run `callback` on the isolate's thread the next time it runs
+    /// JavaScript. Returns `false` if the isolate has been disposed.
+    pub fn request_interrupt(&self, callback: v8::InterruptCallback, data: *mut c_void) -> bool {
+        self.isolate_handle
+            .v8_handle
+            .request_interrupt(callback, data)
+    }
 }
 
 #[derive(Debug, Error)]
@@ -220,3 +241,17 @@ pub struct OutOfMemoryError;
 #[derive(Error, Debug)]
 #[error("Function execution timed out (maximum duration: {0:?})")]
 pub struct UserTimeoutError(Duration);
+
+#[derive(Error, Debug)]
+#[error(
+    "CpuBudgetExceeded: Function ran more than {0} ops of JavaScript. Break up long synchronous \
+     computations or move them into an action."
+)]
+pub struct CpuBudgetExceededError(u64);
+
+#[derive(Error, Debug)]
+#[error(
+    "MemoryLimitExceeded: Function used more than {0} bytes of memory. Process data in smaller \
+     batches or paginate instead of loading it all at once."
+)]
+pub struct MemoryLimitExceededError(usize);
@@ -1,6 +1,7 @@
 use std::{
     str::FromStr,
     sync::LazyLock,
+    time::Duration,
 };
 
 use common::{
@@ -48,6 +49,7 @@ use value::{
 
 use crate::{
     concurrency_limiter::ConcurrencyLimiter,
+    cpu_budget::CpuBudget,
     environment::helpers::MAX_LOG_LINES,
     test_helpers::{
         UdfTest,
@@ -76,6 +78,27 @@ async fn test_time_out(rt: ProdRuntime) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[convex_macro::prod_rt_test]
+async fn test_cpu_budget_stops_runaway_loop(rt: ProdRuntime) -> anyhow::Result<()> {
+    let config = UdfTestConfig {
+        isolate_config: IsolateConfig::new("cpu_budget_test", ConcurrencyLimiter::unlimited())
+            .with_cpu_budget(CpuBudget {
+                op_interval: Duration::from_millis(1),
+                ops_per_yield: 10,
+                max_ops: Some(100),
+            }),
+        udf_server_version: Version::parse("1000.0.0").unwrap(),
+    };
+    let t = UdfTest::default_with_config(config, MAX_ISOLATE_WORKERS, rt.clone()).await?;
+    // The loop runs until it's used up its op budget, yielding its permit every
+    // ten ops along the way, and is stopped well before the query's user timeout.
+    let e = t
+        .query_js_error("adversarial:simpleLoop", assert_obj!())
+        .await?;
+    assert_contains(&e, "CpuBudgetExceeded");
+    Ok(())
+}
+
 #[convex_macro::prod_rt_test]
 async fn test_wasm_simple_loop(rt: ProdRuntime) -> anyhow::Result<()> {
     let t = UdfTest::default_with_config(TIMEOUT_CONFIG.clone(), MAX_ISOLATE_WORKERS, rt.clone())
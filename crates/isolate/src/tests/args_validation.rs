@@ -42,6 +42,56 @@ async fn test_missing_arg(rt: TestRuntime) -> anyhow::Result<()> {
     .await
 }
 
+#[convex_macro::test_runtime]
+async fn test_argument_validation_error_is_distinct(rt: TestRuntime) -> anyhow::Result<()> {
+    UdfTest::run_test_with_isolate2(rt, async move |t: UdfTestType| {
+        let e = t
+            .mutation_js_error(
+                "args_validation:insertObject",
+                assert_obj!("name" => "boat", "count" => "three"),
+            )
+            .await?;
+        must_let!(let Some(validation) = e.argument_validation);
+        assert_eq!(validation.field_path.as_deref(), Some(".count"));
+        assert_eq!(validation.expected.as_deref(), Some("v.float64()"));
+
+        let e = t
+            .mutation_js_error(
+                "args_validation:insertObject",
+                assert_obj!("name" => "boat"),
+            )
+            .await?;
+        must_let!(let Some(validation) = e.argument_validation);
+        assert_eq!(validation.field_path.as_deref(), Some(".count"));
+
+        // An error thrown by the function itself isn't a validation error.
+        let e = t
+            .mutation_js_error("custom_errors:mutationThrows", assert_obj!())
+            .await?;
+        assert!(e.argument_validation.is_none());
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_default_arg(rt: TestRuntime) -> anyhow::Result<()> {
+    UdfTest::run_test_with_isolate2(rt, async move |t: UdfTestType| {
+        let result = t.query("args_validation:defaultArg", assert_obj!()).await?;
+        assert_eq!(result, ConvexValue::try_from("defaultValue")?);
+
+        let result = t
+            .query(
+                "args_validation:defaultArg",
+                assert_obj!("arg" => "argValue"),
+            )
+            .await?;
+        assert_eq!(result, ConvexValue::try_from("argValue")?);
+        Ok(())
+    })
+    .await
+}
+
 #[convex_macro::test_runtime]
 async fn test_extra_arg(rt: TestRuntime) -> anyhow::Result<()> {
     UdfTest::run_test_with_isolate2(rt, async move |t: UdfTestType| {
@@ -11,7 +11,9 @@ use keybroker::{
 };
 use must_let::must_let;
 use runtime::testing::TestRuntime;
+use serde_json::json;
 use sync_types::UserIdentityAttributes;
+use value::assert_val;
 
 use crate::test_helpers::{
     UdfTest,
@@ -118,3 +120,53 @@ async fn test_auth_identity_for_admin(rt: TestRuntime) -> anyhow::Result<()> {
         Ok(())
     }).await
 }
+
+#[convex_macro::test_runtime]
+async fn test_get_identity_claims(rt: TestRuntime) -> anyhow::Result<()> {
+    UdfTest::run_test_with_isolate2(rt, async move |t| {
+        // Without an identity there are no claims to read.
+        must_let!(let ConvexValue::Null = t.query("auth:getClaim", assert_obj!("name" => "email")).await?);
+        let identity = Identity::ActingUser(
+            AdminIdentity::new_for_test_only("chocolate-charlie-420".to_string(), MemberId(0)),
+            UserIdentityAttributes {
+                custom_claims: [("roles".to_string(), r#"["admin"]"#.to_string())].into(),
+                ..UserIdentityAttributes::test()
+            },
+        );
+        let email = t
+            .query_with_identity("auth:getClaim", assert_obj!("name" => "email"), identity.clone())
+            .await?;
+        assert_eq!(email, assert_val!("bozo@convex.dev"));
+        let roles = t
+            .query_with_identity("auth:getClaim", assert_obj!("name" => "roles"), identity.clone())
+            .await?;
+        assert_eq!(roles, assert_val!(["admin"]));
+        must_let!(let ConvexValue::Null = t.query_with_identity("auth:getClaim", assert_obj!("name" => "phoneNumber"), identity).await?);
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_auth_for_test_user(rt: TestRuntime) -> anyhow::Result<()> {
+    UdfTest::run_test_with_isolate2(rt, async move |t| {
+        let admin =
+            Identity::for_test_user("user|1", [("role".to_string(), json!("admin"))].into());
+        t.mutation_with_identity("auth:insertAsAdmin", assert_obj!(), admin)
+            .await?;
+
+        let member =
+            Identity::for_test_user("user|2", [("role".to_string(), json!("member"))].into());
+        let outcome = t
+            .raw_mutation(
+                "auth:insertAsAdmin",
+                vec![ConvexValue::Object(assert_obj!())],
+                member,
+            )
+            .await?;
+        let error = outcome.result.unwrap_err();
+        assert!(error.message.contains("Only admins can insert"), "{error}");
+        Ok(())
+    })
+    .await
+}
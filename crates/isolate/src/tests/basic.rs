@@ -1,5 +1,7 @@
 #![allow(clippy::float_cmp)]
 
+use std::collections::BTreeSet;
+
 use common::{
     assert_obj,
     document::CreationTime,
@@ -15,9 +17,13 @@ use must_let::must_let;
 use runtime::testing::TestRuntime;
 use value::assert_val;
 
-use crate::test_helpers::{
-    UdfTest,
-    UdfTestType,
+use crate::{
+    test_helpers::{
+        UdfTest,
+        UdfTestType,
+        DEFAULT_CONFIG,
+    },
+    IsolateStartStats,
 };
 
 #[convex_macro::test_runtime]
@@ -86,6 +92,50 @@ async fn test_insert_object(rt: TestRuntime) -> anyhow::Result<()> {
     .await
 }
 
+#[convex_macro::test_runtime]
+async fn test_parallel_insert(rt: TestRuntime) -> anyhow::Result<()> {
+    UdfTest::run_test_with_isolate2(rt, async move |t: UdfTestType| {
+        // The inserts are issued concurrently from JavaScript.
+        must_let!(let ConvexValue::Array(ids) = t.mutation(
+            "basic:parallelInsert",
+            assert_obj!("values" => ["a", "b", "c"]),
+        ).await?);
+        assert_eq!(ids.len(), 3);
+        assert_eq!(ids.iter().collect::<BTreeSet<_>>().len(), 3);
+
+        // Ids come back in the same order as the inputs.
+        for (id, expected) in ids.iter().zip(["a", "b", "c"]) {
+            must_let!(let ConvexValue::Object(obj) = t.query("basic:getObject", assert_obj!("id" => id.clone())).await?);
+            assert_eq!(obj.get("field"), Some(&assert_val!(expected)));
+        }
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_patch_returning_previous(rt: TestRuntime) -> anyhow::Result<()> {
+    UdfTest::run_test_with_isolate2(rt, async move |t: UdfTestType| {
+        must_let!(let ConvexValue::Object(inserted) = t.mutation(
+            "basic:insertObject",
+            assert_obj!("field" => "before", "other" => 1),
+        ).await?);
+        must_let!(let Some(id) = inserted.get("_id"));
+
+        must_let!(let ConvexValue::Object(result) = t.mutation(
+            "basic:patchReturningPrevious",
+            assert_obj!("id" => id.clone(), "value" => {"field" => "after"}),
+        ).await?);
+        must_let!(let Some(ConvexValue::Object(previous)) = result.get("previous"));
+        assert_eq!(previous, &inserted);
+        must_let!(let Some(ConvexValue::Object(value)) = result.get("value"));
+        assert_eq!(value.get("field"), Some(&assert_val!("after")));
+        assert_eq!(value.get("other"), Some(&assert_val!(1)));
+        Ok(())
+    })
+    .await
+}
+
 #[convex_macro::test_runtime]
 async fn test_references(rt: TestRuntime) -> anyhow::Result<()> {
     UdfTest::run_test_with_isolate2(rt, async move |t: UdfTestType| {
@@ -100,6 +150,29 @@ async fn test_references(rt: TestRuntime) -> anyhow::Result<()> {
     .await
 }
 
+#[convex_macro::test_runtime]
+async fn test_isolate_start_stats(rt: TestRuntime) -> anyhow::Result<()> {
+    // With a single worker every request lands on the same isolate.
+    let t = UdfTest::default_with_config(DEFAULT_CONFIG.clone(), 1, rt).await?;
+    // Analyzing the modules during setup was the first request, so it had to
+    // create the isolate.
+    assert_eq!(
+        t.isolate.aggregate_start_stats(),
+        IsolateStartStats {
+            cold_starts: 1,
+            warm_starts: 0,
+        }
+    );
+
+    t.query("basic:doNothing", assert_obj!()).await?;
+    let before = t.isolate.aggregate_start_stats();
+    t.query("basic:doNothing", assert_obj!()).await?;
+    let after = t.isolate.aggregate_start_stats();
+    assert_eq!(after.cold_starts, before.cold_starts);
+    assert_eq!(after.warm_starts, before.warm_starts + 1);
+    Ok(())
+}
+
 #[convex_macro::test_runtime]
 async fn test_observed_time(rt: TestRuntime) -> anyhow::Result<()> {
     UdfTest::run_test_with_isolate2(rt, async move |t: UdfTestType| {
@@ -1,10 +1,14 @@
-use std::collections::HashSet;
+use std::collections::{
+    BTreeSet,
+    HashSet,
+};
 
 use common::{
     assert_obj,
     value::ConvexValue,
 };
 use keybroker::Identity;
+use maplit::btreeset;
 use model::environment_variables::{
     types::EnvironmentVariable,
     EnvironmentVariablesModel,
@@ -70,6 +74,78 @@ async fn test_get_environment_variable_null(rt: TestRuntime) -> anyhow::Result<(
     .await
 }
 
+#[convex_macro::test_runtime]
+async fn test_scoped_environment_variables(rt: TestRuntime) -> anyhow::Result<()> {
+    UdfTest::run_test_with_isolate(rt, async |t| {
+        let mut tx = t.database.begin(Identity::system()).await?;
+        let mut model = EnvironmentVariablesModel::new(&mut tx);
+        for (name, value) in [("TEST_NAME", "TEST_VALUE"), ("TEST_NAME_2", "TEST_VALUE_2")] {
+            model
+                .create(
+                    EnvironmentVariable::new(name.parse()?, value.parse()?),
+                    &HashSet::new(),
+                )
+                .await?;
+        }
+        model
+            .set_scope(
+                "TEST_NAME".parse()?,
+                btreeset! { "environmentVariables:getBothEnvironmentVariables".parse()? },
+            )
+            .await?;
+        model
+            .set_scope(
+                "TEST_NAME_2".parse()?,
+                btreeset! { "environmentVariables:getOtherEnvironmentVariable".parse()? },
+            )
+            .await?;
+        t.database.commit(tx).await?;
+
+        // Each function only sees the variables scoped to it.
+        let value = t
+            .query(
+                "environmentVariables:getBothEnvironmentVariables",
+                assert_obj!(),
+            )
+            .await?;
+        assert_eq!(
+            value,
+            assert_val!({"first" => "TEST_VALUE", "second" => ConvexValue::Null})
+        );
+        let value = t
+            .query(
+                "environmentVariables:getOtherEnvironmentVariable",
+                assert_obj!(),
+            )
+            .await?;
+        assert_eq!(value, ConvexValue::try_from("TEST_VALUE_2")?);
+        let value = t
+            .query("environmentVariables:getEnvironmentVariable", assert_obj!())
+            .await?;
+        assert_eq!(value, ConvexValue::Null);
+        let value = t
+            .action(
+                "environmentVariables:actionGetEnvironmentVariable",
+                assert_obj!(),
+            )
+            .await?;
+        assert_eq!(value, ConvexValue::Null);
+
+        // Removing the scope makes the variable visible everywhere again.
+        let mut tx = t.database.begin(Identity::system()).await?;
+        EnvironmentVariablesModel::new(&mut tx)
+            .set_scope("TEST_NAME".parse()?, BTreeSet::new())
+            .await?;
+        t.database.commit(tx).await?;
+        let value = t
+            .query("environmentVariables:getEnvironmentVariable", assert_obj!())
+            .await?;
+        assert_eq!(value, ConvexValue::try_from("TEST_VALUE")?);
+        Ok(())
+    })
+    .await
+}
+
 #[convex_macro::test_runtime]
 async fn test_console_log(rt: TestRuntime) -> anyhow::Result<()> {
     UdfTest::run_test_with_isolate(rt, async |t| {
@@ -1,4 +1,8 @@
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::Duration,
+};
 
 use common::{
     assert_obj,
@@ -32,9 +36,17 @@ use value::{
     ConvexObject,
 };
 
-use crate::test_helpers::{
-    UdfTest,
-    UdfTestType,
+use crate::{
+    concurrency_limiter::{
+        ConcurrencyLimiter,
+        ConcurrencyPermitStats,
+    },
+    test_helpers::{
+        UdfTest,
+        UdfTestConfig,
+        UdfTestType,
+    },
+    IsolateConfig,
 };
 
 async fn add_index<RT: Runtime, P: Persistence>(t: &UdfTest<RT, P>) -> anyhow::Result<()> {
@@ -920,3 +932,43 @@ async fn test_query_with_pending_deletes(rt: TestRuntime) -> anyhow::Result<()>
     })
     .await
 }
+
+#[convex_macro::test_runtime]
+async fn test_query_permit_wait(rt: TestRuntime) -> anyhow::Result<()> {
+    let limiter = ConcurrencyLimiter::new(1);
+    let t = UdfTest::default_with_config(
+        UdfTestConfig {
+            isolate_config: IsolateConfig::new("permit_wait_test", limiter.clone()),
+            udf_server_version: "1000.0.0".parse()?,
+        },
+        1,
+        rt.clone(),
+    )
+    .await?;
+
+    // Hold the only permit so the query has to wait for it.
+    let permit = limiter.acquire(Arc::new("test".to_string())).await;
+    assert_eq!(
+        limiter.permit_stats(),
+        ConcurrencyPermitStats {
+            held: 1,
+            available: Some(0),
+        }
+    );
+    let release = async {
+        rt.wait(Duration::from_millis(50)).await;
+        drop(permit);
+    };
+    let (result, ()) = futures::join!(
+        t.query_outcome("basic:doNothing", assert_obj!(), Identity::system()),
+        release,
+    );
+    let (_, outcome) = result?;
+    assert!(
+        outcome.permit_wait >= Duration::from_millis(50),
+        "{:?}",
+        outcome.permit_wait
+    );
+    assert_eq!(limiter.permit_stats().held, 0);
+    Ok(())
+}
@@ -218,6 +218,12 @@ async fn test_schedule_too_many(rt: TestRuntime) -> anyhow::Result<()> {
             "Too many functions scheduled by this mutation (limit: 1000)",
         );
 
+        // The failed mutation didn't commit any of the jobs it scheduled before
+        // hitting the limit.
+        let result = t.query("scheduler:getScheduledJobs", assert_obj!()).await?;
+        must_let!(let ConvexValue::Array(scheduled_jobs) = result);
+        assert_eq!(scheduled_jobs.len(), 100);
+
         Ok(())
     })
     .await
@@ -170,11 +170,18 @@ impl<RT: Runtime> Timeout<RT> {
     }
 
     pub fn pause(&mut self) -> PauseGuard<'_, RT> {
+        self.try_pause()
+            .expect("Overlapping calls to timeout.pause()")
+    }
+
+    /// Like [`Timeout::pause`], but returns `None` instead of panicking if the
+    /// timeout isn't currently running.
+    pub fn try_pause(&mut self) -> Option<PauseGuard<'_, RT>> {
         let (tx, rx) = broadcast(1);
         let pause_start = {
             let mut inner = self.inner.lock();
             let TimeoutState::Running = inner.state else {
-                panic!("Overlapping calls to timeout.pause()");
+                return None;
             };
             let pause_start = inner.rt.monotonic_now();
             inner.state = TimeoutState::Paused {
@@ -183,11 +190,11 @@ impl<RT: Runtime> Timeout<RT> {
             };
             pause_start
         };
-        PauseGuard {
+        Some(PauseGuard {
             timeout: self,
             pause_start,
             pause_done: Some(tx),
-        }
+        })
     }
 
     pub fn finish(&mut self) {
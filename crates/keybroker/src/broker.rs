@@ -39,6 +39,7 @@ use common::{
         MemberId,
         PersistenceVersion,
         TeamId,
+        Timestamp,
         UdfType,
     },
 };
@@ -76,7 +77,10 @@ use pb::{
         AdminKey as AdminKeyProto,
         StorageToken as StorageTokenProto,
     },
-    convex_query_journal::InstanceQueryJournal as InstanceQueryJournalProto,
+    convex_query_journal::{
+        InstanceQueryJournal as InstanceQueryJournalProto,
+        PaginatedQueryCursor as PaginatedQueryCursorProto,
+    },
 };
 #[cfg(any(test, feature = "testing"))]
 use proptest::prelude::{
@@ -116,6 +120,7 @@ const ADMIN_KEY_VERSION: u8 = 1;
 const CURSOR_VERSION: u8 = 7;
 const STORE_FILE_AUTHZ_VERSION: u8 = 1;
 const QUERY_JOURNAL_VERSION: u8 = 7;
+const PAGINATED_QUERY_CURSOR_VERSION: u8 = 1;
 
 // Max delay from transaction start time -> key being issued that is tolerable.
 const MAX_TS_DELAY: Duration = Duration::from_secs(15);
@@ -128,6 +133,7 @@ pub struct KeyBroker {
     action_callback_encryptor: RandomEncryptor,
     cursor_encryptor: DeterministicEncryptor,
     journal_encryptor: RandomEncryptor,
+    paginated_query_cursor_encryptor: RandomEncryptor,
     store_file_encryptor: RandomEncryptor,
 }
 
@@ -442,7 +448,7 @@ pub type CoreIdTokenWithCustomClaims = IdToken<
 >;
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
-pub struct CustomClaims(HashMap<String, serde_json::Value>);
+pub struct CustomClaims(pub(crate) HashMap<String, serde_json::Value>);
 impl AdditionalClaims for CustomClaims {}
 
 impl UserIdentity {
@@ -773,6 +779,16 @@ pub struct StoreFileAuthorization(String);
 #[derive(Debug, derive_more::Display)]
 pub struct GetFileAuthorization(String);
 
+/// Where a paginated query left off: the function that issued it, the
+/// snapshot its pages read, and the query's own cursor into its index range.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaginatedQueryCursor {
+    pub component_path: String,
+    pub udf_path: String,
+    pub ts: Timestamp,
+    pub cursor: String,
+}
+
 pub fn cursor_parse_error() -> ErrorMetadata {
     ErrorMetadata::bad_request("InvalidCursor", "Failed to parse cursor")
 }
@@ -798,6 +814,10 @@ impl KeyBroker {
                 &instance_secret,
                 Purpose::QUERY_JOURNAL,
             )?,
+            paginated_query_cursor_encryptor: RandomEncryptor::derive_from_secret(
+                &instance_secret,
+                Purpose::PAGINATED_QUERY_CURSOR,
+            )?,
             store_file_encryptor: RandomEncryptor::derive_from_secret(
                 &instance_secret,
                 Purpose::STORE_FILE_AUTHORIZATION,
@@ -1040,6 +1060,33 @@ impl KeyBroker {
         }
     }
 
+    pub fn encrypt_paginated_query_cursor(&self, cursor: &PaginatedQueryCursor) -> String {
+        let proto = PaginatedQueryCursorProto {
+            component_path: Some(cursor.component_path.clone()),
+            udf_path: Some(cursor.udf_path.clone()),
+            ts: Some(cursor.ts.into()),
+            cursor: Some(cursor.cursor.clone()),
+        };
+        self.paginated_query_cursor_encryptor
+            .encrypt_proto(PAGINATED_QUERY_CURSOR_VERSION, &proto)
+    }
+
+    pub fn decrypt_paginated_query_cursor(
+        &self,
+        cursor: &str,
+    ) -> anyhow::Result<PaginatedQueryCursor> {
+        let proto: PaginatedQueryCursorProto = self
+            .paginated_query_cursor_encryptor
+            .decrypt_proto(PAGINATED_QUERY_CURSOR_VERSION, cursor)
+            .with_context(cursor_parse_error)?;
+        Ok(PaginatedQueryCursor {
+            component_path: proto.component_path.context("Missing component_path")?,
+            udf_path: proto.udf_path.context("Missing udf_path")?,
+            ts: proto.ts.context("Missing ts")?.try_into()?,
+            cursor: proto.cursor.context("Missing cursor")?,
+        })
+    }
+
     pub fn issue_action_token(&self, component_id: ComponentId) -> ActionCallbackToken {
         let now = SystemTime::now();
         let since_epoch = now
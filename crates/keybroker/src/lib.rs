@@ -25,6 +25,7 @@ pub use self::{
         GetFileAuthorization,
         Identity,
         KeyBroker,
+        PaginatedQueryCursor,
         StoreFileAuthorization,
         SystemKey,
         UserIdentity,
@@ -1,4 +1,7 @@
-use std::sync::LazyLock;
+use std::{
+    collections::BTreeMap,
+    sync::LazyLock,
+};
 
 use chrono::{
     Duration,
@@ -6,6 +9,7 @@ use chrono::{
 };
 use openidconnect::{
     core::{
+        CoreGenderClaim,
         CoreIdTokenVerifier,
         CoreJwsSigningAlgorithm,
         CoreRsaPrivateSigningKey,
@@ -27,6 +31,7 @@ use crate::{
         CoreIdTokenWithCustomClaims,
         CustomClaims,
     },
+    Identity,
     UserIdentity,
 };
 
@@ -42,33 +47,56 @@ pub trait TestUserIdentity {
 
 impl TestUserIdentity for UserIdentity {
     fn test() -> Self {
-        let subject = "testauth|123".to_owned();
-        let issuer = "https://testauth.fake.domain".to_owned();
-        let audience = Audience::new("client-id-123".to_string());
-
-        let token = CoreIdTokenWithCustomClaims::new(
-            IdTokenClaims::new(
-                IssuerUrl::new(issuer).unwrap(),
-                vec![audience],
-                Utc::now() + Duration::seconds(600),
-                Utc::now(),
-                StandardClaims::new(SubjectIdentifier::new(subject))
-                    .set_email(Some(EndUserEmail::new("foo@bar.com".to_string())))
-                    .set_name(Some(EndUserName::new("Al Pastor".to_string()).into())),
-                CustomClaims::default(),
-            ),
-            &*TEST_SIGNING_KEY,
-            CoreJwsSigningAlgorithm::RsaSsaPkcs1V15Sha256,
-            None,
-            None,
-        )
-        .unwrap();
+        let claims = StandardClaims::new(SubjectIdentifier::new("testauth|123".to_owned()))
+            .set_email(Some(EndUserEmail::new("foo@bar.com".to_string())))
+            .set_name(Some(EndUserName::new("Al Pastor".to_string()).into()));
+        from_signed_token(claims, CustomClaims::default())
+    }
+}
 
-        let verifier = CoreIdTokenVerifier::new_insecure_without_verification();
-        UserIdentity::from_token(token, verifier).unwrap()
+impl Identity {
+    /// An end user with `subject` and `custom_claims`, decoded from a freshly
+    /// signed token the same way a real one is, so functions see exactly the
+    /// fields they would in production.
+    pub fn for_test_user(
+        subject: &str,
+        custom_claims: BTreeMap<String, serde_json::Value>,
+    ) -> Self {
+        let claims = StandardClaims::new(SubjectIdentifier::new(subject.to_owned()));
+        Identity::user(from_signed_token(
+            claims,
+            CustomClaims(custom_claims.into_iter().collect()),
+        ))
     }
 }
 
+fn from_signed_token(
+    standard_claims: StandardClaims<CoreGenderClaim>,
+    custom_claims: CustomClaims,
+) -> UserIdentity {
+    let issuer = "https://testauth.fake.domain".to_owned();
+    let audience = Audience::new("client-id-123".to_string());
+
+    let token = CoreIdTokenWithCustomClaims::new(
+        IdTokenClaims::new(
+            IssuerUrl::new(issuer).unwrap(),
+            vec![audience],
+            Utc::now() + Duration::seconds(600),
+            Utc::now(),
+            standard_claims,
+            custom_claims,
+        ),
+        &*TEST_SIGNING_KEY,
+        CoreJwsSigningAlgorithm::RsaSsaPkcs1V15Sha256,
+        None,
+        None,
+    )
+    .unwrap();
+
+    let verifier = CoreIdTokenVerifier::new_insecure_without_verification();
+    UserIdentity::from_token(token, verifier).unwrap()
+}
+
 impl TestUserIdentity for UserIdentityAttributes {
     fn test() -> Self {
         UserIdentityAttributes {
@@ -17,6 +17,8 @@ pub struct UdfPostRequestWithComponent {
     pub args: UdfArgsJson,
 
     pub format: Option<String>,
+    /// `"gzip"` or `"zstd"` to compress large responses.
+    pub compression: Option<String>,
 }
 
 impl UdfPostRequestWithComponent {
@@ -13,7 +13,10 @@ use keybroker::{
     DEV_SECRET,
 };
 use metrics::SERVER_VERSION_STR;
-use model::database_globals::types::StorageTagInitializer;
+use model::database_globals::types::{
+    StorageTagInitializer,
+    StorageType,
+};
 use serde_json::Value as JsonValue;
 use url::Url;
 
@@ -214,6 +217,21 @@ impl LocalConfig {
         }
     }
 
+    /// Where large document fields go when `DOCUMENT_BLOB_THRESHOLD_BYTES` is
+    /// set. The database reads them while loading, so unlike other storage
+    /// this can't wait for the storage tag and comes straight from the config.
+    pub fn document_blobs_storage_type(&self) -> StorageType {
+        if self.s3_storage {
+            StorageType::S3 {
+                s3_prefix: format!("{}/", self.name()),
+            }
+        } else {
+            StorageType::Local {
+                dir: self.local_storage.clone(),
+            }
+        }
+    }
+
     #[cfg(test)]
     pub fn new_for_test() -> anyhow::Result<Self> {
         use anyhow::Context;
@@ -17,6 +17,7 @@ use ::authentication::{
 use application::{
     self,
     api::ApplicationApi,
+    create_storage,
     log_visibility::RedactLogsToClient,
     Application,
     QueryCache,
@@ -29,6 +30,7 @@ use common::{
     },
     knobs::{
         ACTION_USER_TIMEOUT,
+        DOCUMENT_BLOB_THRESHOLD_BYTES,
         DOCUMENT_RETENTION_RATE_LIMIT,
         UDF_CACHE_MAX_SIZE,
     },
@@ -44,7 +46,10 @@ use common::{
     },
 };
 use config::LocalConfig;
-use database::Database;
+use database::{
+    BlobPersistence,
+    Database,
+};
 use events::usage::NoOpUsageEventLogger;
 use exports::interface::InProcessExportProvider;
 use file_storage::{
@@ -72,6 +77,7 @@ use search::{
     SegmentTermMetadataFetcher,
 };
 use serde::Serialize;
+use storage::StorageUseCase;
 
 pub mod admin;
 mod app_metrics;
@@ -146,6 +152,23 @@ pub async fn make_app(
     preempt_tx: ShutdownSignal,
 ) -> anyhow::Result<LocalAppState> {
     let key_broker = config.key_broker()?;
+    // Everything below, including the function runner, must read through the
+    // wrapped persistence to see out-of-line fields.
+    let persistence: Arc<dyn Persistence> = if *DOCUMENT_BLOB_THRESHOLD_BYTES > 0 {
+        let storage = create_storage(
+            runtime.clone(),
+            &config.document_blobs_storage_type(),
+            StorageUseCase::DocumentBlobs,
+        )
+        .await?;
+        Arc::new(BlobPersistence::new(
+            persistence,
+            storage,
+            *DOCUMENT_BLOB_THRESHOLD_BYTES,
+        ))
+    } else {
+        persistence
+    };
     let in_process_searcher = Arc::new(InProcessSearcher::new(runtime.clone())?);
     let searcher: Arc<dyn Searcher> = in_process_searcher.clone();
     // TODO(CX-6572) Separate `SegmentMetadataFetcher` from `SearcherImpl`
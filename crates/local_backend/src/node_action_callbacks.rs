@@ -4,6 +4,7 @@ use std::{
 };
 
 use anyhow::Context;
+use application::MutationOptions;
 use axum::{
     debug_handler,
     extract::{
@@ -186,6 +187,7 @@ pub async fn internal_mutation_post(
                 parent_execution_id: Some(context.execution_id),
             },
             None,
+            MutationOptions::default(),
         )
         .await?;
     if req.format.is_some() {
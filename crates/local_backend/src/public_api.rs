@@ -12,7 +12,10 @@ use axum::{
         FromRef,
         State,
     },
-    response::IntoResponse,
+    response::{
+        IntoResponse,
+        Response,
+    },
 };
 use common::{
     components::{
@@ -31,21 +34,43 @@ use common::{
         HttpResponseError,
     },
     knobs::MAX_BACKEND_PUBLIC_API_REQUEST_SIZE,
+    obj,
     types::FunctionCaller,
     version::ClientVersion,
 };
-use errors::ErrorMetadata;
+use errors::{
+    ErrorMetadata,
+    ErrorMetadataAnyhowExt,
+};
+use http::{
+    header::{
+        CONTENT_ENCODING,
+        CONTENT_TYPE,
+    },
+    HeaderMap,
+    HeaderValue,
+};
 use isolate::UdfArgsJson;
 use serde::{
     Deserialize,
     Serialize,
 };
 use serde_json::Value as JsonValue;
-use sync_types::Timestamp;
+use sync_types::{
+    LogLinesMessage,
+    Timestamp,
+};
 use utoipa::ToSchema;
 use utoipa_axum::router::OpenApiRouter;
 use value::{
-    export::ValueFormat,
+    export::{
+        ResultFormat,
+        ValueFormat,
+    },
+    result_compression::{
+        EncodedResult,
+        ResultCompression,
+    },
     ConvexValue,
 };
 
@@ -67,6 +92,8 @@ pub struct UdfPostRequest {
     pub args: UdfArgsJson,
 
     pub format: Option<String>,
+    /// `"gzip"` or `"zstd"` to compress large responses.
+    pub compression: Option<String>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -192,6 +219,74 @@ impl UdfResponse {
     }
 }
 
+/// Builds the response to a function call in the request's `format`.
+/// `"msgpack"` sends the fields of [`UdfResponse`] as a MessagePack map, so
+/// `Int64` and `Bytes` values keep their types; any other format is the
+/// [`ValueFormat`] the value is exported to JSON with.
+///
+/// With a `compression`, bodies of at least `MIN_COMPRESSED_RESULT_SIZE`
+/// bytes are compressed and sent with a `Content-Encoding` header. Smaller
+/// ones are sent as is.
+fn udf_response(
+    result: Result<ConvexValue, RedactedJsError>,
+    log_lines: RedactedLogLines,
+    format: Option<&str>,
+    compression: Option<&str>,
+    client_version: ClientVersion,
+) -> anyhow::Result<Response> {
+    let compression: Option<ResultCompression> = compression.map(|c| c.parse()).transpose()?;
+    let (content_type, body) = match format.map(|f| f.parse()).transpose()? {
+        Some(ResultFormat::MessagePack) => {
+            let log_lines = LogLinesMessage::from(log_lines)
+                .0
+                .into_iter()
+                .map(ConvexValue::try_from)
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let response = match result {
+                Ok(value) => obj!(
+                    "status" => "success",
+                    "value" => value,
+                    "logLines" => log_lines,
+                )?,
+                Err(error) => obj!(
+                    "status" => "error",
+                    "errorMessage" => format!("{error}"),
+                    "errorData" => error.custom_data_if_any().unwrap_or(ConvexValue::Null),
+                    "logLines" => log_lines,
+                )?,
+            };
+            (
+                "application/msgpack",
+                ConvexValue::Object(response).to_msgpack(),
+            )
+        },
+        result_format => {
+            let value_format = match result_format {
+                Some(ResultFormat::Json(value_format)) => Some(value_format),
+                _ => None,
+            };
+            let response = match result {
+                Ok(value) => UdfResponse::Success {
+                    value: export_value(value, value_format, client_version)?,
+                    log_lines,
+                },
+                Err(error) => UdfResponse::error(error, log_lines, value_format, client_version)?,
+            };
+            ("application/json", serde_json::to_vec(&response)?)
+        },
+    };
+    let encoded = EncodedResult::new(body, compression)?;
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    if let Some(compression) = encoded.compression {
+        headers.insert(
+            CONTENT_ENCODING,
+            HeaderValue::from_static(compression.content_encoding()),
+        );
+    }
+    Ok((headers, encoded.body).into_response())
+}
+
 /// Execute any function
 ///
 /// Execute a query, mutation, or action function by name.
@@ -236,20 +331,17 @@ pub async fn public_function_post(
             FunctionCaller::HttpApi(client_version.clone()),
         )
         .await?;
-    let value_format = req.format.as_ref().map(|f| f.parse()).transpose()?;
-    let response = match udf_result {
-        Ok(write_return) => UdfResponse::Success {
-            value: export_value(write_return.value.unpack(), value_format, client_version)?,
-            log_lines: write_return.log_lines,
-        },
-        Err(write_error) => UdfResponse::error(
-            write_error.error,
-            write_error.log_lines,
-            value_format,
-            client_version,
-        )?,
+    let (result, log_lines) = match udf_result {
+        Ok(write_return) => (Ok(write_return.value.unpack()), write_return.log_lines),
+        Err(write_error) => (Err(write_error.error), write_error.log_lines),
     };
-    Ok(Json(response))
+    Ok(udf_response(
+        result,
+        log_lines,
+        req.format.as_deref(),
+        req.compression.as_deref(),
+        client_version,
+    )?)
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -459,17 +551,13 @@ pub async fn public_query_post(
             journal,
         )
         .await?;
-    let value_format = req.format.as_ref().map(|f| f.parse()).transpose()?;
-    let response = match query_return.result {
-        Ok(value) => UdfResponse::Success {
-            value: export_value(value.unpack(), value_format, client_version)?,
-            log_lines: query_return.log_lines,
-        },
-        Err(error) => {
-            UdfResponse::error(error, query_return.log_lines, value_format, client_version)?
-        },
-    };
-    Ok(Json(response))
+    Ok(udf_response(
+        query_return.result.map(|value| value.unpack()),
+        query_return.log_lines,
+        req.format.as_deref(),
+        req.compression.as_deref(),
+        client_version,
+    )?)
 }
 
 /// Get latest timestamp
@@ -549,6 +637,11 @@ pub async fn public_query_at_ts_post(
 #[derive(Deserialize, ToSchema)]
 pub struct QueryBatchArgs {
     queries: Vec<UdfPostRequest>,
+    /// If set, a query that fails with a developer error (e.g. an invalid path
+    /// or format) is reported as an error in its slot of `results` instead of
+    /// failing the entire batch. System errors always fail the batch.
+    #[serde(default)]
+    partial: bool,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -582,32 +675,44 @@ pub async fn public_query_batch_post(
         .authenticate(&host, request_id.clone(), auth_token)
         .await?;
     for req in req_batch.queries {
-        let value_format = req.format.as_ref().map(|f| f.parse()).transpose()?;
-        let export_path = parse_export_path(&req.path)?;
-        let udf_return = st
-            .api
-            .execute_public_query(
-                &host,
-                request_id.clone(),
-                identity.clone(),
-                export_path,
-                req.args.into_serialized_args()?,
-                FunctionCaller::HttpApi(client_version.clone()),
-                ExecuteQueryTimestamp::At(*ts),
-                None,
-            )
-            .await?;
-        let response = match udf_return.result {
-            Ok(value) => UdfResponse::Success {
-                value: export_value(value.unpack(), value_format, client_version.clone())?,
-                log_lines: udf_return.log_lines,
+        let result: anyhow::Result<UdfResponse> = async {
+            let value_format = req.format.as_ref().map(|f| f.parse()).transpose()?;
+            let export_path = parse_export_path(&req.path)?;
+            let udf_return = st
+                .api
+                .execute_public_query(
+                    &host,
+                    request_id.clone(),
+                    identity.clone(),
+                    export_path,
+                    req.args.into_serialized_args()?,
+                    FunctionCaller::HttpApi(client_version.clone()),
+                    ExecuteQueryTimestamp::At(*ts),
+                    None,
+                )
+                .await?;
+            match udf_return.result {
+                Ok(value) => Ok(UdfResponse::Success {
+                    value: export_value(value.unpack(), value_format, client_version.clone())?,
+                    log_lines: udf_return.log_lines,
+                }),
+                Err(error) => UdfResponse::error(
+                    error,
+                    udf_return.log_lines,
+                    value_format,
+                    client_version.clone(),
+                ),
+            }
+        }
+        .await;
+        let response = match result {
+            Ok(response) => response,
+            Err(e) if req_batch.partial && e.is_deterministic_user_error() => UdfResponse::Error {
+                error_message: e.user_facing_message(),
+                error_data: None,
+                log_lines: RedactedLogLines::empty(),
             },
-            Err(error) => UdfResponse::error(
-                error,
-                udf_return.log_lines,
-                value_format,
-                client_version.clone(),
-            )?,
+            Err(e) => return Err(e.into()),
         };
         results.push(response);
     }
@@ -655,20 +760,17 @@ pub async fn public_mutation_post(
             None,
         )
         .await?;
-    let value_format = req.format.as_ref().map(|f| f.parse()).transpose()?;
-    let response = match udf_result {
-        Ok(write_return) => UdfResponse::Success {
-            value: export_value(write_return.value.unpack(), value_format, client_version)?,
-            log_lines: write_return.log_lines,
-        },
-        Err(write_error) => UdfResponse::error(
-            write_error.error,
-            write_error.log_lines,
-            value_format,
-            client_version,
-        )?,
+    let (result, log_lines) = match udf_result {
+        Ok(write_return) => (Ok(write_return.value.unpack()), write_return.log_lines),
+        Err(write_error) => (Err(write_error.error), write_error.log_lines),
     };
-    Ok(Json(response))
+    Ok(udf_response(
+        result,
+        log_lines,
+        req.format.as_deref(),
+        req.compression.as_deref(),
+        client_version,
+    )?)
 }
 
 /// Execute action
@@ -711,20 +813,17 @@ pub async fn public_action_post(
             FunctionCaller::HttpApi(client_version.clone()),
         )
         .await?;
-    let value_format = req.format.as_ref().map(|f| f.parse()).transpose()?;
-    let response = match action_result {
-        Ok(action_return) => UdfResponse::Success {
-            value: export_value(action_return.value.unpack(), value_format, client_version)?,
-            log_lines: action_return.log_lines,
-        },
-        Err(action_error) => UdfResponse::error(
-            action_error.error,
-            action_error.log_lines,
-            value_format,
-            client_version,
-        )?,
+    let (result, log_lines) = match action_result {
+        Ok(action_return) => (Ok(action_return.value.unpack()), action_return.log_lines),
+        Err(action_error) => (Err(action_error.error), action_error.log_lines),
     };
-    Ok(Json(response))
+    Ok(udf_response(
+        result,
+        log_lines,
+        req.format.as_deref(),
+        req.compression.as_deref(),
+        client_version,
+    )?)
 }
 
 // The public (stable, no auth required) API of a deployment.
@@ -759,6 +858,14 @@ mod tests {
         json,
         Value as JsonValue,
     };
+    use value::{
+        assert_obj,
+        result_compression::{
+            ResultCompression,
+            MIN_COMPRESSED_RESULT_SIZE,
+        },
+        ConvexValue,
+    };
 
     use crate::test_helpers::setup_backend_for_test;
 
@@ -912,4 +1019,103 @@ mod tests {
         )
         .await
     }
+
+    #[convex_macro::prod_rt_test]
+    async fn test_http_query_msgpack(rt: ProdRuntime) -> anyhow::Result<()> {
+        let backend = setup_backend_for_test(rt).await?;
+        backend.st.application.load_udf_tests_modules().await?;
+        let json_body = json!({
+            "path": "values:intQuery",
+            "args": {},
+            "format": "msgpack",
+        });
+        let req = Request::builder()
+            .uri("/api/query")
+            .method("POST")
+            .header("Content-Type", "application/json")
+            .header("Host", "localhost")
+            .body(Body::from(serde_json::to_vec(&json_body)?))?;
+        let bytes = backend.expect_success_bytes(req).await?;
+        // The int64 result stays an Int64 rather than becoming a JSON string.
+        assert_eq!(
+            ConvexValue::from_msgpack(&bytes)?,
+            ConvexValue::Object(assert_obj!(
+                "status" => "success",
+                "value" => 1i64,
+                "logLines" => Vec::<ConvexValue>::new(),
+            ))
+        );
+        Ok(())
+    }
+
+    #[convex_macro::prod_rt_test]
+    async fn test_http_query_compressed(rt: ProdRuntime) -> anyhow::Result<()> {
+        let backend = setup_backend_for_test(rt).await?;
+        backend.st.application.load_udf_tests_modules().await?;
+        let arg = "x".repeat(MIN_COMPRESSED_RESULT_SIZE * 4);
+        for compression in [ResultCompression::Gzip, ResultCompression::Zstd] {
+            let json_body = json!({
+                "path": "args_validation:stringArg",
+                "args": {"arg": arg},
+                "format": "json",
+                "compression": compression.content_encoding(),
+            });
+            let req = Request::builder()
+                .uri("/api/query")
+                .method("POST")
+                .header("Content-Type", "application/json")
+                .header("Host", "localhost")
+                .body(Body::from(serde_json::to_vec(&json_body)?))?;
+            let bytes = backend.expect_success_bytes(req).await?;
+            assert!(bytes.len() < arg.len());
+            let result: JsonValue = serde_json::from_slice(&compression.decompress(&bytes)?)?;
+            assert_eq!(result, json!({"status": "success", "value": arg}));
+        }
+        Ok(())
+    }
+
+    fn query_batch_request(partial: bool) -> anyhow::Result<Request<Body>> {
+        let json_body = json!({
+            "queries": [
+                {"path": "values:intQuery", "args": {}},
+                {"path": "custom_errors:queryThrows", "args": {}},
+                {"path": "values:intQuery", "args": {}, "format": "bogus"},
+            ],
+            "partial": partial,
+        });
+        Ok(Request::builder()
+            .uri("/api/query_batch")
+            .method("POST")
+            .header("Content-Type", "application/json")
+            .header("Host", "localhost")
+            .body(Body::from(serde_json::to_vec(&json_body)?))?)
+    }
+
+    #[convex_macro::prod_rt_test]
+    async fn test_http_query_batch_partial(rt: ProdRuntime) -> anyhow::Result<()> {
+        let backend = setup_backend_for_test(rt).await?;
+        backend.st.application.load_udf_tests_modules().await?;
+
+        // Without `partial`, the invalid format fails the whole batch.
+        backend
+            .expect_error(
+                query_batch_request(false)?,
+                StatusCode::BAD_REQUEST,
+                "BadFormat",
+            )
+            .await?;
+
+        let result: JsonValue = backend.expect_success(query_batch_request(true)?).await?;
+        let results = result["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], json!({"status": "success", "value": "1"}));
+        assert_eq!(results[1]["status"], "error");
+        assert_eq!(results[1]["errorData"], json!(true));
+        assert_eq!(results[2]["status"], "error");
+        assert!(results[2]["errorMessage"]
+            .as_str()
+            .unwrap()
+            .contains("format param must be one of"));
+        Ok(())
+    }
 }
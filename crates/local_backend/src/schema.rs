@@ -261,6 +261,7 @@ pub async fn prepare_schema_handler(
         Err(e) => return Err(e.into()),
     };
     let schema_validation_enabled = schema.schema_validation;
+    let _schema_lock = st.application.lock_schema_for_change().await?;
     let mut tx = st.application.begin(identity.clone()).await?;
 
     let dry_run = req.dry_run.unwrap_or(true);
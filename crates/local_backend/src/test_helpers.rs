@@ -100,6 +100,15 @@ impl TestLocalBackend {
         &self,
         req: Request<axum::body::Body>,
     ) -> anyhow::Result<T> {
+        let bytes = self.expect_success_bytes(req).await?;
+        serde_json::from_slice(if bytes.is_empty() { b"null" } else { &bytes })
+            .context(format!("Couldn't deserialize as json: {bytes:?}"))
+    }
+
+    pub async fn expect_success_bytes(
+        &self,
+        req: Request<axum::body::Body>,
+    ) -> anyhow::Result<Vec<u8>> {
         tracing::info!("Sending req {req:?}");
         let (parts, body) = self.app.router().clone().oneshot(req).await?.into_parts();
         let bytes = body
@@ -110,8 +119,7 @@ impl TestLocalBackend {
         let msg = format!("Got response: {}", String::from_utf8_lossy(&bytes));
         tracing::info!("{msg}");
         assert_eq!(parts.status, StatusCode::OK, "{msg}");
-        serde_json::from_slice(if bytes.is_empty() { b"null" } else { &bytes })
-            .context(format!("Couldn't deserialize as json: {bytes:?}"))
+        Ok(bytes.to_vec())
     }
 
     pub async fn expect_error(
@@ -30,7 +30,7 @@ pub type DatabaseVersion = i64;
 // migrations unless explicitly dropping support.
 // Add a user name next to the version when you make a change to highlight merge
 // conflicts.
-pub const DATABASE_VERSION: DatabaseVersion = 122; // emma
+pub const DATABASE_VERSION: DatabaseVersion = 126; // emma
 
 pub struct MigrationExecutor<RT: Runtime> {
     pub db: Database<RT>,
@@ -81,6 +81,26 @@ impl<RT: Runtime> MigrationExecutor<RT> {
                 // table for each component, _schema_validation_progress
                 MigrationCompletionCriterion::MigrationComplete(to_version)
             },
+            123 => {
+                // This is an empty migration because we added a new system
+                // table for each component, _sequences
+                MigrationCompletionCriterion::MigrationComplete(to_version)
+            },
+            124 => {
+                // This is an empty migration because we added a new index,
+                // _scheduled_jobs.by_dedup_key_and_next_ts
+                MigrationCompletionCriterion::MigrationComplete(to_version)
+            },
+            125 => {
+                // This is an empty migration because we added a new system
+                // table for each component, _counters
+                MigrationCompletionCriterion::MigrationComplete(to_version)
+            },
+            126 => {
+                // This is an empty migration because we added a new system
+                // table, _environment_variable_scopes
+                MigrationCompletionCriterion::MigrationComplete(to_version)
+            },
             // NOTE: Make sure to increase DATABASE_VERSION when adding new migrations.
             _ => anyhow::bail!("Version did not define a migration! {}", to_version),
         };
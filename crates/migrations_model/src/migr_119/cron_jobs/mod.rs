@@ -177,6 +177,7 @@ impl<'a, RT: Runtime> CronModel<'a, RT> {
                 ConvexValue::from(cron_job_id).into(),
             )],
             order: Order::Asc,
+            collation: None,
         });
         let mut query_stream = ResolvedQuery::new(self.tx, self.component.into(), query)?;
         let next_run = query_stream.expect_at_most_one(self.tx).await?;
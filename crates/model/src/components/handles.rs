@@ -149,6 +149,7 @@ impl<'a, RT: Runtime> FunctionHandlesModel<'a, RT> {
                 ),
             ],
             order: Order::Asc,
+            collation: None,
         };
         let query = Query::index_range(index_range);
         let mut query_stream = ResolvedQuery::new(self.tx, TableNamespace::Global, query)?;
@@ -180,6 +181,7 @@ impl<'a, RT: Runtime> FunctionHandlesModel<'a, RT> {
                 serialized_component.into(),
             )],
             order: Order::Asc,
+            collation: None,
         });
         let mut query_stream = ResolvedQuery::new(self.tx, TableNamespace::Global, index_query)?;
 
@@ -12,10 +12,16 @@ mod index_tests;
 pub mod module_loader;
 pub mod types;
 
-use std::collections::BTreeMap;
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
 
 use common::{
-    components::ComponentId,
+    components::{
+        CanonicalizedComponentFunctionPath,
+        ComponentId,
+    },
     document::ParsedDocument,
     runtime::Runtime,
     schemas::DatabaseSchema,
@@ -26,7 +32,11 @@ use database::{
     SchemaModel,
     Transaction,
 };
-use sync_types::CanonicalizedModulePath;
+use errors::ErrorMetadata;
+use sync_types::{
+    CanonicalizedModulePath,
+    CanonicalizedUdfPath,
+};
 use value::ResolvedDocumentId;
 
 use self::module_loader::ModuleLoader;
@@ -44,6 +54,7 @@ use crate::{
         types::ModuleMetadata,
         ModuleModel,
     },
+    scheduled_jobs::SchedulerModel,
     source_packages::{
         types::SourcePackage,
         SourcePackageModel,
@@ -88,6 +99,8 @@ impl<'a, RT: Runtime> ConfigModel<'a, RT> {
             None => None,
         };
 
+        self.validate_scheduled_functions(&analyze_results).await?;
+
         let cron_diff = CronModel::new(self.tx, self.component)
             .apply(&analyze_results)
             .await?;
@@ -124,6 +137,83 @@ impl<'a, RT: Runtime> ConfigModel<'a, RT> {
         Ok((config_diff, next_schema))
     }
 
+    /// Check that no cron job in `crons.js` refers to a function that won't
+    /// exist after this push. Pending scheduled jobs for functions the push
+    /// removes are only logged as a warning, since they fail when they run
+    /// rather than blocking the push.
+    async fn validate_scheduled_functions(
+        &mut self,
+        analyze_results: &BTreeMap<CanonicalizedModulePath, AnalyzedModule>,
+    ) -> anyhow::Result<()> {
+        let functions: BTreeSet<CanonicalizedUdfPath> = analyze_results
+            .iter()
+            .flat_map(|(module_path, module)| {
+                module.functions.iter().map(|function| {
+                    CanonicalizedUdfPath::new(module_path.clone(), function.name.clone())
+                })
+            })
+            .collect();
+
+        let mut missing = vec![];
+        for module in analyze_results.values() {
+            for (name, cron_spec) in module.cron_specs.iter().flat_map(|specs| specs.iter()) {
+                if !functions.contains(&cron_spec.udf_path) {
+                    missing.push(format!(
+                        "Cron job {name:?} schedules {}",
+                        cron_spec.udf_path
+                    ));
+                }
+            }
+        }
+
+        let removed_functions: BTreeSet<CanonicalizedUdfPath> = ModuleModel::new(self.tx)
+            .get_application_metadata(self.component)
+            .await?
+            .into_iter()
+            .flat_map(|module| {
+                let module = module.into_value();
+                let module_path = module.path;
+                module
+                    .analyze_result
+                    .into_iter()
+                    .flat_map(|result| result.functions.into_iter())
+                    .map(move |function| {
+                        CanonicalizedUdfPath::new(module_path.clone(), function.name)
+                    })
+            })
+            .filter(|udf_path| !functions.contains(udf_path))
+            .collect();
+        if !removed_functions.is_empty() {
+            let component_path = self.tx.must_component_path(self.component)?;
+            for udf_path in removed_functions {
+                let path = CanonicalizedComponentFunctionPath {
+                    component: component_path.clone(),
+                    udf_path,
+                };
+                if SchedulerModel::new(self.tx, self.component.into())
+                    .has_pending(path.clone())
+                    .await?
+                {
+                    tracing::warn!(
+                        "Push removes {}, which pending scheduled jobs still run",
+                        path.udf_path
+                    );
+                }
+            }
+        }
+
+        if !missing.is_empty() {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "ScheduledFunctionNotFound",
+                format!(
+                    "Scheduled functions must exist after the push, but these don't:\n{}",
+                    missing.join("\n")
+                ),
+            ));
+        }
+        Ok(())
+    }
+
     /// Return the latest database configuration. This includes only the
     /// user-configurable state and not internal derived state like shapes. We
     /// might want to store this config in memory but for now just reading it
@@ -14,17 +14,24 @@ use common::{
         },
         DocumentSchema,
     },
-    types::ModuleEnvironment,
+    types::{
+        ModuleEnvironment,
+        UdfType,
+    },
 };
 use database::{
     test_helpers::DbFixtures,
     SchemaModel,
 };
+use errors::ErrorMetadataAnyhowExt;
 use keybroker::Identity;
 use maplit::btreemap;
 use runtime::testing::TestRuntime;
 use storage::LocalDirStorage;
-use value::heap_size::WithHeapSize;
+use value::{
+    heap_size::WithHeapSize,
+    ConvexArray,
+};
 
 use crate::{
     auth::AuthInfoModel,
@@ -36,9 +43,16 @@ use crate::{
         },
         ConfigModel,
     },
+    cron_jobs::types::{
+        CronSchedule,
+        CronSpec,
+    },
     modules::module_versions::{
+        AnalyzedFunction,
         AnalyzedModule,
+        FunctionTimeouts,
         ModuleSource,
+        Visibility,
     },
     source_packages::{
         types::SourcePackage,
@@ -335,3 +349,70 @@ async fn test_schema_in_deployment_audit_log(rt: TestRuntime) -> anyhow::Result<
 
     Ok(())
 }
+
+#[convex_macro::test_runtime]
+async fn test_config_cron_for_missing_function(rt: TestRuntime) -> anyhow::Result<()> {
+    let database = DbFixtures::new_with_model(&rt).await?.db;
+    let modules_storage = Arc::new(LocalDirStorage::new(rt.clone())?);
+
+    let mut tx = database.begin(Identity::system()).await?;
+    let module = ModuleConfig {
+        path: "crons.js".parse()?,
+        source: "// some js".into(),
+        source_map: None,
+        environment: ModuleEnvironment::Isolate,
+    };
+    let path = module.path.clone().canonicalize();
+    let (storage_key, sha256, package_size) =
+        upload_package(btreemap! { path.clone() => &module }, modules_storage, None).await?;
+    let cron_spec = |udf_path: &str| -> anyhow::Result<CronSpec> {
+        Ok(CronSpec {
+            udf_path: udf_path.parse()?,
+            udf_args: ConvexArray::try_from(vec![])?,
+            cron_schedule: CronSchedule::Interval { seconds: 60 },
+        })
+    };
+    let err = ConfigModel::new(&mut tx, ComponentId::test_user())
+        .apply(
+            ConfigMetadata::test_example(),
+            vec![module],
+            UdfConfig::new_for_test(&rt, "1000.0.0".parse()?),
+            Some(SourcePackage {
+                storage_key,
+                sha256,
+                external_deps_package_id: None,
+                package_size,
+                node_version: None,
+            }),
+            btreemap! {
+                path => AnalyzedModule {
+                    functions: vec![AnalyzedFunction {
+                        name: "addOne".parse()?,
+                        pos: None,
+                        udf_type: UdfType::Mutation,
+                        visibility: Some(Visibility::Public),
+                        args_str: None,
+                        returns_str: None,
+                        timeouts: FunctionTimeouts::default(),
+                    }]
+                    .into(),
+                    http_routes: None,
+                    cron_specs: Some(
+                        btreemap! {
+                            "exists".parse()? => cron_spec("crons:addOne")?,
+                            "missing".parse()? => cron_spec("crons:doesNotExist")?,
+                        }
+                        .into(),
+                    ),
+                    source_index: None,
+                },
+            },
+            None,
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.short_msg(), "ScheduledFunctionNotFound");
+    assert!(err.msg().contains("crons.js:doesNotExist"), "{err}");
+    assert!(!err.msg().contains("crons.js:addOne"), "{err}");
+    Ok(())
+}
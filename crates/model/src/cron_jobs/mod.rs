@@ -256,6 +256,7 @@ impl<'a, RT: Runtime> CronModel<'a, RT> {
                 ConvexValue::from(cron_job_id).into(),
             )],
             order: Order::Asc,
+            collation: None,
         });
         let mut query_stream = ResolvedQuery::new(self.tx, self.component.into(), query)?;
         query_stream
@@ -416,6 +417,7 @@ impl<'a, RT: Runtime> CronModel<'a, RT> {
                 ConvexValue::try_from(name.to_string())?.into(),
             )],
             order: Order::Desc,
+            collation: None,
         });
         let mut query_stream = ResolvedQuery::new(self.tx, self.component.into(), index_query)?;
         let mut num_logs = 0;
@@ -447,6 +449,7 @@ pub async fn stream_cron_jobs_to_run<'a, RT: Runtime>(tx: &'a mut Transaction<RT
         index_name: CRON_NEXT_RUN_INDEX_BY_NEXT_TS.name(),
         range: vec![],
         order: Order::Asc,
+        collation: None,
     });
     // Key is (next_ts, namespace), where next_ts is for sorting and namespace
     // is for deduping.
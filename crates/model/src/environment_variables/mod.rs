@@ -1,10 +1,14 @@
 use std::{
     collections::{
         BTreeMap,
+        BTreeSet,
         HashMap,
         HashSet,
     },
-    sync::LazyLock,
+    sync::{
+        Arc,
+        LazyLock,
+    },
 };
 
 use anyhow::Context;
@@ -12,6 +16,7 @@ use common::{
     document::{
         ParseDocument,
         ParsedDocument,
+        CREATION_TIME_FIELD_PATH,
     },
     interval::Interval,
     query::{
@@ -33,6 +38,7 @@ use database::{
     Transaction,
 };
 use errors::ErrorMetadata;
+use sync_types::CanonicalizedUdfPath;
 use value::{
     ConvexValue,
     FieldPath,
@@ -47,6 +53,7 @@ use crate::{
         EnvVarName,
         EnvVarValue,
         EnvironmentVariable,
+        EnvironmentVariableScope,
         PersistedEnvironmentVariable,
     },
     SystemIndex,
@@ -79,12 +86,39 @@ impl SystemTable for EnvironmentVariablesTable {
     }
 }
 
+pub static ENVIRONMENT_VARIABLE_SCOPES_TABLE: LazyLock<TableName> = LazyLock::new(|| {
+    "_environment_variable_scopes"
+        .parse()
+        .expect("Invalid built-in environment variable scopes table")
+});
+
+pub static ENVIRONMENT_VARIABLE_SCOPES_INDEX_BY_NAME: LazyLock<
+    SystemIndex<EnvironmentVariableScopesTable>,
+> = LazyLock::new(|| {
+    SystemIndex::new("by_name", [&NAME_FIELD, &CREATION_TIME_FIELD_PATH]).unwrap()
+});
+
+pub struct EnvironmentVariableScopesTable;
+impl SystemTable for EnvironmentVariableScopesTable {
+    type Metadata = EnvironmentVariableScope;
+
+    fn table_name() -> &'static TableName {
+        &ENVIRONMENT_VARIABLE_SCOPES_TABLE
+    }
+
+    fn indexes() -> Vec<SystemIndex<Self>> {
+        vec![ENVIRONMENT_VARIABLE_SCOPES_INDEX_BY_NAME.clone()]
+    }
+}
+
 pub struct EnvironmentVariablesModel<'a, RT: Runtime> {
     tx: &'a mut Transaction<RT>,
 }
 
 pub struct PreloadedEnvironmentVariables {
     range: PreloadedIndexRange,
+    // Variables scoped to other functions, which should look unset.
+    hidden: BTreeSet<EnvVarName>,
 }
 
 impl PreloadedEnvironmentVariables {
@@ -93,6 +127,9 @@ impl PreloadedEnvironmentVariables {
         tx: &mut Transaction<RT>,
         name: &EnvVarName,
     ) -> anyhow::Result<Option<EnvVarValue>> {
+        if self.hidden.contains(name) {
+            return Ok(None);
+        }
         let key = Some(ConvexValue::try_from(String::from(name.clone()))?);
         let Some(doc) = self.range.get(tx, &key)? else {
             return Ok(None);
@@ -118,7 +155,22 @@ impl<'a, RT: Runtime> EnvironmentVariablesModel<'a, RT> {
                 &Interval::all(),
             )
             .await?;
-        Ok(PreloadedEnvironmentVariables { range })
+        Ok(PreloadedEnvironmentVariables {
+            range,
+            hidden: BTreeSet::new(),
+        })
+    }
+
+    /// Preload environment variables as seen by `function`, hiding the ones
+    /// scoped to other functions.
+    pub async fn preload_for_function(
+        &mut self,
+        function: &CanonicalizedUdfPath,
+    ) -> anyhow::Result<PreloadedEnvironmentVariables> {
+        let hidden = self.hidden_from_function(function).await?;
+        let mut preloaded = self.preload().await?;
+        preloaded.hidden = hidden;
+        Ok(preloaded)
     }
 
     pub async fn get(
@@ -162,6 +214,94 @@ impl<'a, RT: Runtime> EnvironmentVariablesModel<'a, RT> {
         Ok(environment_variables)
     }
 
+    /// Like `get_all`, but without the variables scoped to other functions.
+    pub async fn get_all_for_function(
+        &mut self,
+        function: &CanonicalizedUdfPath,
+    ) -> anyhow::Result<BTreeMap<EnvVarName, EnvVarValue>> {
+        let hidden = self.hidden_from_function(function).await?;
+        let mut environment_variables = self.get_all().await?;
+        environment_variables.retain(|name, _| !hidden.contains(name));
+        Ok(environment_variables)
+    }
+
+    /// Returns the functions each scoped environment variable is restricted
+    /// to. Variables missing from the result are visible to all functions.
+    pub async fn get_scopes(
+        &mut self,
+    ) -> anyhow::Result<BTreeMap<EnvVarName, BTreeSet<CanonicalizedUdfPath>>> {
+        let scopes = self
+            .tx
+            .query_system(
+                TableNamespace::Global,
+                &SystemIndex::<EnvironmentVariableScopesTable>::by_id(),
+            )?
+            .all()
+            .await?;
+        let mut result = BTreeMap::new();
+        for scope in scopes {
+            let EnvironmentVariableScope { name, functions } =
+                Arc::unwrap_or_clone(scope).into_value();
+            let old_value = result.insert(name, functions);
+            anyhow::ensure!(old_value.is_none(), "Duplicate environment variable scope");
+        }
+        Ok(result)
+    }
+
+    /// Restrict the environment variable `name` to `functions`. An empty set
+    /// removes the restriction, making the variable visible to all functions.
+    pub async fn set_scope(
+        &mut self,
+        name: EnvVarName,
+        functions: BTreeSet<CanonicalizedUdfPath>,
+    ) -> anyhow::Result<()> {
+        let existing = self
+            .tx
+            .query_system(
+                TableNamespace::Global,
+                &ENVIRONMENT_VARIABLE_SCOPES_INDEX_BY_NAME,
+            )?
+            .eq(&[name.as_ref()])?
+            .unique()
+            .await?
+            .map(|doc| doc.id());
+        let mut model = SystemMetadataModel::new_global(self.tx);
+        match (existing, functions.is_empty()) {
+            (Some(id), true) => {
+                model.delete(id).await?;
+            },
+            (Some(id), false) => {
+                model
+                    .replace(id, EnvironmentVariableScope { name, functions }.try_into()?)
+                    .await?;
+            },
+            (None, true) => (),
+            (None, false) => {
+                model
+                    .insert(
+                        &ENVIRONMENT_VARIABLE_SCOPES_TABLE,
+                        EnvironmentVariableScope { name, functions }.try_into()?,
+                    )
+                    .await?;
+            },
+        }
+        Ok(())
+    }
+
+    async fn hidden_from_function(
+        &mut self,
+        function: &CanonicalizedUdfPath,
+    ) -> anyhow::Result<BTreeSet<EnvVarName>> {
+        let hidden = self
+            .get_scopes()
+            .await?
+            .into_iter()
+            .filter(|(_, functions)| !functions.contains(function))
+            .map(|(name, _)| name)
+            .collect();
+        Ok(hidden)
+    }
+
     pub async fn create(
         &mut self,
         env_var: EnvironmentVariable,
@@ -188,6 +328,8 @@ impl<'a, RT: Runtime> EnvironmentVariablesModel<'a, RT> {
         let document = SystemMetadataModel::new_global(self.tx)
             .delete(doc.id())
             .await?;
+        // Don't leave the scope behind for a later variable with the same name.
+        self.set_scope(name.clone(), BTreeSet::new()).await?;
         let env_var: ParsedDocument<PersistedEnvironmentVariable> = document.parse()?;
         Ok(Some(env_var.into_value().0))
     }
@@ -274,6 +416,7 @@ fn value_query_from_env_var(env_var: &EnvVarName) -> anyhow::Result<Query> {
         index_name: ENVIRONMENT_VARIABLES_INDEX_BY_NAME.name(),
         range,
         order: Order::Asc,
+        collation: None,
     }))
 }
 
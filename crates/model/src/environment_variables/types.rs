@@ -1,11 +1,20 @@
-use std::collections::BTreeMap;
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
 
 pub use common::types::{
     EnvVarName,
     EnvVarValue,
     EnvironmentVariable,
 };
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sync_types::CanonicalizedUdfPath;
 use value::{
+    codegen_convex_serialization,
     obj,
     ConvexObject,
     ConvexValue,
@@ -47,6 +56,48 @@ impl TryFrom<ConvexObject> for PersistedEnvironmentVariable {
     }
 }
 
+/// Restricts which functions can read the environment variable `name`.
+/// Variables without a scope are readable by every function.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(any(test, feature = "testing"), derive(proptest_derive::Arbitrary))]
+pub struct EnvironmentVariableScope {
+    pub name: EnvVarName,
+    pub functions: BTreeSet<CanonicalizedUdfPath>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializedEnvironmentVariableScope {
+    name: String,
+    functions: Vec<String>,
+}
+
+impl From<EnvironmentVariableScope> for SerializedEnvironmentVariableScope {
+    fn from(value: EnvironmentVariableScope) -> Self {
+        Self {
+            name: value.name.into(),
+            functions: value.functions.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+}
+
+impl TryFrom<SerializedEnvironmentVariableScope> for EnvironmentVariableScope {
+    type Error = anyhow::Error;
+
+    fn try_from(value: SerializedEnvironmentVariableScope) -> anyhow::Result<Self> {
+        Ok(Self {
+            name: value.name.parse()?,
+            functions: value
+                .functions
+                .iter()
+                .map(|f| f.parse())
+                .collect::<anyhow::Result<_>>()?,
+        })
+    }
+}
+
+codegen_convex_serialization!(EnvironmentVariableScope, SerializedEnvironmentVariableScope);
+
 #[cfg(test)]
 mod tests {
 
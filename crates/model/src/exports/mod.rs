@@ -156,6 +156,7 @@ impl<'a, RT: Runtime> ExportsModel<'a, RT> {
                 ConvexValue::try_from(ExportRequestor::CloudBackup.to_string())?.into(),
             )],
             order: Order::Asc,
+            collation: None,
         };
         let completed_filter = Expression::Eq(
             Expression::Field(EXPORTS_STATE_FIELD.clone()).into(),
@@ -106,6 +106,7 @@ impl<'a, RT: Runtime> ExternalPackagesModel<'a, RT> {
             index_name: IndexName::by_creation_time(EXTERNAL_PACKAGES_TABLE.clone()),
             range: vec![],
             order: Order::Desc,
+            collation: None,
         });
         let mut query_stream = ResolvedQuery::new(self.tx, TableNamespace::Global, index_query)?;
         let deps_map: BTreeMap<String, String> = deps
@@ -264,6 +264,7 @@ impl<'a, RT: Runtime> FileStorageModel<'a, RT> {
                     ConvexValue::try_from(storage_id)?.into(),
                 )],
                 order: Order::Asc,
+                collation: None,
             }),
             FileStorageId::DocumentId(document_id) => {
                 let table_name = self
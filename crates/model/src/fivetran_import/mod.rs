@@ -208,6 +208,7 @@ impl<'a, RT: Runtime> FivetranImportModel<'a, RT> {
                 index_name: index.name,
                 range,
                 order: Order::Asc,
+                collation: None,
             }),
         )
     }
@@ -298,6 +299,7 @@ impl<'a, RT: Runtime> FivetranImportModel<'a, RT> {
                 index_name: index.name,
                 range,
                 order: Order::Asc,
+                collation: None,
             }),
         )
     }
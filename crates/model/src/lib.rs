@@ -92,6 +92,7 @@ use database::{
     BootstrapComponentsModel,
     ComponentDefinitionsTable,
     ComponentsTable,
+    CountersTable,
     Database,
     IndexBackfillTable,
     IndexModel,
@@ -104,6 +105,8 @@ use database::{
     COMPONENTS_BY_PARENT_INDEX,
     COMPONENTS_TABLE,
     COMPONENT_DEFINITIONS_TABLE,
+    COUNTERS_INDEX_BY_NAME,
+    COUNTERS_TABLE,
     INDEX_BACKFILLS_BY_INDEX_ID,
     INDEX_BACKFILLS_TABLE,
     INDEX_DOC_ID_INDEX,
@@ -123,6 +126,8 @@ use database_globals::{
 use environment_variables::{
     ENVIRONMENT_VARIABLES_INDEX_BY_NAME,
     ENVIRONMENT_VARIABLES_TABLE,
+    ENVIRONMENT_VARIABLE_SCOPES_INDEX_BY_NAME,
+    ENVIRONMENT_VARIABLE_SCOPES_TABLE,
 };
 use exports::{
     EXPORTS_BY_REQUESTOR,
@@ -155,9 +160,15 @@ use scheduled_jobs::{
     ScheduledJobsTable,
     SCHEDULED_JOBS_INDEX,
     SCHEDULED_JOBS_INDEX_BY_COMPLETED_TS,
+    SCHEDULED_JOBS_INDEX_BY_DEDUP_KEY,
     SCHEDULED_JOBS_INDEX_BY_UDF_PATH,
     SCHEDULED_JOBS_TABLE,
 };
+use sequences::{
+    SequencesTable,
+    SEQUENCES_INDEX_BY_NAME,
+    SEQUENCES_TABLE,
+};
 use session_requests::{
     SessionRequestsTable,
     SESSION_REQUESTS_INDEX,
@@ -196,7 +207,10 @@ use crate::{
         DeploymentAuditLogsTable,
         DEPLOYMENT_AUDIT_LOG_TABLE,
     },
-    environment_variables::EnvironmentVariablesTable,
+    environment_variables::{
+        EnvironmentVariableScopesTable,
+        EnvironmentVariablesTable,
+    },
     exports::ExportsTable,
     external_packages::EXTERNAL_PACKAGES_TABLE,
     log_sinks::LOG_SINKS_TABLE,
@@ -223,6 +237,7 @@ mod metrics;
 pub mod migrations;
 pub mod modules;
 pub mod scheduled_jobs;
+pub mod sequences;
 pub mod session_requests;
 pub mod snapshot_imports;
 pub mod source_packages;
@@ -267,9 +282,12 @@ enum DefaultTableNumber {
     CronNextRun = 35,
     IndexBackfills = 36,
     SchemaValidationProgress = 37,
+    Sequences = 38,
+    Counters = 39,
+    EnvironmentVariableScopes = 40,
     // Keep this number and your user name up to date. The number makes it easy to know
     // what to use next. The username on the same line detects merge conflicts
-    // Next Number - 38 - emma
+    // Next Number - 41 - emma
 }
 
 impl From<DefaultTableNumber> for TableNumber {
@@ -313,6 +331,9 @@ impl From<DefaultTableNumber> for &'static dyn ErasedSystemTable {
             DefaultTableNumber::CronNextRun => &CronNextRunTable,
             DefaultTableNumber::IndexBackfills => &IndexBackfillTable,
             DefaultTableNumber::SchemaValidationProgress => &SchemaValidationProgressTable,
+            DefaultTableNumber::Sequences => &SequencesTable,
+            DefaultTableNumber::Counters => &CountersTable,
+            DefaultTableNumber::EnvironmentVariableScopes => &EnvironmentVariableScopesTable,
         }
     }
 }
@@ -531,6 +552,7 @@ pub fn app_system_tables() -> Vec<&'static dyn ErasedSystemTable> {
         &DatabaseGlobalsTable,
         &DeploymentAuditLogsTable,
         &EnvironmentVariablesTable,
+        &EnvironmentVariableScopesTable,
         &AuthTable,
         &ExternalPackagesTable,
         &SessionRequestsTable,
@@ -560,6 +582,8 @@ pub fn component_system_tables() -> Vec<&'static dyn ErasedSystemTable> {
         &ModulesTable,
         &UdfConfigTable,
         &SourcePackagesTable,
+        &SequencesTable,
+        &CountersTable,
     ]
 }
 
@@ -570,6 +594,7 @@ static APP_TABLES_TO_LOAD_IN_MEMORY: LazyLock<BTreeSet<TableName>> = LazyLock::n
         DATABASE_GLOBALS_TABLE.clone(),
         MODULES_TABLE.clone(),
         ENVIRONMENT_VARIABLES_TABLE.clone(),
+        ENVIRONMENT_VARIABLE_SCOPES_TABLE.clone(),
         CRON_JOBS_TABLE.clone(),
         CRON_NEXT_RUN_TABLE.clone(),
         BACKEND_STATE_TABLE.clone(),
@@ -577,6 +602,8 @@ static APP_TABLES_TO_LOAD_IN_MEMORY: LazyLock<BTreeSet<TableName>> = LazyLock::n
         BACKEND_INFO_TABLE.clone(),
         AWS_LAMBDA_VERSIONS_TABLE.clone(),
         SOURCE_PACKAGES_TABLE.clone(),
+        // The committer merges counter increments using the in-memory index.
+        COUNTERS_TABLE.clone(),
     }
 });
 
@@ -630,6 +657,9 @@ pub static FIRST_SEEN_TABLE: LazyLock<BTreeMap<TableName, DatabaseVersion>> = La
         CANONICAL_URLS_TABLE.clone() => 116,
         INDEX_BACKFILLS_TABLE.clone() => 120,
         SCHEMA_VALIDATION_PROGRESS_TABLE.clone() => 122,
+        SEQUENCES_TABLE.clone() => 123,
+        COUNTERS_TABLE.clone() => 125,
+        ENVIRONMENT_VARIABLE_SCOPES_TABLE.clone() => 126,
     }
 });
 
@@ -657,6 +687,10 @@ pub static FIRST_SEEN_INDEX: LazyLock<BTreeMap<IndexName, DatabaseVersion>> = La
         EXPORTS_BY_REQUESTOR.name() => 110,
         INDEX_BACKFILLS_BY_INDEX_ID.name() => 120,
         SCHEMA_VALIDATION_PROGRESS_BY_SCHEMA_ID.name() => 122,
+        SEQUENCES_INDEX_BY_NAME.name() => 123,
+        SCHEDULED_JOBS_INDEX_BY_DEDUP_KEY.name() => 124,
+        COUNTERS_INDEX_BY_NAME.name() => 125,
+        ENVIRONMENT_VARIABLE_SCOPES_INDEX_BY_NAME.name() => 126,
     }
 });
 
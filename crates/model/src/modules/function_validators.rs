@@ -1,5 +1,10 @@
+use std::collections::BTreeMap;
+
 use common::{
-    errors::JsError,
+    errors::{
+        ArgumentValidationError,
+        JsError,
+    },
     json::JsonForm,
     schemas::validator::{
         ObjectValidator,
@@ -17,6 +22,7 @@ use serde::{
 use value::{
     ConvexArray,
     ConvexValue,
+    FieldName,
     NamespacedTableMapping,
 };
 
@@ -37,6 +43,36 @@ pub enum ArgsValidator {
 }
 
 impl ArgsValidator {
+    /// Fill in declared defaults for any optional arguments the caller
+    /// omitted. Arguments that aren't a single object are returned unchanged
+    /// so that `check_args` can report the error.
+    pub fn apply_defaults(&self, args: ConvexArray) -> anyhow::Result<ConvexArray> {
+        let ArgsValidator::Validated(object_validator) = self else {
+            return Ok(args);
+        };
+        if object_validator
+            .0
+            .values()
+            .all(|field| field.default.is_none())
+        {
+            return Ok(args);
+        }
+        let mut args: Vec<ConvexValue> = args.into();
+        let [ConvexValue::Object(object_arg)] = &mut args[..] else {
+            return args.try_into();
+        };
+        let mut fields: BTreeMap<FieldName, ConvexValue> = object_arg.clone().into();
+        for (field_name, field) in &object_validator.0 {
+            if let Some(default) = &field.default {
+                fields
+                    .entry(field_name.clone().into())
+                    .or_insert_with(|| default.clone());
+            }
+        }
+        *object_arg = fields.try_into()?;
+        args.try_into()
+    }
+
     pub fn check_args(
         &self,
         args: &ConvexArray,
@@ -54,7 +90,15 @@ impl ArgsValidator {
                              Instead received {} arguments: {args}",
                             args.len()
                         );
-                        return Ok(Some(JsError::from_message(error_message)));
+                        return Ok(Some(JsError::argument_validation_error(
+                            error_message,
+                            ArgumentValidationError {
+                                field_path: None,
+                                expected: Some(
+                                    Validator::Object(object_validator.clone()).to_string(),
+                                ),
+                            },
+                        )));
                     },
                 };
                 let object_arg = match single_arg {
@@ -64,7 +108,15 @@ impl ArgsValidator {
                             "Expected to receive an object as the function's argument. Instead \
                              received: {single_arg}"
                         );
-                        return Ok(Some(JsError::from_message(error_message)));
+                        return Ok(Some(JsError::argument_validation_error(
+                            error_message,
+                            ArgumentValidationError {
+                                field_path: None,
+                                expected: Some(
+                                    Validator::Object(object_validator.clone()).to_string(),
+                                ),
+                            },
+                        )));
                     },
                 };
 
@@ -74,7 +126,13 @@ impl ArgsValidator {
                     virtual_system_mapping,
                 );
                 if let Err(error) = validation_error {
-                    Some(JsError::from_message(error.to_string()))
+                    Some(JsError::argument_validation_error(
+                        error.to_string(),
+                        ArgumentValidationError {
+                            field_path: error.field_path(),
+                            expected: error.expected(),
+                        },
+                    ))
                 } else {
                     None
                 }
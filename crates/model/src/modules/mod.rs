@@ -44,6 +44,7 @@ use self::{
     module_versions::{
         AnalyzedFunction,
         AnalyzedModule,
+        FunctionTimeouts,
         ModuleSource,
         SourceMap,
     },
@@ -135,27 +136,30 @@ impl<'a, RT: Runtime> ModuleModel<'a, RT> {
             .get_application_metadata(component)
             .await?
             .into_iter()
-            .map(|module| (module.path.clone(), module.id()))
+            .map(|module| (module.path.clone(), module))
             .collect();
         for module in modules {
             let path = module.path.canonicalize();
-            let existing_module_id = remaining_modules.remove(&path);
-            if existing_module_id.is_none() {
+            let existing_module = remaining_modules.remove(&path);
+            if existing_module.is_none() {
                 added_modules.insert(path.clone());
             }
             let analyze_result = if !path.is_deps() {
                 // We expect AnalyzeResult to always be set for non-dependency modules.
-                let analyze_result = analyze_results.remove(&path).context(format!(
+                let mut analyze_result = analyze_results.remove(&path).context(format!(
                     "Missing analyze result for module {}",
                     path.as_str()
                 ))?;
+                if let Some(existing_module) = &existing_module {
+                    Self::keep_function_timeouts(existing_module, &mut analyze_result);
+                }
                 Some(analyze_result)
             } else {
                 // We don't analyze dependencies.
                 None
             };
             self.put(
-                existing_module_id,
+                existing_module.as_ref().map(|module| module.id()),
                 CanonicalizedComponentModulePath {
                     component,
                     module_path: path.clone(),
@@ -170,13 +174,35 @@ impl<'a, RT: Runtime> ModuleModel<'a, RT> {
         }
 
         let mut removed_modules = BTreeSet::new();
-        for (path, module_id) in remaining_modules {
+        for (path, module) in remaining_modules {
             removed_modules.insert(path.clone());
-            self.delete(component, module_id).await?;
+            self.delete(component, module.id()).await?;
         }
         ModuleDiff::new(added_modules, removed_modules)
     }
 
+    /// Carry the timeouts set with [`Self::set_function_timeouts`] over to
+    /// the functions of the same name in a newly pushed version of the module.
+    fn keep_function_timeouts(
+        existing_module: &ModuleMetadata,
+        analyze_result: &mut AnalyzedModule,
+    ) {
+        let Some(existing_analyze_result) = &existing_module.analyze_result else {
+            return;
+        };
+        let mut functions: Vec<_> = analyze_result.functions.iter().cloned().collect();
+        for function in &mut functions {
+            if let Some(existing_function) = existing_analyze_result
+                .functions
+                .iter()
+                .find(|f| f.name == function.name)
+            {
+                function.timeouts = existing_function.timeouts.clamped_for(function.udf_type);
+            }
+        }
+        analyze_result.functions = functions.into();
+    }
+
     /// Returns the registered modules metadata, including system modules.
     #[fastrace::trace]
     pub async fn get_all_metadata(
@@ -461,6 +487,42 @@ impl<'a, RT: Runtime> ModuleModel<'a, RT> {
         .into()))
     }
 
+    /// Override how long the function at `path` may run. Both timeouts are
+    /// capped at the system timeout for the function's type, and are kept
+    /// when its module is pushed again.
+    pub async fn set_function_timeouts(
+        &mut self,
+        path: CanonicalizedComponentFunctionPath,
+        timeouts: FunctionTimeouts,
+    ) -> anyhow::Result<()> {
+        if !(self.tx.identity().is_admin() || self.tx.identity().is_system()) {
+            anyhow::bail!(unauthorized_error("set_function_timeouts"));
+        }
+        let module_path = BootstrapComponentsModel::new(self.tx).function_path_to_module(&path)?;
+        let component = module_path.component;
+        let module = self
+            .get_metadata(module_path)
+            .await?
+            .with_context(|| format!("Missing module for {:?}", path.udf_path))?;
+        let module_id = module.id();
+        let mut metadata = module.into_value();
+        let analyzed_module = metadata
+            .analyze_result
+            .as_mut()
+            .with_context(|| format!("Expected analyze result for {:?}", path.udf_path))?;
+        let mut functions: Vec<_> = analyzed_module.functions.iter().cloned().collect();
+        let function = functions
+            .iter_mut()
+            .find(|function| &function.name == path.udf_path.function_name())
+            .with_context(|| format!("Missing function {:?}", path.udf_path))?;
+        function.timeouts = timeouts.clamped_for(function.udf_type);
+        analyzed_module.functions = functions.into();
+        SystemMetadataModel::new(self.tx, component.into())
+            .replace(module_id, metadata.try_into()?)
+            .await?;
+        Ok(())
+    }
+
     pub async fn get_http(
         &mut self,
         component: ComponentId,
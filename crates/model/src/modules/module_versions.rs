@@ -4,12 +4,17 @@ use std::{
     ops::Deref,
     str::FromStr,
     sync::Arc,
+    time::Duration,
 };
 
 use async_lru::async_lru::SizedValue;
 use common::{
     http::RoutedHttpPath,
     json::JsonForm as _,
+    knobs::{
+        DATABASE_UDF_SYSTEM_TIMEOUT,
+        V8_ACTION_SYSTEM_TIMEOUT,
+    },
     types::{
         HttpActionRoute,
         RoutableMethod,
@@ -293,6 +298,44 @@ pub fn invalid_function_name_error(
     )
 }
 
+/// Per-function overrides for how long a function may run. Unset timeouts
+/// fall back to the defaults for the function's UDF type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(any(test, feature = "testing"), derive(proptest_derive::Arbitrary))]
+pub struct FunctionTimeouts {
+    #[cfg_attr(
+        any(test, feature = "testing"),
+        proptest(strategy = "proptest::option::of(arbitrary_timeout())")
+    )]
+    pub user: Option<Duration>,
+    #[cfg_attr(
+        any(test, feature = "testing"),
+        proptest(strategy = "proptest::option::of(arbitrary_timeout())")
+    )]
+    pub system: Option<Duration>,
+}
+
+impl FunctionTimeouts {
+    /// Cap both timeouts at the system timeout for functions of `udf_type`,
+    /// the longest any of them may run.
+    pub fn clamped_for(self, udf_type: UdfType) -> Self {
+        let max = match udf_type {
+            UdfType::Query | UdfType::Mutation => *DATABASE_UDF_SYSTEM_TIMEOUT,
+            UdfType::Action | UdfType::HttpAction => *V8_ACTION_SYSTEM_TIMEOUT,
+        };
+        Self {
+            user: self.user.map(|t| t.min(max)),
+            system: self.system.map(|t| t.min(max)),
+        }
+    }
+}
+
+// Timeouts are stored as whole milliseconds.
+#[cfg(any(test, feature = "testing"))]
+fn arbitrary_timeout() -> impl Strategy<Value = Duration> {
+    (0..u32::MAX as u64).prop_map(Duration::from_millis)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(any(test, feature = "testing"), derive(proptest_derive::Arbitrary))]
 pub struct AnalyzedFunction {
@@ -308,6 +351,8 @@ pub struct AnalyzedFunction {
     pub args_str: Option<String>,
     // JSON-serialized ReturnsValidator
     pub returns_str: Option<String>,
+
+    pub timeouts: FunctionTimeouts,
 }
 
 impl AnalyzedFunction {
@@ -328,6 +373,7 @@ impl AnalyzedFunction {
             visibility,
             args_str: Some(args_json),
             returns_str: Some(returns_json),
+            timeouts: FunctionTimeouts::default(),
         })
     }
 
@@ -365,6 +411,10 @@ pub struct SerializedAnalyzedFunction {
     visibility: Option<Visibility>,
     args: Option<String>,
     returns: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    user_timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    system_timeout_ms: Option<u64>,
 }
 
 impl TryFrom<AnalyzedFunction> for SerializedAnalyzedFunction {
@@ -378,6 +428,16 @@ impl TryFrom<AnalyzedFunction> for SerializedAnalyzedFunction {
             visibility: f.visibility,
             args: f.args_str,
             returns: f.returns_str,
+            user_timeout_ms: f
+                .timeouts
+                .user
+                .map(|t| u64::try_from(t.as_millis()))
+                .transpose()?,
+            system_timeout_ms: f
+                .timeouts
+                .system
+                .map(|t| u64::try_from(t.as_millis()))
+                .transpose()?,
         })
     }
 }
@@ -393,6 +453,10 @@ impl TryFrom<SerializedAnalyzedFunction> for AnalyzedFunction {
             visibility: f.visibility,
             args_str: f.args,
             returns_str: f.returns,
+            timeouts: FunctionTimeouts {
+                user: f.user_timeout_ms.map(Duration::from_millis),
+                system: f.system_timeout_ms.map(Duration::from_millis),
+            },
         })
     }
 }
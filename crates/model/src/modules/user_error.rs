@@ -14,6 +14,24 @@ impl ModuleNotFoundError {
         );
         Self { msg }
     }
+
+    /// Like `new`, but suggests some of the modules that do exist.
+    pub fn with_available_modules(module_path: &str, available: &[String]) -> Self {
+        const MAX_SUGGESTIONS: usize = 10;
+        let mut suggestions = available
+            .iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|path| format!("'{path}'"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if available.len() > MAX_SUGGESTIONS {
+            suggestions.push_str(", ...");
+        }
+        let msg = format!(
+            "Couldn't find JavaScript module '{module_path}'. Available modules: {suggestions}."
+        );
+        Self { msg }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -11,6 +11,7 @@ use common::{
     document::{
         ParseDocument,
         ParsedDocument,
+        CREATION_TIME_FIELD_PATH,
     },
     execution_context::ExecutionContext,
     knobs::{
@@ -106,6 +107,16 @@ pub static SCHEDULED_JOBS_INDEX_BY_UDF_PATH: LazyLock<SystemIndex<ScheduledJobsT
         )
         .unwrap()
     });
+/// By dedup key and next ts. Used to find the pending job with a given dedup
+/// key in `schedule_if_absent`.
+pub static SCHEDULED_JOBS_INDEX_BY_DEDUP_KEY: LazyLock<SystemIndex<ScheduledJobsTable>> =
+    LazyLock::new(|| {
+        SystemIndex::new(
+            "by_dedup_key_and_next_ts",
+            [&DEDUP_KEY_FIELD, &NEXT_TS_FIELD, &CREATION_TIME_FIELD_PATH],
+        )
+        .unwrap()
+    });
 /// By completed ts. Used to efficiently find jobs to garbage collect.
 pub static SCHEDULED_JOBS_INDEX_BY_COMPLETED_TS: LazyLock<SystemIndex<ScheduledJobsTable>> =
     LazyLock::new(|| SystemIndex::new("by_completed_ts", [&COMPLETED_TS_FIELD]).unwrap());
@@ -117,6 +128,8 @@ static UDF_PATH_FIELD: LazyLock<FieldPath> =
     LazyLock::new(|| "udfPath".parse().expect("invalid udfPath field"));
 static COMPONENT_PATH_FIELD: LazyLock<FieldPath> =
     LazyLock::new(|| "component".parse().expect("invalid component field"));
+static DEDUP_KEY_FIELD: LazyLock<FieldPath> =
+    LazyLock::new(|| "dedupKey".parse().expect("invalid dedupKey field"));
 
 pub struct ScheduledJobsTable;
 impl SystemTable for ScheduledJobsTable {
@@ -131,6 +144,7 @@ impl SystemTable for ScheduledJobsTable {
             SCHEDULED_JOBS_INDEX_BY_COMPLETED_TS.clone(),
             SCHEDULED_JOBS_INDEX.clone(),
             SCHEDULED_JOBS_INDEX_BY_UDF_PATH.clone(),
+            SCHEDULED_JOBS_INDEX_BY_DEDUP_KEY.clone(),
         ]
     }
 
@@ -164,7 +178,9 @@ impl<'a, RT: Runtime> SchedulerModel<'a, RT> {
     }
 
     fn check_scheduling_limits(&mut self, args: &ConvexArray) -> anyhow::Result<()> {
-        // Limit how much you can schedule from a single transaction.
+        // Limit how much you can schedule from a single transaction. This runs
+        // when each job is scheduled, so a mutation that goes over the limit
+        // fails before committing any of its jobs.
         anyhow::ensure!(
             self.tx.scheduled_size.num_writes < *TRANSACTION_MAX_NUM_SCHEDULED,
             ErrorMetadata::bad_request(
@@ -198,6 +214,68 @@ impl<'a, RT: Runtime> SchedulerModel<'a, RT> {
         args: ConvexArray,
         ts: UnixTimestamp,
         context: ExecutionContext,
+    ) -> anyhow::Result<ResolvedDocumentId> {
+        self.schedule_inner(path, args, ts, context, None).await
+    }
+
+    /// Schedule a job unless there's already a pending job with the same
+    /// `dedup_key`, in which case this is a no-op that returns the existing
+    /// job's id. Once the existing job starts running (or is canceled),
+    /// scheduling with the key creates a new job again, so rapid triggers
+    /// collapse into a single run.
+    pub async fn schedule_if_absent(
+        &mut self,
+        path: CanonicalizedComponentFunctionPath,
+        args: ConvexArray,
+        ts: UnixTimestamp,
+        context: ExecutionContext,
+        dedup_key: String,
+    ) -> anyhow::Result<ResolvedDocumentId> {
+        if let Some(existing) = self.pending_job_with_dedup_key(&dedup_key).await? {
+            return Ok(existing);
+        }
+        self.schedule_inner(path, args, ts, context, Some(dedup_key))
+            .await
+    }
+
+    async fn pending_job_with_dedup_key(
+        &mut self,
+        dedup_key: &str,
+    ) -> anyhow::Result<Option<ResolvedDocumentId>> {
+        // Only pending and in-progress jobs have a `next_ts`, so this skips
+        // completed jobs.
+        let range = vec![
+            IndexRangeExpression::Eq(
+                DEDUP_KEY_FIELD.clone(),
+                ConvexValue::try_from(dedup_key.to_string())?.into(),
+            ),
+            IndexRangeExpression::Gte(NEXT_TS_FIELD.clone(), i64::from(Timestamp::MIN).into()),
+        ];
+        let index_query = Query::index_range(IndexRange {
+            index_name: SCHEDULED_JOBS_INDEX_BY_DEDUP_KEY.name(),
+            range,
+            order: Order::Asc,
+            collation: None,
+        });
+        let mut query_stream = ResolvedQuery::new(self.tx, self.namespace, index_query)?;
+        while let Some(doc) = query_stream.next(self.tx, None).await? {
+            let job: ParsedDocument<ScheduledJob> = doc.parse()?;
+            // A job that has already started running may have missed whatever
+            // prompted this call, so it doesn't count.
+            if job.state == ScheduledJobState::Pending {
+                return Ok(Some(job.id()));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn schedule_inner(
+        &mut self,
+        path: CanonicalizedComponentFunctionPath,
+        args: ConvexArray,
+        ts: UnixTimestamp,
+        context: ExecutionContext,
+        dedup_key: Option<String>,
     ) -> anyhow::Result<ResolvedDocumentId> {
         if path.udf_path.is_system()
             && !(self.tx.identity().is_admin() || self.tx.identity().is_system())
@@ -221,7 +299,7 @@ impl<'a, RT: Runtime> SchedulerModel<'a, RT> {
             original_scheduled_ts,
             ScheduledJobAttempts::default(),
         )?;
-        let job = if let Some((parent_component_id, parent_scheduled_job)) =
+        let mut job = if let Some((parent_component_id, parent_scheduled_job)) =
             context.parent_scheduled_job
         {
             let table_mapping = self.tx.table_mapping().clone();
@@ -268,6 +346,7 @@ impl<'a, RT: Runtime> SchedulerModel<'a, RT> {
         } else {
             scheduled_job
         };
+        job.dedup_key = dedup_key;
         let id = SystemMetadataModel::new(self.tx, self.namespace)
             .insert_metadata(&SCHEDULED_JOBS_TABLE, job.try_into()?)
             .await?;
@@ -374,43 +453,7 @@ impl<'a, RT: Runtime> SchedulerModel<'a, RT> {
         end_next_ts: Option<Timestamp>,
     ) -> anyhow::Result<usize> {
         let index_query = match path {
-            Some(path) => {
-                let udf_path = path.udf_path;
-                let component_path = path.component;
-                let mut component_path_filter = Expression::Eq(
-                    Expression::Field(COMPONENT_PATH_FIELD.clone()).into(),
-                    Expression::Literal(maybe_val!(String::from(component_path.clone()))).into(),
-                );
-                if component_path.is_root() {
-                    component_path_filter = Expression::Or(vec![
-                        component_path_filter,
-                        Expression::Eq(
-                            Expression::Field(COMPONENT_PATH_FIELD.clone()).into(),
-                            Expression::Literal(maybe_val!(undefined)).into(),
-                        ),
-                    ]);
-                }
-                let range = vec![
-                    IndexRangeExpression::Eq(
-                        UDF_PATH_FIELD.clone(),
-                        ConvexValue::try_from(udf_path.to_string())?.into(),
-                    ),
-                    IndexRangeExpression::Gte(
-                        NEXT_TS_FIELD.clone(),
-                        i64::from(start_next_ts.unwrap_or(Timestamp::MIN)).into(),
-                    ),
-                    IndexRangeExpression::Lt(
-                        NEXT_TS_FIELD.clone(),
-                        i64::from(end_next_ts.unwrap_or(Timestamp::MAX)).into(),
-                    ),
-                ];
-                Query::index_range(IndexRange {
-                    index_name: SCHEDULED_JOBS_INDEX_BY_UDF_PATH.name(),
-                    range,
-                    order: Order::Asc,
-                })
-                .filter(component_path_filter)
-            },
+            Some(path) => pending_jobs_for_function_query(path, start_next_ts, end_next_ts)?,
             None => {
                 let range = vec![
                     IndexRangeExpression::Gte(
@@ -426,6 +469,7 @@ impl<'a, RT: Runtime> SchedulerModel<'a, RT> {
                     index_name: SCHEDULED_JOBS_INDEX.name(),
                     range,
                     order: Order::Asc,
+                    collation: None,
                 })
             },
         };
@@ -440,6 +484,16 @@ impl<'a, RT: Runtime> SchedulerModel<'a, RT> {
         Ok(count)
     }
 
+    /// Returns whether any job scheduled to run `path` hasn't completed yet.
+    pub async fn has_pending(
+        &mut self,
+        path: CanonicalizedComponentFunctionPath,
+    ) -> anyhow::Result<bool> {
+        let query = pending_jobs_for_function_query(path, None, None)?;
+        let mut query_stream = ResolvedQuery::new(self.tx, self.namespace, query)?;
+        Ok(query_stream.next(self.tx, None).await?.is_some())
+    }
+
     pub async fn list(&mut self) -> anyhow::Result<Vec<ParsedDocument<ScheduledJob>>> {
         let scheduled_query = Query::full_table_scan(SCHEDULED_JOBS_TABLE.clone(), Order::Asc);
         let mut query_stream = ResolvedQuery::new(self.tx, self.namespace, scheduled_query)?;
@@ -468,6 +522,51 @@ impl<'a, RT: Runtime> SchedulerModel<'a, RT> {
     }
 }
 
+/// Query for the jobs running `path` that haven't completed yet, with
+/// `next_ts` in `[start_next_ts, end_next_ts)`.
+fn pending_jobs_for_function_query(
+    path: CanonicalizedComponentFunctionPath,
+    start_next_ts: Option<Timestamp>,
+    end_next_ts: Option<Timestamp>,
+) -> anyhow::Result<Query> {
+    let udf_path = path.udf_path;
+    let component_path = path.component;
+    let mut component_path_filter = Expression::Eq(
+        Expression::Field(COMPONENT_PATH_FIELD.clone()).into(),
+        Expression::Literal(maybe_val!(String::from(component_path.clone()))).into(),
+    );
+    if component_path.is_root() {
+        component_path_filter = Expression::Or(vec![
+            component_path_filter,
+            Expression::Eq(
+                Expression::Field(COMPONENT_PATH_FIELD.clone()).into(),
+                Expression::Literal(maybe_val!(undefined)).into(),
+            ),
+        ]);
+    }
+    let range = vec![
+        IndexRangeExpression::Eq(
+            UDF_PATH_FIELD.clone(),
+            ConvexValue::try_from(udf_path.to_string())?.into(),
+        ),
+        IndexRangeExpression::Gte(
+            NEXT_TS_FIELD.clone(),
+            i64::from(start_next_ts.unwrap_or(Timestamp::MIN)).into(),
+        ),
+        IndexRangeExpression::Lt(
+            NEXT_TS_FIELD.clone(),
+            i64::from(end_next_ts.unwrap_or(Timestamp::MAX)).into(),
+        ),
+    ];
+    Ok(Query::index_range(IndexRange {
+        index_name: SCHEDULED_JOBS_INDEX_BY_UDF_PATH.name(),
+        range,
+        order: Order::Asc,
+        collation: None,
+    })
+    .filter(component_path_filter))
+}
+
 /// Same as SchedulerModel but works with the respective virtual table instead
 /// of the underlying system table.
 pub struct VirtualSchedulerModel<'a, RT: Runtime> {
@@ -495,6 +594,22 @@ impl<'a, RT: Runtime> VirtualSchedulerModel<'a, RT> {
             .system_resolved_id_to_virtual_developer_id(system_id)
     }
 
+    pub async fn schedule_if_absent(
+        &mut self,
+        path: CanonicalizedComponentFunctionPath,
+        args: ConvexArray,
+        ts: UnixTimestamp,
+        context: ExecutionContext,
+        dedup_key: String,
+    ) -> anyhow::Result<DeveloperDocumentId> {
+        let system_id = SchedulerModel::new(self.tx, self.namespace)
+            .schedule_if_absent(path, args, ts, context, dedup_key)
+            .await?;
+        self.tx
+            .virtual_system_mapping()
+            .system_resolved_id_to_virtual_developer_id(system_id)
+    }
+
     pub async fn cancel(&mut self, virtual_id: DeveloperDocumentId) -> anyhow::Result<()> {
         let table_mapping = self.tx.table_mapping().clone();
         let system_id = self
@@ -50,6 +50,11 @@ pub struct ScheduledJob {
     pub original_scheduled_ts: Timestamp,
 
     pub attempts: ScheduledJobAttempts,
+
+    /// Set for jobs scheduled with
+    /// [`super::SchedulerModel::schedule_if_absent`]. At most one pending
+    /// job has a given key.
+    pub dedup_key: Option<String>,
 }
 
 fn args_to_bytes(args: ConvexArray) -> anyhow::Result<ByteBuf> {
@@ -75,6 +80,7 @@ impl ScheduledJob {
             completed_ts,
             original_scheduled_ts,
             attempts,
+            dedup_key: None,
         })
     }
 
@@ -98,6 +104,8 @@ pub struct SerializedScheduledJob {
     completed_ts: Option<i64>,
     original_scheduled_ts: Option<i64>,
     attempts: Option<ScheduledJobAttempts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dedup_key: Option<String>,
 }
 
 impl TryFrom<ScheduledJob> for SerializedScheduledJob {
@@ -113,6 +121,7 @@ impl TryFrom<ScheduledJob> for SerializedScheduledJob {
             completed_ts: job.completed_ts.map(|ts| ts.into()),
             original_scheduled_ts: Some(job.original_scheduled_ts.into()),
             attempts: Some(job.attempts),
+            dedup_key: job.dedup_key,
         })
     }
 }
@@ -155,6 +164,7 @@ impl TryFrom<SerializedScheduledJob> for ScheduledJob {
             completed_ts,
             original_scheduled_ts,
             attempts: value.attempts.unwrap_or_default(),
+            dedup_key: value.dedup_key,
         })
     }
 }
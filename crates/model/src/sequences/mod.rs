@@ -0,0 +1,108 @@
+//! Transactional id sequences for UDFs.
+//!
+//! Each allocation reads and rewrites the sequence's document in the calling
+//! transaction, so concurrent allocations conflict under OCC and a retried
+//! mutation simply allocates again. Committed mutations therefore never
+//! observe duplicate values, and values are only consumed by transactions that
+//! commit.
+
+use std::sync::LazyLock;
+
+use common::{
+    document::CREATION_TIME_FIELD_PATH,
+    runtime::Runtime,
+};
+use database::{
+    SystemMetadataModel,
+    Transaction,
+};
+use errors::ErrorMetadata;
+use value::{
+    FieldPath,
+    TableName,
+    TableNamespace,
+};
+
+use self::types::SequenceMetadata;
+use crate::{
+    SystemIndex,
+    SystemTable,
+};
+
+pub mod types;
+
+pub static SEQUENCES_TABLE: LazyLock<TableName> = LazyLock::new(|| {
+    "_sequences"
+        .parse()
+        .expect("Invalid built-in sequences table")
+});
+
+pub static SEQUENCES_INDEX_BY_NAME: LazyLock<SystemIndex<SequencesTable>> = LazyLock::new(|| {
+    SystemIndex::new("by_name", [&NAME_FIELD, &CREATION_TIME_FIELD_PATH]).unwrap()
+});
+static NAME_FIELD: LazyLock<FieldPath> =
+    LazyLock::new(|| "name".parse().expect("invalid name field"));
+
+pub struct SequencesTable;
+impl SystemTable for SequencesTable {
+    type Metadata = SequenceMetadata;
+
+    fn table_name() -> &'static TableName {
+        &SEQUENCES_TABLE
+    }
+
+    fn indexes() -> Vec<SystemIndex<Self>> {
+        vec![SEQUENCES_INDEX_BY_NAME.clone()]
+    }
+}
+
+pub struct SequencesModel<'a, RT: Runtime> {
+    tx: &'a mut Transaction<RT>,
+    namespace: TableNamespace,
+}
+
+impl<'a, RT: Runtime> SequencesModel<'a, RT> {
+    pub fn new(tx: &'a mut Transaction<RT>, namespace: TableNamespace) -> Self {
+        Self { tx, namespace }
+    }
+
+    /// Allocate the next value from the sequence `name`, creating it if
+    /// needed. Sequences start at 1.
+    pub async fn next(&mut self, name: &str) -> anyhow::Result<i64> {
+        let existing = self
+            .tx
+            .query_system(self.namespace, &SEQUENCES_INDEX_BY_NAME)?
+            .eq(&[name])?
+            .unique()
+            .await?;
+        match existing {
+            Some(doc) => {
+                let value = doc.next_value;
+                let next_value = value.checked_add(1).ok_or_else(|| {
+                    ErrorMetadata::bad_request(
+                        "SequenceExhausted",
+                        format!("Sequence {name:?} has no more values"),
+                    )
+                })?;
+                let metadata = SequenceMetadata {
+                    name: name.to_string(),
+                    next_value,
+                };
+                SystemMetadataModel::new(self.tx, self.namespace)
+                    .replace(doc.id(), metadata.try_into()?)
+                    .await?;
+                Ok(value)
+            },
+            None => {
+                let metadata = SequenceMetadata {
+                    name: name.to_string(),
+                    next_value: 2,
+                };
+                SystemMetadataModel::new(self.tx, self.namespace)
+                    .insert_metadata(&SEQUENCES_TABLE, metadata.try_into()?)
+                    .await?;
+                Ok(1)
+            },
+        }
+    }
+}
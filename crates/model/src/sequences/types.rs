@@ -0,0 +1,41 @@
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use value::codegen_convex_serialization;
+
+/// A named, monotonically increasing counter. `next_value` is the value that
+/// the next allocation from this sequence will return.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(any(test, feature = "testing"), derive(proptest_derive::Arbitrary))]
+pub struct SequenceMetadata {
+    pub name: String,
+    pub next_value: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializedSequenceMetadata {
+    name: String,
+    next_value: i64,
+}
+
+impl From<SequenceMetadata> for SerializedSequenceMetadata {
+    fn from(value: SequenceMetadata) -> Self {
+        Self {
+            name: value.name,
+            next_value: value.next_value,
+        }
+    }
+}
+
+impl From<SerializedSequenceMetadata> for SequenceMetadata {
+    fn from(value: SerializedSequenceMetadata) -> Self {
+        Self {
+            name: value.name,
+            next_value: value.next_value,
+        }
+    }
+}
+
+codegen_convex_serialization!(SequenceMetadata, SerializedSequenceMetadata);
@@ -115,6 +115,7 @@ impl<'a, RT: Runtime> SessionRequestModel<'a, RT> {
                 ),
             ],
             order: Order::Asc,
+            collation: None,
         };
         let query = Query::index_range(index_range_query);
         let (doc, ts): (ParsedDocument<SessionRequestRecord>, Timestamp) = {
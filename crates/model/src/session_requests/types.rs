@@ -8,12 +8,14 @@ use common::{
         LogLines,
     },
     obj,
+    sha256::Sha256,
     types::{
         SessionId,
         SessionRequestSeqNumber,
     },
     value::ConvexValue,
 };
+use errors::ErrorMetadata;
 use value::{
     ConvexObject,
     JsonPackedValue,
@@ -30,6 +32,48 @@ pub struct SessionRequestIdentifier {
     pub request_id: SessionRequestSeqNumber,
 }
 
+/// Longest idempotency key a mutation can be called with, in bytes.
+pub const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 256;
+
+impl SessionRequestIdentifier {
+    /// Identifies a mutation called with a caller-supplied idempotency key, so
+    /// it's deduplicated like a session's requests. The session is derived
+    /// from the function, the caller and the key, so keys only collide within
+    /// one function and principal. Anonymous callers can't use keys since
+    /// they'd all share one scope.
+    pub fn for_idempotency_key(
+        udf_path: &str,
+        identity: &InertIdentity,
+        key: &str,
+    ) -> anyhow::Result<Self> {
+        if matches!(identity, InertIdentity::Unknown) {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "IdempotencyKeyWithoutIdentity",
+                "Idempotency keys can only be used by authenticated callers"
+            ));
+        }
+        if key.len() > MAX_IDEMPOTENCY_KEY_LENGTH {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "IdempotencyKeyTooLong",
+                format!(
+                    "Idempotency key is {} bytes, but the maximum is {MAX_IDEMPOTENCY_KEY_LENGTH}",
+                    key.len()
+                )
+            ));
+        }
+        let mut hasher = Sha256::new();
+        for part in [udf_path, &identity.to_string(), key] {
+            hasher.update(&(part.len() as u64).to_le_bytes());
+            hasher.update(part.as_bytes());
+        }
+        let session_id = hasher.finalize().as_hex()[..32].parse()?;
+        Ok(Self {
+            session_id,
+            request_id: 0,
+        })
+    }
+}
+
 /// Information for a single session request
 ///
 /// This is used to determine whether a session request has already been
@@ -1,5 +1,9 @@
 use std::{
-    collections::BTreeMap,
+    collections::{
+        BTreeMap,
+        BTreeSet,
+        VecDeque,
+    },
     sync::{
         Arc,
         LazyLock,
@@ -7,34 +11,68 @@ use std::{
     time::Duration,
 };
 
+use anyhow::Context;
+use application::{
+    Application,
+    MutationOptions,
+};
 use common::{
+    bootstrap_model::tables::TABLES_TABLE,
+    components::{
+        CanonicalizedComponentFunctionPath,
+        ComponentPath,
+        PublicFunctionPath,
+    },
+    http::HttpRequestStream,
+    knobs::{
+        MAX_OUTSTANDING_ACTION_ASYNC_OPS,
+        MAX_TOTAL_ACTION_ASYNC_OPS,
+    },
     log_lines::LogLevel,
     runtime::{
-        JoinSet,
         Runtime,
         UnixTimestamp,
     },
-    types::EnvVarValue,
-    value::NamespacedTableMapping,
+    testing::TestIdGenerator,
+    types::{
+        EnvVarName,
+        EnvVarValue,
+        FunctionCaller,
+        UdfType,
+    },
+    value::{
+        ConvexValue,
+        NamespacedTableMapping,
+        TableNamespace,
+    },
+    RequestId,
 };
 use deno_core::{
     sourcemap::SourceMap,
     v8,
 };
-use futures::{
-    future,
-    FutureExt,
-};
+use errors::ErrorMetadata;
+use futures::future;
 use isolate::{
     environment::{
         crypto_rng::CryptoRng,
+        helpers::identity_claims,
+        AsyncOpBudget,
+        AsyncOpConcurrencyLimit,
         AsyncOpRequest,
+        FetchHostPolicy,
         IsolateEnvironment,
         ModuleCodeCacheResult,
     },
     ConcurrencyPermit,
+    ExecutionScope,
     Timeout,
 };
+use keybroker::{
+    Identity,
+    UserIdentityAttributes,
+};
+use maplit::btreemap;
 use model::modules::module_versions::FullModuleSource;
 use rand::{
     Rng,
@@ -42,7 +80,15 @@ use rand::{
 };
 use rand_chacha::ChaCha12Rng;
 use runtime::testing::TestRuntime;
-use serde_json::Value as JsonValue;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use serde_json::{
+    json,
+    Value as JsonValue,
+};
+use tokio::sync::oneshot;
 
 // NB: These files are generated by the *isolate* crate's build script.
 pub const TEST_SOURCE: &str = include_str!("../../../../../npm-packages/simulation/dist/main.js");
@@ -52,26 +98,528 @@ pub static TEST_SOURCE_MAP: LazyLock<SourceMap> = LazyLock::new(|| {
     SourceMap::from_slice(TEST_SOURCE_MAP_STR.as_bytes()).expect("Invalid source map")
 });
 
+// Matches `vector::DEFAULT_VECTOR_LIMIT`.
+const DEFAULT_VECTOR_SEARCH_LIMIT: usize = 10;
+
+/// A canned response for fetches to URLs starting with a registered prefix.
+#[derive(Clone, Debug)]
+pub struct MockFetchResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+/// A function scheduled with `1.0/schedule`, which the simulation records
+/// rather than runs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduledJob {
+    pub id: String,
+    /// The function's name or reference, as passed by the scheduler.
+    pub path: String,
+    pub args: JsonValue,
+    pub ts: UnixTimestamp,
+}
+
+/// The timers and storage ops a run started, and the order and virtual time
+/// they resolved at. Every run records one, and replaying it with
+/// `with_async_op_log` reproduces the run even if the ops would otherwise
+/// finish in a different order or at a different time.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AsyncOpLog {
+    /// The clock's reading when the run started, in milliseconds since the
+    /// epoch.
+    pub start_ms: u64,
+    /// A description of each op, like `sleep 10ms`, indexed by op id.
+    pub started: Vec<String>,
+    /// The ops in the order they resolved.
+    pub resolved: Vec<ResolvedAsyncOp>,
+}
+
+impl AsyncOpLog {
+    /// The ids of the resolved ops, in the order they resolved.
+    pub fn resolved_op_ids(&self) -> Vec<usize> {
+        self.resolved.iter().map(|op| op.op_id).collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedAsyncOp {
+    pub op_id: usize,
+    /// The clock's reading once the op resolved, in milliseconds since the
+    /// epoch.
+    pub at_ms: u64,
+}
+
+/// Handles a syscall by name, returning its JSON result.
+pub type SyscallHandler = Box<dyn FnMut(JsonValue) -> anyhow::Result<JsonValue>>;
+
 pub struct TestEnvironment {
     rt: TestRuntime,
     rng: ChaCha12Rng,
 
-    next_timer_id: usize,
-    timers: JoinSet<usize>,
-    timer_resolvers: BTreeMap<usize, v8::Global<v8::PromiseResolver>>,
+    // Timers and storage ops are numbered in the order they're started, which
+    // is deterministic as long as they're resolved in the same order. Ops
+    // finish in order of their deadline on the runtime's virtual clock, with
+    // ties broken by id.
+    next_async_op_id: usize,
+    scheduled_async_ops: BTreeMap<(tokio::time::Instant, usize), JsonValue>,
+    async_op_resolvers: BTreeMap<usize, v8::Global<v8::PromiseResolver>>,
+    completed_async_ops: BTreeMap<usize, JsonValue>,
+    // `start_ms` is set when the run first reads the clock or starts an op.
+    run_started: bool,
+    async_op_log: AsyncOpLog,
+    // While replaying a log, ops must start as recorded and resolve in the
+    // recorded order, and the clock reads as it did when the last op to
+    // resolve resolved. This run's position in it is the length of
+    // `async_op_log.resolved`.
+    replay: Option<AsyncOpLog>,
+
+    // When non-empty, the front is the current time, and it's popped each
+    // time one of `timer_ops` resolves, as long as another instant follows.
+    scripted_clock: VecDeque<UnixTimestamp>,
+    timer_ops: BTreeSet<usize>,
+
+    async_op_budget: AsyncOpBudget,
+    async_op_concurrency_limit: AsyncOpConcurrencyLimit,
+    fetch_host_policy: FetchHostPolicy,
+    fetch_requests: Vec<HttpRequestStream>,
+    fetch_mocks: Vec<(String, MockFetchResponse)>,
+    // Bodies of mocked responses, written to their streams before the fetches
+    // resolve.
+    mocked_fetch_bodies: Vec<(uuid::Uuid, bytes::Bytes)>,
+
+    sequences: BTreeMap<String, i64>,
+    // Settled async syscalls waiting to be resolved in JS, with either the
+    // JSON-serialized result or an error message to reject with.
+    async_syscall_results: Vec<(v8::Global<v8::PromiseResolver>, Result<String, String>)>,
+    // Async syscalls the test asked to complete itself, keyed by id.
+    deferred_async_syscalls: BTreeSet<String>,
+    next_async_syscall_id: usize,
+    pending_async_syscalls: BTreeMap<usize, PendingAsyncSyscall>,
+    // Jobs run in order of their scheduled time, with ties broken by the
+    // order they were scheduled in.
+    next_scheduled_job_id: usize,
+    scheduled_jobs: BTreeMap<(UnixTimestamp, usize), ScheduledJob>,
+    identity: Option<UserIdentityAttributes>,
+    env_vars: BTreeMap<EnvVarName, EnvVarValue>,
+    table_mapping: NamespacedTableMapping,
+    // Results served for each vector index, best match first.
+    vector_indexes: BTreeMap<String, Vec<JsonValue>>,
+    // Documents searched by each full-text index, in relevance order, and
+    // the results left in each open search query stream.
+    search_indexes: BTreeMap<String, Vec<JsonValue>>,
+    next_query_id: u32,
+    search_queries: BTreeMap<u32, VecDeque<JsonValue>>,
+    // Console messages, in the order they were logged. Only kept if the test
+    // asked for them with `with_captured_log_lines`.
+    log_lines: Option<Vec<(LogLevel, String)>>,
+    min_log_level: LogLevel,
+
+    // Registered by tests, and consulted before the built-in syscalls.
+    syscall_handlers: BTreeMap<String, SyscallHandler>,
+    async_syscall_handlers: BTreeMap<String, SyscallHandler>,
+
+    // Runs the queries and mutations actions call, in the order they're
+    // called.
+    application: Option<Application<TestRuntime>>,
+    pending_function_calls: VecDeque<PendingFunctionCall>,
+
+    storage_latency: Duration,
+    stored_files: BTreeMap<String, StoredFile>,
+
+    cancellation: Option<oneshot::Receiver<()>>,
+
+    // Module sources by path, e.g. `test.js` for `convex:/test.js`.
+    modules: BTreeMap<String, FullModuleSource>,
+}
+
+fn default_table_mapping() -> NamespacedTableMapping {
+    let mut id_generator = TestIdGenerator::new();
+    // Registering any system table also registers `_tables` and `_index`.
+    id_generator.system_table_id(&TABLES_TABLE);
+    id_generator.namespace(TableNamespace::test_user())
+}
+
+struct PendingAsyncSyscall {
+    name: String,
+    args: JsonValue,
+    resolver: v8::Global<v8::PromiseResolver>,
+}
+
+struct PendingFunctionCall {
+    udf_type: UdfType,
+    path: CanonicalizedComponentFunctionPath,
+    args: JsonValue,
+    resolver: v8::Global<v8::PromiseResolver>,
+}
+
+/// Metadata for a file written with `storage.store()`. Contents aren't kept
+/// since nothing in the simulation reads them back.
+struct StoredFile {
+    content_type: Option<String>,
+    content_length: usize,
 }
 
 impl TestEnvironment {
     pub fn new(rt: TestRuntime) -> Self {
-        let rng = ChaCha12Rng::from_seed(rt.rng().random());
+        let seed = rt.rng().random();
+        Self::with_seed(rt, seed)
+    }
+
+    /// Seed the RNG behind `Math.random()` explicitly rather than from the
+    /// runtime, so a driver can explore many seeds and replay a failing one.
+    pub fn with_seed(rt: TestRuntime, seed: [u8; 32]) -> Self {
         Self {
             rt,
-            rng,
+            rng: ChaCha12Rng::from_seed(seed),
+
+            next_async_op_id: 0,
+            scheduled_async_ops: BTreeMap::new(),
+            async_op_resolvers: BTreeMap::new(),
+            completed_async_ops: BTreeMap::new(),
+            run_started: false,
+            async_op_log: AsyncOpLog::default(),
+            replay: None,
+
+            scripted_clock: VecDeque::new(),
+            timer_ops: BTreeSet::new(),
+
+            async_op_budget: AsyncOpBudget::new(*MAX_TOTAL_ACTION_ASYNC_OPS),
+            async_op_concurrency_limit: AsyncOpConcurrencyLimit::new(
+                *MAX_OUTSTANDING_ACTION_ASYNC_OPS,
+            ),
+            fetch_host_policy: FetchHostPolicy::from_knobs(),
+            fetch_requests: vec![],
+            fetch_mocks: vec![],
+            mocked_fetch_bodies: vec![],
 
-            next_timer_id: 0,
-            timers: JoinSet::new(),
-            timer_resolvers: BTreeMap::new(),
+            sequences: BTreeMap::new(),
+            async_syscall_results: vec![],
+            deferred_async_syscalls: BTreeSet::new(),
+            next_async_syscall_id: 0,
+            pending_async_syscalls: BTreeMap::new(),
+            next_scheduled_job_id: 0,
+            scheduled_jobs: BTreeMap::new(),
+            identity: None,
+            env_vars: BTreeMap::new(),
+            table_mapping: default_table_mapping(),
+            vector_indexes: BTreeMap::new(),
+            search_indexes: BTreeMap::new(),
+            next_query_id: 0,
+            search_queries: BTreeMap::new(),
+            log_lines: None,
+            min_log_level: LogLevel::Debug,
+
+            syscall_handlers: BTreeMap::new(),
+            async_syscall_handlers: BTreeMap::new(),
+
+            application: None,
+            pending_function_calls: VecDeque::new(),
+
+            storage_latency: Duration::ZERO,
+            stored_files: BTreeMap::new(),
+
+            cancellation: None,
+
+            modules: btreemap! {
+                "test.js".to_string() => FullModuleSource {
+                    source: TEST_SOURCE.into(),
+                    source_map: Some(TEST_SOURCE_MAP_STR.to_string()),
+                },
+            },
+        }
+    }
+
+    /// Limit the total number of async ops the environment will start.
+    pub fn with_async_op_limit(mut self, limit: usize) -> Self {
+        self.async_op_budget = AsyncOpBudget::new(limit);
+        self
+    }
+
+    /// Limit how many async ops may be outstanding at once.
+    pub fn with_concurrent_async_op_limit(mut self, limit: usize) -> Self {
+        self.async_op_concurrency_limit = AsyncOpConcurrencyLimit::new(limit);
+        self
+    }
+
+    /// Restrict which hosts `fetch()` may make requests to.
+    pub fn with_fetch_host_policy(mut self, policy: FetchHostPolicy) -> Self {
+        self.fetch_host_policy = policy;
+        self
+    }
+
+    /// Run as a user with the given identity rather than unauthenticated.
+    pub fn with_identity(mut self, identity: UserIdentityAttributes) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Delay every simulated storage read and write by `latency` of virtual
+    /// time.
+    pub fn with_storage_latency(mut self, latency: Duration) -> Self {
+        self.storage_latency = latency;
+        self
+    }
+
+    /// Replay a log from `take_async_op_log`: ops resolve in the recorded
+    /// order rather than the order they complete in, the clock reads the
+    /// recorded times, and starting an op other than the recorded one fails.
+    /// An op that completes early is held back until it's next.
+    pub fn with_async_op_log(mut self, log: AsyncOpLog) -> Self {
+        self.replay = Some(log);
+        self
+    }
+
+    /// Respond to fetches of URLs starting with `url_prefix` with `response`.
+    /// The first matching mock wins, and fetches that match none of them are
+    /// rejected.
+    pub fn with_fetch_mock(mut self, url_prefix: &str, response: MockFetchResponse) -> Self {
+        self.fetch_mocks.push((url_prefix.to_string(), response));
+        self
+    }
+
+    /// Serve `process.env` from `env_vars`.
+    pub fn with_env_vars(mut self, env_vars: BTreeMap<EnvVarName, EnvVarValue>) -> Self {
+        self.env_vars = env_vars;
+        self
+    }
+
+    /// Resolve table names with `table_mapping` instead of the default
+    /// mapping, which only has the `_tables` and `_index` system tables.
+    pub fn with_table_mapping(mut self, table_mapping: NamespacedTableMapping) -> Self {
+        self.table_mapping = table_mapping;
+        self
+    }
+
+    /// Answer vector searches against `index_name` (e.g.
+    /// `"documents.by_embedding"`) with the first `limit` of `results`,
+    /// which should be `{ _id, _score }` objects in descending score order.
+    /// The query vector and filters are ignored. Searches against indexes
+    /// that weren't registered are rejected.
+    pub fn with_vector_index(mut self, index_name: &str, results: Vec<JsonValue>) -> Self {
+        self.vector_indexes.insert(index_name.to_string(), results);
+        self
+    }
+
+    /// Answer full-text searches against `index_name` (e.g.
+    /// `"messages.search_body"`) from `documents`, which are taken to be in
+    /// relevance order. A document matches if its search field contains any
+    /// of the query's terms, ignoring case, and it equals every `eq` filter.
+    /// Searches against indexes that weren't registered are rejected.
+    pub fn with_search_index(mut self, index_name: &str, documents: Vec<JsonValue>) -> Self {
+        self.search_indexes
+            .insert(index_name.to_string(), documents);
+        self
+    }
+
+    /// Keep console messages for `take_log_lines`, in addition to forwarding
+    /// them to `tracing`.
+    pub fn with_captured_log_lines(mut self) -> Self {
+        self.log_lines = Some(vec![]);
+        self
+    }
+
+    /// Drop console messages below `level`. Errors are always kept.
+    pub fn with_min_log_level(mut self, level: LogLevel) -> Self {
+        self.min_log_level = level;
+        self
+    }
+
+    /// Leave `Convex.asyncSyscall(name, ...)` calls pending until the test
+    /// completes them with `complete_async_syscall`. Async syscalls that
+    /// are neither built in nor registered are rejected.
+    pub fn with_deferred_async_syscall(mut self, name: &str) -> Self {
+        self.deferred_async_syscalls.insert(name.to_string());
+        self
+    }
+
+    /// Handle `Convex.syscall(name, ...)` with `handler`, overriding any
+    /// built-in implementation.
+    pub fn with_syscall(
+        mut self,
+        name: &str,
+        handler: impl FnMut(JsonValue) -> anyhow::Result<JsonValue> + 'static,
+    ) -> Self {
+        self.syscall_handlers
+            .insert(name.to_string(), Box::new(handler));
+        self
+    }
+
+    /// Handle `Convex.asyncSyscall(name, ...)` with `handler`, overriding any
+    /// built-in implementation. The promise resolves with the handler's result
+    /// serialized to a JSON string, or rejects with its error's message.
+    pub fn with_async_syscall(
+        mut self,
+        name: &str,
+        handler: impl FnMut(JsonValue) -> anyhow::Result<JsonValue> + 'static,
+    ) -> Self {
+        self.async_syscall_handlers
+            .insert(name.to_string(), Box::new(handler));
+        self
+    }
+
+    /// Run the queries and mutations called with `1.0/actions/query` and
+    /// `1.0/actions/mutation` against `application`, as the system user. They
+    /// only run when the test calls `run_function_calls`.
+    pub fn with_application(mut self, application: Application<TestRuntime>) -> Self {
+        self.application = Some(application);
+        self
+    }
+
+    /// Cancel all pending timers and storage ops once `cancellation` fires,
+    /// failing the `next_async_op` call that's waiting on them.
+    pub fn with_cancellation(mut self, cancellation: oneshot::Receiver<()>) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Serve `source` for the module at `path`, e.g. `helpers.js` for
+    /// `convex:/helpers.js`. Modules can import each other, and `test.js`
+    /// defaults to the simulation's bundled client.
+    pub fn with_module(mut self, path: &str, source: FullModuleSource) -> Self {
+        self.modules.insert(path.to_string(), source);
+        self
+    }
+
+    /// Have `unix_timestamp` return `instants` in order instead of the
+    /// runtime's clock, moving to the next instant each time a timer fires.
+    /// The clock stays at the last instant once the script runs out.
+    pub fn with_scripted_clock(mut self, instants: Vec<UnixTimestamp>) -> Self {
+        self.scripted_clock = instants.into();
+        self
+    }
+
+    fn now(&self) -> UnixTimestamp {
+        if let Some(log) = &self.replay {
+            let resolved = self.async_op_log.resolved.len().min(log.resolved.len());
+            let now_ms = log.resolved[..resolved]
+                .last()
+                .map_or(log.start_ms, |op| op.at_ms);
+            return UnixTimestamp::from_millis(now_ms);
+        }
+        match self.scripted_clock.front() {
+            Some(now) => *now,
+            None => self.rt.unix_timestamp(),
+        }
+    }
+
+    /// Open a query stream over a full-text search's results. Other queries
+    /// read the database, which the environment doesn't have.
+    fn start_search_query(&mut self, args: JsonValue) -> anyhow::Result<JsonValue> {
+        #[derive(Deserialize)]
+        #[serde(tag = "type")]
+        enum Source {
+            Search {
+                #[serde(rename = "indexName")]
+                index_name: String,
+                filters: Vec<SearchFilter>,
+            },
+            #[serde(other)]
+            Other,
+        }
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        enum Operator {
+            Limit(usize),
+            Filter(JsonValue),
+        }
+        #[derive(Deserialize)]
+        struct Query {
+            source: Source,
+            operators: Vec<Operator>,
+        }
+        #[derive(Deserialize)]
+        struct QueryStreamArgs {
+            query: Query,
+        }
+        let QueryStreamArgs { query } = serde_json::from_value(args)?;
+        let Source::Search {
+            index_name,
+            filters,
+        } = query.source
+        else {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "UnsupportedQuery",
+                "Only search queries are supported in the simulation"
+            ));
+        };
+        let Some(documents) = self.search_indexes.get(&index_name) else {
+            anyhow::bail!(ErrorMetadata::bad_request(
+                "SearchIndexNotFound",
+                format!(
+                    "Search index {index_name:?} not found. Known indexes: {:?}",
+                    self.search_indexes.keys().collect::<Vec<_>>(),
+                )
+            ));
+        };
+        let mut limit = usize::MAX;
+        for operator in query.operators {
+            match operator {
+                Operator::Limit(n) => limit = limit.min(n),
+                Operator::Filter(_) => anyhow::bail!(ErrorMetadata::bad_request(
+                    "UnsupportedQuery",
+                    "Filters on search queries aren't supported in the simulation"
+                )),
+            }
+        }
+        let results = documents
+            .iter()
+            .filter(|document| filters.iter().all(|filter| filter.matches(document)))
+            .take(limit)
+            .cloned()
+            .collect();
+        let query_id = self.next_query_id;
+        self.next_query_id += 1;
+        self.search_queries.insert(query_id, results);
+        Ok(json!({ "queryId": query_id }))
+    }
+
+    fn start_run(&mut self) -> anyhow::Result<()> {
+        if !self.run_started {
+            self.async_op_log.start_ms = self.now().as_ms_since_epoch()?;
+            self.run_started = true;
+        }
+        Ok(())
+    }
+
+    /// The replayed op that resolves next, if replaying.
+    fn next_replayed_op(&self) -> Option<&ResolvedAsyncOp> {
+        self.replay
+            .as_ref()?
+            .resolved
+            .get(self.async_op_log.resolved.len())
+    }
+
+    fn start_timed_async_op(
+        &mut self,
+        description: String,
+        duration: Duration,
+        result: JsonValue,
+        resolver: v8::Global<v8::PromiseResolver>,
+    ) -> anyhow::Result<()> {
+        let id = self.next_async_op_id;
+        if let Some(log) = &self.replay {
+            let recorded = log.started.get(id);
+            anyhow::ensure!(
+                recorded == Some(&description),
+                "Replay diverged: async op {id} is `{description}`, but was {recorded:?} when \
+                 recorded"
+            );
         }
+        self.async_op_log.started.push(description);
+        self.next_async_op_id += 1;
+        let deadline = self.rt.monotonic_now() + duration;
+        self.scheduled_async_ops.insert((deadline, id), result);
+        self.async_op_resolvers.insert(id, resolver);
+        Ok(())
+    }
+
+    fn start_storage_op(
+        &mut self,
+        description: String,
+        result: JsonValue,
+        resolver: v8::Global<v8::PromiseResolver>,
+    ) -> anyhow::Result<()> {
+        self.start_timed_async_op(description, self.storage_latency, result, resolver)
     }
 }
 
@@ -82,34 +630,214 @@ impl IsolateEnvironment<TestRuntime> for TestEnvironment {
         _timeout: &mut Timeout<TestRuntime>,
         _permit: &mut Option<ConcurrencyPermit>,
     ) -> anyhow::Result<Option<(Arc<FullModuleSource>, ModuleCodeCacheResult)>> {
-        if path != "test.js" {
+        let Some(source) = self.modules.get(path) else {
             return Ok(None);
-        }
+        };
         Ok(Some((
-            Arc::new(FullModuleSource {
-                source: TEST_SOURCE.into(),
-                source_map: Some(TEST_SOURCE_MAP_STR.to_string()),
-            }),
+            Arc::new(source.clone()),
             ModuleCodeCacheResult::noop(),
         )))
     }
 
-    fn syscall(&mut self, _name: &str, _args: JsonValue) -> anyhow::Result<JsonValue> {
-        panic!("syscall() unimplemented");
+    async fn module_paths(
+        &mut self,
+        _timeout: &mut Timeout<TestRuntime>,
+        _permit: &mut Option<ConcurrencyPermit>,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        Ok(Some(self.modules.keys().cloned().collect()))
+    }
+
+    fn syscall(&mut self, name: &str, args: JsonValue) -> anyhow::Result<JsonValue> {
+        if let Some(handler) = self.syscall_handlers.get_mut(name) {
+            return handler(args);
+        }
+        match name {
+            "1.0/queryStream" => self.start_search_query(args),
+            "1.0/queryCleanup" => {
+                #[derive(Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                struct QueryCleanupArgs {
+                    query_id: u32,
+                }
+                let QueryCleanupArgs { query_id } = serde_json::from_value(args)?;
+                let cleaned_up = self.search_queries.remove(&query_id).is_some();
+                Ok(JsonValue::Bool(cleaned_up))
+            },
+            // Surface unimplemented syscalls as errors JS can catch rather
+            // than aborting the isolate.
+            _ => anyhow::bail!(ErrorMetadata::bad_request(
+                "UnknownOperation",
+                format!("Unknown operation {name}")
+            )),
+        }
     }
 
     fn start_async_syscall(
         &mut self,
         name: String,
         args: JsonValue,
-        _resolver: v8::Global<v8::PromiseResolver>,
+        resolver: v8::Global<v8::PromiseResolver>,
     ) -> anyhow::Result<()> {
-        tracing::info!("Ignoring async syscall: {name:?} {args:?}");
+        if let Some(handler) = self.async_syscall_handlers.get_mut(&name) {
+            let result = handler(args)
+                .map(|value| value.to_string())
+                .map_err(|e| e.to_string());
+            self.async_syscall_results.push((resolver, result));
+            return Ok(());
+        }
+        if self.deferred_async_syscalls.contains(&name) {
+            let id = self.next_async_syscall_id;
+            self.next_async_syscall_id += 1;
+            self.pending_async_syscalls.insert(
+                id,
+                PendingAsyncSyscall {
+                    name,
+                    args,
+                    resolver,
+                },
+            );
+            return Ok(());
+        }
+        match &name[..] {
+            "1.0/sequenceNext" => {
+                #[derive(Deserialize)]
+                struct SequenceNextArgs {
+                    name: String,
+                }
+                let args: SequenceNextArgs = serde_json::from_value(args)?;
+                // There's no concurrency within a single isolate, so every
+                // allocation commits immediately.
+                let next_value = self.sequences.entry(args.name).or_insert(1);
+                let value = *next_value;
+                *next_value += 1;
+                let result = ConvexValue::from(value).to_internal_json().to_string();
+                self.async_syscall_results.push((resolver, Ok(result)));
+            },
+            "1.0/schedule" => {
+                #[derive(Deserialize)]
+                struct ScheduleArgs {
+                    name: Option<String>,
+                    reference: Option<String>,
+                    ts: f64,
+                    args: JsonValue,
+                }
+                let args: ScheduleArgs = serde_json::from_value(args)?;
+                let path = args
+                    .name
+                    .or(args.reference)
+                    .context("Missing scheduled function name")?;
+                let ts = UnixTimestamp::from_secs_f64(args.ts)?;
+                let seq = self.next_scheduled_job_id;
+                self.next_scheduled_job_id += 1;
+                let id = format!("scheduled-job-{seq}");
+                self.scheduled_jobs.insert(
+                    (ts, seq),
+                    ScheduledJob {
+                        id: id.clone(),
+                        path,
+                        args: args.args,
+                        ts,
+                    },
+                );
+                let result = JsonValue::String(id).to_string();
+                self.async_syscall_results.push((resolver, Ok(result)));
+            },
+            "1.0/queryStreamNext" => {
+                #[derive(Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                struct QueryStreamNextArgs {
+                    query_id: u32,
+                }
+                let QueryStreamNextArgs { query_id } = serde_json::from_value(args)?;
+                let results = self
+                    .search_queries
+                    .get_mut(&query_id)
+                    .with_context(|| format!("Unknown query {query_id}"))?;
+                let result = match results.pop_front() {
+                    Some(value) => json!({ "value": value, "done": false }),
+                    None => {
+                        self.search_queries.remove(&query_id);
+                        json!({ "value": null, "done": true })
+                    },
+                };
+                self.async_syscall_results
+                    .push((resolver, Ok(result.to_string())));
+            },
+            "1.0/actions/vectorSearch" => {
+                #[derive(Deserialize)]
+                #[serde(rename_all = "camelCase")]
+                struct VectorSearchQuery {
+                    index_name: String,
+                    limit: Option<usize>,
+                }
+                #[derive(Deserialize)]
+                struct VectorSearchArgs {
+                    query: VectorSearchQuery,
+                }
+                let VectorSearchArgs { query } = serde_json::from_value(args)?;
+                let result = match self.vector_indexes.get(&query.index_name) {
+                    Some(results) => {
+                        let limit = query.limit.unwrap_or(DEFAULT_VECTOR_SEARCH_LIMIT);
+                        let results: Vec<_> = results.iter().take(limit).cloned().collect();
+                        Ok(json!({ "results": results }).to_string())
+                    },
+                    None => Err(format!(
+                        "Vector index {:?} not found. Known indexes: {:?}",
+                        query.index_name,
+                        self.vector_indexes.keys().collect::<Vec<_>>(),
+                    )),
+                };
+                self.async_syscall_results.push((resolver, result));
+            },
+            "1.0/actions/query" | "1.0/actions/mutation" if self.application.is_some() => {
+                #[derive(Deserialize)]
+                struct RunFunctionArgs {
+                    name: Option<String>,
+                    args: JsonValue,
+                }
+                let RunFunctionArgs {
+                    name: function_name,
+                    args,
+                } = serde_json::from_value(args)?;
+                let udf_path = function_name
+                    .context("Only functions called by name are supported")?
+                    .parse()?;
+                let udf_type = if name == "1.0/actions/query" {
+                    UdfType::Query
+                } else {
+                    UdfType::Mutation
+                };
+                self.pending_function_calls.push_back(PendingFunctionCall {
+                    udf_type,
+                    path: CanonicalizedComponentFunctionPath {
+                        component: ComponentPath::root(),
+                        udf_path,
+                    },
+                    args,
+                    resolver,
+                });
+            },
+            "1.0/getIdentityClaims" => {
+                let user_identity = match &self.identity {
+                    Some(identity) => identity.clone().try_into()?,
+                    None => JsonValue::Null,
+                };
+                let result = identity_claims(user_identity, args)?.to_string();
+                self.async_syscall_results.push((resolver, Ok(result)));
+            },
+            _ => {
+                let message = format!("Unknown async syscall {name}");
+                self.async_syscall_results.push((resolver, Err(message)));
+            },
+        }
         Ok(())
     }
 
     fn trace(&mut self, level: LogLevel, messages: Vec<String>) -> anyhow::Result<()> {
-        for message in messages {
+        if !level.meets_threshold(&self.min_log_level) {
+            return Ok(());
+        }
+        for message in &messages {
             match level {
                 LogLevel::Debug => tracing::debug!("[console] {message}"),
                 LogLevel::Error => tracing::error!("[console] {message}"),
@@ -118,6 +846,9 @@ impl IsolateEnvironment<TestRuntime> for TestEnvironment {
                 LogLevel::Log => tracing::info!("[console] {message}"),
             }
         }
+        if let Some(log_lines) = &mut self.log_lines {
+            log_lines.push((level, messages.join(" ")));
+        }
         Ok(())
     }
 
@@ -130,18 +861,19 @@ impl IsolateEnvironment<TestRuntime> for TestEnvironment {
     }
 
     fn unix_timestamp(&mut self) -> anyhow::Result<UnixTimestamp> {
-        Ok(self.rt.unix_timestamp())
+        self.start_run()?;
+        Ok(self.now())
     }
 
     fn get_environment_variable(
         &mut self,
-        _name: common::types::EnvVarName,
+        name: EnvVarName,
     ) -> anyhow::Result<Option<EnvVarValue>> {
-        Ok(None)
+        Ok(self.env_vars.get(&name).cloned())
     }
 
     fn get_all_table_mappings(&mut self) -> anyhow::Result<NamespacedTableMapping> {
-        panic!("get_all_table_mappings() unimplemented");
+        Ok(self.table_mapping.clone())
     }
 
     fn start_async_op(
@@ -149,20 +881,98 @@ impl IsolateEnvironment<TestRuntime> for TestEnvironment {
         request: AsyncOpRequest,
         resolver: v8::Global<v8::PromiseResolver>,
     ) -> anyhow::Result<()> {
+        self.start_run()?;
+        self.fetch_host_policy.check(&request)?;
+        self.async_op_concurrency_limit
+            .check(&request, self.async_op_resolvers.len())?;
+        self.async_op_budget.start(&request)?;
         match request {
             AsyncOpRequest::Sleep { until, .. } => {
-                let id = self.next_timer_id;
-                self.next_timer_id += 1;
-
-                let now = self.rt.unix_timestamp();
+                let now = self.now();
                 let duration = if until > now {
                     until - now
                 } else {
                     Duration::ZERO
                 };
-                self.timers
-                    .spawn("timer", tokio::time::sleep(duration).map(move |_| id));
-                self.timer_resolvers.insert(id, resolver);
+                self.timer_ops.insert(self.next_async_op_id);
+                self.start_timed_async_op(
+                    format!("sleep {duration:?}"),
+                    duration,
+                    JsonValue::Null,
+                    resolver,
+                )?;
+            },
+            AsyncOpRequest::Fetch {
+                request,
+                response_body_stream_id,
+            } => {
+                let url = request.url.to_string();
+                // Keep the request around so tests can inspect what was sent.
+                self.fetch_requests.push(request);
+                let Some((_, response)) = self
+                    .fetch_mocks
+                    .iter()
+                    .find(|(url_prefix, _)| url.starts_with(&url_prefix[..]))
+                else {
+                    anyhow::bail!(ErrorMetadata::bad_request(
+                        "FetchNotMocked",
+                        format!("No mock response registered for fetch to {url}"),
+                    ));
+                };
+                let response = response.clone();
+                self.mocked_fetch_bodies
+                    .push((response_body_stream_id, response.body.into()));
+                let result = json!({
+                    "streamId": response_body_stream_id.to_string(),
+                    "status": response.status,
+                    "headerPairs": [],
+                    "url": url,
+                });
+                self.start_timed_async_op(
+                    format!("fetch {url}"),
+                    Duration::ZERO,
+                    result,
+                    resolver,
+                )?;
+            },
+            AsyncOpRequest::StorageStore {
+                content_type,
+                content_length,
+                ..
+            } => {
+                let storage_id = format!("storage{}", self.stored_files.len());
+                let content_length = content_length
+                    .map(|length| length.parse::<usize>())
+                    .transpose()?
+                    .unwrap_or(0);
+                self.stored_files.insert(
+                    storage_id.clone(),
+                    StoredFile {
+                        content_type,
+                        content_length,
+                    },
+                );
+                self.start_storage_op(
+                    format!("storage store {content_length} bytes"),
+                    JsonValue::String(storage_id),
+                    resolver,
+                )?;
+            },
+            AsyncOpRequest::StorageGet {
+                storage_id,
+                stream_id,
+            } => {
+                // The body stream is never written to, so reading the blob's
+                // contents hangs like an unresolved fetch.
+                let result = match self.stored_files.get(&storage_id) {
+                    Some(file) => json!({
+                        "bodyStreamId": stream_id.to_string(),
+                        "contentType": file.content_type,
+                        "contentLength": file.content_length,
+                    }),
+                    None => JsonValue::Null,
+                };
+                self.start_storage_op(format!("storage get {storage_id}"), result, resolver)?;
             },
             req => {
                 tracing::debug!("Ignoring async op request: {req:?}");
@@ -181,15 +991,362 @@ impl IsolateEnvironment<TestRuntime> for TestEnvironment {
 }
 
 impl TestEnvironment {
-    pub async fn next_timer(&mut self) -> anyhow::Result<v8::Global<v8::PromiseResolver>> {
-        let Some(timer) = self.timers.join_next().await else {
-            return future::pending().await;
+    /// All `fetch()` requests issued so far, in order.
+    pub fn fetch_requests(&self) -> &[HttpRequestStream] {
+        &self.fetch_requests
+    }
+
+    fn take_mocked_fetch_bodies(&mut self) -> Vec<(uuid::Uuid, bytes::Bytes)> {
+        std::mem::take(&mut self.mocked_fetch_bodies)
+    }
+
+    /// Take the console messages logged since the last call, in order. Each
+    /// `console` call's arguments are joined with spaces, like in the
+    /// dashboard. Always empty unless the environment was created with
+    /// `with_captured_log_lines`.
+    pub fn take_log_lines(&mut self) -> Vec<(LogLevel, String)> {
+        self.log_lines
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Take the results of async syscalls that have completed but haven't
+    /// been resolved in JS yet.
+    pub fn take_async_syscall_results(
+        &mut self,
+    ) -> Vec<(v8::Global<v8::PromiseResolver>, Result<String, String>)> {
+        std::mem::take(&mut self.async_syscall_results)
+    }
+
+    /// Async syscalls registered with `with_deferred_async_syscall` that
+    /// haven't been completed yet, as `(id, name, args)` in the order they
+    /// were started.
+    pub fn pending_async_syscalls(&self) -> impl Iterator<Item = (usize, &str, &JsonValue)> + '_ {
+        self.pending_async_syscalls
+            .iter()
+            .map(|(id, syscall)| (*id, &syscall.name[..], &syscall.args))
+    }
+
+    /// Resolve the pending async syscall `id` with `result`, or reject it with
+    /// the error's message.
+    pub fn complete_async_syscall(
+        &mut self,
+        id: usize,
+        result: anyhow::Result<JsonValue>,
+    ) -> anyhow::Result<()> {
+        let syscall = self
+            .pending_async_syscalls
+            .remove(&id)
+            .with_context(|| format!("No pending async syscall {id}"))?;
+        let result = result
+            .map(|value| value.to_string())
+            .map_err(|e| e.to_string());
+        self.async_syscall_results.push((syscall.resolver, result));
+        Ok(())
+    }
+
+    pub fn has_async_syscall_results(&self) -> bool {
+        !self.async_syscall_results.is_empty()
+    }
+
+    /// Run the queries and mutations called since the last call, one at a
+    /// time in the order they were called, returning whether there were any.
+    /// Each call's promise resolves with the function's result, or rejects
+    /// with its error, including system errors like running out of OCC
+    /// retries.
+    pub async fn run_function_calls(&mut self) -> bool {
+        let Some(application) = &self.application else {
+            return false;
         };
-        let timer_id = timer?;
-        let resolver = self
-            .timer_resolvers
-            .remove(&timer_id)
-            .ok_or_else(|| anyhow::anyhow!("Timer resolver not found"))?;
-        Ok(resolver)
+        let ran = !self.pending_function_calls.is_empty();
+        while let Some(call) = self.pending_function_calls.pop_front() {
+            let result = run_function_call(application, call.udf_type, call.path, call.args)
+                .await
+                .map(|value| value.to_string())
+                .map_err(|e| e.to_string());
+            self.async_syscall_results.push((call.resolver, result));
+        }
+        ran
+    }
+
+    /// The next value each sequence allocated via `1.0/sequenceNext` will
+    /// return.
+    pub fn sequences(&self) -> impl Iterator<Item = (&str, i64)> + '_ {
+        self.sequences
+            .iter()
+            .map(|(name, next_value)| (&name[..], *next_value))
+    }
+
+    /// Jobs scheduled so far and not yet taken with `next_scheduled_job`, in
+    /// the order they'll run.
+    pub fn scheduled_jobs(&self) -> impl Iterator<Item = &ScheduledJob> + '_ {
+        self.scheduled_jobs.values()
+    }
+
+    /// Wait on the virtual clock until the next scheduled job is due, and
+    /// take it so the test can run it. Jobs scheduled in the past are due
+    /// immediately. Returns `None` if nothing is scheduled.
+    pub async fn next_scheduled_job(&mut self) -> Option<ScheduledJob> {
+        let (&(ts, _), _) = self.scheduled_jobs.first_key_value()?;
+        if let Some(delay) = ts.checked_sub(self.now()) {
+            self.rt.wait(delay).await;
+        }
+        self.scheduled_jobs.pop_first().map(|(_, job)| job)
+    }
+
+    /// Drop the resolvers for all pending timers, storage ops, async
+    /// syscalls, and function calls, so their promises never settle.
+    pub fn cancel_async_ops(&mut self) {
+        self.scheduled_async_ops.clear();
+        self.completed_async_ops.clear();
+        self.async_op_resolvers.clear();
+        self.timer_ops.clear();
+        self.async_syscall_results.clear();
+        self.pending_async_syscalls.clear();
+        self.pending_function_calls.clear();
+        self.mocked_fetch_bodies.clear();
+    }
+
+    /// Drop the resolver for the timer or storage op `op_id`, e.g. because
+    /// the function timed out or its promise was garbage collected, so its
+    /// promise never settles.
+    pub fn cancel_async_op(&mut self, op_id: usize) {
+        self.scheduled_async_ops.retain(|&(_, id), _| id != op_id);
+        self.completed_async_ops.remove(&op_id);
+        self.async_op_resolvers.remove(&op_id);
+        self.timer_ops.remove(&op_id);
+    }
+
+    pub fn has_pending_async_ops(&self) -> bool {
+        !self.scheduled_async_ops.is_empty() || !self.completed_async_ops.is_empty()
+    }
+
+    /// The timers and storage ops started and resolved so far.
+    pub fn async_op_log(&self) -> &AsyncOpLog {
+        &self.async_op_log
+    }
+
+    /// Take the log recorded so far. Pass it to `with_async_op_log` to
+    /// reproduce the same run.
+    pub fn take_async_op_log(&mut self) -> AsyncOpLog {
+        std::mem::take(&mut self.async_op_log)
+    }
+
+    /// Wait for the next timer or simulated storage op to finish, returning
+    /// its resolver and result. This is cancel safe.
+    pub async fn next_async_op(
+        &mut self,
+    ) -> anyhow::Result<(v8::Global<v8::PromiseResolver>, JsonValue)> {
+        loop {
+            let next = match self.next_replayed_op() {
+                Some(op) => self.completed_async_ops.remove_entry(&op.op_id),
+                None => self.completed_async_ops.pop_first(),
+            };
+            if let Some((op_id, result)) = next {
+                // Skip ops whose resolver was dropped out from under them.
+                if let Some(resolved) = self.resolve_async_op(op_id, result)? {
+                    return Ok(resolved);
+                }
+                continue;
+            }
+            let deadline = self
+                .scheduled_async_ops
+                .keys()
+                .next()
+                .map(|&(deadline, _)| deadline);
+            // Nothing left to run can unblock the ops we're holding back.
+            if deadline.is_none() {
+                if let Some(op) = self.next_replayed_op() {
+                    anyhow::ensure!(
+                        self.completed_async_ops.is_empty(),
+                        "Replay diverged: async op {} was never started",
+                        op.op_id
+                    );
+                }
+            }
+            // Only take the op once its deadline has passed, so cancelling
+            // this future leaves it scheduled.
+            let rt = &self.rt;
+            let wait = async {
+                match deadline {
+                    Some(deadline) => {
+                        rt.wait(deadline.saturating_duration_since(rt.monotonic_now()))
+                            .await
+                    },
+                    None => future::pending().await,
+                }
+            };
+            tokio::select! {
+                () = wait => {},
+                fired = cancelled(&mut self.cancellation) => {
+                    if fired {
+                        self.cancel_async_ops();
+                        anyhow::bail!("Cancelled");
+                    }
+                    continue;
+                },
+            }
+            let ((_, op_id), result) = self
+                .scheduled_async_ops
+                .pop_first()
+                .context("Scheduled async op disappeared")?;
+            self.completed_async_ops.insert(op_id, result);
+        }
+    }
+
+    fn resolve_async_op(
+        &mut self,
+        op_id: usize,
+        result: JsonValue,
+    ) -> anyhow::Result<Option<(v8::Global<v8::PromiseResolver>, JsonValue)>> {
+        let is_timer = self.timer_ops.remove(&op_id);
+        let Some(resolver) = self.async_op_resolvers.remove(&op_id) else {
+            return Ok(None);
+        };
+        if is_timer && self.scripted_clock.len() > 1 {
+            self.scripted_clock.pop_front();
+        }
+        let at_ms = match self.next_replayed_op() {
+            Some(op) => op.at_ms,
+            None => self.now().as_ms_since_epoch()?,
+        };
+        self.async_op_log
+            .resolved
+            .push(ResolvedAsyncOp { op_id, at_ms });
+        Ok(Some((resolver, result)))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum SearchFilter {
+    Search {
+        #[serde(rename = "fieldPath")]
+        field_path: String,
+        value: String,
+    },
+    Eq {
+        #[serde(rename = "fieldPath")]
+        field_path: String,
+        // Missing when comparing against `undefined`.
+        #[serde(default)]
+        value: JsonValue,
+    },
+}
+
+impl SearchFilter {
+    fn matches(&self, document: &JsonValue) -> bool {
+        let field = |field_path: &str| {
+            field_path
+                .split('.')
+                .try_fold(document, |value, field| value.get(field))
+        };
+        match self {
+            SearchFilter::Search { field_path, value } => {
+                let Some(JsonValue::String(text)) = field(field_path) else {
+                    return false;
+                };
+                let text = text.to_lowercase();
+                value
+                    .split_whitespace()
+                    .any(|term| text.contains(&term.to_lowercase()))
+            },
+            SearchFilter::Eq { field_path, value } => {
+                field(field_path).unwrap_or(&JsonValue::Null) == value
+            },
+        }
+    }
+}
+
+async fn run_function_call(
+    application: &Application<TestRuntime>,
+    udf_type: UdfType,
+    path: CanonicalizedComponentFunctionPath,
+    args: JsonValue,
+) -> anyhow::Result<JsonValue> {
+    let path = PublicFunctionPath::Component(path);
+    let caller = FunctionCaller::Action {
+        parent_scheduled_job: None,
+        parent_execution_id: None,
+    };
+    let value = match udf_type {
+        UdfType::Query => application
+            .read_only_udf(
+                RequestId::new(),
+                path,
+                vec![args],
+                Identity::system(),
+                caller,
+            )
+            .await?
+            .result
+            .map_err(|e| anyhow::anyhow!("{e}"))?,
+        _ => {
+            application
+                .mutation_udf(
+                    RequestId::new(),
+                    path,
+                    vec![args],
+                    Identity::system(),
+                    None,
+                    caller,
+                    None,
+                    MutationOptions::default(),
+                )
+                .await?
+                .map_err(|e| anyhow::anyhow!("{}", e.error))?
+                .value
+        },
+    };
+    Ok(value.json_value())
+}
+
+/// Wait for `cancellation` to fire, returning false if its sender is dropped
+/// instead. Waits forever if there's no cancellation.
+async fn cancelled(cancellation: &mut Option<oneshot::Receiver<()>>) -> bool {
+    let Some(receiver) = cancellation else {
+        return future::pending().await;
+    };
+    let fired = receiver.await.is_ok();
+    *cancellation = None;
+    fired
+}
+
+/// Resolve the promises for any async syscalls that have completed, returning
+/// whether there were any. This also writes out the bodies of mocked fetch
+/// responses, which must happen before their fetches resolve.
+pub fn resolve_async_syscalls(
+    scope: &mut ExecutionScope<TestRuntime, TestEnvironment>,
+) -> anyhow::Result<bool> {
+    let state = scope.state_mut()?;
+    for (stream_id, body) in state.environment.take_mocked_fetch_bodies() {
+        let part_id = state.create_blob_part(body)?;
+        state.streams.mutate(&stream_id, |stream| {
+            let Some(Ok(stream)) = stream else {
+                anyhow::bail!("Unrecognized stream id {stream_id}");
+            };
+            stream.parts.push_back(part_id);
+            stream.done = true;
+            Ok(())
+        })?;
+    }
+    let results = scope.state_mut()?.environment.take_async_syscall_results();
+    let resolved = !results.is_empty();
+    for (resolver, result) in results {
+        let resolver = resolver.open(scope);
+        match result {
+            Ok(result) => {
+                let result = v8::String::new(scope, &result).context("Failed to create result")?;
+                resolver.resolve(scope, result.into());
+            },
+            Err(message) => {
+                let message =
+                    v8::String::new(scope, &message).context("Failed to create message")?;
+                let error = v8::Exception::error(scope, message);
+                resolver.reject(scope, error);
+            },
+        }
     }
+    Ok(resolved)
 }
@@ -15,7 +15,10 @@ use runtime::testing::TestRuntime;
 use tokio::sync::mpsc;
 
 use super::{
-    environment::TestEnvironment,
+    environment::{
+        resolve_async_syscalls,
+        TestEnvironment,
+    },
     state::JsThreadState,
     JsClientThread,
     JsClientThreadRequest,
@@ -58,6 +61,9 @@ impl JsClientThread {
                 tracing::debug!("Processing outbox");
                 state.process_js_outbox(&mut scope)?;
 
+                tracing::debug!("Resolving async syscalls");
+                resolve_async_syscalls(&mut scope)?;
+
                 tracing::debug!("Performing microtask checkpoint");
                 scope.perform_microtask_checkpoint();
                 pump_message_loop(&mut scope);
@@ -73,7 +79,10 @@ impl JsClientThread {
                 // Don't block if we have something to do.
                 // NB: We perform the microtask checkpoint last since we can't directly check
                 // whether the microtask queue is empty.
-                if !state.is_outbox_empty() || !state.is_inbox_empty() {
+                if !state.is_outbox_empty()
+                    || !state.is_inbox_empty()
+                    || scope.state()?.environment.has_async_syscall_results()
+                {
                     continue;
                 }
 
@@ -90,10 +99,10 @@ impl JsClientThread {
                     (web_socket_id, maybe_msg) = state.next_message() => {
                         state.handle_websocket_message(web_socket_id, maybe_msg)?;
                     }
-                    resolver = environment.next_timer() => {
-                        let resolver = resolver?;
+                    op = environment.next_async_op() => {
+                        let (resolver, result) = op?;
                         let resolver = resolver.open(&mut scope);
-                        let result = serde_v8::to_v8(&mut scope, ())?;
+                        let result = serde_v8::to_v8(&mut scope, result)?;
                         resolver.resolve(&mut scope, result);
                     }
                 }
@@ -26,7 +26,7 @@ use uuid::Uuid;
 
 use super::server::ServerThread;
 
-mod environment;
+pub mod environment;
 mod go;
 mod js_protocol;
 mod state;
@@ -0,0 +1,1078 @@
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::Context;
+use application::{
+    test_helpers::ApplicationTestExt,
+    Application,
+};
+use common::{
+    components::{
+        CanonicalizedComponentFunctionPath,
+        ComponentPath,
+        PublicFunctionPath,
+    },
+    log_lines::LogLevel,
+    runtime::{
+        Runtime,
+        UnixTimestamp,
+    },
+    testing::TestIdGenerator,
+    types::FunctionCaller,
+    value::TableNamespace,
+    RequestId,
+};
+use deno_core::{
+    serde_v8,
+    v8,
+    ModuleSpecifier,
+};
+use errors::ErrorMetadataAnyhowExt;
+use futures::FutureExt;
+use isolate::{
+    environment::{
+        FetchHostPolicy,
+        IsolateEnvironment,
+    },
+    isolate::Isolate,
+    ConcurrencyLimiter,
+    RequestScope,
+};
+use keybroker::{
+    testing::TestUserIdentity,
+    Identity,
+    UserIdentityAttributes,
+};
+use maplit::btreemap;
+use model::modules::module_versions::FullModuleSource;
+use rand::Rng;
+use runtime::testing::TestRuntime;
+use serde_json::json;
+use tokio::sync::oneshot;
+
+use crate::test_helpers::js_client::environment::{
+    resolve_async_syscalls,
+    AsyncOpLog,
+    MockFetchResponse,
+    TestEnvironment,
+};
+
+fn ok_response() -> MockFetchResponse {
+    MockFetchResponse {
+        status: 200,
+        body: "ok".to_string(),
+    }
+}
+
+/// Evaluate `source` as a script in a fresh isolate backed by `environment`,
+/// and then run `check` against the environment once the microtask queue has
+/// drained and all async syscalls, timers and storage ops have been resolved.
+async fn run_script(
+    rt: TestRuntime,
+    environment: TestEnvironment,
+    source: &str,
+    check: impl FnOnce(&mut TestEnvironment) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    run_script_with_driver(rt, environment, source, |_| Ok(()), check).await
+}
+
+/// Like `run_script`, but calls `drive` each time the script is blocked, so
+/// the test can complete deferred async syscalls.
+async fn run_script_with_driver(
+    rt: TestRuntime,
+    environment: TestEnvironment,
+    source: &str,
+    drive: impl FnMut(&mut TestEnvironment) -> anyhow::Result<()>,
+    check: impl FnOnce(&mut TestEnvironment) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    run(rt, environment, Entry::Script(source), drive, check).await
+}
+
+/// Evaluate the module at `path`, added with `TestEnvironment::with_module`,
+/// and run until it's done, returning the error if it fails.
+async fn run_module(
+    rt: TestRuntime,
+    environment: TestEnvironment,
+    path: &str,
+) -> anyhow::Result<()> {
+    run(rt, environment, Entry::Module(path), |_| Ok(()), |_| Ok(())).await
+}
+
+/// What a test run evaluates before running the event loop.
+enum Entry<'a> {
+    /// The source of a classic script.
+    Script(&'a str),
+    /// The path of a module.
+    Module(&'a str),
+}
+
+async fn run(
+    rt: TestRuntime,
+    environment: TestEnvironment,
+    entry: Entry<'_>,
+    mut drive: impl FnMut(&mut TestEnvironment) -> anyhow::Result<()>,
+    check: impl FnOnce(&mut TestEnvironment) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut isolate = Isolate::new(rt, None, ConcurrencyLimiter::unlimited());
+    let client_id = Arc::new(String::new());
+    let (handle, state) = isolate.start_request(client_id, environment).await?;
+    let mut handle_scope = isolate.handle_scope();
+    let v8_context = v8::Context::new(&mut handle_scope, v8::ContextOptions::default());
+    let mut context_scope = v8::ContextScope::new(&mut handle_scope, v8_context);
+    let mut isolate_context =
+        RequestScope::new(&mut context_scope, handle.clone(), state, false).await?;
+    {
+        let mut v8_scope = isolate_context.scope();
+        let mut scope = RequestScope::<TestRuntime, TestEnvironment>::enter(&mut v8_scope);
+        match entry {
+            Entry::Script(source) => {
+                let source =
+                    v8::String::new(&mut scope, source).context("Failed to create source")?;
+                let script =
+                    v8::Script::compile(&mut scope, source, None).context("Failed to compile")?;
+                script
+                    .run(&mut scope)
+                    .context("Script threw an exception")?;
+            },
+            Entry::Module(path) => {
+                let specifier = ModuleSpecifier::parse(&format!("convex:/{path}"))?;
+                scope.eval_module(&specifier).await?;
+            },
+        }
+        loop {
+            scope.perform_microtask_checkpoint();
+            while resolve_async_syscalls(&mut scope)? {
+                scope.perform_microtask_checkpoint();
+            }
+            // Advance the virtual clock to the next timer or storage op.
+            let environment = &mut scope.state_mut()?.environment;
+            drive(environment)?;
+            if environment.run_function_calls().await || environment.has_async_syscall_results() {
+                continue;
+            }
+            if !environment.has_pending_async_ops() {
+                break;
+            }
+            let (resolver, result) = environment.next_async_op().await?;
+            let resolver = resolver.open(&mut scope);
+            let result = serde_v8::to_v8(&mut scope, result)?;
+            resolver.resolve(&mut scope, result);
+        }
+        let rejections = scope.pending_unhandled_promise_rejections_mut();
+        if let Some(promise) = rejections.exceptions.keys().next().cloned() {
+            let err = rejections.exceptions.remove(&promise).unwrap();
+            let err = v8::Local::new(&mut scope, err);
+            anyhow::bail!(
+                "Unhandled promise rejection: {}",
+                err.to_rust_string_lossy(&mut scope)
+            );
+        }
+        check(&mut scope.state_mut()?.environment)?;
+    }
+    drop(isolate_context);
+    handle.take_termination_error(None, "test")??;
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_fetch_default_user_agent(rt: TestRuntime) -> anyhow::Result<()> {
+    let environment =
+        TestEnvironment::new(rt.clone()).with_fetch_mock("https://example.com/", ok_response());
+    let source = r#"
+        fetch("https://example.com/default");
+        fetch("https://example.com/custom", { headers: { "User-Agent": "my-app/2.0" } });
+    "#;
+    run_script(rt, environment, source, |environment| {
+        let mut user_agents = vec![];
+        for request in environment.fetch_requests() {
+            let user_agent = request.headers["user-agent"].to_str()?;
+            user_agents.push((request.url.path(), user_agent));
+        }
+        assert_eq!(
+            user_agents,
+            vec![("/default", "Convex/1.0"), ("/custom", "my-app/2.0")]
+        );
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_async_op_budget_exceeded(rt: TestRuntime) -> anyhow::Result<()> {
+    let environment = TestEnvironment::new(rt.clone())
+        .with_async_op_limit(2)
+        .with_fetch_mock("https://example.com/", ok_response());
+    let source = r#"
+        fetch("https://example.com/0");
+        fetch("https://example.com/1");
+        fetch("https://example.com/2").then(
+            () => {
+                throw new Error("Expected fetch to fail");
+            },
+            (e) => {
+                if (!e.message.includes("at most 2 async operations")) {
+                    throw e;
+                }
+            },
+        );
+    "#;
+    run_script(rt, environment, source, |environment| {
+        let paths: Vec<_> = environment
+            .fetch_requests()
+            .iter()
+            .map(|request| request.url.path())
+            .collect();
+        assert_eq!(paths, vec!["/0", "/1"]);
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_concurrent_async_op_limit(rt: TestRuntime) -> anyhow::Result<()> {
+    let environment = TestEnvironment::new(rt.clone()).with_concurrent_async_op_limit(2);
+    let source = r#"
+        const sleep = async (ms) => Convex.asyncOp("sleep", "sleep", ms);
+        const first = sleep(1000);
+        const second = sleep(1000);
+        sleep(1000).then(
+            () => {
+                throw new Error("Expected the third sleep to fail");
+            },
+            (e) => {
+                if (!e.message.includes("too many concurrent async operations")) {
+                    throw e;
+                }
+            },
+        );
+        // Once the first two finish there's room for another.
+        Promise.all([first, second]).then(() => sleep(0));
+    "#;
+    run_script(rt, environment, source, |_| Ok(())).await
+}
+
+#[convex_macro::test_runtime]
+async fn test_fetch_host_policy(rt: TestRuntime) -> anyhow::Result<()> {
+    let policy = FetchHostPolicy::new(
+        vec!["Example.com".to_string()],
+        vec!["internal.example.com.".to_string()],
+    );
+    let environment = TestEnvironment::new(rt.clone())
+        .with_fetch_host_policy(policy)
+        .with_fetch_mock("https://example.com/", ok_response())
+        .with_fetch_mock("https://api.example.com/", ok_response());
+    let source = r#"
+        const expectNotAllowed = (url) =>
+            fetch(url).then(
+                () => {
+                    throw new Error(`Expected fetch to ${url} to fail`);
+                },
+                (e) => {
+                    if (!e.message.includes("is not allowed")) {
+                        throw e;
+                    }
+                },
+            );
+        fetch("https://example.com/allowed");
+        fetch("https://api.example.com/subdomain");
+        expectNotAllowed("https://internal.example.com/denied");
+        expectNotAllowed("https://INTERNAL.example.com/denied");
+        expectNotAllowed("https://internal.example.com./denied");
+        expectNotAllowed("https://169.254.169.254/latest/meta-data");
+        expectNotAllowed("https://notexample.com/unlisted");
+    "#;
+    run_script(rt, environment, source, |environment| {
+        // Only the allowed fetches reached the stub.
+        let urls: Vec<_> = environment
+            .fetch_requests()
+            .iter()
+            .map(|request| request.url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://example.com/allowed",
+                "https://api.example.com/subdomain"
+            ]
+        );
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_sequence_next(rt: TestRuntime) -> anyhow::Result<()> {
+    let environment = TestEnvironment::new(rt.clone());
+    let source = r#"
+        (async () => {
+            const next = async (name) =>
+                await Convex.asyncSyscall("1.0/sequenceNext", JSON.stringify({ name }));
+            const ids = [await next("a"), await next("a"), await next("a")];
+            if (new Set(ids).size !== ids.length) {
+                throw new Error(`Duplicate ids: ${ids}`);
+            }
+            // Sequences are independent of each other.
+            if ((await next("b")) !== ids[0]) {
+                throw new Error("Expected a fresh sequence to start at 1");
+            }
+        })();
+    "#;
+    run_script(rt, environment, source, |environment| {
+        let sequences: BTreeMap<_, _> = environment.sequences().collect();
+        assert_eq!(sequences, btreemap! { "a" => 4, "b" => 2 });
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_scheduled_jobs(rt: TestRuntime) -> anyhow::Result<()> {
+    let environment = TestEnvironment::new(rt.clone());
+    let source = r#"
+        (async () => {
+            const schedule = (name, ts) =>
+                Convex.asyncSyscall(
+                    "1.0/schedule",
+                    JSON.stringify({ name, ts, args: [{}] }),
+                );
+            const later = Date.now() / 1000 + 60;
+            await schedule("jobs:first", later);
+            await schedule("jobs:past", 0);
+            await schedule("jobs:second", later);
+        })();
+    "#;
+    run_script(rt, environment, source, |environment| {
+        let paths: Vec<_> = environment
+            .scheduled_jobs()
+            .map(|job| job.path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["jobs:past", "jobs:first", "jobs:second"]);
+
+        // The job scheduled in the past is due without advancing the clock,
+        // but the others aren't.
+        let job = environment
+            .next_scheduled_job()
+            .now_or_never()
+            .context("Past job wasn't due")?
+            .context("Missing scheduled job")?;
+        assert_eq!(job.path, "jobs:past");
+        assert!(environment.next_scheduled_job().now_or_never().is_none());
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_storage_latency_exceeds_deadline(rt: TestRuntime) -> anyhow::Result<()> {
+    // A "mutation" that writes a file and reads it back, racing a 1s deadline.
+    let source = r#"
+        const mutation = async () => {
+            const storageId = await Convex.asyncOp("storage/store", null, "text/plain", "5");
+            const file = await Convex.asyncOp("storage/get", storageId);
+            if (file === null || file.contentLength !== 5) {
+                throw new Error(`Unexpected file ${JSON.stringify(file)}`);
+            }
+            return "committed";
+        };
+        const deadline = new Promise((resolve) => setTimeout(() => resolve("deadline"), 1000));
+        Promise.race([mutation(), deadline]).then((winner) => {
+            if (winner !== globalThis.expectedWinner) {
+                throw new Error(`Expected ${globalThis.expectedWinner}, got ${winner}`);
+            }
+        });
+    "#;
+
+    let fast = TestEnvironment::new(rt.clone());
+    let fast_source = format!("globalThis.expectedWinner = 'committed';{source}");
+    run_script(rt.clone(), fast, &fast_source, |_| Ok(())).await?;
+
+    // Each of the two storage ops takes 600ms, so the mutation misses the
+    // deadline.
+    let slow = TestEnvironment::new(rt.clone()).with_storage_latency(Duration::from_millis(600));
+    let slow_source = format!("globalThis.expectedWinner = 'deadline';{source}");
+    run_script(rt, slow, &slow_source, |_| Ok(())).await
+}
+
+#[convex_macro::test_runtime]
+async fn test_get_identity_claims(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        (async () => {
+            const result = await Convex.asyncSyscall(
+                "1.0/getIdentityClaims",
+                JSON.stringify({ names: ["email", "roles"] }),
+            );
+            const claims = JSON.parse(result);
+            const actual = JSON.stringify(claims);
+            if (actual !== JSON.stringify(globalThis.expectedClaims)) {
+                throw new Error(`Unexpected claims ${actual}`);
+            }
+        })();
+    "#;
+
+    let anonymous = TestEnvironment::new(rt.clone());
+    let anonymous_source = format!("globalThis.expectedClaims = null;{source}");
+    run_script(rt.clone(), anonymous, &anonymous_source, |_| Ok(())).await?;
+
+    let identity = UserIdentityAttributes {
+        custom_claims: btreemap! { "roles".to_string() => r#"["admin"]"#.to_string() },
+        ..UserIdentityAttributes::test()
+    };
+    let authenticated = TestEnvironment::new(rt.clone()).with_identity(identity);
+    let authenticated_source = format!(
+        r#"globalThis.expectedClaims = {{ email: "bozo@convex.dev", roles: ["admin"] }};{source}"#
+    );
+    run_script(rt, authenticated, &authenticated_source, |_| Ok(())).await
+}
+
+#[convex_macro::test_runtime]
+async fn test_async_op_record_replay(rt: TestRuntime) -> anyhow::Result<()> {
+    // Two timers and a storage op that finish in a different order than
+    // they're started.
+    let source = r#"
+        const order = [];
+        Promise.all([
+            new Promise((resolve) => setTimeout(resolve, 20)).then(() => order.push("slow")),
+            new Promise((resolve) => setTimeout(resolve, 10)).then(() => order.push("fast")),
+            Convex.asyncOp("storage/store", null, "text/plain", "5").then(() =>
+                order.push("store"),
+            ),
+        ]).then(() => {
+            if (order.join() !== globalThis.expectedOrder.join()) {
+                throw new Error(`Expected ${globalThis.expectedOrder}, got ${order}`);
+            }
+        });
+    "#;
+
+    let mut recorded = AsyncOpLog::default();
+    let live = TestEnvironment::new(rt.clone());
+    let live_source = format!("globalThis.expectedOrder = ['store', 'fast', 'slow'];{source}");
+    run_script(rt.clone(), live, &live_source, |environment| {
+        recorded = environment.take_async_op_log();
+        Ok(())
+    })
+    .await?;
+    assert_eq!(recorded.resolved_op_ids(), vec![2, 1, 0]);
+
+    // Replaying the recording reproduces the same interleaving.
+    let replay = TestEnvironment::new(rt.clone()).with_async_op_log(recorded.clone());
+    run_script(rt.clone(), replay, &live_source, |environment| {
+        assert_eq!(environment.async_op_log(), &recorded);
+        Ok(())
+    })
+    .await?;
+
+    // The replayed order wins even when the ops complete in a different one.
+    let mut reordered_log = recorded;
+    reordered_log.resolved.sort_by_key(|op| op.op_id);
+    let reordered = TestEnvironment::new(rt.clone()).with_async_op_log(reordered_log);
+    let reordered_source = format!("globalThis.expectedOrder = ['slow', 'fast', 'store'];{source}");
+    run_script(rt, reordered, &reordered_source, |environment| {
+        assert_eq!(environment.async_op_log().resolved_op_ids(), vec![0, 1, 2]);
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_async_op_log_replay(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        const start = Date.now();
+        const log = (name) => console.log(name, Date.now() - start);
+        new Promise((resolve) => setTimeout(resolve, 5)).then(() => log("sleep"));
+        Convex.asyncOp("storage/store", null, "text/plain", "5").then(() => log("store"));
+    "#;
+
+    let mut log = AsyncOpLog::default();
+    let mut log_lines = vec![];
+    let recording = TestEnvironment::new(rt.clone())
+        .with_storage_latency(Duration::from_millis(1))
+        .with_captured_log_lines();
+    run_script(rt.clone(), recording, source, |environment| {
+        log = environment.take_async_op_log();
+        log_lines = environment.take_log_lines();
+        Ok(())
+    })
+    .await?;
+    assert_eq!(log.started, vec!["sleep 5ms", "storage store 5 bytes"]);
+    assert_eq!(
+        log_lines,
+        vec![
+            (LogLevel::Log, "store 1".to_string()),
+            (LogLevel::Log, "sleep 5".to_string()),
+        ]
+    );
+
+    // Storage is slow enough now that the sleep would finish first, but the
+    // replay resolves ops in the recorded order and at the recorded times.
+    let log: AsyncOpLog = serde_json::from_str(&serde_json::to_string(&log)?)?;
+    let replay = TestEnvironment::new(rt.clone())
+        .with_storage_latency(Duration::from_millis(10))
+        .with_captured_log_lines()
+        .with_async_op_log(log.clone());
+    run_script(rt, replay, source, |environment| {
+        assert_eq!(environment.take_log_lines(), log_lines);
+        assert_eq!(environment.take_async_op_log(), log);
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_scripted_clock(rt: TestRuntime) -> anyhow::Result<()> {
+    // An "action" that sleeps between reading the clock.
+    let source = r#"
+        (async () => {
+            const sleep = (ms) => new Promise((resolve) => setTimeout(resolve, ms));
+            const observed = [Date.now()];
+            for (let i = 0; i < 3; i++) {
+                await sleep(10);
+                observed.push(Date.now());
+            }
+            if (observed.join() !== "1000,5000,9000,9000") {
+                throw new Error(`Unexpected times ${observed}`);
+            }
+        })();
+    "#;
+    let instants = [1000, 5000, 9000]
+        .into_iter()
+        .map(UnixTimestamp::from_millis)
+        .collect();
+    let environment = TestEnvironment::new(rt.clone()).with_scripted_clock(instants);
+    run_script(rt, environment, source, |environment| {
+        assert_eq!(environment.async_op_log().resolved_op_ids(), vec![0, 1, 2]);
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_custom_syscalls(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        (async () => {
+            const doubled = JSON.parse(Convex.syscall("test/double", JSON.stringify({ x: 21 })));
+            if (doubled !== 42) {
+                throw new Error(`Unexpected sync result ${doubled}`);
+            }
+            const sequence = JSON.parse(
+                await Convex.asyncSyscall("1.0/sequenceNext", JSON.stringify({ name: "a" })),
+            );
+            if (sequence !== "overridden") {
+                throw new Error(`Unexpected async result ${sequence}`);
+            }
+            // A failing async handler rejects its promise.
+            try {
+                await Convex.asyncSyscall("test/fail", JSON.stringify({}));
+                throw new Error("Expected test/fail to reject");
+            } catch (e) {
+                if (!e.message.includes("Handler failed")) {
+                    throw e;
+                }
+            }
+        })();
+    "#;
+    let environment = TestEnvironment::new(rt.clone())
+        .with_syscall("test/double", |args| {
+            let x = args["x"].as_i64().context("Missing x")?;
+            Ok(json!(x * 2))
+        })
+        // Registered handlers take precedence over the built-in syscalls.
+        .with_async_syscall("1.0/sequenceNext", |_| Ok(json!("overridden")))
+        .with_async_syscall("test/fail", |_| anyhow::bail!("Handler failed"));
+    run_script(rt, environment, source, |environment| {
+        assert_eq!(environment.sequences().count(), 0);
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_env_vars(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        const mode = Convex.op("environmentVariables/get", "MODE");
+        if (mode !== "staging") {
+            throw new Error(`Unexpected MODE ${mode}`);
+        }
+        const missing = Convex.op("environmentVariables/get", "MISSING");
+        if (missing !== null) {
+            throw new Error(`Unexpected MISSING ${missing}`);
+        }
+    "#;
+    let env_vars = btreemap! { "MODE".parse()? => "staging".parse()? };
+    let environment = TestEnvironment::new(rt.clone()).with_env_vars(env_vars);
+    run_script(rt, environment, source, |_| Ok(())).await
+}
+
+#[convex_macro::test_runtime]
+async fn test_with_seed(rt: TestRuntime) -> anyhow::Result<()> {
+    let sample = |seed: [u8; 32]| -> anyhow::Result<[u64; 4]> {
+        let mut environment = TestEnvironment::with_seed(rt.clone(), seed);
+        Ok(environment.rng()?.random())
+    };
+    assert_eq!(sample([1; 32])?, sample([1; 32])?);
+    assert_ne!(sample([1; 32])?, sample([2; 32])?);
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_vector_search(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        (async () => {
+            const search = (indexName) => Convex.asyncSyscall(
+                "1.0/actions/vectorSearch",
+                JSON.stringify({ query: { indexName, vector: [0.5, 0.5], limit: 1 } }),
+            );
+            const { results } = JSON.parse(await search("documents.by_embedding"));
+            if (results.length !== 1 || results[0]._id !== "doc1") {
+                throw new Error(`Unexpected results ${JSON.stringify(results)}`);
+            }
+            try {
+                await search("documents.by_embeding");
+                throw new Error("Expected unknown index to be rejected");
+            } catch (e) {
+                if (!e.message.includes("documents.by_embeding")) {
+                    throw e;
+                }
+            }
+        })();
+    "#;
+    let environment = TestEnvironment::new(rt.clone()).with_vector_index(
+        "documents.by_embedding",
+        vec![
+            json!({ "_id": "doc1", "_score": 0.9 }),
+            json!({ "_id": "doc2", "_score": 0.4 }),
+        ],
+    );
+    run_script(rt, environment, source, |_| Ok(())).await
+}
+
+#[convex_macro::test_runtime]
+async fn test_full_text_search(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        (async () => {
+            const search = async (indexName, filters, operators = []) => {
+                const query = { source: { type: "Search", indexName, filters }, operators };
+                const { queryId } = JSON.parse(
+                    Convex.syscall("1.0/queryStream", JSON.stringify({ query })),
+                );
+                const bodies = [];
+                for (;;) {
+                    const { value, done } = JSON.parse(
+                        await Convex.asyncSyscall("1.0/queryStreamNext", JSON.stringify({ queryId })),
+                    );
+                    if (done) {
+                        return bodies.join("|");
+                    }
+                    bodies.push(value.body);
+                }
+            };
+            const check = (actual, expected) => {
+                if (actual !== expected) {
+                    throw new Error(`Expected ${expected}, got ${actual}`);
+                }
+            };
+            const terms = { type: "Search", fieldPath: "body", value: "HELLO there" };
+            const inChannel = { type: "Eq", fieldPath: "channel", value: "b" };
+            check(
+                await search("messages.search_body", [terms]),
+                "hello world|well hello|there it is",
+            );
+            check(await search("messages.search_body", [terms], [{ limit: 1 }]), "hello world");
+            check(await search("messages.search_body", [terms, inChannel]), "well hello");
+            try {
+                await search("messages.search_bdy", [terms]);
+                throw new Error("Expected unknown index to be rejected");
+            } catch (e) {
+                if (!e.message.includes("messages.search_bdy")) {
+                    throw e;
+                }
+            }
+        })();
+    "#;
+    let environment = TestEnvironment::new(rt.clone()).with_search_index(
+        "messages.search_body",
+        vec![
+            json!({ "body": "hello world", "channel": "a" }),
+            json!({ "body": "goodbye", "channel": "a" }),
+            json!({ "body": "well hello", "channel": "b" }),
+            json!({ "body": "there it is", "channel": "a" }),
+        ],
+    );
+    run_script(rt, environment, source, |_| Ok(())).await
+}
+
+#[convex_macro::test_runtime]
+async fn test_unknown_syscall(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        let message = null;
+        try {
+            Convex.syscall("1.0/doesNotExist", JSON.stringify({}));
+        } catch (e) {
+            message = e.message;
+        }
+        if (!message?.includes("Unknown operation 1.0/doesNotExist")) {
+            throw new Error(`Unexpected error ${message}`);
+        }
+    "#;
+    let environment = TestEnvironment::new(rt.clone());
+    run_script(rt, environment, source, |_| Ok(())).await
+}
+
+#[convex_macro::test_runtime]
+async fn test_fetch_mock(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        (async () => {
+            const response = await fetch("https://api.example.com/users/1");
+            const body = await response.text();
+            if (response.status !== 404 || body !== "no such user") {
+                throw new Error(`Unexpected response ${response.status} ${body}`);
+            }
+            let message = null;
+            try {
+                await fetch("https://unmocked.example.com/");
+            } catch (e) {
+                message = e.message;
+            }
+            if (!message?.includes("No mock response registered")) {
+                throw new Error(`Unexpected error ${message}`);
+            }
+        })();
+    "#;
+    let environment = TestEnvironment::new(rt.clone())
+        .with_fetch_mock(
+            "https://api.example.com/users/",
+            MockFetchResponse {
+                status: 404,
+                body: "no such user".to_string(),
+            },
+        )
+        .with_fetch_mock("https://api.example.com/", ok_response());
+    run_script(rt, environment, source, |environment| {
+        assert_eq!(environment.fetch_requests().len(), 2);
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_async_ops_resolve_in_deadline_order(rt: TestRuntime) -> anyhow::Result<()> {
+    // Ops with the same deadline resolve in the order they were started.
+    let source = r#"
+        setTimeout(() => {}, 10);
+        Convex.asyncOp("storage/store", null, "text/plain", "5");
+        setTimeout(() => {}, 5);
+        setTimeout(() => {}, 10);
+    "#;
+    let environment =
+        TestEnvironment::new(rt.clone()).with_storage_latency(Duration::from_millis(10));
+    run_script(rt, environment, source, |environment| {
+        assert_eq!(
+            environment.async_op_log().resolved_op_ids(),
+            vec![2, 0, 1, 3]
+        );
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_cancel_async_ops(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        setTimeout(() => {}, 3600 * 1000);
+        Convex.asyncOp("storage/store", null, "text/plain", "5");
+    "#;
+    let (cancel_tx, cancel_rx) = oneshot::channel();
+    let environment = TestEnvironment::new(rt.clone())
+        .with_storage_latency(Duration::from_secs(60))
+        .with_cancellation(cancel_rx);
+    let start = rt.monotonic_now();
+    let cancel = async {
+        rt.wait(Duration::from_secs(1)).await;
+        _ = cancel_tx.send(());
+    };
+    let (result, ()) = tokio::join!(
+        run_script(rt.clone(), environment, source, |_| Ok(())),
+        cancel
+    );
+    assert!(format!("{:?}", result.unwrap_err()).contains("Cancelled"));
+    assert!(rt.monotonic_now() - start < Duration::from_secs(60));
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_cancel_async_op(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        setTimeout(() => {}, 10);
+        setTimeout(() => {}, 20);
+    "#;
+    let environment = TestEnvironment::new(rt.clone());
+    run_script_with_driver(
+        rt,
+        environment,
+        source,
+        |environment| {
+            environment.cancel_async_op(0);
+            Ok(())
+        },
+        |environment| {
+            assert_eq!(environment.async_op_log().resolved_op_ids(), vec![1]);
+            assert!(!environment.has_pending_async_ops());
+            Ok(())
+        },
+    )
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_captured_log_lines(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        Convex.op("console/message", "LOG", ["starting", "up"]);
+        Convex.op("console/message", "WARN", ["careful"]);
+        Convex.op("console/message", "ERROR", ["oops"]);
+    "#;
+    let environment = TestEnvironment::new(rt.clone()).with_captured_log_lines();
+    run_script(rt, environment, source, |environment| {
+        assert_eq!(
+            environment.take_log_lines(),
+            vec![
+                (LogLevel::Log, "starting up".to_string()),
+                (LogLevel::Warn, "careful".to_string()),
+                (LogLevel::Error, "oops".to_string()),
+            ]
+        );
+        assert!(environment.take_log_lines().is_empty());
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_min_log_level(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        Convex.op("console/message", "DEBUG", ["noise"]);
+        Convex.op("console/message", "INFO", ["more noise"]);
+        Convex.op("console/message", "WARN", ["careful"]);
+        Convex.op("console/message", "ERROR", ["oops"]);
+    "#;
+    let environment = TestEnvironment::new(rt.clone())
+        .with_captured_log_lines()
+        .with_min_log_level(LogLevel::Warn);
+    run_script(rt, environment, source, |environment| {
+        assert_eq!(
+            environment.take_log_lines(),
+            vec![
+                (LogLevel::Warn, "careful".to_string()),
+                (LogLevel::Error, "oops".to_string()),
+            ]
+        );
+        Ok(())
+    })
+    .await
+}
+
+#[convex_macro::test_runtime]
+async fn test_table_mapping(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        const names = Object.values(Convex.op("getTableMapping")).sort();
+        if (JSON.stringify(names) !== JSON.stringify(EXPECTED)) {
+            throw new Error(`Unexpected tables ${names}`);
+        }
+    "#;
+    let environment = TestEnvironment::new(rt.clone());
+    let default_source = format!(r#"const EXPECTED = ["_index", "_tables"]; {source}"#);
+    run_script(rt.clone(), environment, &default_source, |_| Ok(())).await?;
+
+    let mut id_generator = TestIdGenerator::new();
+    id_generator.user_table_id(&"messages".parse()?);
+    let environment = TestEnvironment::new(rt.clone())
+        .with_table_mapping(id_generator.namespace(TableNamespace::test_user()));
+    let source = format!(r#"const EXPECTED = ["_index", "_tables", "messages"]; {source}"#);
+    run_script(rt, environment, &source, |_| Ok(())).await
+}
+
+#[convex_macro::test_runtime]
+async fn test_multiple_modules(rt: TestRuntime) -> anyhow::Result<()> {
+    let module = |source: &str| FullModuleSource {
+        source: source.into(),
+        source_map: None,
+    };
+    let environment = TestEnvironment::new(rt.clone())
+        .with_module("helpers.js", module("export const double = (x) => 2 * x;"))
+        .with_module(
+            "app.js",
+            module(r#"import { double } from "./helpers.js"; export const answer = double(21);"#),
+        );
+    let mut isolate = Isolate::new(rt, None, ConcurrencyLimiter::unlimited());
+    let client_id = Arc::new(String::new());
+    let (handle, state) = isolate.start_request(client_id, environment).await?;
+    let mut handle_scope = isolate.handle_scope();
+    let v8_context = v8::Context::new(&mut handle_scope, v8::ContextOptions::default());
+    let mut context_scope = v8::ContextScope::new(&mut handle_scope, v8_context);
+    let mut isolate_context =
+        RequestScope::new(&mut context_scope, handle.clone(), state, false).await?;
+    {
+        let mut v8_scope = isolate_context.scope();
+        let mut scope = RequestScope::<TestRuntime, TestEnvironment>::enter(&mut v8_scope);
+        let module = scope
+            .eval_module(&ModuleSpecifier::parse("convex:/app.js")?)
+            .await?;
+        let namespace = module
+            .get_module_namespace()
+            .to_object(&mut scope)
+            .context("Module namespace isn't an object")?;
+        let key = v8::String::new(&mut scope, "answer").context("Failed to create key")?;
+        let answer = namespace
+            .get(&mut scope, key.into())
+            .context("Missing export")?;
+        assert_eq!(answer.integer_value(&mut scope), Some(42));
+    }
+    drop(isolate_context);
+    handle.take_termination_error(None, "test")??;
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_missing_module(rt: TestRuntime) -> anyhow::Result<()> {
+    let module = |source: &str| FullModuleSource {
+        source: source.into(),
+        source_map: None,
+    };
+    let environment = TestEnvironment::new(rt.clone())
+        .with_module("helpers.js", module("export const double = (x) => 2 * x;"))
+        .with_module(
+            "app.js",
+            module(r#"import { double } from "./helper.js"; export const answer = double(21);"#),
+        );
+    let err = run_module(rt, environment, "app.js")
+        .await
+        .expect_err("Expected the import to fail");
+    assert_eq!(err.short_msg(), "ModuleNotFound");
+    let msg = err.to_string();
+    assert!(msg.contains("'helper.js'"), "{msg}");
+    assert!(msg.contains("'helpers.js'"), "{msg}");
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_deferred_async_syscall(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        (async () => {
+            const first = await Convex.asyncSyscall("1.0/queryPage", JSON.stringify({ page: 0 }));
+            const second = await Convex.asyncSyscall("1.0/queryPage", JSON.stringify({ page: 1 }));
+            if (JSON.parse(first).page !== 0 || JSON.parse(second).page !== 1) {
+                throw new Error(`Unexpected pages ${first} ${second}`);
+            }
+        })();
+    "#;
+    let environment = TestEnvironment::new(rt.clone()).with_deferred_async_syscall("1.0/queryPage");
+    let mut requested = vec![];
+    run_script_with_driver(
+        rt,
+        environment,
+        source,
+        |environment| {
+            let pending: Vec<_> = environment
+                .pending_async_syscalls()
+                .map(|(id, name, args)| (id, name.to_string(), args.clone()))
+                .collect();
+            for (id, name, args) in pending {
+                assert_eq!(name, "1.0/queryPage");
+                requested.push(args["page"].clone());
+                environment.complete_async_syscall(id, Ok(json!({ "page": args["page"] })))?;
+            }
+            Ok(())
+        },
+        |_| Ok(()),
+    )
+    .await?;
+    assert_eq!(requested, vec![json!(0), json!(1)]);
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_action_calls_application(rt: TestRuntime) -> anyhow::Result<()> {
+    let application = Application::new_for_tests(&rt).await?;
+    application.load_udf_tests_modules().await?;
+    let source = r#"
+        (async () => {
+            const call = async (syscall, name, args) =>
+                JSON.parse(await Convex.asyncSyscall(syscall, JSON.stringify({ name, args })));
+            await call("1.0/actions/mutation", "basic:insertObject", { an: "object" });
+            await call("1.0/actions/mutation", "basic:insertObject", { an: "object" });
+            let message;
+            try {
+                await call("1.0/actions/mutation", "custom_errors:mutationThrows", {});
+            } catch (e) {
+                message = e.message;
+            }
+            if (!message?.includes("ConvexError")) {
+                throw new Error(`Unexpected error ${message}`);
+            }
+            const count = await call("1.0/actions/query", "basic:count", {});
+            if (count !== 2) {
+                throw new Error(`Unexpected count ${count}`);
+            }
+        })();
+    "#;
+    let environment = TestEnvironment::new(rt.clone()).with_application(application.clone());
+    run_script(rt, environment, source, |_| Ok(())).await?;
+
+    let result = application
+        .read_only_udf(
+            RequestId::new(),
+            PublicFunctionPath::Component(CanonicalizedComponentFunctionPath {
+                component: ComponentPath::root(),
+                udf_path: "basic:count".parse()?,
+            }),
+            vec![json!({})],
+            Identity::system(),
+            FunctionCaller::Test,
+        )
+        .await?;
+    assert_eq!(result.result?.json_value(), json!(2.0));
+    Ok(())
+}
+
+#[convex_macro::test_runtime]
+async fn test_rejected_async_syscalls(rt: TestRuntime) -> anyhow::Result<()> {
+    let source = r#"
+        (async () => {
+            const messages = [];
+            for (const name of ["1.0/queryPage", "1.0/doesNotExist"]) {
+                try {
+                    await Convex.asyncSyscall(name, "{}");
+                } catch (e) {
+                    messages.push(e.message);
+                }
+            }
+            const expected = ["Page not found", "Unknown async syscall 1.0/doesNotExist"];
+            if (JSON.stringify(messages) !== JSON.stringify(expected)) {
+                throw new Error(`Unexpected messages ${messages}`);
+            }
+        })();
+    "#;
+    let environment = TestEnvironment::new(rt.clone()).with_deferred_async_syscall("1.0/queryPage");
+    let mut rejected = 0;
+    run_script_with_driver(
+        rt,
+        environment,
+        source,
+        |environment| {
+            let ids: Vec<_> = environment
+                .pending_async_syscalls()
+                .map(|(id, ..)| id)
+                .collect();
+            for id in ids {
+                environment.complete_async_syscall(id, Err(anyhow::anyhow!("Page not found")))?;
+                rejected += 1;
+            }
+            Ok(())
+        },
+        |_| Ok(()),
+    )
+    .await?;
+    assert_eq!(rejected, 1);
+    Ok(())
+}
@@ -1,3 +1,4 @@
 mod basic;
 mod elle;
+mod environment;
 mod sync;
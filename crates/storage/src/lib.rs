@@ -1116,6 +1116,8 @@ pub enum StorageUseCase {
     Files,
     /// Search index snapshots
     SearchIndexes,
+    /// Large document fields stored out-of-line
+    DocumentBlobs,
 }
 
 impl Display for StorageUseCase {
@@ -1126,6 +1128,7 @@ impl Display for StorageUseCase {
             StorageUseCase::Modules => write!(f, "modules"),
             StorageUseCase::Files => write!(f, "files"),
             StorageUseCase::SearchIndexes => write!(f, "search"),
+            StorageUseCase::DocumentBlobs => write!(f, "document_blobs"),
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use anyhow::Context;
 use common::{
     components::CanonicalizedComponentFunctionPath,
@@ -55,6 +57,9 @@ pub struct ActionOutcome {
 
     pub result: Result<JsonPackedValue, JsError>,
     pub syscall_trace: SyscallTrace,
+    /// How long the action's JS sat idle waiting on async ops (syscalls,
+    /// `fetch`, timers) to resolve. Always zero for Node actions.
+    pub async_op_wait: Duration,
 
     pub udf_server_version: Option<semver::Version>,
 }
@@ -77,6 +82,7 @@ impl ActionOutcome {
             unix_timestamp: rt.unix_timestamp(),
             result: Err(js_error),
             syscall_trace: SyscallTrace::new(),
+            async_op_wait: Duration::ZERO,
             udf_server_version,
         }
     }
@@ -86,6 +92,7 @@ impl ActionOutcome {
             unix_timestamp,
             result,
             syscall_trace,
+            async_op_wait,
         }: ActionOutcomeProto,
         path_and_args: ValidatedPathAndArgs,
         identity: InertIdentity,
@@ -108,6 +115,10 @@ impl ActionOutcome {
                 .try_into()?,
             result,
             syscall_trace: syscall_trace.context("Missing syscall_trace")?.try_into()?,
+            async_op_wait: async_op_wait
+                .map(Duration::try_from)
+                .transpose()?
+                .unwrap_or_default(),
             udf_server_version,
         })
     }
@@ -124,6 +135,7 @@ impl TryFrom<ActionOutcome> for ActionOutcomeProto {
             unix_timestamp,
             result,
             syscall_trace,
+            async_op_wait,
             udf_server_version: _,
         }: ActionOutcome,
     ) -> anyhow::Result<Self> {
@@ -137,6 +149,7 @@ impl TryFrom<ActionOutcome> for ActionOutcomeProto {
                 result: Some(result),
             }),
             syscall_trace: Some(syscall_trace.try_into()?),
+            async_op_wait: Some(async_op_wait.try_into()?),
         })
     }
 }
@@ -156,15 +169,26 @@ impl Arbitrary for ActionOutcome {
             any::<UnixTimestamp>(),
             any::<Result<JsonPackedValue, JsError>>(),
             any::<SyscallTrace>(),
+            (0..=i64::MAX as u64, 0..1_000_000_000u32)
+                .prop_map(|(secs, nanos)| Duration::new(secs, nanos)),
         )
             .prop_map(
-                |(path, arguments, identity, unix_timestamp, result, syscall_trace)| Self {
+                |(
+                    path,
+                    arguments,
+                    identity,
+                    unix_timestamp,
+                    result,
+                    syscall_trace,
+                    async_op_wait,
+                )| Self {
                     path,
                     arguments,
                     identity,
                     unix_timestamp,
                     result,
                     syscall_trace,
+                    async_op_wait,
                     // Ok to not generate semver::Version because it is not serialized anyway
                     udf_server_version: None,
                 },
@@ -35,6 +35,36 @@ pub fn parse_udf_args(
         })
 }
 
+/// Arguments to a function, either as JSON from a client or as values a Rust
+/// caller already has. The latter skip the JSON round trip, so they can hold
+/// values JSON can't represent exactly, like `Int64`s outside of 2^53.
+#[derive(Clone, Debug)]
+pub enum UdfArgs {
+    Json(Vec<JsonValue>),
+    Values(ConvexArray),
+}
+
+impl UdfArgs {
+    pub fn into_convex_array(self, path: &CanonicalizedUdfPath) -> Result<ConvexArray, JsError> {
+        match self {
+            UdfArgs::Json(args) => parse_udf_args(path, args),
+            UdfArgs::Values(args) => Ok(args),
+        }
+    }
+}
+
+impl From<Vec<JsonValue>> for UdfArgs {
+    fn from(args: Vec<JsonValue>) -> Self {
+        UdfArgs::Json(args)
+    }
+}
+
+impl From<ConvexArray> for UdfArgs {
+    fn from(args: ConvexArray) -> Self {
+        UdfArgs::Values(args)
+    }
+}
+
 pub fn validate_udf_args_size(
     path: &CanonicalizedUdfPath,
     args: &ConvexArray,
@@ -67,6 +67,50 @@ impl HttpActionRequestHead {
             path: path.to_string(),
         }
     }
+
+    /// The request's method, path, and headers, for the action handling it
+    /// to read.
+    pub fn metadata(&self) -> HttpRequestMetadata {
+        HttpRequestMetadata {
+            method: self.method.to_string(),
+            path: self.url.path().to_string(),
+            headers: self
+                .headers
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        name.to_string(),
+                        String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The HTTP request that triggered an HTTP action, as the action reads it
+/// through the `requestMetadata` syscalls. It's only kept in the action's
+/// environment, so it isn't logged or sent along with other functions the
+/// action calls.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpRequestMetadata {
+    pub method: String,
+    pub path: String,
+    /// In the order they were received. A header sent several times has an
+    /// entry per value.
+    pub headers: Vec<(String, String)>,
+}
+
+impl HttpRequestMetadata {
+    /// All values of the header `name`, which is matched case-insensitively,
+    /// in the order they were received.
+    pub fn header_values(&self, name: &str) -> Vec<&str> {
+        self.headers
+            .iter()
+            .filter(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| &value[..])
+            .collect()
+    }
 }
 
 impl TryFrom<pb::common::HttpActionRequestHead> for HttpActionRequestHead {
@@ -31,6 +31,7 @@ pub use crate::{
         HttpActionResponseHead,
         HttpActionResponsePart,
         HttpActionResponseStreamer,
+        HttpRequestMetadata,
         HTTP_ACTION_BODY_LIMIT,
     },
     syscall_stats::SyscallStats,
@@ -85,6 +85,15 @@ impl SyscallTrace {
         });
     }
 
+    /// Total time spent in async syscalls. Syscalls that ran concurrently are
+    /// each counted in full.
+    pub fn total_duration(&self) -> Duration {
+        self.async_syscalls
+            .values()
+            .map(|stats| stats.total_duration)
+            .sum()
+    }
+
     pub fn merge(&mut self, other: &Self) {
         for (name, syscall) in &other.async_syscalls {
             self.async_syscalls
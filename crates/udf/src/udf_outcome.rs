@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use anyhow::Context;
 use common::{
     components::CanonicalizedComponentFunctionPath,
@@ -21,6 +23,8 @@ use pb::{
     },
     outcome::UdfOutcome as UdfOutcomeProto,
 };
+#[cfg(any(test, feature = "testing"))]
+use proptest::prelude::*;
 use rand::Rng;
 use value::{
     heap_size::HeapSize,
@@ -61,6 +65,16 @@ pub struct UdfOutcome {
     #[cfg_attr(any(test, feature = "testing"), proptest(value = "None"))]
     pub udf_server_version: Option<semver::Version>,
     pub memory_in_mb: u64,
+    /// How long the function waited for a concurrency permit before it
+    /// started running.
+    #[cfg_attr(
+        any(test, feature = "testing"),
+        proptest(
+            strategy = "(0..=i64::MAX as u64, 0..1_000_000_000u32).prop_map(|(secs, nanos)| \
+                        Duration::new(secs, nanos))"
+        )
+    )]
+    pub permit_wait: Duration,
 }
 
 impl HeapSize for UdfOutcome {
@@ -94,6 +108,7 @@ impl TryFrom<UdfOutcome> for UdfOutcomeProto {
             syscall_trace,
             udf_server_version: _,
             memory_in_mb,
+            permit_wait,
         }: UdfOutcome,
     ) -> anyhow::Result<Self> {
         let result = match result {
@@ -113,6 +128,7 @@ impl TryFrom<UdfOutcome> for UdfOutcomeProto {
             syscall_trace: Some(syscall_trace.try_into()?),
             observed_identity: Some(observed_identity),
             memory_in_mb,
+            permit_wait: Some(permit_wait.try_into()?),
         })
     }
 }
@@ -143,6 +159,7 @@ impl UdfOutcome {
             udf_server_version,
             observed_identity: false,
             memory_in_mb: 0,
+            permit_wait: Duration::ZERO,
         })
     }
 
@@ -158,6 +175,7 @@ impl UdfOutcome {
             syscall_trace,
             observed_identity,
             memory_in_mb,
+            permit_wait,
         }: UdfOutcomeProto,
         path_and_args: ValidatedPathAndArgs,
         identity: InertIdentity,
@@ -199,6 +217,10 @@ impl UdfOutcome {
             // TODO(lee): Remove the default once we've pushed all services.
             observed_identity: observed_identity.unwrap_or(true),
             memory_in_mb,
+            permit_wait: permit_wait
+                .map(Duration::try_from)
+                .transpose()?
+                .unwrap_or_default(),
         })
     }
 }
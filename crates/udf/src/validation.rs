@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use anyhow::Context;
 use common::{
     components::{
@@ -40,6 +42,7 @@ use model::{
         function_validators::ReturnsValidator,
         module_versions::{
             AnalyzedFunction,
+            FunctionTimeouts,
             Visibility,
         },
         ModuleModel,
@@ -267,6 +270,7 @@ pub struct ValidatedPathAndArgs {
     args: ConvexArray,
     // Not set for system modules.
     npm_version: Option<Version>,
+    timeouts: FunctionTimeouts,
 }
 
 #[cfg(any(test, feature = "testing"))]
@@ -283,8 +287,9 @@ impl Arbitrary for ValidatedPathAndArgs {
             ConvexArray,
             ComponentId,
             ComponentPath,
+            FunctionTimeouts,
         )>()
-        .prop_map(|(udf_path, args, component_id, component_path)| {
+        .prop_map(|(udf_path, args, component_id, component_path, timeouts)| {
             ValidatedPathAndArgs {
                 path: ResolvedComponentFunctionPath {
                     component: component_id,
@@ -293,6 +298,7 @@ impl Arbitrary for ValidatedPathAndArgs {
                 },
                 args,
                 npm_version: None,
+                timeouts,
             }
         })
     }
@@ -354,6 +360,7 @@ impl ValidatedPathAndArgs {
                         path,
                         args,
                         npm_version: None,
+                        timeouts: FunctionTimeouts::default(),
                     },
                     ReturnsValidator::Unvalidated,
                 ))
@@ -486,22 +493,25 @@ impl ValidatedPathAndArgs {
 
         let table_mapping = &tx.table_mapping().namespace(path.component.into());
 
-        // If the UDF has an args validator, check that these args match.
+        // If the UDF has an args validator, fill in defaults for any omitted
+        // optional arguments and check that these args match.
+        let args_validator = analyzed_function.args()?;
+        let args = args_validator.apply_defaults(args)?;
         let args_validation_error =
-            analyzed_function
-                .args()?
-                .check_args(&args, table_mapping, virtual_system_mapping())?;
+            args_validator.check_args(&args, table_mapping, virtual_system_mapping())?;
 
         if let Some(error) = args_validation_error {
-            return Ok(Err(JsError::from_message(format!(
-                "ArgumentValidationError: {error}",
-            ))));
+            return Ok(Err(JsError {
+                message: format!("ArgumentValidationError: {error}"),
+                ..error
+            }));
         }
 
         Ok(Ok(ValidatedPathAndArgs {
             path,
             args,
             npm_version: Some(version),
+            timeouts: analyzed_function.timeouts,
         }))
     }
 
@@ -539,6 +549,7 @@ impl ValidatedPathAndArgs {
             },
             args,
             npm_version,
+            timeouts: FunctionTimeouts::default(),
         }
     }
 
@@ -546,6 +557,11 @@ impl ValidatedPathAndArgs {
         &self.path
     }
 
+    /// The function's overrides for the default user and system timeouts.
+    pub fn timeouts(&self) -> FunctionTimeouts {
+        self.timeouts
+    }
+
     pub fn consume(self) -> (ResolvedComponentFunctionPath, ConvexArray, Option<Version>) {
         (self.path, self.args, self.npm_version)
     }
@@ -561,6 +577,8 @@ impl ValidatedPathAndArgs {
             npm_version,
             component_path,
             component_id,
+            user_timeout_ms,
+            system_timeout_ms,
         }: pb::common::ValidatedPathAndArgs,
     ) -> anyhow::Result<Self> {
         let args_json: JsonValue =
@@ -579,6 +597,10 @@ impl ValidatedPathAndArgs {
             },
             args,
             npm_version: npm_version.map(|v| Version::parse(&v)).transpose()?,
+            timeouts: FunctionTimeouts {
+                user: user_timeout_ms.map(Duration::from_millis),
+                system: system_timeout_ms.map(Duration::from_millis),
+            },
         })
     }
 }
@@ -591,6 +613,7 @@ impl TryFrom<ValidatedPathAndArgs> for pb::common::ValidatedPathAndArgs {
             path,
             args,
             npm_version,
+            timeouts,
         }: ValidatedPathAndArgs,
     ) -> anyhow::Result<Self> {
         let args = args.json_serialize()?.into_bytes();
@@ -603,6 +626,14 @@ impl TryFrom<ValidatedPathAndArgs> for pb::common::ValidatedPathAndArgs {
             npm_version: npm_version.map(|v| v.to_string()),
             component_path,
             component_id: path.component.serialize_to_string(),
+            user_timeout_ms: timeouts
+                .user
+                .map(|t| t.as_millis().try_into())
+                .transpose()?,
+            system_timeout_ms: timeouts
+                .system
+                .map(|t| t.as_millis().try_into())
+                .transpose()?,
         })
     }
 }
@@ -807,6 +838,7 @@ pub struct ValidatedUdfOutcome {
     pub udf_server_version: Option<semver::Version>,
     pub mutation_queue_length: Option<usize>,
     pub memory_in_mb: u64,
+    pub permit_wait: Duration,
 }
 
 impl HeapSize for ValidatedUdfOutcome {
@@ -847,6 +879,7 @@ impl ValidatedUdfOutcome {
             udf_server_version,
             mutation_queue_length: None,
             memory_in_mb: 0,
+            permit_wait: Duration::ZERO,
         })
     }
 
@@ -871,6 +904,7 @@ impl ValidatedUdfOutcome {
             udf_server_version: outcome.udf_server_version,
             mutation_queue_length,
             memory_in_mb: outcome.memory_in_mb,
+            permit_wait: outcome.permit_wait,
         };
 
         // TODO(CX-6318) Don't pack json value until it's been validated.
@@ -899,6 +933,7 @@ pub struct ValidatedActionOutcome {
 
     pub result: Result<JsonPackedValue, JsError>,
     pub syscall_trace: SyscallTrace,
+    pub async_op_wait: Duration,
 
     pub udf_server_version: Option<semver::Version>,
     pub mutation_queue_length: Option<usize>,
@@ -917,6 +952,7 @@ impl ValidatedActionOutcome {
             unix_timestamp: outcome.unix_timestamp,
             result: outcome.result,
             syscall_trace: outcome.syscall_trace,
+            async_op_wait: outcome.async_op_wait,
             udf_server_version: outcome.udf_server_version,
             mutation_queue_length: None,
         };
@@ -950,6 +986,7 @@ impl ValidatedActionOutcome {
             unix_timestamp: rt.unix_timestamp(),
             result: Err(js_error),
             syscall_trace: SyscallTrace::new(),
+            async_op_wait: Duration::ZERO,
             udf_server_version,
             mutation_queue_length: None,
         }
@@ -969,6 +1006,7 @@ impl ValidatedActionOutcome {
             unix_timestamp,
             result: Err(JsError::from_error_ref(e)),
             syscall_trace: SyscallTrace::new(),
+            async_op_wait: Duration::ZERO,
             udf_server_version: None,
             mutation_queue_length: None,
         }
@@ -51,7 +51,53 @@ impl FromStr for ValueFormat {
     }
 }
 
+/// The wire encoding used for a UDF's result.
+///
+/// Every encoding writes object keys sorted by field name (byte-wise), no
+/// matter what order the function built its return value in, so encoding the
+/// same value always produces the same bytes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(any(test, feature = "testing"), derive(proptest_derive::Arbitrary))]
+pub enum ResultFormat {
+    /// JSON, using the given [`ValueFormat`] for Convex-specific types.
+    Json(ValueFormat),
+    /// MessagePack, which natively preserves `Int64` and `Bytes` values. See
+    /// [`crate::msgpack`].
+    MessagePack,
+}
+
+impl Default for ResultFormat {
+    fn default() -> Self {
+        Self::Json(ValueFormat::ConvexEncodedJSON)
+    }
+}
+
+impl FromStr for ResultFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "msgpack" | "messagepack" => Ok(Self::MessagePack),
+            s => Ok(Self::Json(s.parse()?)),
+        }
+    }
+}
+
+impl ConvexValue {
+    /// Encode this value as the body of a UDF result. Object keys are emitted
+    /// in sorted order, see [`ResultFormat`].
+    pub fn encode(self, result_format: ResultFormat) -> anyhow::Result<Vec<u8>> {
+        match result_format {
+            ResultFormat::Json(value_format) => Ok(serde_json::to_vec(&self.export(value_format))?),
+            ResultFormat::MessagePack => Ok(self.to_msgpack()),
+        }
+    }
+}
+
 impl ConvexObject {
+    /// Export this object as JSON. `ConvexObject` keeps its fields sorted,
+    /// and `serde_json::Map` preserves insertion order, so the exported keys
+    /// are sorted as well.
     pub fn export(self, value_format: ValueFormat) -> JsonValue {
         let v: serde_json::Map<_, _> = self
             .into_iter()
@@ -171,4 +217,26 @@ mod tests {
         let value = ConvexValue::Int64(42);
         assert_eq!(value.export_clean(), JsonValue::String("42".to_string()));
     }
+
+    #[test]
+    fn encode_emits_sorted_keys() -> anyhow::Result<()> {
+        // Build the same object from JSON with different key orders.
+        let forward: ConvexValue = json!({"a": 1, "b": {"c": 2, "d": 3}, "e": 4}).try_into()?;
+        let backward: ConvexValue = json!({"e": 4, "b": {"d": 3, "c": 2}, "a": 1}).try_into()?;
+        for result_format in [
+            ResultFormat::Json(ValueFormat::ConvexEncodedJSON),
+            ResultFormat::Json(ValueFormat::ConvexCleanJSON),
+            ResultFormat::MessagePack,
+        ] {
+            let encoded = forward.clone().encode(result_format)?;
+            assert_eq!(encoded, backward.clone().encode(result_format)?);
+            assert_eq!(encoded, forward.clone().encode(result_format)?);
+        }
+        let encoded = forward.encode(ResultFormat::Json(ValueFormat::ConvexCleanJSON))?;
+        assert_eq!(
+            String::from_utf8(encoded)?,
+            r#"{"a":1.0,"b":{"c":2.0,"d":3.0},"e":4.0}"#
+        );
+        Ok(())
+    }
 }
@@ -1,25 +1,48 @@
-use std::sync::Arc;
+use std::{
+    ops::Range,
+    sync::Arc,
+};
 
+use anyhow::Context;
 #[cfg(any(test, feature = "testing"))]
 use proptest::prelude::*;
-use serde_json::Value as JsonValue;
+use serde_json::{
+    value::RawValue,
+    Value as JsonValue,
+};
 
 use super::json_deserialize;
 use crate::{
+    export::{
+        ResultFormat,
+        ValueFormat,
+    },
     heap_size::HeapSize,
     ConvexValue,
 };
 
 #[derive(Clone, Debug)]
 #[cfg_attr(any(test, feature = "testing"), derive(PartialEq))]
-pub struct JsonPackedValue(Arc<str>);
+pub struct JsonPackedValue {
+    json: Arc<str>,
+    /// The raw contents of a packed `ConvexValue::Bytes`, kept alongside the
+    /// JSON so reading them doesn't decode the base64 encoding.
+    bytes: Option<Arc<[u8]>>,
+}
 
 impl JsonPackedValue {
     pub fn pack(value: ConvexValue) -> Self {
+        let bytes = match &value {
+            ConvexValue::Bytes(bytes) => Some(Arc::from(&bytes[..])),
+            _ => None,
+        };
         let serialized = value
             .json_serialize()
             .expect("Failed to serialize to string");
-        Self(serialized.into())
+        Self {
+            json: serialized.into(),
+            bytes,
+        }
     }
 
     pub fn unpack(&self) -> ConvexValue {
@@ -27,11 +50,27 @@ impl JsonPackedValue {
     }
 
     pub fn json_value(&self) -> JsonValue {
-        serde_json::from_str(&self.0).expect("Failed to deserialize packed JSON value")
+        serde_json::from_str(&self.json).expect("Failed to deserialize packed JSON value")
     }
 
     pub fn as_str(&self) -> &str {
-        &self.0
+        &self.json
+    }
+
+    /// The raw contents of a packed `ConvexValue::Bytes`, or `None` if the
+    /// value is anything else.
+    pub fn bytes(&self) -> Option<&[u8]> {
+        self.bytes.as_deref()
+    }
+
+    /// Encode the packed value in the caller's requested result format.
+    pub fn encode(&self, result_format: ResultFormat) -> anyhow::Result<Vec<u8>> {
+        match result_format {
+            // The packed representation is already internal JSON, so avoid
+            // reparsing it.
+            ResultFormat::Json(ValueFormat::ConvexEncodedJSON) => Ok(self.json.as_bytes().to_vec()),
+            result_format => self.unpack().encode(result_format),
+        }
     }
 
     pub fn from_network(json: String) -> anyhow::Result<Self> {
@@ -39,11 +78,62 @@ impl JsonPackedValue {
         // the string data through
         json_deserialize(&json).map(Self::pack)
     }
+
+    /// View a packed array without deserializing its elements. Fails if the
+    /// packed value isn't an array.
+    pub fn lazy_array(&self) -> anyhow::Result<LazyJsonArray> {
+        let elements: Vec<&RawValue> =
+            serde_json::from_str(&self.json).context("Packed JSON value isn't an array")?;
+        let start = self.json.as_ptr() as usize;
+        let ranges = elements
+            .into_iter()
+            .map(|element| {
+                let offset = element.get().as_ptr() as usize - start;
+                offset..offset + element.get().len()
+            })
+            .collect();
+        Ok(LazyJsonArray {
+            packed: self.json.clone(),
+            ranges,
+        })
+    }
+}
+
+/// A packed array whose elements are only deserialized when they're accessed,
+/// so callers reading a few documents out of a large result don't pay for
+/// materializing the rest.
+#[derive(Clone, Debug)]
+pub struct LazyJsonArray {
+    packed: Arc<str>,
+    ranges: Vec<Range<usize>>,
+}
+
+impl LazyJsonArray {
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Deserialize the element at `index`, or return `None` if it's out of
+    /// bounds.
+    pub fn get(&self, index: usize) -> Option<anyhow::Result<ConvexValue>> {
+        let range = self.ranges.get(index)?;
+        Some(json_deserialize(&self.packed[range.clone()]))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = anyhow::Result<ConvexValue>> + '_ {
+        self.ranges
+            .iter()
+            .map(|range| json_deserialize(&self.packed[range.clone()]))
+    }
 }
 
 impl HeapSize for JsonPackedValue {
     fn heap_size(&self) -> usize {
-        self.0.len()
+        self.json.len() + self.bytes.as_ref().map_or(0, |bytes| bytes.len())
     }
 }
 
@@ -66,3 +156,51 @@ impl Arbitrary for JsonPackedValue {
         any::<ConvexValue>().prop_map(JsonPackedValue::pack)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_obj;
+
+    #[test]
+    fn test_lazy_array_only_deserializes_accessed_elements() -> anyhow::Result<()> {
+        // Every element after the first is an invalid Convex value, so
+        // accessing the first one only succeeds if the rest are left alone.
+        let mut elements = vec![r#"{"name":"first"}"#.to_string()];
+        elements.extend((0..1000).map(|i| format!(r#"{{"$invalid":{i}}}"#)));
+        let packed = JsonPackedValue {
+            json: format!("[{}]", elements.join(",")).into(),
+            bytes: None,
+        };
+
+        let lazy = packed.lazy_array()?;
+        assert_eq!(lazy.len(), 1001);
+        assert_eq!(
+            lazy.get(0).unwrap()?,
+            ConvexValue::from(assert_obj!("name" => "first"))
+        );
+        assert!(lazy.get(1).unwrap().is_err());
+        assert!(lazy.get(1001).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bytes() -> anyhow::Result<()> {
+        let packed = JsonPackedValue::pack(ConvexValue::Bytes(vec![0, 1, 255].try_into()?));
+        assert_eq!(packed.bytes(), Some(&[0, 1, 255][..]));
+
+        // The bytes survive a round trip through the network encoding.
+        let packed = JsonPackedValue::from_network(packed.as_str().to_string())?;
+        assert_eq!(packed.bytes(), Some(&[0, 1, 255][..]));
+
+        let packed = JsonPackedValue::pack(ConvexValue::try_from("AAE=".to_string())?);
+        assert_eq!(packed.bytes(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lazy_array_rejects_non_arrays() {
+        let packed = JsonPackedValue::pack(assert_obj!("name" => "first").into());
+        assert!(packed.lazy_array().is_err());
+    }
+}
@@ -17,8 +17,10 @@ mod field_name;
 mod field_path;
 pub mod id_v6;
 mod json;
+pub mod msgpack;
 pub mod numeric;
 mod object;
+pub mod result_compression;
 pub mod serde;
 pub mod serialized_args_ext;
 pub mod sha256;
@@ -78,7 +80,10 @@ pub use crate::{
         float::JsonFloat,
         integer::JsonInteger,
         json_deserialize,
-        json_packed_value::JsonPackedValue,
+        json_packed_value::{
+            JsonPackedValue,
+            LazyJsonArray,
+        },
         object as json_object,
         value as json_value,
     },
@@ -0,0 +1,257 @@
+//! MessagePack encoding for [`ConvexValue`]s.
+//!
+//! Unlike our JSON encodings, MessagePack has native representations for
+//! 64-bit integers and binary data, so every [`ConvexValue`] round-trips
+//! without any of the `{"$integer": ...}` or `{"$bytes": ...}` wrappers.
+//!
+//! See <https://github.com/msgpack/msgpack/blob/master/spec.md>.
+
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+
+use crate::{
+    size::check_nesting,
+    ConvexObject,
+    ConvexValue,
+    FieldName,
+};
+
+const NIL: u8 = 0xc0;
+const FALSE: u8 = 0xc2;
+const TRUE: u8 = 0xc3;
+const BIN32: u8 = 0xc6;
+const FLOAT64: u8 = 0xcb;
+const INT64: u8 = 0xd3;
+const STR32: u8 = 0xdb;
+const ARRAY32: u8 = 0xdd;
+const MAP32: u8 = 0xdf;
+
+impl ConvexValue {
+    /// Serialize this value to MessagePack. Integers are always written as
+    /// `int 64`, and containers always use their 32-bit length variants, so
+    /// the encoding of a value doesn't depend on its magnitude.
+    pub fn to_msgpack(&self) -> Vec<u8> {
+        let mut buf = vec![];
+        write_value(&mut buf, self);
+        buf
+    }
+
+    /// Deserialize a value previously written with
+    /// [`ConvexValue::to_msgpack`]. The decoder also accepts the compact
+    /// encodings other MessagePack writers emit (fixint, fixstr, etc.).
+    pub fn from_msgpack(buf: &[u8]) -> anyhow::Result<Self> {
+        let mut reader = Reader { buf, pos: 0 };
+        let value = reader.read_value(0)?;
+        anyhow::ensure!(
+            reader.pos == buf.len(),
+            "Trailing bytes after MessagePack value"
+        );
+        Ok(value)
+    }
+}
+
+fn write_len(buf: &mut Vec<u8>, tag: u8, len: usize) {
+    buf.push(tag);
+    buf.extend_from_slice(&(len as u32).to_be_bytes());
+}
+
+fn write_value(buf: &mut Vec<u8>, value: &ConvexValue) {
+    match value {
+        ConvexValue::Null => buf.push(NIL),
+        ConvexValue::Boolean(false) => buf.push(FALSE),
+        ConvexValue::Boolean(true) => buf.push(TRUE),
+        ConvexValue::Int64(n) => {
+            buf.push(INT64);
+            buf.extend_from_slice(&n.to_be_bytes());
+        },
+        ConvexValue::Float64(f) => {
+            buf.push(FLOAT64);
+            buf.extend_from_slice(&f.to_be_bytes());
+        },
+        ConvexValue::String(s) => {
+            write_len(buf, STR32, s.len());
+            buf.extend_from_slice(s.as_bytes());
+        },
+        ConvexValue::Bytes(b) => {
+            write_len(buf, BIN32, b.len());
+            buf.extend_from_slice(b);
+        },
+        ConvexValue::Array(array) => {
+            write_len(buf, ARRAY32, array.len());
+            for element in array.iter() {
+                write_value(buf, element);
+            }
+        },
+        ConvexValue::Object(object) => {
+            write_len(buf, MAP32, object.len());
+            for (field, element) in object.iter() {
+                write_len(buf, STR32, field.len());
+                buf.extend_from_slice(field.as_bytes());
+                write_value(buf, element);
+            }
+        },
+    }
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> anyhow::Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.buf.len())
+            .context("Unexpected end of MessagePack input")?;
+        let slice = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> anyhow::Result<[u8; N]> {
+        Ok(self.take(N)?.try_into()?)
+    }
+
+    fn read_len(&mut self, width: usize) -> anyhow::Result<usize> {
+        Ok(match width {
+            1 => u8::from_be_bytes(self.take_array()?) as usize,
+            2 => u16::from_be_bytes(self.take_array()?) as usize,
+            4 => u32::from_be_bytes(self.take_array()?) as usize,
+            _ => anyhow::bail!("Invalid MessagePack length width {width}"),
+        })
+    }
+
+    fn read_str(&mut self, len: usize) -> anyhow::Result<String> {
+        Ok(String::from_utf8(self.take(len)?.to_vec())?)
+    }
+
+    /// `nesting` counts the containers enclosing the elements, including this
+    /// one. It's checked before reading them so deeply nested input fails
+    /// instead of overflowing the stack.
+    fn read_array(&mut self, len: usize, nesting: usize) -> anyhow::Result<ConvexValue> {
+        check_nesting(nesting)?;
+        let elements = (0..len)
+            .map(|_| self.read_value(nesting))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        ConvexValue::try_from(elements)
+    }
+
+    fn read_map(&mut self, len: usize, nesting: usize) -> anyhow::Result<ConvexValue> {
+        check_nesting(nesting)?;
+        let mut fields = BTreeMap::new();
+        for _ in 0..len {
+            let ConvexValue::String(key) = self.read_value(nesting)? else {
+                anyhow::bail!("MessagePack map keys must be strings");
+            };
+            let field: FieldName = String::from(key).parse()?;
+            fields.insert(field, self.read_value(nesting)?);
+        }
+        Ok(ConvexValue::Object(ConvexObject::try_from(fields)?))
+    }
+
+    fn read_value(&mut self, nesting: usize) -> anyhow::Result<ConvexValue> {
+        let [tag] = self.take_array()?;
+        let value = match tag {
+            0x00..=0x7f => ConvexValue::Int64(tag as i64),
+            0xe0..=0xff => ConvexValue::Int64(tag as i8 as i64),
+            0x80..=0x8f => self.read_map((tag & 0x0f) as usize, nesting + 1)?,
+            0x90..=0x9f => self.read_array((tag & 0x0f) as usize, nesting + 1)?,
+            0xa0..=0xbf => ConvexValue::try_from(self.read_str((tag & 0x1f) as usize)?)?,
+            NIL => ConvexValue::Null,
+            FALSE => ConvexValue::Boolean(false),
+            TRUE => ConvexValue::Boolean(true),
+            0xc4 | 0xc5 | BIN32 => {
+                let len = self.read_len(1 << (tag - 0xc4))?;
+                ConvexValue::try_from(self.take(len)?.to_vec())?
+            },
+            0xca => ConvexValue::Float64(f32::from_be_bytes(self.take_array()?) as f64),
+            FLOAT64 => ConvexValue::Float64(f64::from_be_bytes(self.take_array()?)),
+            0xcc => ConvexValue::Int64(u8::from_be_bytes(self.take_array()?) as i64),
+            0xcd => ConvexValue::Int64(u16::from_be_bytes(self.take_array()?) as i64),
+            0xce => ConvexValue::Int64(u32::from_be_bytes(self.take_array()?) as i64),
+            0xcf => {
+                let n = u64::from_be_bytes(self.take_array()?);
+                ConvexValue::Int64(i64::try_from(n).context("uint 64 out of range for Int64")?)
+            },
+            0xd0 => ConvexValue::Int64(i8::from_be_bytes(self.take_array()?) as i64),
+            0xd1 => ConvexValue::Int64(i16::from_be_bytes(self.take_array()?) as i64),
+            0xd2 => ConvexValue::Int64(i32::from_be_bytes(self.take_array()?) as i64),
+            INT64 => ConvexValue::Int64(i64::from_be_bytes(self.take_array()?)),
+            0xd9 | 0xda | STR32 => {
+                let len = self.read_len(1 << (tag - 0xd9))?;
+                ConvexValue::try_from(self.read_str(len)?)?
+            },
+            0xdc | ARRAY32 => {
+                let len = self.read_len(if tag == 0xdc { 2 } else { 4 })?;
+                self.read_array(len, nesting + 1)?
+            },
+            0xde | MAP32 => {
+                let len = self.read_len(if tag == 0xde { 2 } else { 4 })?;
+                self.read_map(len, nesting + 1)?
+            },
+            _ => anyhow::bail!("Unsupported MessagePack type tag {tag:#x}"),
+        };
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cmd_util::env::env_config;
+    use proptest::prelude::*;
+
+    use crate::{
+        assert_obj,
+        ConvexValue,
+        MAX_NESTING,
+    };
+
+    proptest! {
+        #![proptest_config(
+            ProptestConfig { cases: 256 * env_config("CONVEX_PROPTEST_MULTIPLIER", 1), failure_persistence: None, ..ProptestConfig::default() }
+        )]
+
+        #[test]
+        fn test_msgpack_roundtrips(value in any::<ConvexValue>()) {
+            let encoded = value.to_msgpack();
+            prop_assert_eq!(ConvexValue::from_msgpack(&encoded).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_msgpack_preserves_int64() -> anyhow::Result<()> {
+        // Larger than 2^53, so this can't be represented exactly as a JSON number.
+        let n: i64 = (1 << 60) + 1;
+        let value = ConvexValue::Object(assert_obj!("n" => n));
+        let encoded = value.to_msgpack();
+        assert_eq!(ConvexValue::from_msgpack(&encoded)?, value);
+        Ok(())
+    }
+
+    #[test]
+    fn test_msgpack_decodes_compact_ints() -> anyhow::Result<()> {
+        assert_eq!(ConvexValue::from_msgpack(&[0x05])?, ConvexValue::Int64(5));
+        assert_eq!(ConvexValue::from_msgpack(&[0xff])?, ConvexValue::Int64(-1));
+        assert_eq!(
+            ConvexValue::from_msgpack(&[0xcd, 0x01, 0x00])?,
+            ConvexValue::Int64(256)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_msgpack_rejects_deep_nesting() {
+        // A fixarray of length 1 per level, far deeper than the stack allows.
+        let mut encoded = vec![0x91; 1_000_000];
+        encoded.push(0xc0);
+        let err = ConvexValue::from_msgpack(&encoded).unwrap_err();
+        assert!(err.to_string().contains("too nested"), "{err}");
+
+        let mut encoded = vec![0x91; MAX_NESTING];
+        encoded.push(0xc0);
+        assert!(ConvexValue::from_msgpack(&encoded).is_ok());
+    }
+}
@@ -0,0 +1,203 @@
+//! Optional compression for encoded UDF results.
+//!
+//! Callers opt in by asking for a [`ResultCompression`]. Small results are
+//! sent uncompressed since compressing them rarely saves enough bytes to be
+//! worth the CPU, so callers must check [`EncodedResult::compression`] to
+//! know whether the body needs decompressing.
+
+use std::{
+    io::{
+        Read,
+        Write,
+    },
+    str::FromStr,
+};
+
+use errors::ErrorMetadata;
+use flate2::{
+    read::GzDecoder,
+    write::GzEncoder,
+    Compression,
+};
+
+use crate::{
+    export::ResultFormat,
+    ConvexValue,
+    JsonPackedValue,
+};
+
+/// Results smaller than this are never compressed.
+pub const MIN_COMPRESSED_RESULT_SIZE: usize = 1 << 12;
+
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(any(test, feature = "testing"), derive(proptest_derive::Arbitrary))]
+pub enum ResultCompression {
+    Gzip,
+    Zstd,
+}
+
+impl FromStr for ResultCompression {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gzip" => Ok(Self::Gzip),
+            "zstd" => Ok(Self::Zstd),
+            _ => Err(anyhow::anyhow!("unrecognized compression {s:?}").context(
+                ErrorMetadata::bad_request(
+                    "BadCompression",
+                    format!("compression param must be one of [`gzip`, `zstd`]. Got {s}"),
+                ),
+            )),
+        }
+    }
+}
+
+impl ResultCompression {
+    /// The value of the HTTP `Content-Encoding` header for this compression.
+    pub fn content_encoding(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    pub fn compress(&self, buf: &[u8]) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(vec![], Compression::default());
+                encoder.write_all(buf)?;
+                Ok(encoder.finish()?)
+            },
+            Self::Zstd => Ok(zstd::encode_all(buf, ZSTD_LEVEL)?),
+        }
+    }
+
+    pub fn decompress(&self, buf: &[u8]) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Self::Gzip => {
+                let mut out = vec![];
+                GzDecoder::new(buf).read_to_end(&mut out)?;
+                Ok(out)
+            },
+            Self::Zstd => Ok(zstd::decode_all(buf)?),
+        }
+    }
+}
+
+/// The body of a UDF result, along with the compression applied to it, if
+/// any.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncodedResult {
+    pub body: Vec<u8>,
+    pub compression: Option<ResultCompression>,
+}
+
+impl EncodedResult {
+    /// Compress `body` with `compression` if it's at least
+    /// [`MIN_COMPRESSED_RESULT_SIZE`] bytes.
+    pub fn new(body: Vec<u8>, compression: Option<ResultCompression>) -> anyhow::Result<Self> {
+        match compression {
+            Some(compression) if body.len() >= MIN_COMPRESSED_RESULT_SIZE => Ok(Self {
+                body: compression.compress(&body)?,
+                compression: Some(compression),
+            }),
+            _ => Ok(Self {
+                body,
+                compression: None,
+            }),
+        }
+    }
+
+    /// Undo the compression, returning the encoded result.
+    pub fn into_uncompressed(self) -> anyhow::Result<Vec<u8>> {
+        match self.compression {
+            Some(compression) => compression.decompress(&self.body),
+            None => Ok(self.body),
+        }
+    }
+}
+
+impl ConvexValue {
+    /// Like [`ConvexValue::encode`], additionally compressing large results.
+    pub fn encode_compressed(
+        self,
+        result_format: ResultFormat,
+        compression: Option<ResultCompression>,
+    ) -> anyhow::Result<EncodedResult> {
+        EncodedResult::new(self.encode(result_format)?, compression)
+    }
+}
+
+impl JsonPackedValue {
+    /// Like [`JsonPackedValue::encode`], additionally compressing large
+    /// results.
+    pub fn encode_compressed(
+        &self,
+        result_format: ResultFormat,
+        compression: Option<ResultCompression>,
+    ) -> anyhow::Result<EncodedResult> {
+        EncodedResult::new(self.encode(result_format)?, compression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        export::ValueFormat,
+        json::json_deserialize,
+    };
+
+    fn large_value() -> anyhow::Result<ConvexValue> {
+        let rows: Vec<_> = (0..1000)
+            .map(|i| json!({"id": i, "name": format!("row {i}")}))
+            .collect();
+        json!({ "rows": rows }).try_into()
+    }
+
+    #[test]
+    fn test_large_result_roundtrips_compressed() -> anyhow::Result<()> {
+        let value = large_value()?;
+        for compression in [ResultCompression::Gzip, ResultCompression::Zstd] {
+            let uncompressed = value
+                .clone()
+                .encode(ResultFormat::Json(ValueFormat::ConvexEncodedJSON))?;
+            let encoded = value.clone().encode_compressed(
+                ResultFormat::Json(ValueFormat::ConvexEncodedJSON),
+                Some(compression),
+            )?;
+            assert_eq!(encoded.compression, Some(compression));
+            assert!(encoded.body.len() < uncompressed.len());
+            let body = encoded.into_uncompressed()?;
+            assert_eq!(json_deserialize(std::str::from_utf8(&body)?)?, value);
+
+            let encoded = value
+                .clone()
+                .encode_compressed(ResultFormat::MessagePack, Some(compression))?;
+            assert_eq!(encoded.compression, Some(compression));
+            let body = encoded.into_uncompressed()?;
+            assert_eq!(ConvexValue::from_msgpack(&body)?, value);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_small_result_is_not_compressed() -> anyhow::Result<()> {
+        let value = ConvexValue::try_from("small")?;
+        let encoded = value.clone().encode_compressed(
+            ResultFormat::Json(ValueFormat::ConvexEncodedJSON),
+            Some(ResultCompression::Zstd),
+        )?;
+        assert_eq!(encoded.compression, None);
+        assert_eq!(
+            encoded.body,
+            value.encode(ResultFormat::Json(ValueFormat::ConvexEncodedJSON))?
+        );
+        Ok(())
+    }
+}
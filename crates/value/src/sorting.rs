@@ -19,6 +19,14 @@
 //!    for an explanation of the algorithm.
 //! 5) Compound types, like arrays, are stored sequentially, with a null
 //!    terminator at the end.
+//!
+//! Strings are stored as their UTF-8 bytes, so they sort by code point rather
+//! than by any locale's collation (e.g. "Zoo" < "apple" < "zoo" < "éclair").
+//! Index ranges, cursors, and read sets are all expressed in terms of these
+//! sort keys, so locale-aware ordering can't be layered onto an index scan and
+//! has to be applied to the results instead. An index range with a collation
+//! reads its range in this order and re-sorts the rows afterwards (see
+//! `common::collation`), which is why such queries can't be paginated.
 use std::cmp::Ordering;
 
 use bytes::BufMut;
@@ -573,6 +581,21 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_strings_sort_by_code_point() -> anyhow::Result<()> {
+        let mut values = ["éclair", "zoo", "apple", "Zoo", "eclair"]
+            .into_iter()
+            .map(ConvexValue::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        values.sort_by_key(|v| v.sort_key());
+        let expected = ["Zoo", "apple", "eclair", "zoo", "éclair"]
+            .into_iter()
+            .map(ConvexValue::try_from)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(values, expected);
+        Ok(())
+    }
+
     fn test_compatible_with_ord<F: Ord + TryInto<ConvexValue>>(l: F, r: F)
     where
         <F as TryInto<ConvexValue>>::Error: Debug,
@@ -2,6 +2,10 @@ import { httpRouter } from "convex/server";
 import { httpAction } from "./_generated/server";
 import { api } from "./_generated/api";
 
+declare const Convex: {
+  op: (op: string, ...args: any[]) => any;
+};
+
 const http = httpRouter();
 
 http.route({
@@ -79,6 +83,16 @@ http.route({
   }),
 });
 
+http.route({
+  path: "/requestMetadata",
+  method: "GET",
+  handler: httpAction(async () => {
+    const { method, path } = Convex.op("requestMetadata/get");
+    const signatures = Convex.op("requestMetadata/getHeader", "X-Signature");
+    return new Response(JSON.stringify({ method, path, signatures }));
+  }),
+});
+
 http.route({
   path: "/writeAfterDisconnect",
   method: "GET",
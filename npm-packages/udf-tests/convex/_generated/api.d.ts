@@ -17,6 +17,7 @@ import type * as asyncTests from "../asyncTests.js";
 import type * as auth from "../auth.js";
 import type * as basic from "../basic.js";
 import type * as benches from "../benches.js";
+import type * as counters from "../counters.js";
 import type * as creationTime from "../creationTime.js";
 import type * as crons from "../crons.js";
 import type * as crons_error from "../crons_error.js";
@@ -64,6 +65,7 @@ import type * as query from "../query.js";
 import type * as returns_validation from "../returns_validation.js";
 import type * as scheduler from "../scheduler.js";
 import type * as search from "../search.js";
+import type * as sequences from "../sequences.js";
 import type * as shapes from "../shapes.js";
 import type * as size_errors from "../size_errors.js";
 import type * as sourceMaps from "../sourceMaps.js";
@@ -99,6 +101,7 @@ declare const fullApi: ApiFromModules<{
   auth: typeof auth;
   basic: typeof basic;
   benches: typeof benches;
+  counters: typeof counters;
   creationTime: typeof creationTime;
   crons: typeof crons;
   crons_error: typeof crons_error;
@@ -146,6 +149,7 @@ declare const fullApi: ApiFromModules<{
   returns_validation: typeof returns_validation;
   scheduler: typeof scheduler;
   search: typeof search;
+  sequences: typeof sequences;
   shapes: typeof shapes;
   size_errors: typeof size_errors;
   sourceMaps: typeof sourceMaps;
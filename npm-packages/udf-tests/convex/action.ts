@@ -16,6 +16,10 @@ export const getSiteUrl = action(async () => {
   return process.env.CONVEX_SITE_URL;
 });
 
+export const readTimeMs = action(async () => {
+  return Date.now();
+});
+
 export const insertObject = action(async ({ runMutation, runQuery }, args) => {
   await runMutation(api.basic.insertObject, args);
   const count: number = await runQuery(api.basic.count, {});
@@ -33,6 +37,14 @@ export const sleep = action({
   },
 });
 
+// Identical to `sleep`, for tests that configure the two differently.
+export const sleepAgain = action({
+  args: { ms: v.number() },
+  handler: async (_ctx, { ms }) => {
+    await new Promise((resolve) => setTimeout(resolve, ms));
+  },
+});
+
 export const inc = mutation({
   args: {},
   handler: async (ctx) => {
@@ -12,6 +12,18 @@ export const stringArg = query({
   },
 });
 
+export const insertObject = mutation({
+  args: {
+    name: v.string(),
+    count: v.number(),
+  },
+
+  handler: async ({ db }, obj) => {
+    const id = await db.insert("objects", obj);
+    return await db.get(id);
+  },
+});
+
 export const returnRecord = mutation({
   args: {},
   handler: async (ctx) => {
@@ -35,3 +47,22 @@ export const recordArg = query({
     return arg;
   },
 });
+
+export const defaultArg = query({
+  args: {
+    arg: v.optional(v.string()),
+  },
+
+  handler: (_, { arg }) => {
+    return arg;
+  },
+});
+
+// The `convex` package doesn't have syntax for defaults, so add one to the
+// exported validator by hand.
+const exportDefaultArgArgs = (defaultArg as any).exportArgs;
+(defaultArg as any).exportArgs = () => {
+  const args = JSON.parse(exportDefaultArgArgs());
+  args.value.arg.default = "defaultValue";
+  return JSON.stringify(args);
+};
@@ -1,5 +1,10 @@
-import { query } from "./_generated/server";
+import { mutation, query } from "./_generated/server";
 import { api } from "./_generated/api";
+import { v } from "convex/values";
+
+declare const Convex: {
+  asyncSyscall: (op: string, jsonArgs: string) => Promise<string>;
+};
 
 export const getName = query(async function ({ auth }) {
   const user = await auth.getUserIdentity();
@@ -35,3 +40,27 @@ export const conditionallyCheckAuthInSubquery = query(
     return await ctx.runQuery(api.auth.conditionallyCheckAuth);
   },
 );
+
+// Reads a single claim without fetching the whole identity.
+export const getClaim = query({
+  args: { name: v.string() },
+  handler: async (_ctx, { name }) => {
+    const result = await Convex.asyncSyscall(
+      "1.0/getIdentityClaims",
+      JSON.stringify({ names: [name] }),
+    );
+    const claims = JSON.parse(result);
+    return claims?.[name] ?? null;
+  },
+});
+
+export const insertAsAdmin = mutation({
+  args: {},
+  handler: async (ctx) => {
+    const user = await ctx.auth.getUserIdentity();
+    if (user?.role !== "admin") {
+      throw new Error("Only admins can insert");
+    }
+    return await ctx.db.insert("table", {});
+  },
+});
@@ -1,6 +1,11 @@
+import { convexToJson, jsonToConvex } from "convex/values";
 import { Id } from "./_generated/dataModel";
 import { mutation, query, action } from "./_generated/server";
 
+declare const Convex: {
+  asyncSyscall: (op: string, jsonArgs: string) => Promise<string>;
+};
+
 export const addOneInt = query(async (_, { x }: { x: bigint }) => {
   return x + 1n;
 });
@@ -27,6 +32,18 @@ export const insertObject = mutation(async ({ db }, obj) => {
   return await db.get(id);
 });
 
+// Inserts a batch of objects in a single transaction, so they commit together
+// and contend with other writers only once. If any insert fails, none of the
+// batch is committed.
+export const insertObjects = mutation(
+  async ({ db }, { objects }: { objects: any[] }) => {
+    for (const obj of objects) {
+      await db.insert("objects", obj);
+    }
+    return objects.length;
+  },
+);
+
 // Regression test, ensuring that `db.patch` updates the table summary.
 // If it doesn't, the db.delete will try to delete an object larger than
 // the one that was inserted, and the table summary's size will go negative.
@@ -46,6 +63,14 @@ export const insertTwoObjects = mutation(
   },
 );
 
+export const parallelInsert = mutation(
+  async ({ db }, { values }: { values: any[] }) => {
+    return await Promise.all(
+      values.map((value) => db.insert("objects", { field: value })),
+    );
+  },
+);
+
 export const patchObject = mutation(
   async ({ db }, { id, obj }: { id: Id<any>; obj: any }) => {
     await db.patch(id, obj);
@@ -119,6 +144,29 @@ export const explicitDbTableApi = mutation(async ({ db }) => {
 
 export const doNothing = query(async () => "hi");
 
+export const insertNow = mutation(async ({ db }) => {
+  const id = await db.insert("objects", { now: Date.now() });
+  return await db.get(id);
+});
+
+export const growArray = mutation(
+  async ({ db }, { chunks }: { chunks: number }) => {
+    const arrays = [];
+    for (let i = 0; i < chunks; i++) {
+      arrays.push(new Array(1 << 17).fill(i));
+      // Yield so the runtime gets a chance to check memory usage.
+      await db.query("objects").first();
+    }
+    return arrays.length;
+  },
+);
+
+export const paginateObjects = query(
+  async ({ db }, { paginationOpts }: { paginationOpts: any }) => {
+    return await db.query("objects").paginate(paginationOpts);
+  },
+);
+
 export const count = query(async ({ db }) => {
   return await db.query("objects").count();
 });
@@ -128,6 +176,11 @@ export const insertAndCount = mutation(async ({ db }, obj) => {
   return await db.query("objects").count();
 });
 
+export const insertAndCollect = mutation(async ({ db }, obj) => {
+  await db.insert("objects", obj);
+  return (await db.query("objects").collect()).length;
+});
+
 export const deleteAndCount = mutation(
   async ({ db }, { id }: { id: Id<any> }) => {
     await db.delete(id);
@@ -139,6 +192,28 @@ export const simpleMutation = mutation(async () => {
   return 2;
 });
 
+export const randomMutation = mutation(async () => {
+  return Math.random();
+});
+
+export const echoArgs = mutation(async (_, args) => {
+  return args;
+});
+
+export const echoArgsQuery = query(async (_, args) => {
+  return args;
+});
+
 export const simpleAction = action(async () => {
   return 2;
 });
+
+export const patchReturningPrevious = mutation(
+  async (_, { id, value }: { id: Id<any>; value: any }) => {
+    const result = await Convex.asyncSyscall(
+      "1.0/shallowMerge",
+      JSON.stringify({ id, value: convexToJson(value), returnPrevious: true }),
+    );
+    return jsonToConvex(JSON.parse(result));
+  },
+);
@@ -0,0 +1,37 @@
+import { jsonToConvex } from "convex/values";
+import { mutation, query } from "./_generated/server";
+
+declare const Convex: {
+  asyncSyscall: (op: string, jsonArgs: string) => Promise<string>;
+};
+
+async function counterIncrement(name: string, delta: number) {
+  await Convex.asyncSyscall(
+    "1.0/counterIncrement",
+    JSON.stringify({ name, delta }),
+  );
+}
+
+async function counterGet(name: string) {
+  const result = await Convex.asyncSyscall(
+    "1.0/counterGet",
+    JSON.stringify({ name }),
+  );
+  return jsonToConvex(JSON.parse(result));
+}
+
+export const increment = mutation(async () => {
+  await counterIncrement("hits", 1);
+});
+
+export const incrementMany = mutation(
+  async (_, { count }: { count: number }) => {
+    for (let i = 0; i < count; i++) {
+      await counterIncrement(`counter${i}`, 1);
+    }
+  },
+);
+
+export const get = query(async () => {
+  return await counterGet("hits");
+});
@@ -10,6 +10,13 @@ export const getOtherEnvironmentVariable = query(async () => {
   return process.env.TEST_NAME_2;
 });
 
+export const getBothEnvironmentVariables = query(async () => {
+  return {
+    first: process.env.TEST_NAME ?? null,
+    second: process.env.TEST_NAME_2 ?? null,
+  };
+});
+
 export const actionGetEnvironmentVariable = action(async () => {
   return process.env.TEST_NAME;
 });
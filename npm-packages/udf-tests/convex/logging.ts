@@ -40,6 +40,13 @@ export const logArray = query(() => {
   console.log(["string", 42]);
 });
 
+export const logAtEachLevel = mutation(() => {
+  console.debug("debug");
+  console.info("info");
+  console.warn("warn");
+  console.error("error");
+});
+
 export const logDocument = mutation(async ({ db }) => {
   const id = await db.insert("table", { property: "value" });
   const document = await db.get(id);
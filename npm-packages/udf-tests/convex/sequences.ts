@@ -0,0 +1,18 @@
+import { jsonToConvex } from "convex/values";
+import { mutation } from "./_generated/server";
+
+declare const Convex: {
+  asyncSyscall: (op: string, jsonArgs: string) => Promise<string>;
+};
+
+async function sequenceNext(name: string) {
+  const result = await Convex.asyncSyscall(
+    "1.0/sequenceNext",
+    JSON.stringify({ name }),
+  );
+  return jsonToConvex(JSON.parse(result));
+}
+
+export const allocate = mutation(async () => {
+  return await sequenceNext("ids");
+});
@@ -13,6 +13,10 @@ export const intAction = action(async () => {
   return 1n;
 });
 
+export const bytesMutation = mutation(async () => {
+  return new Uint8Array([0, 1, 2, 254, 255]).buffer;
+});
+
 export const insertObject = mutation({
   args: { obj: v.any() },
   handler: async (ctx, { obj }) => {